@@ -6,6 +6,8 @@
 )]
 
 use walkdir::WalkDir;
+use tracing::{info, warn, error};
+use globset::{GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
 use ini::Ini;
 use tauri::PathResolver;
 use regex::Regex;
@@ -15,23 +17,26 @@ use serde::{Serialize, Deserialize};
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{self, BufReader, BufRead, Read, Seek, Cursor, Write};
-use std::path::{Path, PathBuf};
-use std::sync::{Mutex, Arc};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, Arc};
+use std::sync::atomic::{AtomicBool, Ordering};
 use tauri::{
     command, generate_context, generate_handler, AppHandle, Manager, State, api::dialog,
-    api::process::Command, Window
+    api::process::Command, Window, WindowEvent, GlobalShortcutManager, RunEvent
 };
-use std::process::exit;
 use thiserror::Error;
 use once_cell::sync::Lazy;
+use tracing_subscriber::fmt::writer::MakeWriterExt;
 use tauri::async_runtime;
 use toml;
-use tauri::api::file::read_binary;
 use sevenz_rust::{Password, decompress_file};
 use zip::{ZipArchive, result::ZipError};
 use unrar::{Archive, Process, List, ListSplit};
 use rusqlite::Transaction;
 use std::ffi::OsStr;
+use rayon::prelude::*;
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+use base64::Engine;
 
 // --- Structs for Deserializing Definitions ---
 #[derive(Deserialize, Debug, Clone)]
@@ -75,12 +80,158 @@ struct ApplyProgress {
 }
 
 #[derive(Serialize, Debug, Clone)]
+struct MergeEntitiesResult {
+    moved: usize,
+    conflicts: Vec<String>, // One message per asset that could not be moved
+    deleted_source: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct MergeAssetsResult {
+    moved_files: usize,
+    nested_folder: Option<String>, // Subfolder of the primary the secondary's files went into, if nested
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct RededuceMove {
+    asset_id: i64,
+    name: String,
+    from_entity_slug: String,
+    to_entity_slug: String,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct RededuceResult {
+    checked: usize,
+    moved: Vec<RededuceMove>,
+    errors: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ReconcileChange {
+    asset_id: i64,
+    name: String,
+    entity_slug: String,
+    folder_name: String, // Clean relative path
+    previous_enabled: Option<bool>,
+    current_enabled: Option<bool>,
+    previous_state_source: Option<String>, // STATE_SOURCE_* recorded before this change; None if never toggled in the app
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct BulkDeleteItem {
+    asset_id: i64,
+    success: bool,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct BulkDeleteResult {
+    deleted: usize,
+    bytes_freed: u64,
+    results: Vec<BulkDeleteItem>, // One entry per requested id, in request order
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct ScanDecisionResult {
+    inserted: usize,
+    reassigned: usize, // Already in the DB under another entity
+    errors: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct DeductionRule {
+    id: i64,
+    pattern: String,
+    entity_slug: String,
+    entity_name: Option<String>, // None when the entity no longer exists; such rules are skipped
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct EntityScanResult {
+    inserted: usize,
+    already_known: usize, // Already in the DB (under any entity)
+    errors: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+struct DefinitionSyncCounts {
+    added: usize,
+    updated: usize,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct PrefixRename {
+    from: String, // Relative to the mods folder, '/' separated
+    to: String,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct PrefixNormalizationReport {
+    renamed: Vec<PrefixRename>,
+    conflicts: Vec<String>, // Left alone because the normalized name already exists next to them
+    errors: Vec<String>,
+    db_paths_fixed: usize,  // Stored paths that still carried a disable prefix
+    reconcile: ReconcileReport,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct OtherEntityRepairReport {
+    created: Vec<String>, // Slugs of the "-other" entities that had to be recreated
+    assets_reassigned: usize, // Assets whose entity no longer existed
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct ForeignKeyViolation {
+    table: String,  // Table holding the broken reference
+    parent: String, // Table it should point into
+    count: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct IntegrityReport {
+    found: Vec<ForeignKeyViolation>,
+    other_entities_created: Vec<String>,
+    assets_reassigned: usize,
+    rows_deleted: usize, // Preset entries and hotkeys for assets/presets that no longer exist
+    remaining: Vec<ForeignKeyViolation>, // What repair couldn't fix (same as `found` without repair)
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct ReconcileReport {
+    checked: usize,
+    state_changes: Vec<ReconcileChange>,
+    missing: Vec<ReconcileChange>, // Folder not found in either state
+    hidden_by_disabled_parent: usize,
+    case_fixed: usize,
+    pruned: usize,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
 struct DashboardStats {
+    total_categories: i64,
+    total_entities: i64,
     total_mods: i64,
     enabled_mods: i64,
     disabled_mods: i64,
+    missing_mods: i64, // In the DB but not found on disk in either state
     uncategorized_mods: i64, // Mods in entities ending with "-other"
     category_counts: HashMap<String, i64>, // Category Name -> Count
+    disk_usage_bytes: u64, // Total size of all mod folders found on disk
+}
+
+// Frontend view preferences, stored as one JSON settings row. Missing fields fall back to their defaults,
+// so fields can be added without a migration.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+struct UiPrefs {
+    last_category: Option<String>, // Category slug last shown on the home page
+    entity_sort: Option<String>, // Default sort for entity lists
+    asset_sort: Option<String>, // Default sort for mod lists
+    asset_view_mode: Option<String>, // "grid" or "list"
+    thumbnail_size: Option<u32>, // Mod card width in pixels
+    hide_empty_entities: bool,
+    delete_archive_after_import: bool,
 }
 
 #[derive(Serialize, Debug, Clone)] // Add Serialize
@@ -89,16 +240,77 @@ struct KeybindInfo {
     key: String,
 }
 
+#[derive(Serialize, Debug, Clone)]
+struct IniSectionState {
+    name: String,
+    enabled: bool,
+}
+
+// One [Key...] section that switches a swap variable (`$swapvar = 0,1,2`), as declared in the mod's INI
+#[derive(Serialize, Debug, Clone)]
+struct VariantKeyBinding {
+    ini_file: String,
+    section: String,
+    key: Option<String>,
+    back: Option<String>,
+    kind: String,             // 3DMigoto key type: "cycle" (default), "toggle", "hold" or "activate"
+    variable: String,         // Variable with the most listed values in the section
+    states: usize,            // Number of values the key cycles through
+    requires_active: bool,    // `condition = $active == 1`: only works while the character is on screen
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct AssetVariantInfo {
+    variant_count: usize, // Largest `states` among the bindings; 1 when the mod has no swap keys
+    bindings: Vec<VariantKeyBinding>,
+}
+
 // Type alias for the top-level structure (HashMap: category_slug -> CategoryDefinition)
 type Definitions = HashMap<String, CategoryDefinition>;
 
 // --- Constants for Settings Keys ---
 const SETTINGS_KEY_MODS_FOLDER: &str = "mods_folder_path";
+const SETTINGS_KEY_QUICK_LAUNCH: &str = "quick_launch_path";
+const SETTINGS_KEY_USER_DEFINITIONS_LAST_SYNC: &str = "user_definitions_last_sync";
+const SETTINGS_KEY_BUNDLED_DEFINITIONS_LAST_SYNC: &str = "bundled_definitions_last_sync";
+// How long (hours) a GameBanana update lookup is reused before asking the API again
+const SETTINGS_KEY_UPDATE_CHECK_TTL_HOURS: &str = "update_check_ttl_hours";
+const DEFAULT_UPDATE_CHECK_TTL_HOURS: i64 = 6;
+const SETTINGS_KEY_SCAN_IGNORE_PATTERNS: &str = "scan_ignore_patterns"; // Newline or comma separated globs
+const SCAN_IGNORE_FILENAME: &str = ".gmmignore";
+const SETTINGS_KEY_SCAN_THREADS: &str = "scan_threads"; // Cap on deduction threads during a scan; 0/unset = all CPUs
+const SETTINGS_KEY_AUTO_SCAN_ON_STARTUP: &str = "auto_scan_on_startup"; // "true" runs scan_mods_directory in the background after setup
+const SETTINGS_KEY_UI_PREFS: &str = "ui_prefs"; // JSON-serialized UiPrefs
+const SETTINGS_KEY_DISABLE_STRATEGY: &str = "disable_strategy"; // "folder" (default) renames the mod folder, "ini" renames its INIs
+const DISABLE_STRATEGY_INI: &str = "ini";
+const SETTINGS_KEY_FALLBACK_CATEGORY: &str = "fallback_category"; // Category whose -other entity gets mods nothing was deduced for
+const DEFAULT_FALLBACK_CATEGORY: &str = "characters";
+const SETTINGS_KEY_NAME_CLEANUP_PATTERNS: &str = "name_cleanup_patterns"; // Newline-separated regexes stripped from deduced mod names
+// Archives past either limit are refused before analysis/extraction (zip-bomb guard)
+const SETTINGS_KEY_ARCHIVE_MAX_ENTRIES: &str = "archive_max_entries";
+const DEFAULT_ARCHIVE_MAX_ENTRIES: usize = 50_000;
+const SETTINGS_KEY_ARCHIVE_MAX_SIZE_MB: &str = "archive_max_size_mb"; // Total uncompressed size
+const DEFAULT_ARCHIVE_MAX_SIZE_MB: u64 = 20 * 1024;
 const OTHER_ENTITY_SUFFIX: &str = "-other";
 const OTHER_ENTITY_NAME: &str = "Other/Unknown";
 const DB_NAME: &str = "app_data.sqlite";
 const DISABLED_PREFIX: &str = "DISABLED_";
+// assets.state_source values: who last changed the enabled state (NULL = never recorded, e.g. as scanned)
+const STATE_SOURCE_APP: &str = "app";
+const STATE_SOURCE_DISK: &str = "disk"; // Renamed outside the app; noticed on reconcile or scan
 const TARGET_IMAGE_FILENAME: &str = "preview.png";
+// Prefix for INI lines switched off by toggle_ini_section; a plain `;` comment to 3DMigoto
+const INI_SECTION_DISABLED_MARKER: &str = ";GMM-disabled; ";
+const INI_BACKUP_SUFFIX: &str = ".gmm.bak";
+// Files that mark a folder as a mod even without an INI (texture/mesh replacement mods)
+const MOD_ASSET_EXTENSIONS: [&str; 3] = ["dds", "buf", "ib"];
+const IMAGE_EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "gif", "webp"];
+// With preview_storage = "app", custom previews go to <app data>/previews/<game>/<asset id>.<ext> instead of
+// the mod folder, and image_filename stores "external:<asset id>.<ext>"
+const SETTINGS_KEY_PREVIEW_STORAGE: &str = "preview_storage";
+const PREVIEW_STORAGE_APP: &str = "app";
+const PREVIEWS_DIR_NAME: &str = "previews";
+const EXTERNAL_PREVIEW_PREFIX: &str = "external:";
 
 // --- Error Handling ---
 #[derive(Debug, Error)]
@@ -129,6 +341,60 @@ enum AppError {
     Rar(#[from] unrar::error::UnrarError),
     #[error("Unsupported archive type: {0}")]
     UnsupportedArchive(String),
+    #[error("Download error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("{0}")]
+    FileLocked(String), // A mod file/folder is held open by another process (usually the game)
+    #[error("{0}")]
+    Other(String),
+}
+
+impl AppError {
+    // Stable identifier the frontend can match on to pick a recovery action
+    fn kind(&self) -> &'static str {
+        match self {
+            AppError::Sqlite(_) => "Database",
+            AppError::Io(_) => "Io",
+            AppError::Json(_) => "Json",
+            AppError::TauriPath(_) => "TauriPath",
+            AppError::Config(_) => "Config",
+            AppError::ModOperation(_) => "ModOperation",
+            AppError::NotFound(_) => "NotFound",
+            AppError::UserCancelled => "UserCancelled",
+            AppError::ShellCommand(_) => "ShellCommand",
+            AppError::Zip(_) => "Zip",
+            AppError::SevenZ(_) => "SevenZ",
+            AppError::Rar(_) => "Rar",
+            AppError::UnsupportedArchive(_) => "UnsupportedArchive",
+            AppError::Http(_) => "Http",
+            AppError::FileLocked(_) => "FileLocked",
+            AppError::Other(_) => "Other",
+        }
+    }
+}
+
+// Sent to the frontend as { kind, message }. `message` keeps the old plain string.
+impl Serialize for AppError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("AppError", 2)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+// Lets existing `format!(...)` / `.to_string()` error paths keep working with `?`
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Other(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Other(message.to_string())
+    }
 }
 
 // --- Event Payload Struct ---
@@ -140,17 +406,71 @@ struct ScanProgress {
   message: String,
 }
 
+#[derive(Clone, serde::Serialize)]
+struct ImportProgress {
+  extracted: usize, // Archive entries processed so far
+  total: usize,
+  current_file: Option<String>,
+  archive_index: Option<usize>, // Position in a batch import, if any
+}
+
+#[derive(Clone, serde::Serialize)]
+struct AnalyzeProgress {
+  processed: usize, // Archive entries read so far
+  total: usize, // 0 when the archive format doesn't tell up front (RAR)
+  current_file: Option<String>,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct BackupProgress {
+  operation: String, // "backup" or "restore"
+  processed: usize, // Files written so far
+  total: usize,
+  current_file: Option<String>,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct DownloadProgress {
+  downloaded: u64, // Bytes received so far
+  total: Option<u64>, // None when the server sends no Content-Length
+  file_name: String,
+}
+
+#[derive(Clone, serde::Serialize)]
+struct HotkeyTogglePayload {
+  asset_id: i64,
+  accelerator: String,
+  new_enabled_state: Option<bool>, // None when the toggle failed
+  error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct AssetHotkey {
+    asset_id: i64,
+    asset_name: String,
+    entity_slug: String,
+    accelerator: String,
+}
+
 const APP_CONFIG_FILENAME: &str = "app_config.json";
 const DEFAULT_GAME_SLUG: &str = "genshin";
 const PREDEFINED_GAMES: [&str; 3] = ["genshin", "wuwa", "zzz"];
 const DB_INTERNAL_GAME_SLUG_KEY: &str = "database_game_slug";
 const DB_FILENAME_PREFIX: &str = "app_data_"; // Prefix for archived game dbs
 const ACTIVE_DB_FILENAME: &str = "app_data.sqlite";
+// Stored in PRAGMA user_version once initialize_database's migrations have run; bump with each new migration
+// 2: assets.version, mod_update_cache.remote_version
+// 3: assets.state_source
+// 4: deduction_rules table
+const DB_SCHEMA_VERSION: i64 = 4;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct AppConfig {
     last_active_game: String,
     requested_active_game: String,
+    // Game slug -> bundled definitions file stem, for profiles that don't load definitions/<slug>.toml
+    #[serde(default)]
+    definition_sets: HashMap<String, String>,
 }
 
 // --- Event Names ---
@@ -168,14 +488,136 @@ const PRUNING_START_EVENT: &str = "prune://start";
 const PRUNING_PROGRESS_EVENT: &str = "prune://progress";
 const PRUNING_COMPLETE_EVENT: &str = "prune://complete";
 const PRUNING_ERROR_EVENT: &str = "prune://error";
+// Import Event Name
+const IMPORT_PROGRESS_EVENT: &str = "import://progress";
+const IMPORT_PROGRESS_INTERVAL: usize = 50; // Entries between import progress events
+// Import progress is sent every IMPORT_PROGRESS_INTERVAL entries, or sooner once this long has passed
+const IMPORT_PROGRESS_MAX_DELAY: std::time::Duration = std::time::Duration::from_millis(100);
+const ANALYZE_PROGRESS_EVENT: &str = "analyze://progress";
+const ANALYZE_PROGRESS_INTERVAL: usize = 50; // Entries between analyze progress events
+const MAX_ANALYZE_INI_BYTES: u64 = 1024 * 1024; // INIs are config; anything bigger is not read during analysis
+const DOWNLOAD_PROGRESS_EVENT: &str = "download://progress";
+const BACKUP_PROGRESS_EVENT: &str = "backup://progress";
+// Payload: slugs of the entities whose stored asset states a background reconcile corrected
+const ASSET_STATE_DRIFT_EVENT: &str = "assets://state-drift";
+// Window focus triggers a background reconcile at most this often
+const BACKGROUND_RECONCILE_MIN_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+// Hotkey Event Name
+const HOTKEY_TOGGLE_EVENT: &str = "hotkey://asset-toggled";
 // -------------------------
 
 const SETTINGS_KEY_TRAVELER_MIGRATION_COMPLETE: &str = "traveler_migration_complete_v1"; // Added v1 for potential future migrations
 
-type CmdResult<T> = Result<T, String>;
+type CmdResult<T> = Result<T, AppError>;
 
 struct DbState(Arc<Mutex<Connection>>);
 
+// Planned enable/disable changes (asset id -> wanted state) that haven't been applied to disk yet
+#[derive(Default)]
+struct StagedChangesState(Mutex<HashMap<i64, bool>>);
+
+// Locks the shared connection. A panic while holding the lock poisons the mutex; the connection
+// itself is still usable (rusqlite rolls back unfinished transactions on drop), so we reclaim the
+// guard and clear the poison instead of failing every later command until restart.
+fn lock_connection(mutex: &Mutex<Connection>) -> MutexGuard<'_, Connection> {
+    mutex.lock().unwrap_or_else(|poisoned| {
+        warn!("DB lock was poisoned by a panicking command, recovering the connection.");
+        mutex.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+fn lock_db(db_state: &DbState) -> MutexGuard<'_, Connection> {
+    lock_connection(&db_state.0)
+}
+
+// Set while a scan or VACUUM runs; both hold long write locks (the scan on its own connection), so they must not overlap
+static BULK_DB_TASK_RUNNING: AtomicBool = AtomicBool::new(false);
+
+struct BulkDbTaskGuard;
+
+impl BulkDbTaskGuard {
+    fn acquire(task: &str) -> CmdResult<Self> {
+        if BULK_DB_TASK_RUNNING.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_err() {
+            return Err(AppError::ModOperation(format!("Cannot start {} while a scan or database optimization is running.", task)));
+        }
+        Ok(BulkDbTaskGuard)
+    }
+}
+
+impl Drop for BulkDbTaskGuard {
+    fn drop(&mut self) {
+        BULK_DB_TASK_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+// Cancellation flags for long-running commands, keyed by operation name ("backup", "restore").
+// A command registers a CancelToken for its run; cancel_operation flips the flag and the worker
+// checks it between steps.
+static CANCEL_FLAGS: Lazy<Mutex<HashMap<&'static str, Arc<AtomicBool>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// When the last background reconcile started; None until the first one runs
+static LAST_BACKGROUND_RECONCILE: Lazy<Mutex<Option<std::time::Instant>>> = Lazy::new(|| Mutex::new(None));
+
+struct CancelToken {
+    operation: &'static str,
+    flag: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    // Replaces any flag left from an earlier run of the same operation
+    fn register(operation: &'static str) -> Self {
+        let flag = Arc::new(AtomicBool::new(false));
+        CANCEL_FLAGS.lock().unwrap_or_else(|e| e.into_inner()).insert(operation, flag.clone());
+        CancelToken { operation, flag }
+    }
+
+    fn check(&self) -> CmdResult<()> {
+        if self.flag.load(Ordering::SeqCst) { Err(AppError::UserCancelled) } else { Ok(()) }
+    }
+}
+
+impl Drop for CancelToken {
+    fn drop(&mut self) {
+        let mut flags = CANCEL_FLAGS.lock().unwrap_or_else(|e| e.into_inner());
+        if flags.get(self.operation).is_some_and(|flag| Arc::ptr_eq(flag, &self.flag)) {
+            flags.remove(self.operation);
+        }
+    }
+}
+
+// Files and folders a command created, removed again on drop unless keep() was called.
+// Lets cancelled or failed operations leave no half-written output behind.
+#[derive(Default)]
+struct PartialOutput {
+    paths: Vec<PathBuf>,
+    kept: bool,
+}
+
+impl PartialOutput {
+    fn track(&mut self, path: PathBuf) {
+        self.paths.push(path);
+    }
+
+    fn keep(mut self) {
+        self.kept = true;
+    }
+}
+
+impl Drop for PartialOutput {
+    fn drop(&mut self) {
+        if self.kept { return; }
+        for path in self.paths.iter().rev() {
+            let removed = if path.is_dir() { fs::remove_dir_all(path) } else { fs::remove_file(path) };
+            match removed {
+                Ok(()) => info!("[PartialOutput] Removed partial output '{}'", path.display()),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => warn!("[PartialOutput] Failed to remove partial output '{}': {}", path.display(), e),
+            }
+        }
+    }
+}
+
 static DB_CONNECTION: Lazy<Mutex<SqlResult<Connection>>> = Lazy::new(|| {
     Mutex::new(Err(rusqlite::Error::InvalidPath("DB not initialized yet".into())))
 });
@@ -197,6 +639,14 @@ lazy_static! {
     };
     static ref NAME_CLEANUP_REGEX: Regex = Regex::new(r"(?i)[_\-.\s]+|(_v\d+(\.\d+)*)|(_af)|(_nsfw)|(\(disabled\))|(\(.*\))|(\[.*\])|(^DISABLED_)").unwrap();
     static ref POTENTIAL_NAME_PART_REGEX: Regex = Regex::new(r"^[a-zA-Z\s]+").unwrap();
+    // A run of disable markers left by mixing manual and app renames: "DISABLED_DISABLED_", "disabled_", "DISABLED Mod".
+    // Mixed-case words need the underscore so names like "Disabled Effects" are left alone.
+    static ref DISABLED_PREFIX_RUN_REGEX: Regex = Regex::new(r"^(?:DISABLED[_\- ]?|(?i:disabled)_)+").unwrap();
+    // Version suffix in a folder/archive name, the part MOD_NAME_CLEANUP_REGEX strips: "Mod_v1.2" -> "1.2"
+    static ref NAME_VERSION_REGEX: Regex = Regex::new(r"(?i)[_\-\s]v(\d+(?:\.\d+)*)\b").unwrap();
+    static ref CAMEL_CASE_BOUNDARY_REGEX: Regex = Regex::new(r"([a-z0-9])([A-Z])").unwrap();
+    // GameBanana mod pages, e.g. https://gamebanana.com/mods/123456 or .../mods/download/123456
+    static ref GAMEBANANA_MOD_URL_REGEX: Regex = Regex::new(r"^/mods/(?:download/)?(\d+)").unwrap();
 }
 
 #[derive(Debug)]
@@ -207,6 +657,7 @@ struct DeducedInfo {
     author: Option<String>,
     description: Option<String>,
     image_filename: Option<String>,
+    version: Option<String>,
 }
 
 #[derive(Clone)]
@@ -218,11 +669,71 @@ struct DeductionMaps {
     entity_slug_to_category_slug: HashMap<String, String>,
     lowercase_entity_firstname_to_slug: HashMap<String, String>, // e.g., "ellen" -> "ellen-joe"
     lowercase_entity_first_two_words_to_slug: HashMap<String, String>, // e.g., "ellen joe" -> "ellen-joe"
+    lowercase_hash_to_entity_slug: HashMap<String, String>, // e.g., "0a1b2c3d" -> "raiden-shogun"
+    fallback_category_slug: String, // Its "-other" entity receives mods with no entity or category hint
+    name_cleanup_patterns: Vec<Regex>, // User patterns applied before MOD_NAME_CLEANUP_REGEX
+    entity_rules: Vec<(GlobMatcher, String)>, // User deduction_rules (matcher, entity slug), first match wins
+}
+
+// Identification data found in 3DMigoto INIs that carry no [Mod]/[Settings]-style metadata
+#[derive(Debug, Default)]
+struct IniHashHints {
+    namespace: Option<String>,
+    hashes: Vec<String>,         // Lowercased `hash = ...` values from override sections
+    override_names: Vec<String>, // [TextureOverrideXyz] section names with the prefix stripped
 }
 
 #[derive(Serialize, Deserialize, Debug)] struct Category { id: i64, name: String, slug: String }
-#[derive(Serialize, Deserialize, Debug)] struct Entity { id: i64, category_id: i64, name: String, slug: String, description: Option<String>, details: Option<String>, base_image: Option<String>, mod_count: i32, enabled_mod_count: Option<i32>, recent_mod_count: Option<i32>, favorite_mod_count: Option<i32> }
-#[derive(Serialize, Deserialize, Debug, Clone)] struct Asset { id: i64, entity_id: i64, name: String, description: Option<String>, folder_name: String, image_filename: Option<String>, author: Option<String>, category_tag: Option<String>, is_enabled: bool }
+#[derive(Serialize, Deserialize, Debug)] struct Entity { id: i64, category_id: i64, name: String, slug: String, description: Option<String>, details: EntityDetails, base_image: Option<String>, mod_count: i32, enabled_mod_count: Option<i32>, disabled_mod_count: Option<i32>, recent_mod_count: Option<i32>, favorite_mod_count: Option<i32> }
+#[derive(Serialize, Deserialize, Debug, Clone)] struct Asset { id: i64, entity_id: i64, name: String, description: Option<String>, folder_name: String, image_filename: Option<String>, author: Option<String>, category_tag: Option<String>, is_enabled: bool, #[serde(default)] hidden_by_disabled_parent: bool, created_at: Option<i64>, source_url: Option<String>, sort_order: Option<i64>, #[serde(default)] version: Option<String>, #[serde(default)] state_source: Option<String>, #[serde(flatten, default)] context: Option<AssetContext> }
+// Breadcrumb info for showing an asset outside its entity page (opt-in, flattened into Asset)
+#[derive(Serialize, Deserialize, Debug, Clone)] struct AssetContext { entity_slug: String, entity_name: String, category_slug: String, category_name: String }
+
+// Typed view of the `entities.details` JSON. Known attributes get their own fields, anything
+// else (e.g. keys from user definition files) is kept in `extra` so nothing is lost.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct EntityDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")] rarity: Option<String>,
+    // Genshin
+    #[serde(default, skip_serializing_if = "Option::is_none")] element: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] weapon: Option<String>,
+    // ZZZ
+    #[serde(default, skip_serializing_if = "Option::is_none")] attribute: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] specialty: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] rank: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] types: Option<Vec<String>>,
+    // Wuwa
+    #[serde(default, skip_serializing_if = "Option::is_none")] resonator_attribute: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] resonator_weapon: Option<String>,
+    // NPCs / enemies
+    #[serde(default, rename = "type", skip_serializing_if = "Option::is_none")] entity_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")] faction: Option<String>,
+    #[serde(flatten)] extra: serde_json::Map<String, serde_json::Value>,
+}
+
+// Parses the raw details column. Invalid JSON yields empty details (logged); a valid object whose
+// known keys have unexpected types is kept as-is in `extra`.
+fn parse_entity_details(raw: Option<&str>, entity_slug: &str) -> EntityDetails {
+    let raw = match raw.map(str::trim) {
+        Some(raw) if !raw.is_empty() => raw,
+        _ => return EntityDetails::default(),
+    };
+    match serde_json::from_str::<serde_json::Value>(raw) {
+        Ok(serde_json::Value::Object(map)) => serde_json::from_value(serde_json::Value::Object(map.clone()))
+            .unwrap_or_else(|e| {
+                error!("[parse_entity_details] Unexpected details shape for '{}': {}", entity_slug, e);
+                EntityDetails { extra: map, ..Default::default() }
+            }),
+        Ok(_) => {
+            error!("[parse_entity_details] Details for '{}' are not a JSON object, ignoring.", entity_slug);
+            EntityDetails::default()
+        }
+        Err(e) => {
+            error!("[parse_entity_details] Invalid details JSON for '{}': {}", entity_slug, e);
+            EntityDetails::default()
+        }
+    }
+}
 
 #[derive(Serialize, Debug, Clone)]
 struct EntityWithCounts {
@@ -236,14 +747,52 @@ struct EntityWithCounts {
     // Counts
     total_mods: i64,
     enabled_mods: i64,
+    disabled_mods: i64,
+    sort_order: Option<i64>, // Custom position set via set_entity_order
+}
+
+// Flat entity listing for pickers, carrying its category alongside
+#[derive(Serialize, Debug, Clone)]
+struct EntityWithCategory {
+    id: i64,
+    name: String,
+    slug: String,
+    details: Option<String>, // JSON string
+    base_image: Option<String>,
+    category_id: i64,
+    category_name: String,
+    category_slug: String,
 }
 
 // Structs for Import/Analysis
+// Entries come back as a flat list sorted by path; parent_index links turn it into a folder tree.
 #[derive(Serialize, Debug, Clone)]
 struct ArchiveEntry {
     path: String,
     is_dir: bool,
     is_likely_mod_root: bool,
+    uncompressed_size: u64, // 0 for directories
+    parent_index: Option<usize>, // Index of the containing directory in the same list; None at the archive root
+    depth: usize, // Number of folders above this entry
+}
+
+// Where analyze_archive found the entity, strongest signal first
+#[derive(Serialize, Debug, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+enum DeductionSource { UserRule, IniTarget, IniHash, InternalFilename, NameToken, ArchiveFilename }
+
+impl DeductionSource {
+    // Rough likelihood that the entity is right; the import dialog only pre-selects confident matches
+    fn confidence(self) -> f32 {
+        match self {
+            DeductionSource::UserRule => 1.0,
+            DeductionSource::IniTarget => 0.95,
+            DeductionSource::IniHash => 0.9,
+            DeductionSource::InternalFilename => 0.75,
+            DeductionSource::NameToken => 0.6,
+            DeductionSource::ArchiveFilename => 0.5,
+        }
+    }
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -252,50 +801,53 @@ struct ArchiveAnalysisResult {
     entries: Vec<ArchiveEntry>,
     deduced_mod_name: Option<String>,
     deduced_author: Option<String>,
+    deduced_version: Option<String>,       // INI `Version = ...`, else a "_v1.2" suffix on the mod root or archive name
     deduced_category_slug: Option<String>, // Keep for potential future backend use
     deduced_entity_slug: Option<String>,   // Keep for potential future backend use
     // --> Added Raw INI fields <--
     raw_ini_type: Option<String>,          // e.g., "Character", "Weapon"
     raw_ini_target: Option<String>,        // e.g., "Nahida", "Raiden Shogun", "Aqua Simulacra"
     // --------------------------
+    deduced_entity_token: Option<String>,  // Name token that matched the entity, when found by tokenizing a folder/archive name
+    deduction_source: Option<DeductionSource>, // Which signal produced deduced_entity_slug
+    confidence: f32,                       // 0.0 (no entity) to 1.0; see DeductionSource::confidence
     detected_preview_internal_path: Option<String>,
+    preview_candidate_paths: Vec<String>, // Every image in the archive, known preview names first
+    total_uncompressed_size: u64,
 }
 
 // --- Migration Logic ---
 fn run_traveler_migration_logic(
     db_state: &DbState,
     app_handle: &AppHandle, // Keep for path resolution if needed later
-) -> Result<String, String> { // Returns success message or error string
-    println!("[Migration] Starting Traveler -> Aether/Lumine migration logic...");
+) -> CmdResult<String> { // Returns a success/skip message
+    info!("[Migration] Starting Traveler -> Aether/Lumine migration logic...");
 
-    let base_mods_path = get_mods_base_path_from_settings(db_state)
-        .map_err(|e| format!("[Migration] Failed to get mods base path: {}", e))?;
+    let base_mods_path = get_mods_base_path_from_settings(db_state)?;
 
     // --- Use a single lock scope for all DB operations ---
-    let mut conn_guard = db_state.0.lock().map_err(|_| "[Migration] DB lock poisoned".to_string())?;
+    let mut conn_guard = lock_db(db_state);
     let conn = &mut *conn_guard; // Get mutable access for the transaction
 
     // --- Check if migration already done ---
-    let migration_status = get_setting_value(conn, SETTINGS_KEY_TRAVELER_MIGRATION_COMPLETE)
-        .map_err(|e| format!("[Migration] DB Error checking migration status: {}", e))?;
+    let migration_status = get_setting_value(conn, SETTINGS_KEY_TRAVELER_MIGRATION_COMPLETE)?;
     if migration_status == Some("true".to_string()) {
         let msg = "[Migration] Traveler migration already marked as complete. Skipping.";
-        println!("{}", msg);
+        info!("{}", msg);
         return Ok(msg.to_string());
     }
 
     // --- Get Entity IDs and Category Slugs ---
     let traveler_info: Option<(i64, String)> = conn.query_row(
         "SELECT id, slug FROM entities WHERE slug = 'traveler'", [], |row| Ok((row.get(0)?, row.get(1)?))
-    ).optional().map_err(|e| format!("[Migration] DB Error fetching Traveler info: {}", e))?;
+    ).optional()?;
 
     if traveler_info.is_none() {
         let msg = "[Migration] Traveler entity not found. Migration not needed or already partially done.";
-        println!("{}", msg);
+        info!("{}", msg);
         // Mark as complete anyway if Traveler doesn't exist
         conn.execute("INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
-                     params![SETTINGS_KEY_TRAVELER_MIGRATION_COMPLETE, "true"])
-            .map_err(|e| format!("[Migration] Failed to mark as complete after Traveler not found: {}", e))?;
+                     params![SETTINGS_KEY_TRAVELER_MIGRATION_COMPLETE, "true"])?;
         return Ok(msg.to_string());
     }
     let (traveler_id, _traveler_slug) = traveler_info.unwrap(); // Safe due to check above
@@ -304,26 +856,26 @@ fn run_traveler_migration_logic(
     let aether_info: Option<(i64, String, String)> = conn.query_row(
         "SELECT e.id, e.slug, c.slug FROM entities e JOIN categories c ON e.category_id = c.id WHERE e.slug = 'aether'",
         [], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))
-    ).optional().map_err(|e| format!("[Migration] DB Error fetching Aether info: {}", e))?;
+    ).optional()?;
 
     // Fetch Lumine info (ID, Category Slug)
     let lumine_info: Option<(i64, String, String)> = conn.query_row(
         "SELECT e.id, e.slug, c.slug FROM entities e JOIN categories c ON e.category_id = c.id WHERE e.slug = 'lumine'",
         [], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))
-    ).optional().map_err(|e| format!("[Migration] DB Error fetching Lumine info: {}", e))?;
+    ).optional()?;
 
     if aether_info.is_none() || lumine_info.is_none() {
         let msg = "[Migration] Aether or Lumine entity not found. Cannot perform migration. Ensure definitions are loaded.";
-        println!("{}", msg);
+        info!("{}", msg);
         // Don't mark as complete, definitions might load later
-        return Err(msg.to_string());
+        return Err(AppError::NotFound(msg.to_string()));
     }
     let (aether_id, aether_slug, aether_cat_slug) = aether_info.unwrap();
     let (lumine_id, lumine_slug, lumine_cat_slug) = lumine_info.unwrap();
 
     // Basic sanity check: Ensure they are in the same category (expected)
     if aether_cat_slug != lumine_cat_slug {
-         println!("[Migration] Warning: Aether ({}) and Lumine ({}) appear to be in different categories. Using Aether's category for path construction.", aether_cat_slug, lumine_cat_slug);
+         warn!("[Migration] Aether ({}) and Lumine ({}) appear to be in different categories. Using Aether's category for path construction.", aether_cat_slug, lumine_cat_slug);
          // Proceed using aether_cat_slug as the base category for paths
     }
     let target_category_slug = aether_cat_slug; // Use Aether's (or Lumine's) category slug
@@ -331,8 +883,7 @@ fn run_traveler_migration_logic(
     // --- Get Assets associated with Traveler ---
     let mut assets_to_migrate = Vec::<(i64, String, String)>::new(); // (id, name, folder_name)
     { // Scope for statement
-        let mut stmt = conn.prepare("SELECT id, name, folder_name FROM assets WHERE entity_id = ?1")
-            .map_err(|e| format!("[Migration] Failed to prepare asset fetch statement: {}", e))?;
+        let mut stmt = conn.prepare("SELECT id, name, folder_name FROM assets WHERE entity_id = ?1")?;
         let rows = stmt.query_map(
             params![traveler_id],
             |row| Ok((
@@ -340,40 +891,38 @@ fn run_traveler_migration_logic(
                 row.get(1)?,
                 row.get::<_, String>(2)?
             ))
-        )
-        .map_err(|e| format!("[Migration] Failed to query Traveler assets: {}", e))?;
+        )?;
 
         for row_result in rows {
              match row_result {
                  // Note: No change needed here, as `folder` will now correctly be a String
                  Ok((id, name, folder)) => assets_to_migrate.push((id, name, folder.replace("\\", "/"))),
-                 Err(e) => return Err(format!("[Migration] Error reading asset row: {}", e)),
+                 Err(e) => return Err(e.into()),
              }
         }
     }
 
     if assets_to_migrate.is_empty() {
-        println!("[Migration] No assets found linked to Traveler (ID: {}).", traveler_id);
+        info!("[Migration] No assets found linked to Traveler (ID: {}).", traveler_id);
         // Still need to delete the Traveler entity if it exists
     } else {
-        println!("[Migration] Found {} assets to migrate from Traveler.", assets_to_migrate.len());
+        info!("[Migration] Found {} assets to migrate from Traveler.", assets_to_migrate.len());
     }
 
     // --- Fetch Deduction Maps for Hinting ---
     // Note: We are already inside a lock, so fetch_deduction_maps needs &Connection
-    let maps = fetch_deduction_maps(conn)
-        .map_err(|e| format!("[Migration] Failed to fetch deduction maps: {}", e))?;
+    let maps = fetch_deduction_maps(conn)?;
 
 
     // --- Start Transaction ---
-    let tx = conn.transaction().map_err(|e| format!("[Migration] Failed to start transaction: {}", e))?;
+    let tx = conn.transaction()?;
 
     let mut migrated_count = 0;
     let mut errors: Vec<String> = Vec::new();
 
     // --- Process each asset ---
     for (asset_id, asset_name, current_clean_relative_path) in assets_to_migrate {
-        println!("[Migration] Processing Asset ID: {}, Name: '{}', Current DB Path: '{}'", asset_id, asset_name, current_clean_relative_path);
+        info!("[Migration] Processing Asset ID: {}, Name: '{}', Current DB Path: '{}'", asset_id, asset_name, current_clean_relative_path);
 
         // --- Determine Target (Aether/Lumine) ---
         let mut target_id = aether_id; // Default to Aether
@@ -408,13 +957,13 @@ fn run_traveler_migration_logic(
                 target_reason = "Folder name keyword";
             }
         }
-        println!("[Migration]   -> Assigning to {} (ID: {}) based on: {}", target_slug, target_id, target_reason);
+        info!("[Migration]   -> Assigning to {} (ID: {}) based on: {}", target_slug, target_id, target_reason);
 
         // --- Calculate Paths ---
         let mod_folder_base_name_from_db = current_relative_path_buf.file_name().unwrap_or_default().to_string_lossy();
         if mod_folder_base_name_from_db.is_empty() {
-            let err = format!("[Migration]   -> ERROR: Cannot extract base name from DB path '{}'. Skipping asset {}.", current_clean_relative_path, asset_id);
-            println!("{}", err);
+            let err = format!("[Migration]   -> Cannot extract base name from DB path '{}'. Skipping asset {}.", current_clean_relative_path, asset_id);
+            warn!("{}", err);
             errors.push(err);
             continue;
         }
@@ -424,27 +973,17 @@ fn run_traveler_migration_logic(
         let new_clean_relative_path_str = new_clean_relative_path_buf.to_string_lossy().replace("\\", "/");
 
         // Determine the current *actual* path on disk (check enabled/disabled)
-        let disabled_filename_current = format!("{}{}", DISABLED_PREFIX, mod_folder_base_name_from_db);
-        let relative_parent_path_current = current_relative_path_buf.parent();
-
-        let full_path_if_enabled_current = base_mods_path.join(&current_relative_path_buf);
-        let full_path_if_disabled_current = match relative_parent_path_current {
-            Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_filename_current),
-            _ => base_mods_path.join(&disabled_filename_current),
-        };
-
         let (current_actual_path_on_disk, is_currently_disabled) =
-            if full_path_if_enabled_current.is_dir() {
-                (full_path_if_enabled_current, false)
-            } else if full_path_if_disabled_current.is_dir() {
-                (full_path_if_disabled_current, true)
-            } else {
-                let err = format!("[Migration]   -> ERROR: Source folder not found on disk for asset {} at '{}' or '{}'. Skipping.", asset_id, full_path_if_enabled_current.display(), full_path_if_disabled_current.display());
-                println!("{}", err);
-                errors.push(err);
-                continue; // Skip this asset
+            match resolve_asset_on_disk(&base_mods_path, &current_relative_path_buf) {
+                Some((path, is_enabled)) => (path, !is_enabled),
+                None => {
+                    let err = format!("[Migration]   -> Source folder not found on disk for asset {} at '{}' (enabled or disabled). Skipping.", asset_id, base_mods_path.join(&current_relative_path_buf).display());
+                    warn!("{}", err);
+                    errors.push(err);
+                    continue; // Skip this asset
+                }
             };
-        println!("[Migration]   -> Current path on disk: '{}' (Disabled: {})", current_actual_path_on_disk.display(), is_currently_disabled);
+        info!("[Migration]   -> Current path on disk: '{}' (Disabled: {})", current_actual_path_on_disk.display(), is_currently_disabled);
 
         // Construct the new *actual* destination path on disk, preserving disabled state
         let new_folder_name_on_disk = if is_currently_disabled {
@@ -453,60 +992,57 @@ fn run_traveler_migration_logic(
             mod_folder_base_name_from_db.to_string()
         };
         let new_actual_dest_path_on_disk = base_mods_path.join(&target_category_slug).join(&target_slug).join(&new_folder_name_on_disk);
-        println!("[Migration]   -> New destination path on disk: '{}'", new_actual_dest_path_on_disk.display());
+        info!("[Migration]   -> New destination path on disk: '{}'", new_actual_dest_path_on_disk.display());
 
         // --- Perform Filesystem Move (before DB commit, but after tx start) ---
         if current_actual_path_on_disk == new_actual_dest_path_on_disk {
-             println!("[Migration]   -> Skipping filesystem move (paths are the same). Might indicate only DB update needed.");
+             info!("[Migration]   -> Skipping filesystem move (paths are the same). Might indicate only DB update needed.");
         } else {
             // Ensure parent directory exists
             if let Some(parent) = new_actual_dest_path_on_disk.parent() {
                  if !parent.exists() {
-                     println!("[Migration]   -> Creating parent directory: {}", parent.display());
+                     info!("[Migration]   -> Creating parent directory: {}", parent.display());
                      if let Err(e) = fs::create_dir_all(parent) {
-                         let err = format!("[Migration]   -> ERROR: Failed to create parent directory '{}': {}. Skipping asset {}.", parent.display(), e, asset_id);
-                         println!("{}", err);
+                         let err = format!("[Migration]   -> Failed to create parent directory '{}': {}. Skipping asset {}.", parent.display(), e, asset_id);
+                         warn!("{}", err);
                          errors.push(err);
                          continue; // Skip this asset
                      }
                  }
             } else {
-                 let err = format!("[Migration]   -> ERROR: Cannot determine parent directory for new path '{}'. Skipping asset {}.", new_actual_dest_path_on_disk.display(), asset_id);
-                 println!("{}", err);
+                 let err = format!("[Migration]   -> Cannot determine parent directory for new path '{}'. Skipping asset {}.", new_actual_dest_path_on_disk.display(), asset_id);
+                 warn!("{}", err);
                  errors.push(err);
                  continue; // Skip this asset
             }
 
             // Check if target exists unexpectedly
             if new_actual_dest_path_on_disk.exists() {
-                let err = format!("[Migration]   -> ERROR: Target path '{}' already exists. Skipping asset {}.", new_actual_dest_path_on_disk.display(), asset_id);
-                println!("{}", err);
+                let err = format!("[Migration]   -> Target path '{}' already exists. Skipping asset {}.", new_actual_dest_path_on_disk.display(), asset_id);
+                warn!("{}", err);
                 errors.push(err);
                 continue; // Skip this asset
             }
 
             // Perform the rename
-            println!("[Migration]   -> Moving '{}' -> '{}'", current_actual_path_on_disk.display(), new_actual_dest_path_on_disk.display());
+            info!("[Migration]   -> Moving '{}' -> '{}'", current_actual_path_on_disk.display(), new_actual_dest_path_on_disk.display());
             if let Err(e) = fs::rename(&current_actual_path_on_disk, &new_actual_dest_path_on_disk) {
-                 let err = format!("[Migration]   -> ERROR: Failed to move folder for asset {}: {}. Skipping.", asset_id, e);
-                 println!("{}", err);
+                 let err = format!("[Migration]   -> Failed to move folder for asset {}: {}. Skipping.", asset_id, e);
+                 warn!("{}", err);
                  errors.push(err);
                  continue; // Skip this asset
             }
         }
 
         // --- Update Database Record (within transaction) ---
-        println!("[Migration]   -> Updating DB: asset_id={}, new_entity_id={}, new_folder_name='{}'", asset_id, target_id, new_clean_relative_path_str);
+        info!("[Migration]   -> Updating DB: asset_id={}, new_entity_id={}, new_folder_name='{}'", asset_id, target_id, new_clean_relative_path_str);
         let changes = tx.execute(
             "UPDATE assets SET entity_id = ?1, folder_name = ?2 WHERE id = ?3",
             params![target_id, new_clean_relative_path_str, asset_id],
-        ).map_err(|e| {
-            // Don't automatically rollback here, let the main error handling do it
-            format!("[Migration]   -> DB Update failed for asset {}: {}", asset_id, e)
-        })?; // Propagate error to outer scope
+        )?; // Propagate error to outer scope; dropping the transaction rolls back
 
         if changes == 0 {
-            println!("[Migration]   -> Warning: DB update affected 0 rows for asset {}.", asset_id);
+            warn!("[Migration]   -> DB update affected 0 rows for asset {}.", asset_id);
         }
         migrated_count += 1;
 
@@ -514,46 +1050,56 @@ fn run_traveler_migration_logic(
 
     // --- Delete the Traveler Entity (if migration was successful so far) ---
     if errors.is_empty() {
-        println!("[Migration] Deleting Traveler entity (ID: {}) from database.", traveler_id);
-        let deleted_entity_count = tx.execute("DELETE FROM entities WHERE id = ?1", params![traveler_id])
-           .map_err(|e| format!("[Migration] Failed to delete Traveler entity: {}", e))?;
+        info!("[Migration] Deleting Traveler entity (ID: {}) from database.", traveler_id);
+        let deleted_entity_count = tx.execute("DELETE FROM entities WHERE id = ?1", params![traveler_id])?;
         if deleted_entity_count > 0 {
-            println!("[Migration] Traveler entity successfully deleted.");
+            info!("[Migration] Traveler entity successfully deleted.");
         } else {
-            println!("[Migration] Traveler entity already deleted or delete failed (0 rows affected).");
+            info!("[Migration] Traveler entity already deleted or delete failed (0 rows affected).");
         }
 
         // Mark migration as complete in settings
-        println!("[Migration] Marking migration as complete in settings.");
+        info!("[Migration] Marking migration as complete in settings.");
         tx.execute("INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
-                    params![SETTINGS_KEY_TRAVELER_MIGRATION_COMPLETE, "true"])
-           .map_err(|e| format!("[Migration] Failed to mark migration as complete: {}", e))?;
+                    params![SETTINGS_KEY_TRAVELER_MIGRATION_COMPLETE, "true"])?;
 
         // --- Commit Transaction ---
-        tx.commit().map_err(|e| format!("[Migration] Failed to commit transaction: {}", e))?;
+        tx.commit()?;
 
         let final_msg = format!("Traveler migration completed successfully. Migrated {} assets.", migrated_count);
-        println!("[Migration] {}", final_msg);
+        info!("[Migration] {}", final_msg);
         Ok(final_msg)
 
     } else {
         // --- Rollback Transaction due to errors ---
         let err_summary = format!("[Migration] Migration failed with {} error(s). Rolling back changes.", errors.len());
-        println!("{}", err_summary);
+        info!("{}", err_summary);
         for e in &errors {
-            eprintln!("  - {}", e);
+            error!("  - {}", e);
         }
         // Rollback happens automatically when `tx` is dropped due to error return
-        Err(format!("{}\n{}", err_summary, errors.join("\n")))
+        Err(AppError::ModOperation(format!("{}\n{}", err_summary, errors.join("\n"))))
     }
 }
 
 // --- Helper Functions for Deduction ---
 
+// Decomposes to NFKD and drops combining marks: "Lumière" -> "Lumiere". Also folds full-width and
+// ligature forms, and handles names that arrive already decomposed (macOS file names).
+fn strip_diacritics(input: &str) -> String {
+    input.nfkd().filter(|c| !is_combining_mark(*c)).collect()
+}
+
+// Case- and accent-insensitive key used for entity name lookups
+fn fold_name_for_matching(input: &str) -> String {
+    strip_diacritics(input).to_lowercase()
+}
+
 // Function to clean and extract potential base name
 fn clean_and_extract_name(input: &str) -> String {
+    let folded = fold_name_for_matching(input);
     // First pass: remove specific tags, versions, prefixes, and replace separators with space
-    let separators_removed = NAME_CLEANUP_REGEX.replace_all(input, " ");
+    let separators_removed = NAME_CLEANUP_REGEX.replace_all(&folded, " ");
     // Second pass: Trim whitespace aggressively
     let trimmed = separators_removed.trim();
     // Third pass: Try to isolate the starting name part before numbers or leftover symbols
@@ -569,34 +1115,34 @@ fn find_entity_slug_from_hint(hint: &str, maps: &DeductionMaps) -> Option<String
     if hint.is_empty() { return None; }
 
     let cleaned_hint = clean_and_extract_name(hint);
-    let lower_hint = hint.to_lowercase(); // Original lowercase for exact matches
-    println!("[find_entity_slug] Hint: '{}', Cleaned Lower: '{}'", hint, cleaned_hint);
+    let lower_hint = fold_name_for_matching(hint); // Original (accent-folded) lowercase for exact matches
+    info!("[find_entity_slug] Hint: '{}', Cleaned Lower: '{}'", hint, cleaned_hint);
 
     // --- Matching Strategies ---
 
     // Priority 1: Exact slug match (original hint, case-sensitive)
     if maps.entity_slug_to_id.contains_key(hint) {
-        println!("[find_entity_slug]   -> Match via P1: exact slug.");
+        info!("[find_entity_slug]   -> Match via P1: exact slug.");
         return Some(hint.to_string());
     }
     // Priority 2: Exact lowercase name match (original hint) -> original slug
     if let Some(slug) = maps.lowercase_entity_name_to_slug.get(&lower_hint) {
-         println!("[find_entity_slug]   -> Match via P2: exact lowercase name.");
+         info!("[find_entity_slug]   -> Match via P2: exact lowercase name.");
         return Some(slug.clone());
     }
     // Priority 3: Exact *cleaned* hint matches full lowercase name
      if let Some(slug) = maps.lowercase_entity_name_to_slug.get(&cleaned_hint) {
-          println!("[find_entity_slug]   -> Match via P3: exact cleaned hint vs full name.");
+          info!("[find_entity_slug]   -> Match via P3: exact cleaned hint vs full name.");
          return Some(slug.clone());
      }
     // Priority 4: Exact *cleaned* hint matches first two words
      if let Some(slug) = maps.lowercase_entity_first_two_words_to_slug.get(&cleaned_hint) {
-         println!("[find_entity_slug]   -> Match via P4: exact cleaned hint vs first two words.");
+         info!("[find_entity_slug]   -> Match via P4: exact cleaned hint vs first two words.");
         return Some(slug.clone());
      }
     // Priority 5: Exact *cleaned* hint matches first name
      if let Some(slug) = maps.lowercase_entity_firstname_to_slug.get(&cleaned_hint) {
-          println!("[find_entity_slug]   -> Match via P5: exact cleaned hint vs first name.");
+          info!("[find_entity_slug]   -> Match via P5: exact cleaned hint vs first name.");
          return Some(slug.clone());
      }
 
@@ -604,7 +1150,7 @@ fn find_entity_slug_from_hint(hint: &str, maps: &DeductionMaps) -> Option<String
     if let Some(first_word_cleaned) = cleaned_hint.split_whitespace().next() {
         if first_word_cleaned.len() > 1 { // Avoid matching single letters
              if let Some(slug) = maps.lowercase_entity_firstname_to_slug.get(first_word_cleaned) {
-                 println!("[find_entity_slug]   -> Match via P6: first word of cleaned hint ('{}') vs first name map.", first_word_cleaned);
+                 info!("[find_entity_slug]   -> Match via P6: first word of cleaned hint ('{}') vs first name map.", first_word_cleaned);
                  return Some(slug.clone());
             }
         }
@@ -614,21 +1160,21 @@ fn find_entity_slug_from_hint(hint: &str, maps: &DeductionMaps) -> Option<String
     for (entity_name_lower, entity_slug) in &maps.lowercase_entity_name_to_slug {
          // Ensure the known name isn't tiny compared to hint if starts_with is used
          if cleaned_hint.starts_with(entity_name_lower) && entity_name_lower.len() > 2 {
-              println!("[find_entity_slug]   -> Match via P7: cleaned hint starts with known full name ('{}').", entity_name_lower);
+              info!("[find_entity_slug]   -> Match via P7: cleaned hint starts with known full name ('{}').", entity_name_lower);
              return Some(entity_slug.clone());
          }
      }
     // *** NEW Priority 8: Cleaned hint STARTS WITH known first two words ***
     for (entity_name_first_two, entity_slug) in &maps.lowercase_entity_first_two_words_to_slug {
         if cleaned_hint.starts_with(entity_name_first_two) {
-            println!("[find_entity_slug]   -> Match via P8: cleaned hint starts with known first two words ('{}').", entity_name_first_two);
+            info!("[find_entity_slug]   -> Match via P8: cleaned hint starts with known first two words ('{}').", entity_name_first_two);
             return Some(entity_slug.clone());
         }
     }
     // *** NEW Priority 9: Cleaned hint STARTS WITH known first name ***
     for (entity_name_first, entity_slug) in &maps.lowercase_entity_firstname_to_slug {
         if cleaned_hint.starts_with(entity_name_first) && entity_name_first.len() > 1 { // Avoid matching 'a' etc.
-             println!("[find_entity_slug]   -> Match via P9: cleaned hint starts with known first name ('{}').", entity_name_first);
+             info!("[find_entity_slug]   -> Match via P9: cleaned hint starts with known first name ('{}').", entity_name_first);
             return Some(entity_slug.clone());
         }
     }
@@ -637,7 +1183,7 @@ fn find_entity_slug_from_hint(hint: &str, maps: &DeductionMaps) -> Option<String
     // Priority 10: Known full name STARTS WITH cleaned hint (less likely useful)
     // for (entity_name_lower, entity_slug) in &maps.lowercase_entity_name_to_slug {
     //     if entity_name_lower.starts_with(&cleaned_hint) && cleaned_hint.len() > 2 {
-    //          println!("[find_entity_slug]   -> Match via P10: known name starts with cleaned hint.");
+    //          info!("[find_entity_slug]   -> Match via P10: known name starts with cleaned hint.");
     //         return Some(entity_slug.clone());
     //     }
     // }
@@ -647,7 +1193,7 @@ fn find_entity_slug_from_hint(hint: &str, maps: &DeductionMaps) -> Option<String
      if cleaned_hint.len() > 3 {
          for (entity_name_lower, entity_slug) in &maps.lowercase_entity_name_to_slug {
              if entity_name_lower.contains(&cleaned_hint) {
-                  println!("[find_entity_slug]   -> Match via P11: known name contains cleaned hint.");
+                  info!("[find_entity_slug]   -> Match via P11: known name contains cleaned hint.");
                  return Some(entity_slug.clone());
              }
          }
@@ -656,7 +1202,7 @@ fn find_entity_slug_from_hint(hint: &str, maps: &DeductionMaps) -> Option<String
       if cleaned_hint.len() > 3 {
          for (entity_name_first_two, entity_slug) in &maps.lowercase_entity_first_two_words_to_slug {
              if entity_name_first_two.contains(&cleaned_hint) {
-                  println!("[find_entity_slug]   -> Match via P12: known first two words contains cleaned hint.");
+                  info!("[find_entity_slug]   -> Match via P12: known first two words contains cleaned hint.");
                  return Some(entity_slug.clone());
              }
          }
@@ -665,13 +1211,13 @@ fn find_entity_slug_from_hint(hint: &str, maps: &DeductionMaps) -> Option<String
       if cleaned_hint.len() > 2 { // Can be shorter here maybe
          for (entity_name_first, entity_slug) in &maps.lowercase_entity_firstname_to_slug {
              if entity_name_first.contains(&cleaned_hint) {
-                 println!("[find_entity_slug]   -> Match via P13: known first name contains cleaned hint.");
+                 info!("[find_entity_slug]   -> Match via P13: known first name contains cleaned hint.");
                  return Some(entity_slug.clone());
              }
          }
       }
 
-    println!("[find_entity_slug]   -> No match found.");
+    info!("[find_entity_slug]   -> No match found.");
     None // No match found
 }
 
@@ -683,7 +1229,7 @@ fn get_internal_db_slug(db_path: &PathBuf) -> Result<Option<String>, AppError> {
     let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX;
     let conn = Connection::open_with_flags(db_path, flags)
         .or_else(|e| {
-             eprintln!("Failed to open DB read-only ({}), trying read-write: {}", db_path.display(), e);
+             error!("Failed to open DB read-only ({}), trying read-write: {}", db_path.display(), e);
              // Fallback to read-write if read-only fails (e.g., during schema creation?)
              Connection::open(db_path)
         })?;
@@ -693,34 +1239,22 @@ fn get_internal_db_slug(db_path: &PathBuf) -> Result<Option<String>, AppError> {
 }
 
 fn find_asset_ini_paths(conn: &Connection, asset_id: i64, base_mods_path: &PathBuf) -> Result<Vec<PathBuf>, AppError> {
-    println!("[find_asset_ini_paths] CALLED for asset ID: {}", asset_id);
+    info!("[find_asset_ini_paths] CALLED for asset ID: {}", asset_id);
     let asset_info = get_asset_location_info(conn, asset_id)?;
 
     let relative_path_buf = PathBuf::from(&asset_info.clean_relative_path);
-    let filename_osstr = relative_path_buf.file_name().ok_or_else(|| AppError::ModOperation(format!("Could not extract filename from DB path: {}", asset_info.clean_relative_path)))?;
-    let filename_str = filename_osstr.to_string_lossy();
-    if filename_str.is_empty() {
-        println!("[find_asset_ini_paths] ERROR: Filename extracted from DB path is empty: {}", asset_info.clean_relative_path);
-        return Err(AppError::ModOperation("Current filename is empty".to_string()));
-     }
-    let disabled_filename = format!("{}{}", DISABLED_PREFIX, filename_str);
-    let relative_parent_path = relative_path_buf.parent();
-
-    let full_path_if_enabled = base_mods_path.join(&relative_path_buf);
-    let full_path_if_disabled = match relative_parent_path {
-        Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_filename),
-        _ => base_mods_path.join(&disabled_filename),
-    };
+    let (full_path_if_enabled, full_path_if_disabled) = asset_disk_paths(base_mods_path, &relative_path_buf)
+        .ok_or_else(|| AppError::ModOperation(format!("Could not extract filename from DB path: {}", asset_info.clean_relative_path)))?;
 
-    let mod_folder_path = if full_path_if_enabled.is_dir() {
-        println!("[find_asset_ini_paths] Found enabled path: {}", full_path_if_enabled.display());
-        full_path_if_enabled
-    } else if full_path_if_disabled.is_dir() {
-        println!("[find_asset_ini_paths] Found disabled path: {}", full_path_if_disabled.display());
-        full_path_if_disabled
-    } else {
-        println!("[find_asset_ini_paths] Mod folder not found for asset ID {}. Checked {} and {}", asset_id, full_path_if_enabled.display(), full_path_if_disabled.display());
-        return Ok(Vec::new()); // Return empty vec if folder not found
+    let mod_folder_path = match resolve_asset_on_disk(base_mods_path, &relative_path_buf) {
+        Some((path, is_enabled)) => {
+            info!("[find_asset_ini_paths] Found {} path: {}", if is_enabled { "enabled" } else { "disabled" }, path.display());
+            path
+        }
+        None => {
+            info!("[find_asset_ini_paths] Mod folder not found for asset ID {}. Checked {} and {}", asset_id, full_path_if_enabled.display(), full_path_if_disabled.display());
+            return Ok(Vec::new()); // Return empty vec if folder not found
+        }
     };
 
     // --- Collect all .ini files ---
@@ -734,7 +1268,7 @@ fn find_asset_ini_paths(conn: &Connection, asset_id: i64, base_mods_path: &PathB
             }
         }
     }
-    println!("[find_asset_ini_paths] Found {} INI files in {}: {:?}", ini_paths.len(), mod_folder_path.display(), ini_paths);
+    info!("[find_asset_ini_paths] Found {} INI files in {}: {:?}", ini_paths.len(), mod_folder_path.display(), ini_paths);
     Ok(ini_paths) // Return the collected paths
 }
 
@@ -746,7 +1280,7 @@ fn fetch_deduction_maps(conn: &Connection) -> SqlResult<DeductionMaps> {
     // ---
     let mut cat_stmt = conn.prepare("SELECT slug, id, name FROM categories")?;
     let cat_rows = cat_stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?)))?;
-    println!("[fetch_deduction_maps] Processing categories...");
+    info!("[fetch_deduction_maps] Processing categories...");
     let mut cat_count = 0;
     for row in cat_rows {
         if let Ok((slug, id, name)) = row {
@@ -758,7 +1292,7 @@ fn fetch_deduction_maps(conn: &Connection) -> SqlResult<DeductionMaps> {
             cat_count += 1;
         }
     }
-    println!("[fetch_deduction_maps] Processed {} categories.", cat_count);
+    info!("[fetch_deduction_maps] Processed {} categories.", cat_count);
 
 
     // --- Entity fetching (Modified to get category_id) ---
@@ -773,12 +1307,12 @@ fn fetch_deduction_maps(conn: &Connection) -> SqlResult<DeductionMaps> {
         row.get::<_, String>(0)?, row.get::<_, i64>(1)?, row.get::<_, String>(2)?, row.get::<_, i64>(3)?
     )))?;
 
-    println!("[fetch_deduction_maps] Processing entities for advanced lookup...");
+    info!("[fetch_deduction_maps] Processing entities for advanced lookup...");
     let mut entity_count = 0;
     for row in entity_rows {
         if let Ok((slug, id, name, category_id)) = row {
             entity_slug_to_id.insert(slug.clone(), id);
-            let lower_name = name.to_lowercase();
+            let lower_name = fold_name_for_matching(&name);
             lowercase_entity_name_to_slug.insert(lower_name.clone(), slug.clone());
 
             if let Some(cat_slug) = category_id_to_slug.get(&category_id) {
@@ -805,8 +1339,63 @@ fn fetch_deduction_maps(conn: &Connection) -> SqlResult<DeductionMaps> {
             entity_count += 1;
         } else if let Err(e) = row { /* log error */ }
     }
-    println!("[fetch_deduction_maps] Processed {} entities.", entity_count);
+    info!("[fetch_deduction_maps] Processed {} entities.", entity_count);
+
+    // --- Known texture/buffer hashes (only those pointing at existing entities) ---
+    let mut lowercase_hash_to_entity_slug = HashMap::new();
+    let mut hash_stmt = conn.prepare("SELECT hash, entity_slug FROM entity_hashes")?;
+    let hash_rows = hash_stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    for (hash, entity_slug) in hash_rows.flatten() {
+        if entity_slug_to_id.contains_key(&entity_slug) {
+            lowercase_hash_to_entity_slug.insert(hash.to_lowercase(), entity_slug);
+        }
+    }
+    info!("[fetch_deduction_maps] Loaded {} known hashes.", lowercase_hash_to_entity_slug.len());
+
+    // --- Fallback category: the user's choice, else "characters", else the first category ---
+    let configured_fallback = get_setting_value(conn, SETTINGS_KEY_FALLBACK_CATEGORY).ok().flatten()
+        .filter(|slug| !slug.is_empty());
+    let fallback_category_slug = match configured_fallback {
+        Some(slug) if category_slug_to_id.contains_key(&slug) => slug,
+        configured => {
+            if let Some(slug) = configured { warn!("[fetch_deduction_maps] Fallback category '{}' no longer exists, using default.", slug); }
+            if category_slug_to_id.contains_key(DEFAULT_FALLBACK_CATEGORY) {
+                DEFAULT_FALLBACK_CATEGORY.to_string()
+            } else {
+                conn.query_row("SELECT slug FROM categories ORDER BY id LIMIT 1", [], |row| row.get(0))
+                    .optional()?.unwrap_or_else(|| DEFAULT_FALLBACK_CATEGORY.to_string())
+            }
+        }
+    };
+    // Categories added outside the definitions (or by older versions) may lack their -other entity
+    if let Some(&category_id) = category_slug_to_id.get(&fallback_category_slug) {
+        let other_slug = format!("{}{}", fallback_category_slug, OTHER_ENTITY_SUFFIX);
+        if !entity_slug_to_id.contains_key(&other_slug) {
+            insert_other_entity(conn, category_id, &fallback_category_slug)?;
+            let other_id: i64 = conn.query_row("SELECT id FROM entities WHERE slug = ?1", params![other_slug], |row| row.get(0))?;
+            info!("[fetch_deduction_maps] Created missing fallback entity '{}'.", other_slug);
+            entity_slug_to_id.insert(other_slug.clone(), other_id);
+            entity_slug_to_category_slug.insert(other_slug, fallback_category_slug.clone());
+        }
+    }
+    info!("[fetch_deduction_maps] Fallback category: {}", fallback_category_slug);
+
+    // Saved patterns were validated when previewed, but skip any that no longer compile rather than failing every scan
+    let name_cleanup_patterns = get_setting_value(conn, SETTINGS_KEY_NAME_CLEANUP_PATTERNS).ok().flatten().unwrap_or_default()
+        .lines()
+        .filter_map(|line| compile_name_cleanup_patterns(&[line.to_string()])
+            .map_err(|e| warn!("[fetch_deduction_maps] Ignoring name cleanup pattern: {}", e)).ok())
+        .flatten()
+        .collect();
 
+    // Rules for entities that no longer exist, or that stopped compiling, are skipped rather than failing the scan
+    let entity_rules: Vec<(GlobMatcher, String)> = load_deduction_rules(conn)?.into_iter()
+        .filter(|rule| entity_slug_to_id.contains_key(&rule.entity_slug))
+        .filter_map(|rule| compile_deduction_rule(&rule.pattern)
+            .map_err(|e| warn!("[fetch_deduction_maps] Ignoring entity rule: {}", e)).ok()
+            .map(|matcher| (matcher, rule.entity_slug)))
+        .collect();
+    info!("[fetch_deduction_maps] Loaded {} entity rules.", entity_rules.len());
 
     Ok(DeductionMaps {
         category_slug_to_id,
@@ -816,20 +1405,235 @@ fn fetch_deduction_maps(conn: &Connection) -> SqlResult<DeductionMaps> {
         entity_slug_to_category_slug,
         lowercase_entity_firstname_to_slug,
         lowercase_entity_first_two_words_to_slug,
+        lowercase_hash_to_entity_slug,
+        fallback_category_slug,
+        name_cleanup_patterns,
+        entity_rules,
     })
 }
 
+// Compiles an entity rule. Like the scan ignore list, a pattern without a '/' matches the mod folder's own
+// name ("*Raiden*") and one with a '/' matches its path relative to the mods root; both ignore case.
+fn compile_deduction_rule(pattern: &str) -> Result<GlobMatcher, String> {
+    let pattern = pattern.trim().replace('\\', "/");
+    let pattern = pattern.trim_matches('/');
+    if pattern.is_empty() { return Err("Pattern is empty.".to_string()); }
+    let glob = if pattern.contains('/') { pattern.to_string() } else { format!("**/{}", pattern) };
+    GlobBuilder::new(&glob).case_insensitive(true).literal_separator(true).build()
+        .map(|glob| glob.compile_matcher())
+        .map_err(|e| format!("Pattern '{}' is invalid: {}", pattern, e))
+}
+
+// Index of the first rule matching `relative_path` (mods-root relative, '/' separators). DISABLED_ prefixes
+// are dropped first so a rule keeps matching whether the mod is enabled or not.
+fn match_deduction_rule(rules: &[(GlobMatcher, String)], relative_path: &str) -> Option<usize> {
+    let clean_path = relative_path.split('/')
+        .filter(|part| !part.is_empty())
+        .map(|part| part.strip_prefix(DISABLED_PREFIX).unwrap_or(part))
+        .collect::<Vec<_>>()
+        .join("/");
+    rules.iter().position(|(matcher, _)| matcher.is_match(&clean_path))
+}
+
+fn load_deduction_rules(conn: &Connection) -> SqlResult<Vec<DeductionRule>> {
+    let mut stmt = conn.prepare(
+        "SELECT r.id, r.pattern, r.entity_slug, e.name FROM deduction_rules r LEFT JOIN entities e ON e.slug = r.entity_slug ORDER BY r.id",
+    )?;
+    let rows = stmt.query_map([], |row| Ok(DeductionRule {
+        id: row.get(0)?,
+        pattern: row.get(1)?,
+        entity_slug: row.get(2)?,
+        entity_name: row.get(3)?,
+    }))?;
+    rows.collect()
+}
+
+// Compiles user cleanup patterns, skipping blank lines. Errors name the offending pattern.
+fn compile_name_cleanup_patterns(patterns: &[String]) -> Result<Vec<Regex>, String> {
+    patterns.iter()
+        .map(|p| p.trim())
+        .enumerate()
+        .filter(|(_, p)| !p.is_empty())
+        .map(|(i, p)| Regex::new(p).map_err(|e| format!("Pattern {} ('{}') is invalid: {}", i + 1, p, e)))
+        .collect()
+}
+
+// Trims a declared version and drops a leading "v" ("v1.2" -> "1.2"); None when nothing is left
+fn normalize_mod_version(raw: &str) -> Option<String> {
+    let trimmed = raw.trim().trim_matches('"').trim();
+    let version = trimmed.strip_prefix(['v', 'V']).filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit())).unwrap_or(trimmed);
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+// Last "_v1.2"-style suffix in a folder or archive name
+fn version_from_name(name: &str) -> Option<String> {
+    NAME_VERSION_REGEX.captures_iter(name).last().map(|caps| caps[1].to_string())
+}
+
+// Strips user patterns, then the built-in version/DISABLED markers, from a mod name
+fn clean_mod_name(name: &str, extra_patterns: &[Regex]) -> String {
+    let stripped = extra_patterns.iter()
+        .fold(name.to_string(), |current, re| re.replace_all(&current, "").into_owned());
+    MOD_NAME_CLEANUP_REGEX.replace_all(&stripped, "").trim().to_string()
+}
+
+// Line-based scan (rust-ini rejects the `if`/`endif` command lists many mod INIs contain)
+fn extract_ini_hash_hints(ini_content: &str) -> IniHashHints {
+    let mut hints = IniHashHints::default();
+    let mut in_override_section = false;
+    for line in ini_content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') { continue; }
+
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let lower_section = section.to_lowercase();
+            in_override_section = lower_section.starts_with("textureoverride") || lower_section.starts_with("shaderoverride");
+            if let Some(name) = section.get("TextureOverride".len()..).filter(|_| lower_section.starts_with("textureoverride")) {
+                if !name.is_empty() { hints.override_names.push(name.to_string()); }
+            }
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=') {
+            let (key, value) = (key.trim(), value.trim());
+            if key.eq_ignore_ascii_case("namespace") && hints.namespace.is_none() && !value.is_empty() {
+                hints.namespace = Some(value.to_string());
+            } else if in_override_section && key.eq_ignore_ascii_case("hash") && !value.is_empty() {
+                hints.hashes.push(value.to_lowercase());
+            }
+        }
+    }
+    hints
+}
+
+// Same line-based approach as extract_ini_hash_hints. Handles both the plain `$swapvar` style and the
+// `$active`-guarded one; keys without any variable assignment (e.g. reload keys) are skipped.
+fn parse_ini_variant_bindings(ini_content: &str, ini_file: &str) -> Vec<VariantKeyBinding> {
+    let mut bindings = Vec::new();
+    let mut current: Option<VariantKeyBinding> = None;
+    for line in ini_content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') { continue; }
+
+        if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            bindings.extend(current.take());
+            if section.to_lowercase().starts_with("key") {
+                current = Some(VariantKeyBinding {
+                    ini_file: ini_file.to_string(), section: section.trim().to_string(), key: None, back: None,
+                    kind: "cycle".to_string(), variable: String::new(), states: 0, requires_active: false,
+                });
+            }
+            continue;
+        }
+
+        let Some(binding) = current.as_mut() else { continue };
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let (key, value) = (key.trim(), value.trim());
+        match key.to_lowercase().as_str() {
+            "key" if !value.is_empty() => binding.key = Some(value.to_string()),
+            "back" if !value.is_empty() => binding.back = Some(value.to_string()),
+            "type" if !value.is_empty() => binding.kind = value.to_lowercase(),
+            "condition" => binding.requires_active |= value.to_lowercase().contains("$active"),
+            name if name.starts_with('$') => {
+                let values = value.split(',').filter(|v| !v.trim().is_empty()).count();
+                if values > binding.states {
+                    binding.variable = key.to_string();
+                    binding.states = values;
+                }
+            }
+            _ => {}
+        }
+    }
+    bindings.extend(current.take());
+
+    bindings.into_iter()
+        .filter(|b| b.key.is_some() && b.states > 0)
+        .map(|mut b| {
+            // A toggle/hold key lists only the "on" value; the variable's initial value is the other state
+            if b.kind != "cycle" { b.states = b.states.max(2); }
+            b
+        })
+        .collect()
+}
+
+// Tries known hashes first, then namespace segments, then TextureOverride names ("RaidenShogunBody" -> "Raiden Shogun Body")
+fn find_entity_slug_from_ini_hash_hints(hints: &IniHashHints, maps: &DeductionMaps) -> Option<String> {
+    if let Some((hash, slug)) = hints.hashes.iter().find_map(|h| maps.lowercase_hash_to_entity_slug.get(h).map(|slug| (h, slug))) {
+        info!("[Deduce V2]   -> Found entity via known hash: '{}' -> {}", hash, slug);
+        return Some(slug.clone());
+    }
+    if let Some(namespace) = &hints.namespace {
+        for segment in namespace.rsplit(['\\', '/', '.']).filter(|s| !s.is_empty()) {
+            let spaced = CAMEL_CASE_BOUNDARY_REGEX.replace_all(segment, "$1 $2");
+            if let Some(slug) = find_entity_slug_from_hint(&spaced, maps) {
+                info!("[Deduce V2]   -> Found entity via INI namespace: '{}' -> {}", namespace, slug);
+                return Some(slug);
+            }
+        }
+    }
+    for name in &hints.override_names {
+        let spaced = CAMEL_CASE_BOUNDARY_REGEX.replace_all(name, "$1 $2");
+        if let Some(slug) = find_entity_slug_from_hint(&spaced, maps) {
+            info!("[Deduce V2]   -> Found entity via TextureOverride section: '{}' -> {}", name, slug);
+            return Some(slug);
+        }
+    }
+    None
+}
+
+// Splits a descriptive folder/archive name into lowercase word tokens:
+// "RaidenShogunBikini_v2_byAuthor" -> ["raiden", "shogun", "bikini"]. Version and number tokens are dropped,
+// and everything from a "by" token on is treated as the author credit.
+fn tokenize_mod_name(name: &str) -> Vec<String> {
+    let stripped = strip_diacritics(name);
+    let spaced = CAMEL_CASE_BOUNDARY_REGEX.replace_all(&stripped, "$1 $2");
+    spaced.split(|c: char| !c.is_alphanumeric())
+        .map(str::to_lowercase)
+        .take_while(|token| token != "by")
+        .filter(|token| {
+            let digits = token.strip_prefix('v').unwrap_or(token);
+            !digits.is_empty() && !digits.chars().all(|c| c.is_ascii_digit())
+        })
+        .collect()
+}
+
+// Matches runs of name tokens (longest first) against entity full names, first two words, first names
+// and space-less names ("raidenshogun"). Returns the entity slug and the token run that matched.
+fn find_entity_slug_from_name_tokens(name: &str, maps: &DeductionMaps) -> Option<(String, String)> {
+    let tokens = tokenize_mod_name(name);
+    if tokens.is_empty() { return None; }
+    let compact_names: HashMap<String, &String> = maps.lowercase_entity_name_to_slug.iter()
+        .map(|(entity_name, slug)| (entity_name.chars().filter(|c| c.is_alphanumeric()).collect(), slug))
+        .collect();
+
+    for window in (1..=tokens.len().min(3)).rev() {
+        for run in tokens.windows(window) {
+            let phrase = run.join(" ");
+            let compact = run.concat();
+            let slug = maps.lowercase_entity_name_to_slug.get(&phrase)
+                .or_else(|| if window >= 2 { maps.lowercase_entity_first_two_words_to_slug.get(&phrase) } else { None })
+                .or_else(|| if compact.len() > 3 { compact_names.get(&compact).copied() } else { None })
+                .or_else(|| if window == 1 && phrase.len() > 2 { maps.lowercase_entity_firstname_to_slug.get(&phrase) } else { None });
+            if let Some(slug) = slug {
+                info!("[find_entity_slug_from_name_tokens] '{}' -> {} (matched token '{}')", name, slug, phrase);
+                return Some((slug.clone(), phrase));
+            }
+        }
+    }
+    None
+}
+
 fn deduce_mod_info_v2(
     mod_folder_path: &PathBuf,
     base_mods_path: &PathBuf,
     maps: &DeductionMaps,
 ) -> Option<DeducedInfo> {
-    println!("[Deduce V2 - Entity First] Input Path: {}", mod_folder_path.display());
+    info!("[Deduce V2 - Entity First] Input Path: {}", mod_folder_path.display());
 
     let mod_folder_name = match mod_folder_path.file_name() {
          Some(name) => name.to_string_lossy().to_string(),
          None => {
-             eprintln!("[Deduce V2] Error: Cannot get folder name from path: {}", mod_folder_path.display());
+             error!("[Deduce V2] Cannot get folder name from path: {}", mod_folder_path.display());
              return None;
          }
      };
@@ -840,32 +1644,45 @@ fn deduce_mod_info_v2(
         mod_name: mod_folder_name.clone(),
         mod_type_tag: None, author: None, description: None,
         image_filename: find_preview_image(mod_folder_path),
+        version: None,
     };
 
     let mut found_entity_slug: Option<String> = None;
     let mut ini_target_hint: Option<String> = None;
     let mut ini_type_hint: Option<String> = None;
+    let mut ini_hash_hints: Option<IniHashHints> = None;
+
+    // --- 0. User Entity Rules (always win) ---
+    if !maps.entity_rules.is_empty() {
+        let relative_path = mod_folder_path.strip_prefix(base_mods_path)
+            .map(|p| p.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_else(|_| mod_folder_name.clone());
+        if let Some(index) = match_deduction_rule(&maps.entity_rules, &relative_path) {
+            found_entity_slug = Some(maps.entity_rules[index].1.clone());
+            info!("[Deduce V2]   -> Found entity via rule #{} for '{}' -> {}", index + 1, relative_path, maps.entity_rules[index].1);
+        }
+    }
 
     // --- 1. Check Parent Folders for ENTITY Match ---
-    println!("[Deduce V2] Checking parent folders for ENTITY match...");
+    info!("[Deduce V2] Checking parent folders for ENTITY match...");
     // ... (existing parent folder checking logic using find_entity_slug_from_hint) ...
-    let mut current_path = mod_folder_path.parent();
+    let mut current_path = if found_entity_slug.is_none() { mod_folder_path.parent() } else { None };
     while let Some(path) = current_path {
         if path == *base_mods_path || path.parent() == Some(base_mods_path) { break; }
         if let Some(folder_name) = path.file_name().and_then(|n| n.to_str()) {
              if let Some(slug) = find_entity_slug_from_hint(folder_name, maps) {
                  found_entity_slug = Some(slug);
-                 println!("[Deduce V2]   -> Found entity via parent folder: '{}' -> {}", folder_name, found_entity_slug.as_ref().unwrap());
+                 info!("[Deduce V2]   -> Found entity via parent folder: '{}' -> {}", folder_name, found_entity_slug.as_ref().unwrap());
                  break;
              }
         }
         current_path = path.parent();
     }
-    println!("[Deduce V2] Parent folder check done. Found Entity Slug: {:?}", found_entity_slug);
+    info!("[Deduce V2] Parent folder check done. Found Entity Slug: {:?}", found_entity_slug);
 
 
     // --- 2. Parse INI File (if entity not found yet or for metadata) ---
-    println!("[Deduce V2] Checking INI file...");
+    info!("[Deduce V2] Checking INI file...");
     let ini_path_option = WalkDir::new(mod_folder_path)
         .max_depth(1).min_depth(1).into_iter()
         .filter_map(|e| e.ok())
@@ -873,8 +1690,9 @@ fn deduce_mod_info_v2(
         .map(|e| e.into_path());
 
     if let Some(ini_path) = ini_path_option {
-        println!("[Deduce V2] Found INI: {}", ini_path.display());
+        info!("[Deduce V2] Found INI: {}", ini_path.display());
         if let Ok(ini_content) = fs::read_to_string(&ini_path) {
+            ini_hash_hints = Some(extract_ini_hash_hints(&ini_content));
             if let Ok(ini) = Ini::load_from_str(&ini_content) {
                  for section_name in ["Mod", "Settings", "Info", "General"] {
                     if let Some(section) = ini.section(Some(section_name)) {
@@ -882,36 +1700,45 @@ fn deduce_mod_info_v2(
                         if let Some(name) = section.get("Name").or_else(|| section.get("ModName")) { info.mod_name = name.trim().to_string(); }
                         if let Some(author) = section.get("Author") { info.author = Some(author.trim().to_string()); }
                         if let Some(desc) = section.get("Description") { info.description = Some(desc.trim().to_string()); }
+                        if let Some(version) = section.get("Version").and_then(normalize_mod_version) { info.version = Some(version); }
                         // Get hints (even if entity found, these might be useful someday)
                         if let Some(target) = section.get("Target").or_else(|| section.get("Entity")).or_else(|| section.get("Character")) { ini_target_hint = Some(target.trim().to_string()); }
                         if let Some(typ) = section.get("Type").or_else(|| section.get("Category")) { info.mod_type_tag = Some(typ.trim().to_string()); ini_type_hint = info.mod_type_tag.clone(); } // Store type hint
                     }
                 }
-                println!("[Deduce V2] INI parsed. Name='{}', Author='{:?}', TargetHint='{:?}', TypeHint='{:?}'", info.mod_name, info.author, ini_target_hint, ini_type_hint);
+                info!("[Deduce V2] INI parsed. Name='{}', Author='{:?}', TargetHint='{:?}', TypeHint='{:?}'", info.mod_name, info.author, ini_target_hint, ini_type_hint);
             } else {
-                eprintln!("[Deduce V2] Warning: Failed to parse INI content from {}", ini_path.display());
+                warn!("[Deduce V2] Failed to parse INI content from {}", ini_path.display());
             }
         } else {
-             eprintln!("[Deduce V2] Warning: Failed to read INI file content from {}", ini_path.display());
+             warn!("[Deduce V2] Failed to read INI file content from {}", ini_path.display());
         }
     } else {
-        println!("[Deduce V2] No INI file found in mod folder.");
+        info!("[Deduce V2] No INI file found in mod folder.");
     }
 
     // --- 3. Try Matching INI Target Hint (if entity still not found) ---
     if found_entity_slug.is_none() {
         if let Some(target_hint) = &ini_target_hint {
-            println!("[Deduce V2] Trying INI target hint matching...");
+            info!("[Deduce V2] Trying INI target hint matching...");
             if let Some(slug) = find_entity_slug_from_hint(target_hint, maps) {
                  found_entity_slug = Some(slug);
-                 println!("[Deduce V2]   -> Found entity via INI target hint: '{}' -> {}", target_hint, found_entity_slug.as_ref().unwrap());
+                 info!("[Deduce V2]   -> Found entity via INI target hint: '{}' -> {}", target_hint, found_entity_slug.as_ref().unwrap());
             }
         }
     }
 
+    // --- 3b. Try Known Hashes / Namespace / TextureOverride Names (INIs without metadata sections) ---
+    if found_entity_slug.is_none() {
+        if let Some(hints) = &ini_hash_hints {
+            info!("[Deduce V2] Trying INI hash/namespace matching ({} hashes, namespace {:?})...", hints.hashes.len(), hints.namespace);
+            found_entity_slug = find_entity_slug_from_ini_hash_hints(hints, maps);
+        }
+    }
+
     // --- 4. Try Matching Internal Filenames (NEW STEP) ---
     if found_entity_slug.is_none() {
-        println!("[Deduce V2] Trying internal filename matching...");
+        info!("[Deduce V2] Trying internal filename matching...");
         let mut file_match_found = false;
         // Iterate through files directly inside the mod folder (depth 1)
         for entry_result in WalkDir::new(mod_folder_path).min_depth(1).max_depth(1).into_iter() {
@@ -924,7 +1751,7 @@ fn deduce_mod_info_v2(
                                  // Use the helper to check if the stem matches an entity
                                  if let Some(slug) = find_entity_slug_from_hint(stem, maps) {
                                      found_entity_slug = Some(slug);
-                                     println!("[Deduce V2]   -> Found entity via internal filename stem: '{}' -> {}", stem, found_entity_slug.as_ref().unwrap());
+                                     info!("[Deduce V2]   -> Found entity via internal filename stem: '{}' -> {}", stem, found_entity_slug.as_ref().unwrap());
                                      file_match_found = true;
                                      break; // Found a match from a file, stop searching files
                                  }
@@ -933,61 +1760,69 @@ fn deduce_mod_info_v2(
                      }
                  },
                  Err(e) => {
-                     eprintln!("[Deduce V2] Warning: Error accessing entry during internal file scan: {}", e);
+                     warn!("[Deduce V2] Error accessing entry during internal file scan: {}", e);
                      // Continue scanning other files if possible
                  }
              }
         }
         if !file_match_found {
-            println!("[Deduce V2]   -> No entity match found from internal filenames.");
+            info!("[Deduce V2]   -> No entity match found from internal filenames.");
         }
     }
 
 
     // --- 5. Try Matching Mod Folder Name (Lower Priority) ---
      if found_entity_slug.is_none() {
-         println!("[Deduce V2] Trying mod folder name matching: '{}'", mod_folder_name);
+         info!("[Deduce V2] Trying mod folder name matching: '{}'", mod_folder_name);
          if let Some(slug) = find_entity_slug_from_hint(&mod_folder_name, maps) {
               found_entity_slug = Some(slug);
-              println!("[Deduce V2]   -> Found entity via mod folder name: '{}' -> {}", mod_folder_name, found_entity_slug.as_ref().unwrap());
+              info!("[Deduce V2]   -> Found entity via mod folder name: '{}' -> {}", mod_folder_name, found_entity_slug.as_ref().unwrap());
          }
      }
 
+    // --- 5b. Try Tokens of a Descriptive Folder Name ("RaidenShogunBikini_v2_byAuthor") ---
+    if found_entity_slug.is_none() {
+        if let Some((slug, token)) = find_entity_slug_from_name_tokens(&mod_folder_name, maps) {
+            info!("[Deduce V2]   -> Found entity via folder name token '{}' -> {}", token, slug);
+            found_entity_slug = Some(slug);
+        }
+    }
+
     // --- 6. Final Assignment Logic ---
-    println!("[Deduce V2] Final Assignment Logic. Found Entity Slug So Far: {:?}", found_entity_slug);
+    info!("[Deduce V2] Final Assignment Logic. Found Entity Slug So Far: {:?}", found_entity_slug);
     if let Some(ref entity_slug) = found_entity_slug {
         // ---- ENTITY FOUND ----
         // Assign the specific entity slug directly.
         // The category is implicitly determined by this entity's relationship in the DB.
         info.entity_slug = entity_slug.clone();
-        println!("[Deduce V2] SUCCESS: Assigning specific entity slug: {}", info.entity_slug);
+        info!("[Deduce V2] SUCCESS: Assigning specific entity slug: {}", info.entity_slug);
 
     } else {
         // ---- ENTITY NOT FOUND ----
         // Fallback: Try to find the most likely CATEGORY to place this mod under,
         //           using the "<category-slug>-other" pattern.
-        println!("[Deduce V2] Entity not found. Trying CATEGORY fallback deduction...");
+        info!("[Deduce V2] Entity not found. Trying CATEGORY fallback deduction...");
         let mut fallback_category_slug: Option<String> = None;
 
         // Fallback Priority 1: Parent folder names matching a CATEGORY name/slug
-        println!("[Deduce V2]   Fallback Prio 1: Checking parent folders for CATEGORY match...");
+        info!("[Deduce V2]   Fallback Prio 1: Checking parent folders for CATEGORY match...");
         let mut current_path_cat = mod_folder_path.parent();
         while let Some(path) = current_path_cat {
              // Stop if we reach the base mods path or its immediate parent
              if path == *base_mods_path || path.parent() == Some(base_mods_path) { break; }
              if let Some(folder_name) = path.file_name().and_then(|n| n.to_str()) {
                  let lower_folder_name = folder_name.to_lowercase();
-                 println!("[Deduce V2]     Checking parent folder for category: {}", folder_name);
+                 info!("[Deduce V2]     Checking parent folder for category: {}", folder_name);
                   // Check Category Slug (exact match)
                   if maps.category_slug_to_id.contains_key(folder_name) {
                       fallback_category_slug = Some(folder_name.to_string());
-                      println!("[Deduce V2]       -> Found category via parent exact slug: {}", folder_name);
+                      info!("[Deduce V2]       -> Found category via parent exact slug: {}", folder_name);
                       break; // Found best match, stop walking up
                   }
                   // Check Lowercase Category Name
                   if let Some(slug) = maps.lowercase_category_name_to_slug.get(&lower_folder_name) {
                       fallback_category_slug = Some(slug.clone());
-                      println!("[Deduce V2]       -> Found category via parent lowercase name: {} -> {}", lower_folder_name, slug);
+                      info!("[Deduce V2]       -> Found category via parent lowercase name: {} -> {}", lower_folder_name, slug);
                       break; // Found best match, stop walking up
                   }
              }
@@ -996,26 +1831,26 @@ fn deduce_mod_info_v2(
 
         // Fallback Priority 2: INI Type Hint matching a CATEGORY name/slug
         if fallback_category_slug.is_none() {
-            println!("[Deduce V2]   Fallback Prio 2: Checking INI type hint for CATEGORY match...");
+            info!("[Deduce V2]   Fallback Prio 2: Checking INI type hint for CATEGORY match...");
             if let Some(type_hint) = &ini_type_hint {
                 let lower_type_hint = type_hint.to_lowercase();
-                println!("[Deduce V2]     Trying INI type hint: '{}'", type_hint);
+                info!("[Deduce V2]     Trying INI type hint: '{}'", type_hint);
                 // Prio 1: Exact slug
                 if maps.category_slug_to_id.contains_key(type_hint) {
                     fallback_category_slug = Some(type_hint.clone());
-                    println!("[Deduce V2]       -> Matched category via INI exact slug: {}", type_hint);
+                    info!("[Deduce V2]       -> Matched category via INI exact slug: {}", type_hint);
                 }
                 // Prio 2: Exact lowercase name -> original slug
                 else if let Some(slug) = maps.lowercase_category_name_to_slug.get(&lower_type_hint) {
                     fallback_category_slug = Some(slug.clone());
-                    println!("[Deduce V2]       -> Matched category via INI exact lowercase name: {} -> {}", lower_type_hint, slug);
+                    info!("[Deduce V2]       -> Matched category via INI exact lowercase name: {} -> {}", lower_type_hint, slug);
                 }
                  // Prio 3: Known name starts with hint (optional)
                  else {
                      for (cat_name_lower, cat_slug) in &maps.lowercase_category_name_to_slug {
                          if cat_name_lower.starts_with(&lower_type_hint) {
                              fallback_category_slug = Some(cat_slug.clone());
-                             println!("[Deduce V2]       -> Matched category via INI name prefix: {} -> {}", cat_name_lower, cat_slug);
+                             info!("[Deduce V2]       -> Matched category via INI name prefix: {} -> {}", cat_name_lower, cat_slug);
                              break;
                          }
                      }
@@ -1025,40 +1860,40 @@ fn deduce_mod_info_v2(
                      for (cat_name_lower, cat_slug) in &maps.lowercase_category_name_to_slug {
                          if lower_type_hint.len() > 2 && cat_name_lower.contains(&lower_type_hint) {
                              fallback_category_slug = Some(cat_slug.clone());
-                             println!("[Deduce V2]       -> Matched category via INI name contains: {} -> {}", cat_name_lower, cat_slug);
+                             info!("[Deduce V2]       -> Matched category via INI name contains: {} -> {}", cat_name_lower, cat_slug);
                              break;
                          }
                      }
                  }
             } else {
-                 println!("[Deduce V2]     No INI type hint available.");
+                 info!("[Deduce V2]     No INI type hint available.");
             }
         }
 
         // Fallback Priority 3: Top-level folder name (relative to base) matching a CATEGORY name/slug
         if fallback_category_slug.is_none() {
-             println!("[Deduce V2]   Fallback Prio 3: Trying top-level folder name for CATEGORY match...");
+             info!("[Deduce V2]   Fallback Prio 3: Trying top-level folder name for CATEGORY match...");
              let relative_path_result = mod_folder_path.strip_prefix(base_mods_path);
              if let Ok(relative_path) = relative_path_result {
                  if let Some(top_level_component) = relative_path.components().next() {
                      if let Some(top_folder_name) = top_level_component.as_os_str().to_str() {
                          let lower_top_folder = top_folder_name.to_lowercase();
-                         println!("[Deduce V2]     Top-level folder: {}", top_folder_name);
+                         info!("[Deduce V2]     Top-level folder: {}", top_folder_name);
                           // Fuzzy match logic (simplified: check starts_with or contains)
                          // Prioritize exact slug/name match if possible within top-level check
                          if maps.category_slug_to_id.contains_key(top_folder_name) {
                               fallback_category_slug = Some(top_folder_name.to_string());
-                              println!("[Deduce V2]       -> Matched category via top-level exact slug: {}", top_folder_name);
+                              info!("[Deduce V2]       -> Matched category via top-level exact slug: {}", top_folder_name);
                          } else if let Some(slug) = maps.lowercase_category_name_to_slug.get(&lower_top_folder) {
                               fallback_category_slug = Some(slug.clone());
-                              println!("[Deduce V2]       -> Matched category via top-level exact name: {} -> {}", lower_top_folder, slug);
+                              info!("[Deduce V2]       -> Matched category via top-level exact name: {} -> {}", lower_top_folder, slug);
                          } else {
                              // Then try fuzzy matching
                              let mut fuzzy_match_found = false;
                              for (cat_slug, _) in &maps.category_slug_to_id {
                                  if cat_slug.starts_with(&lower_top_folder) || lower_top_folder.starts_with(cat_slug) {
                                      fallback_category_slug = Some(cat_slug.clone());
-                                     println!("[Deduce V2]       -> Matched category via top-level fuzzy slug prefix: {}", cat_slug);
+                                     info!("[Deduce V2]       -> Matched category via top-level fuzzy slug prefix: {}", cat_slug);
                                      fuzzy_match_found = true;
                                      break;
                                  }
@@ -1067,7 +1902,7 @@ fn deduce_mod_info_v2(
                                  for (cat_name_lower, cat_slug) in &maps.lowercase_category_name_to_slug {
                                      if cat_name_lower.starts_with(&lower_top_folder) || lower_top_folder.starts_with(cat_name_lower) {
                                          fallback_category_slug = Some(cat_slug.clone());
-                                         println!("[Deduce V2]       -> Matched category via top-level fuzzy name prefix: {} -> {}", cat_name_lower, cat_slug);
+                                         info!("[Deduce V2]       -> Matched category via top-level fuzzy name prefix: {} -> {}", cat_name_lower, cat_slug);
                                          fuzzy_match_found = true;
                                          break;
                                      }
@@ -1078,42 +1913,45 @@ fn deduce_mod_info_v2(
                                  for (cat_name_lower, cat_slug) in &maps.lowercase_category_name_to_slug {
                                      if lower_top_folder.len() > 2 && cat_name_lower.contains(&lower_top_folder) {
                                           fallback_category_slug = Some(cat_slug.clone());
-                                          println!("[Deduce V2]       -> Matched category via top-level fuzzy name contains: {} -> {}", cat_name_lower, cat_slug);
+                                          info!("[Deduce V2]       -> Matched category via top-level fuzzy name contains: {} -> {}", cat_name_lower, cat_slug);
                                           break;
                                      }
                                  }
                              }
                          }
-                     } else { println!("[Deduce V2]     Could not convert top-level OsStr to str."); }
-                 } else { println!("[Deduce V2]     Could not get top-level component."); }
-             } else { println!("[Deduce V2]     Could not strip base path prefix."); }
+                     } else { info!("[Deduce V2]     Could not convert top-level OsStr to str."); }
+                 } else { info!("[Deduce V2]     Could not get top-level component."); }
+             } else { info!("[Deduce V2]     Could not strip base path prefix."); }
         }
 
         // --- Assign final fallback slug ---
         if let Some(cat_slug) = fallback_category_slug {
              // Found a category hint, assign to its -other group
              info.entity_slug = format!("{}{}", cat_slug, OTHER_ENTITY_SUFFIX);
-             println!("[Deduce V2] Assigning fallback category slug: {}", info.entity_slug);
+             info!("[Deduce V2] Assigning fallback category slug: {}", info.entity_slug);
         } else {
-             // Absolute last resort: Use a default category's -other group
-             let hardcoded_fallback_category = "characters"; // Or use "unknown" if you have an unknown-other slug
-             info.entity_slug = format!("{}{}", hardcoded_fallback_category, OTHER_ENTITY_SUFFIX);
-             println!("[Deduce V2] No category hint found, assigning hardcoded fallback: {}", info.entity_slug);
+             // Absolute last resort: the configured fallback category's -other group
+             info.entity_slug = format!("{}{}", maps.fallback_category_slug, OTHER_ENTITY_SUFFIX);
+             info!("[Deduce V2] No category hint found, assigning fallback: {}", info.entity_slug);
         }
     } // --- End of else block (Entity Not Found) ---
 
     // --- 7. Clean up Mod Name ---
+    // The version suffix is about to be stripped from the name; keep it if the INI declared none
+    if info.version.is_none() {
+        info.version = version_from_name(&info.mod_name).or_else(|| version_from_name(&mod_folder_name));
+    }
     let original_mod_name = info.mod_name.clone();
-    info.mod_name = MOD_NAME_CLEANUP_REGEX.replace_all(&info.mod_name, "").trim().to_string();
+    info.mod_name = clean_mod_name(&info.mod_name, &maps.name_cleanup_patterns);
     // If cleaning results in empty, use original folder name as fallback
     if info.mod_name.is_empty() {
          info.mod_name = mod_folder_name;
-         println!("[Deduce V2] Warning: Name cleanup resulted in empty string, using folder name '{}'", info.mod_name);
+         warn!("[Deduce V2] Name cleanup resulted in empty string, using folder name '{}'", info.mod_name);
     } else if info.mod_name != original_mod_name {
-        println!("[Deduce V2] Cleaned mod name: '{}' -> '{}'", original_mod_name, info.mod_name);
+        info!("[Deduce V2] Cleaned mod name: '{}' -> '{}'", original_mod_name, info.mod_name);
     }
 
-    println!("[Deduce V2 - Entity First] Final Deduced Info: {:?}", info);
+    info!("[Deduce V2 - Entity First] Final Deduced Info: {:?}", info);
     Some(info)
 }
 
@@ -1141,6 +1979,33 @@ fn get_asset_location_info(conn: &Connection, asset_id: i64) -> Result<AssetLoca
     })
 }
 
+// Decides where the scan stops descending. A folder is a mod root when it has a (non-excluded) INI, unless it
+// is a grouping folder: one with no mod resources (.dds/.buf/.ib) of its own whose direct subfolders hold INIs.
+// That covers e.g. "Character/stray.ini" + "Character/ModA/mod.ini", where ModA is the real mod. A folder whose
+// INI sits next to resources is a mod even if subfolders carry INIs (variants/toggles belong to it).
+fn is_mod_root_folder(dir_path: &Path) -> bool {
+    let dir_path = dir_path.to_path_buf();
+    if !has_ini_file(&dir_path) { return false; }
+
+    let Ok(read_dir) = fs::read_dir(&dir_path) else { return true; };
+    let (mut has_own_resources, mut has_mod_subfolder) = (false, false);
+    for entry in read_dir.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            has_mod_subfolder = has_mod_subfolder || has_ini_file(&path);
+        } else if path.extension().and_then(OsStr::to_str)
+            .is_some_and(|ext| MOD_ASSET_EXTENSIONS.contains(&ext.to_lowercase().as_str())) {
+            has_own_resources = true;
+            break;
+        }
+    }
+    if !has_own_resources && has_mod_subfolder {
+        info!("[is_mod_root_folder] '{}' only groups other mods; descending into it.", dir_path.display());
+        return false;
+    }
+    true
+}
+
 fn has_ini_file(dir_path: &PathBuf) -> bool {
     if !dir_path.is_dir() { return false; }
 
@@ -1181,7 +2046,7 @@ fn has_ini_file(dir_path: &PathBuf) -> bool {
             },
             Err(e) => {
                 // Log error accessing entry but continue scan if possible
-                eprintln!("[has_ini_file] Error accessing entry in {}: {}", dir_path.display(), e);
+                error!("[has_ini_file] Error accessing entry in {}: {}", dir_path.display(), e);
             }
         }
     }
@@ -1215,11 +2080,12 @@ fn get_app_config_path(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
 fn read_app_config(app_handle: &AppHandle) -> Result<AppConfig, AppError> {
     let config_path = get_app_config_path(app_handle)?;
     if !config_path.exists() {
-        println!("App config not found, creating default.");
+        info!("App config not found, creating default.");
         // Default has last and requested as the same initially
         let default_config = AppConfig {
             last_active_game: DEFAULT_GAME_SLUG.to_string(),
             requested_active_game: DEFAULT_GAME_SLUG.to_string(),
+            definition_sets: HashMap::new(),
         };
         write_app_config(app_handle, &default_config)?;
         return Ok(default_config);
@@ -1253,249 +2119,1477 @@ fn get_current_asset_enabled_state(conn: &Connection, asset_id: i64, base_mods_p
     Ok(full_path_if_enabled.is_dir()) // Return true if the 'enabled' path exists
 }
 
+// --- Helpers to locate a mod folder on disk (enabled or DISABLED_ prefixed) ---
+// Returns (path if enabled, path if disabled) for a clean relative path from the DB
+fn asset_disk_paths(base_mods_path: &Path, clean_relative_path: &Path) -> Option<(PathBuf, PathBuf)> {
+    let filename_str = clean_relative_path.file_name()?.to_string_lossy();
+    if filename_str.is_empty() { return None; }
+    let disabled_filename = format!("{}{}", DISABLED_PREFIX, filename_str);
+
+    let full_path_if_enabled = base_mods_path.join(clean_relative_path);
+    let full_path_if_disabled = match clean_relative_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => base_mods_path.join(parent).join(&disabled_filename),
+        _ => base_mods_path.join(&disabled_filename), // No parent or parent is root
+    };
+    Some((full_path_if_enabled, full_path_if_disabled))
+}
+
+// Returns the existing folder path and whether it is enabled, or None if neither variant exists
+fn resolve_asset_on_disk(base_mods_path: &Path, clean_relative_path: &Path) -> Option<(PathBuf, bool)> {
+    let (full_path_if_enabled, full_path_if_disabled) = asset_disk_paths(base_mods_path, clean_relative_path)?;
+    if full_path_if_enabled.is_dir() {
+        Some((full_path_if_enabled, true))
+    } else if full_path_if_disabled.is_dir() {
+        Some((full_path_if_disabled, false))
+    } else {
+        None
+    }
+}
+
+// Top-level mod INIs of a folder as (path, has DISABLED_ prefix). Helper INIs like orfix.ini are skipped.
+fn mod_ini_files(mod_folder: &Path) -> Vec<(PathBuf, bool)> {
+    let disabled_prefix_lower = DISABLED_PREFIX.to_lowercase();
+    WalkDir::new(mod_folder).min_depth(1).max_depth(1).into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ini")))
+        .filter_map(|e| {
+            let file_name_lower = e.file_name().to_string_lossy().to_lowercase();
+            let is_disabled = file_name_lower.starts_with(&disabled_prefix_lower);
+            let base_name = if is_disabled { &file_name_lower[disabled_prefix_lower.len()..] } else { file_name_lower.as_str() };
+            (!EXCLUDED_INI_FILENAMES.contains(base_name)).then(|| (e.into_path(), is_disabled))
+        })
+        .collect()
+}
+
+// 3DMigoto convention: the folder keeps its name but every mod INI carries the DISABLED_ prefix
+fn is_ini_disabled_folder(mod_folder: &Path) -> bool {
+    let inis = mod_ini_files(mod_folder);
+    !inis.is_empty() && inis.iter().all(|(_, is_disabled)| *is_disabled)
+}
+
+// Like resolve_asset_on_disk, but the state also accounts for INI-disabled folders.
+// Use this for state detection; use resolve_asset_on_disk when picking folder paths.
+fn asset_state_on_disk(base_mods_path: &Path, clean_relative_path: &Path) -> Option<(PathBuf, bool)> {
+    resolve_asset_on_disk(base_mods_path, clean_relative_path)
+        .map(|(path, folder_enabled)| {
+            let is_enabled = folder_enabled && !is_ini_disabled_folder(&path);
+            (path, is_enabled)
+        })
+}
+
+const FILE_LOCKED_HINT: &str = "If the game is running, it may be locking the mod's files; close it (or any program using the folder) and try again.";
+const FILE_LOCKED_RETRIES: u32 = 3; // Extra attempts after the first, for locks that are released a moment later
+const FILE_LOCKED_RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
+// Sharing/lock violations (os error 32/33) on Windows; access denied is how the same lock shows up for folders
+fn is_file_lock_error(e: &io::Error) -> bool {
+    e.kind() == io::ErrorKind::PermissionDenied || matches!(e.raw_os_error(), Some(32 | 33))
+}
+
+// Runs a file operation, retrying briefly while it fails because something holds the file open
+fn retry_if_locked<T>(mut op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(e) if is_file_lock_error(&e) && attempt < FILE_LOCKED_RETRIES => {
+                attempt += 1;
+                warn!("[retry_if_locked] File in use ({}), retrying ({}/{})...", e, attempt, FILE_LOCKED_RETRIES);
+                std::thread::sleep(FILE_LOCKED_RETRY_DELAY);
+            }
+            result => return result,
+        }
+    }
+}
+
+// Turns a failed file operation into FileLocked (with the close-the-game hint) or a plain ModOperation error
+fn file_operation_error(action: &str, path: &Path, e: &io::Error) -> AppError {
+    if is_file_lock_error(e) {
+        AppError::FileLocked(format!("Could not {} '{}' because it is in use. {}", action, path.display(), FILE_LOCKED_HINT))
+    } else {
+        AppError::ModOperation(format!("Failed to {} '{}': {}", action, path.display(), e))
+    }
+}
+
+// fs::rename that also checks the result: on Windows a rename of a folder the game holds open can
+// report success while leaving the source in place.
+fn rename_verified(from: &Path, to: &Path) -> Result<(), AppError> {
+    retry_if_locked(|| fs::rename(from, to))
+        .map_err(|e| file_operation_error(&format!("rename to '{}'", to.display()), from, &e))?;
+    if fs::symlink_metadata(from).is_ok() || fs::symlink_metadata(to).is_err() {
+        return Err(AppError::FileLocked(format!(
+            "Renaming '{}' to '{}' did not take effect. {}", from.display(), to.display(), FILE_LOCKED_HINT
+        )));
+    }
+    Ok(())
+}
+
+// Adds or strips the DISABLED_ prefix on a folder's mod INIs. Renames already done are undone if one fails.
+fn set_ini_files_disabled(mod_folder: &Path, disabled: bool) -> Result<usize, AppError> {
+    let mut renamed: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for (ini_path, is_disabled) in mod_ini_files(mod_folder) {
+        if is_disabled == disabled { continue; }
+        let file_name = ini_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let new_name = if disabled { format!("{}{}", DISABLED_PREFIX, file_name) } else { file_name[DISABLED_PREFIX.len()..].to_string() };
+        let new_path = ini_path.with_file_name(&new_name);
+        if let Err(e) = rename_verified(&ini_path, &new_path) {
+            for (from, to) in renamed.iter().rev() { fs::rename(to, from).ok(); }
+            return Err(e);
+        }
+        renamed.push((ini_path, new_path));
+    }
+    Ok(renamed.len())
+}
+
+// Brings an asset's files to the wanted state. Enabling undoes whichever mechanism disabled the mod: a
+// DISABLED_ folder is renamed back with its INIs left as shipped (mods may carry optional DISABLED_ INI
+// variants), and only an enabled folder whose mod INIs are all prefixed gets them un-prefixed. So nothing
+// can fail after a folder rename. Disabling uses the INI strategy when asked and the folder has mod INIs,
+// and renames the folder otherwise. Returns the mod folder path afterwards.
+fn set_asset_state_on_disk(base_mods_path: &Path, clean_relative_path: &Path, enable: bool, use_ini_strategy: bool) -> Result<PathBuf, AppError> {
+    let (full_path_if_enabled, full_path_if_disabled) = asset_disk_paths(base_mods_path, clean_relative_path)
+        .ok_or_else(|| AppError::ModOperation(format!("Filename extracted from DB path is empty: {}", clean_relative_path.display())))?;
+    let (current_path, folder_enabled) = resolve_asset_on_disk(base_mods_path, clean_relative_path)
+        .ok_or_else(|| AppError::NotFound(format!("Mod folder not found at '{}' or '{}'.", full_path_if_enabled.display(), full_path_if_disabled.display())))?;
+    if enable {
+        if !folder_enabled {
+            rename_verified(&current_path, &full_path_if_enabled)?;
+        } else if is_ini_disabled_folder(&current_path) {
+            set_ini_files_disabled(&current_path, false)?;
+        }
+        return Ok(full_path_if_enabled);
+    }
+    if !folder_enabled || is_ini_disabled_folder(&current_path) { return Ok(current_path); } // Already disabled
+    if use_ini_strategy && !mod_ini_files(&current_path).is_empty() {
+        set_ini_files_disabled(&current_path, true)?;
+        return Ok(current_path);
+    }
+    rename_verified(&current_path, &full_path_if_disabled)?;
+    Ok(full_path_if_disabled)
+}
+
+// Whether disabling should rename INIs instead of the mod folder (settings value "ini")
+fn use_ini_disable_strategy(conn: &Connection) -> bool {
+    get_setting_value(conn, SETTINGS_KEY_DISABLE_STRATEGY).ok().flatten().as_deref() == Some(DISABLE_STRATEGY_INI)
+}
+
+// Slow fallback for case-sensitive filesystems (e.g. mods authored on Windows, used on Linux).
+// Matches each component ignoring case and returns the clean relative path as cased on disk plus its enabled state.
+fn find_asset_path_ignoring_case(base_mods_path: &Path, clean_relative_path: &Path) -> Option<(PathBuf, bool)> {
+    let components: Vec<String> = clean_relative_path.components().map(|c| c.as_os_str().to_string_lossy().to_string()).collect();
+    let (leaf, parents) = components.split_last()?;
+    let find_child_dir = |dir: &Path, wanted: &str| -> Option<String> {
+        let wanted_lower = wanted.to_lowercase();
+        fs::read_dir(dir).ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .find(|name| name.to_lowercase() == wanted_lower)
+    };
+
+    let mut disk_relative_path = PathBuf::new();
+    for parent in parents {
+        disk_relative_path.push(find_child_dir(&base_mods_path.join(&disk_relative_path), parent)?);
+    }
+    let parent_dir = base_mods_path.join(&disk_relative_path);
+    if let Some(name) = find_child_dir(&parent_dir, leaf) {
+        disk_relative_path.push(name);
+        return Some((disk_relative_path, true));
+    }
+    let disabled_name = find_child_dir(&parent_dir, &format!("{}{}", DISABLED_PREFIX, leaf))?;
+    disk_relative_path.push(disabled_name.get(DISABLED_PREFIX.len()..)?);
+    Some((disk_relative_path, false))
+}
+
+// Walks the parent folders of a mod, following DISABLED_ prefixed ones.
+// Returns the mod folder path only when it exists because an ancestor folder was disabled.
+fn resolve_asset_under_disabled_parent(base_mods_path: &Path, clean_relative_path: &Path) -> Option<PathBuf> {
+    let components: Vec<Component> = clean_relative_path.components().collect();
+    let (leaf, parents) = components.split_last()?;
+    let mut current_path = base_mods_path.to_path_buf();
+    let mut found_disabled_parent = false;
+    for component in parents {
+        let name = component.as_os_str().to_string_lossy();
+        let path_if_enabled = current_path.join(name.as_ref());
+        let path_if_disabled = current_path.join(format!("{}{}", DISABLED_PREFIX, name));
+        if path_if_enabled.is_dir() {
+            current_path = path_if_enabled;
+        } else if path_if_disabled.is_dir() {
+            current_path = path_if_disabled;
+            found_disabled_parent = true;
+        } else {
+            return None;
+        }
+    }
+    if !found_disabled_parent { return None; }
+    resolve_asset_on_disk(&current_path, Path::new(leaf.as_os_str())).map(|(path, _)| path)
+}
+
+// A mod folder moved by relocate_asset_folder. It is moved back on drop unless commit() is called,
+// so a failed DB update never leaves the row pointing at the old location.
+struct FolderRelocation {
+    relative_path: String, // New clean relative path for the DB
+    from: PathBuf,
+    to: PathBuf,
+    enabled: bool, // Enabled state at the destination
+    committed: bool,
+}
+
+impl FolderRelocation {
+    fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for FolderRelocation {
+    fn drop(&mut self) {
+        if self.committed { return; }
+        warn!("[relocate_asset_folder] Rolling back move of '{}' to '{}'.", self.from.display(), self.to.display());
+        if let Err(e) = retry_if_locked(|| fs::rename(&self.to, &self.from)) {
+            error!("[relocate_asset_folder] Failed to move '{}' back to '{}': {}", self.to.display(), self.from.display(), e);
+        }
+    }
+}
+
+// --- Helper to move a mod folder under another entity ---
+// The folder keeps its current enabled/disabled state unless `enabled_override` says otherwise.
+// The caller must commit() the returned relocation once the DB reflects the new path.
+fn relocate_asset_folder(base_mods_path: &Path, clean_relative_path: &str, new_category_slug: &str, new_entity_slug: &str, enabled_override: Option<bool>) -> Result<FolderRelocation, AppError> {
+    let current_relative_path_buf = PathBuf::from(clean_relative_path);
+    let (current_full_path, is_currently_enabled) = resolve_asset_on_disk(base_mods_path, &current_relative_path_buf)
+        .ok_or_else(|| AppError::NotFound(format!("Cannot relocate: Source folder not found at '{}' or disabled variant.", base_mods_path.join(&current_relative_path_buf).display())))?;
+    info!("[relocate_asset_folder] Current full path on disk: {}", current_full_path.display());
+
+    // --- Construct New Relative (for DB) and Full (for Disk) Paths ---
+    // The clean name comes from the DB path, never from the folder found on disk, so a stray or
+    // repeated DISABLED_ prefix on disk can't leak into the destination name
+    let mod_base_name = current_relative_path_buf.file_name()
+        .map(|name| name.to_string_lossy().trim_start_matches(DISABLED_PREFIX).to_string())
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| AppError::ModOperation(format!("Cannot get current filename from '{}'", clean_relative_path)))?;
+    let new_relative_path_buf = PathBuf::new().join(new_category_slug).join(new_entity_slug).join(&mod_base_name);
+    let new_relative_path_str = new_relative_path_buf.to_string_lossy().replace("\\", "/");
+
+    let (new_path_if_enabled, new_path_if_disabled) = asset_disk_paths(base_mods_path, &new_relative_path_buf)
+        .ok_or_else(|| AppError::ModOperation(format!("Invalid destination path '{}'", new_relative_path_str)))?;
+    let enable_at_destination = enabled_override.unwrap_or(is_currently_enabled);
+    let new_full_dest_path_on_disk = if enable_at_destination { new_path_if_enabled.clone() } else { new_path_if_disabled.clone() };
+
+    // --- Create Parent Directory & Perform Move ---
+    let parent = new_full_dest_path_on_disk.parent()
+        .ok_or_else(|| AppError::ModOperation("Could not determine parent for new path".to_string()))?;
+    fs::create_dir_all(parent)?;
+    // Either variant existing would leave two folders for the same mod at the destination
+    if let Some(existing) = [&new_path_if_enabled, &new_path_if_disabled].into_iter().find(|p| p.exists()) {
+        return Err(AppError::ModOperation(format!("Cannot relocate: Target path '{}' already exists.", existing.display())));
+    }
+    rename_verified(&current_full_path, &new_full_dest_path_on_disk)?;
+    let relocation = FolderRelocation {
+        relative_path: new_relative_path_str,
+        from: current_full_path,
+        to: new_full_dest_path_on_disk,
+        enabled: enable_at_destination,
+        committed: false,
+    };
+
+    // The move must leave exactly one folder behind: nothing at the source, the mod at the destination.
+    // Returning the error drops the relocation, which moves the folder back.
+    if relocation.from.exists() || !relocation.to.is_dir() {
+        return Err(AppError::ModOperation(format!("Relocation of '{}' to '{}' could not be verified on disk.", relocation.from.display(), relocation.to.display())));
+    }
+    Ok(relocation)
+}
+
+// Helper for schema migrations on existing databases
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<(), AppError> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let has_column = stmt.query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|name| name.ok())
+        .any(|name| name == column);
+    if !has_column {
+        info!("Migrating database: adding column {}.{}", table, column);
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(())
+}
+
+// Best-effort creation time of a folder as unix seconds (falls back to modified time)
+fn folder_created_at(path: &Path) -> Option<i64> {
+    let metadata = fs::metadata(path).ok()?;
+    let time = metadata.created().or_else(|_| metadata.modified()).ok()?;
+    time.duration_since(std::time::UNIX_EPOCH).ok().map(|d| d.as_secs() as i64)
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0)
+}
+
 // --- Database Initialization (Result type uses AppError internally) ---
+// How long a connection waits on a lock held by another connection (e.g. the scan task's own
+// connection) before a statement fails with SQLITE_BUSY ("database is locked").
+const DB_BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+// Opens a read-write connection to the active DB, configured for concurrent use with the other
+// connections the app opens (the managed state connection and the scan task's connection).
+fn open_db_connection(db_path: &Path) -> SqlResult<Connection> {
+    let conn = Connection::open(db_path)?;
+    conn.busy_timeout(DB_BUSY_TIMEOUT)?;
+    conn.execute("PRAGMA foreign_keys = ON;", [])?;
+    Ok(conn)
+}
+
 fn initialize_database(app_handle: &AppHandle, active_game_slug: &str) -> Result<Connection, AppError> {
     let data_dir = get_app_data_dir(app_handle)?;
     let db_path = data_dir.join(ACTIVE_DB_FILENAME);
-    println!("Initializing database for game '{}' at: {}", active_game_slug, db_path.display());
+    info!("Initializing database for game '{}' at: {}", active_game_slug, db_path.display());
     let needs_schema_setup = !db_path.exists();
 
-    let conn = Connection::open(&db_path)?;
-    conn.execute("PRAGMA foreign_keys = ON;", [])?;
+    let conn = open_db_connection(&db_path)?;
 
     if needs_schema_setup {
-        println!("Performing initial schema setup for {}", db_path.display());
+        info!("Performing initial schema setup for {}", db_path.display());
         // --- Create Tables (Same as before) ---
         conn.execute_batch(
             "BEGIN;
              CREATE TABLE categories ( id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT UNIQUE NOT NULL, slug TEXT UNIQUE NOT NULL );
-             CREATE TABLE entities ( id INTEGER PRIMARY KEY AUTOINCREMENT, category_id INTEGER NOT NULL, name TEXT NOT NULL, slug TEXT UNIQUE NOT NULL, description TEXT, details TEXT, base_image TEXT, FOREIGN KEY (category_id) REFERENCES categories (id) ON DELETE CASCADE );
-             CREATE TABLE assets ( id INTEGER PRIMARY KEY AUTOINCREMENT, entity_id INTEGER NOT NULL, name TEXT NOT NULL, description TEXT, folder_name TEXT NOT NULL UNIQUE, image_filename TEXT, author TEXT, category_tag TEXT, FOREIGN KEY (entity_id) REFERENCES entities (id) ON DELETE CASCADE );
+             CREATE TABLE entities ( id INTEGER PRIMARY KEY AUTOINCREMENT, category_id INTEGER NOT NULL, name TEXT NOT NULL, slug TEXT UNIQUE NOT NULL, description TEXT, details TEXT, base_image TEXT, default_category_tag TEXT, sort_order INTEGER, FOREIGN KEY (category_id) REFERENCES categories (id) ON DELETE CASCADE );
+             CREATE TABLE assets ( id INTEGER PRIMARY KEY AUTOINCREMENT, entity_id INTEGER NOT NULL, name TEXT NOT NULL, description TEXT, folder_name TEXT NOT NULL UNIQUE, image_filename TEXT, author TEXT, category_tag TEXT, created_at INTEGER, is_enabled INTEGER, source_url TEXT, sort_order INTEGER, version TEXT, state_source TEXT, FOREIGN KEY (entity_id) REFERENCES entities (id) ON DELETE CASCADE );
              CREATE TABLE settings ( key TEXT PRIMARY KEY NOT NULL, value TEXT NOT NULL );
              CREATE TABLE presets ( id INTEGER PRIMARY KEY AUTOINCREMENT, name TEXT UNIQUE NOT NULL, is_favorite INTEGER NOT NULL DEFAULT 0 );
              CREATE TABLE preset_assets ( preset_id INTEGER NOT NULL, asset_id INTEGER NOT NULL, is_enabled INTEGER NOT NULL, PRIMARY KEY (preset_id, asset_id), FOREIGN KEY (preset_id) REFERENCES presets(id) ON DELETE CASCADE, FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE );
              COMMIT;",
         )?;
-        println!("Database tables created for {}.", db_path.display());
-        println!("Storing internal game slug '{}' in the new database.", active_game_slug);
+        info!("Database tables created for {}.", db_path.display());
+        info!("Storing internal game slug '{}' in the new database.", active_game_slug);
         conn.execute(
             "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
             params![DB_INTERNAL_GAME_SLUG_KEY, active_game_slug],
         )?;
 
     } else {
-        println!("Database file {} already exists.", db_path.display());
+        info!("Database file {} already exists.", db_path.display());
         // Optional: Verify internal slug matches expected active_game_slug?
         match get_internal_db_slug(&db_path) {
             Ok(Some(internal_slug)) if internal_slug != active_game_slug => {
-                 eprintln!("WARNING: Existing database {} contains slug '{}' but expected '{}'. Check startup logic.", db_path.display(), internal_slug, active_game_slug);
+                 warn!("Existing database {} contains slug '{}' but expected '{}'. Check startup logic.", db_path.display(), internal_slug, active_game_slug);
                  // We proceed, assuming the startup logic handled the rename correctly.
             },
-            Err(e) => eprintln!("Warning: Could not read internal slug from existing DB {}: {}", db_path.display(), e),
+            Err(e) => warn!("Could not read internal slug from existing DB {}: {}", db_path.display(), e),
             _ => {} // Slug matches or doesn't exist (old DB?)
         }
     }
 
+    // --- Schema Migrations (columns added after the initial release) ---
+    add_column_if_missing(&conn, "assets", "created_at", "INTEGER")?;
+    // NULL means the on-disk state is unknown and must be resolved from the filesystem
+    add_column_if_missing(&conn, "assets", "is_enabled", "INTEGER")?;
+    // Page the mod was downloaded from (e.g. GameBanana), for revisiting/update checks
+    add_column_if_missing(&conn, "assets", "source_url", "TEXT")?;
+    // Tag applied to imports for this entity when none is given
+    add_column_if_missing(&conn, "entities", "default_category_tag", "TEXT")?;
+    // User-arranged position within the category; NULL sorts after ordered entities, by name
+    add_column_if_missing(&conn, "entities", "sort_order", "INTEGER")?;
+    add_column_if_missing(&conn, "assets", "sort_order", "INTEGER")?;
+    // Version the mod declares in its INI or folder name (e.g. "1.2" from `Version = 1.2` or "_v1.2")
+    add_column_if_missing(&conn, "assets", "version", "TEXT")?;
+    // Whether the enabled state was last set by the app or changed on disk behind its back (STATE_SOURCE_*)
+    add_column_if_missing(&conn, "assets", "state_source", "TEXT")?;
+    // User globs that file matching mod folders under an entity, ahead of any other deduction
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS deduction_rules ( id INTEGER PRIMARY KEY AUTOINCREMENT, pattern TEXT NOT NULL, entity_slug TEXT NOT NULL )",
+        [],
+    )?;
+    // Known texture/buffer hashes used to deduce the entity of INIs without metadata
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS entity_hashes ( hash TEXT PRIMARY KEY NOT NULL COLLATE NOCASE, entity_slug TEXT NOT NULL )",
+        [],
+    )?;
+    // Last known GameBanana update time per mod id, see check_for_updates
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS mod_update_cache ( mod_id TEXT PRIMARY KEY NOT NULL, remote_updated_at INTEGER, checked_at INTEGER NOT NULL )",
+        [],
+    )?;
+    add_column_if_missing(&conn, "mod_update_cache", "remote_version", "TEXT")?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS hotkeys ( asset_id INTEGER PRIMARY KEY NOT NULL, accelerator TEXT UNIQUE NOT NULL, FOREIGN KEY (asset_id) REFERENCES assets(id) ON DELETE CASCADE )",
+        [],
+    )?;
+
+    conn.pragma_update(None, "user_version", DB_SCHEMA_VERSION)?;
+
+    // --- Load Definitions (bundled, user files, hashes) ---
+    sync_definitions(&conn, app_handle, active_game_slug, false)?;
+
+    Ok(conn)
+}
+
+// Loads the bundled definitions for the game, then the user definition files and hash tables,
+// inserting new categories/entities and refreshing changed ones. Shared by startup and reload_definitions.
+// `force_update` refreshes existing entities even when no file changed since the last sync.
+fn sync_definitions(conn: &Connection, app_handle: &AppHandle, active_game_slug: &str, force_update: bool) -> Result<DefinitionSyncCounts, AppError> {
     // --- Load Definitions ---
-    let definition_resource_path = format!("definitions/{}.toml", active_game_slug);
-    println!("Attempting to load definitions from resource: {}", definition_resource_path);
+    let definition_set = definition_set_for_game(app_handle, active_game_slug);
+    let definition_resource_path = format!("definitions/{}.toml", definition_set);
+    info!("Attempting to load definitions from resource: {}", definition_resource_path);
 
-    let definitions: Definitions = match app_handle.path_resolver().resolve_resource(&definition_resource_path) {
+    let definition_file_path = app_handle.path_resolver().resolve_resource(&definition_resource_path);
+    let definitions: Definitions = match &definition_file_path {
         Some(path) => {
-            println!("Found definition file at: {}", path.display());
-            match fs::read_to_string(&path) {
+            info!("Found definition file at: {}", path.display());
+            match fs::read_to_string(path) {
                 Ok(toml_content) => {
                     match toml::from_str(&toml_content) {
                         Ok(defs) => {
-                            println!("Successfully parsed definitions for '{}'.", active_game_slug);
+                            info!("Successfully parsed definitions for '{}'.", active_game_slug);
                             defs
                         },
                         Err(e) => {
-                            eprintln!("ERROR: Failed to parse TOML from {}: {}. Using empty definitions.", path.display(), e);
+                            error!("Failed to parse TOML from {}: {}. Using empty definitions.", path.display(), e);
                             HashMap::new() // Use empty definitions on parse error
                         }
                     }
                 },
                 Err(e) => {
-                    eprintln!("ERROR: Failed to read definition file {}: {}. Using empty definitions.", path.display(), e);
+                    error!("Failed to read definition file {}: {}. Using empty definitions.", path.display(), e);
                     HashMap::new() // Use empty definitions on read error
                 }
             }
         },
         None => {
-            eprintln!("ERROR: Definition file resource '{}' not found. Using empty definitions.", definition_resource_path);
+            error!("Definition file resource '{}' not found. Using empty definitions.", definition_resource_path);
             HashMap::new() // Use empty definitions if file not found
         }
     };
 
-    println!("Loaded {} categories from definitions for '{}'.", definitions.len(), active_game_slug);
+    info!("Loaded {} categories from definitions for '{}'.", definitions.len(), active_game_slug);
 
     // --- Populate DB from loaded definitions (Same logic as before) ---
+    let mut counts = DefinitionSyncCounts::default();
     if !definitions.is_empty() {
-         for (category_slug, category_def) in definitions.iter() {
-             // Wrap inserts in transaction for potential rollback if needed later
-             conn.execute( "INSERT OR IGNORE INTO categories (name, slug) VALUES (?1, ?2)", params![category_def.name, category_slug],)?;
-             let category_id: i64 = conn.query_row( "SELECT id FROM categories WHERE slug = ?1", params![category_slug], |row| row.get(0), )?;
-
-             let other_slug = format!("{}{}", category_slug, OTHER_ENTITY_SUFFIX);
-             conn.execute( "INSERT OR IGNORE INTO entities (category_id, name, slug, description, details, base_image) VALUES (?1, ?2, ?3, ?4, ?5, ?6)", params![ category_id, OTHER_ENTITY_NAME, other_slug, "Uncategorized assets.", "{}", None::<String> ] )?;
-
-             for entity_def in category_def.entities.iter() {
-                 conn.execute( "INSERT OR IGNORE INTO entities (category_id, name, slug, description, details, base_image) VALUES (?1, ?2, ?3, ?4, ?5, ?6)", params![ category_id, entity_def.name, entity_def.slug, entity_def.description, entity_def.details.as_ref().map(|s| s.to_string()).unwrap_or("{}".to_string()), entity_def.base_image, ] )?;
-             }
-         }
-         println!("Populated database with definitions for '{}'.", active_game_slug);
+         // Existing entities are only refreshed when forced or when the bundled file changed since the last sync
+         let bundled_modified = definition_file_path.as_deref().map_or(0, file_modified_secs);
+         let last_sync: i64 = get_setting_value(conn, SETTINGS_KEY_BUNDLED_DEFINITIONS_LAST_SYNC)?
+             .and_then(|v| v.parse().ok())
+             .unwrap_or(0);
+         let update_existing = force_update || bundled_modified > last_sync;
+         counts = populate_definitions(conn, &definitions, update_existing)?;
+         conn.execute(
+             "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+             params![SETTINGS_KEY_BUNDLED_DEFINITIONS_LAST_SYNC, unix_now().to_string()],
+         )?;
+         info!("Populated database with definitions for '{}' ({} new, {} updated entities).", active_game_slug, counts.added, counts.updated);
     } else {
-         println!("Skipping definition population as no definitions were loaded for '{}'.", active_game_slug);
+         info!("Skipping definition population as no definitions were loaded for '{}'.", active_game_slug);
+    }
+
+    // --- User Definition Files (<app data>/definitions/<game>/*.toml) ---
+    match sync_user_definitions(conn, app_handle, active_game_slug, force_update) {
+        Ok(user_counts) => {
+            counts.added += user_counts.added;
+            counts.updated += user_counts.updated;
+        }
+        Err(e) => warn!("Failed to sync user definitions for '{}': {}", active_game_slug, e),
     }
     // --- End Definition Population ---
-    
-    Ok(conn)
-}
 
-// --- Utility Functions ---
-fn get_app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> { // Internal error type
-    app_handle.path_resolver()
-        .app_data_dir()
-        .ok_or_else(|| AppError::TauriPath("Failed to resolve app data directory".to_string()))
-}
+    // --- Load Hash Definitions (bundled, then user overrides) ---
+    // Hash tables point at entity slugs, so they follow the definition set rather than the profile name
+    if let Err(e) = load_hash_definitions(conn, app_handle, &definition_set) {
+        warn!("Failed to load hash definitions for '{}': {}", active_game_slug, e);
+    }
 
-// Helper to get a setting value (Internal error type)
-fn get_setting_value(conn: &Connection, key: &str) -> Result<Option<String>, AppError> { // Internal error type
-    let mut stmt = conn.prepare("SELECT value FROM settings WHERE key = ?1")?;
-    let result = stmt.query_row(params![key], |row| row.get(0)).optional()?;
-    Ok(result)
+    Ok(counts)
 }
 
-// Helper to get the configured mods base path (Internal error type)
-fn get_mods_base_path_from_settings(db_state: &DbState) -> Result<PathBuf, AppError> { // Internal error type
-    let conn = db_state.0.lock().map_err(|_| AppError::Config("DB lock poisoned".into()))?;
-    get_setting_value(&conn, SETTINGS_KEY_MODS_FOLDER)?
-        .map(PathBuf::from)
-        .ok_or_else(|| AppError::Config("Mods folder path not set".to_string()))
+fn file_modified_secs(path: &Path) -> i64 {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs() as i64)
 }
 
-// Helper to get entity mods path using settings (Internal error type)
-// FIX: Removed unused app_handle parameter
-fn get_entity_mods_path(db_state: &DbState, entity_slug: &str) -> Result<PathBuf, AppError> {
-    let base_path = get_mods_base_path_from_settings(db_state)?;
-    Ok(base_path.join(entity_slug))
+// Every category has a "{slug}-other" entity for mods nothing more specific was found for.
+// Returns 1 if it was created, 0 if it already existed.
+fn insert_other_entity(conn: &Connection, category_id: i64, category_slug: &str) -> SqlResult<usize> {
+    conn.execute(
+        "INSERT OR IGNORE INTO entities (category_id, name, slug, description, details, base_image) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![category_id, OTHER_ENTITY_NAME, format!("{}{}", category_slug, OTHER_ENTITY_SUFFIX), "Uncategorized assets.", "{}", None::<String>],
+    )
 }
 
-// --- Tauri Commands (Return CmdResult<T> = Result<T, String>) ---
+// Inserts the categories/entities of a parsed definitions file. With `update_existing`, entities
+// whose slug already exists get their name/description/details/base_image refreshed as well.
+fn populate_definitions(conn: &Connection, definitions: &Definitions, update_existing: bool) -> Result<DefinitionSyncCounts, AppError> {
+    let mut counts = DefinitionSyncCounts::default();
+    for (raw_category_slug, category_def) in definitions.iter() {
+        let category_slug = &slugify(raw_category_slug);
+        if category_slug != raw_category_slug {
+            warn!("Category slug '{}' is not folder-safe, using '{}' instead.", raw_category_slug, category_slug);
+        }
+        // Wrap inserts in transaction for potential rollback if needed later
+        conn.execute( "INSERT OR IGNORE INTO categories (name, slug) VALUES (?1, ?2)", params![category_def.name, category_slug],)?;
+        if update_existing {
+            conn.execute("UPDATE categories SET name = ?1 WHERE slug = ?2 AND name IS NOT ?1", params![category_def.name, category_slug])?;
+        }
+        let category_id: i64 = conn.query_row( "SELECT id FROM categories WHERE slug = ?1", params![category_slug], |row| row.get(0), )?;
 
-// == Settings Commands ==
+        insert_other_entity(conn, category_id, category_slug)?;
 
-#[command]
-fn get_setting(key: String, db_state: State<DbState>) -> CmdResult<Option<String>> {
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
-    get_setting_value(&conn, &key).map_err(|e| e.to_string()) // Convert internal error to string
+        for entity_def in category_def.entities.iter() {
+            let entity_slug = slugify(&entity_def.slug);
+            if entity_slug.is_empty() {
+                warn!("Skipping entity '{}' with empty slug.", entity_def.name);
+                continue;
+            }
+            if entity_slug != entity_def.slug {
+                warn!("Entity slug '{}' is not folder-safe, using '{}' instead.", entity_def.slug, entity_slug);
+            }
+            let inserted = conn.execute( "INSERT OR IGNORE INTO entities (category_id, name, slug, description, details, base_image) VALUES (?1, ?2, ?3, ?4, ?5, ?6)", params![ category_id, entity_def.name, entity_slug, entity_def.description, entity_def.details.as_ref().map(|s| s.to_string()).unwrap_or("{}".to_string()), entity_def.base_image, ] )?;
+            counts.added += inserted;
+            if update_existing && inserted == 0 {
+                // Omitted optional fields keep their stored value; only rows that actually differ count as updated
+                counts.updated += conn.execute(
+                    "UPDATE entities SET name = ?1, description = COALESCE(?2, description), details = COALESCE(?3, details), base_image = COALESCE(?4, base_image)
+                     WHERE slug = ?5 AND (name IS NOT ?1 OR description IS NOT COALESCE(?2, description) OR details IS NOT COALESCE(?3, details) OR base_image IS NOT COALESCE(?4, base_image))",
+                    params![entity_def.name, entity_def.description, entity_def.details, entity_def.base_image, entity_slug],
+                )?;
+            }
+        }
+    }
+    Ok(counts)
 }
 
-#[command]
-fn set_setting(key: String, value: String, db_state: State<DbState>) -> CmdResult<()> { // Returns Result<(), String>
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+// Loads every *.toml in the user definitions directory when any of them changed since the last
+// sync (tracked in settings), so new characters can be added without recompiling.
+fn sync_user_definitions(conn: &Connection, app_handle: &AppHandle, game_slug: &str, force_update: bool) -> Result<DefinitionSyncCounts, AppError> {
+    let user_defs_dir = get_app_data_dir(app_handle)?.join("definitions").join(game_slug);
+    let mut counts = DefinitionSyncCounts::default();
+    if !user_defs_dir.is_dir() {
+        return Ok(counts);
+    }
+
+    let mut toml_files: Vec<(PathBuf, i64)> = fs::read_dir(&user_defs_dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("toml")))
+        .map(|path| {
+            let modified = file_modified_secs(&path);
+            (path, modified)
+        })
+        .collect();
+    toml_files.sort();
+
+    let last_sync: i64 = get_setting_value(conn, SETTINGS_KEY_USER_DEFINITIONS_LAST_SYNC)?
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if !force_update && !toml_files.iter().any(|(_, modified)| *modified > last_sync) {
+        info!("User definitions in {} unchanged since last sync.", user_defs_dir.display());
+        return Ok(counts);
+    }
+
+    for (path, modified) in &toml_files {
+        let parsed = fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| toml::from_str::<Definitions>(&content).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(defs) => {
+                let file_counts = populate_definitions(conn, &defs, force_update || *modified > last_sync)?;
+                info!("Synced user definitions from {} ({} new, {} updated entities).", path.display(), file_counts.added, file_counts.updated);
+                counts.added += file_counts.added;
+                counts.updated += file_counts.updated;
+            }
+            Err(e) => warn!("Skipping user definitions file {}: {}", path.display(), e),
+        }
+    }
+
     conn.execute(
         "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
-        params![key, value],
-    ).map_err(|e| e.to_string())?; // Convert error
-    println!("Set setting '{}' to '{}'", key, value);
-    Ok(())
+        params![SETTINGS_KEY_USER_DEFINITIONS_LAST_SYNC, unix_now().to_string()],
+    )?;
+    Ok(counts)
 }
 
-#[command]
-async fn select_directory() -> CmdResult<Option<PathBuf>> { // Removed AppHandle
-    // FIX: Remove AppHandle from new(), use blocking dialog directly
-    let result = dialog::blocking::FileDialogBuilder::new()
-        .set_title("Select Mods Folder")
-        .pick_folder();
+// Bundled definition file stems (definitions/*.toml, minus the shared hash table)
+fn list_bundled_definition_sets(app_handle: &AppHandle) -> Vec<String> {
+    let Some(definitions_dir) = app_handle.path_resolver().resolve_resource("definitions") else { return Vec::new() };
+    let mut sets: Vec<String> = fs::read_dir(&definitions_dir).into_iter().flatten().flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("toml")))
+        .filter_map(|path| path.file_stem().map(|stem| stem.to_string_lossy().to_string()))
+        .filter(|stem| !stem.eq_ignore_ascii_case("hashes"))
+        .collect();
+    sets.sort();
+    sets
+}
 
-    match result {
-        Some(path) => Ok(Some(path)),
-        None => Ok(None), // User cancelled
-    }
+// Definition set chosen for a profile when it was created; a game's own slug otherwise
+fn definition_set_for_game(app_handle: &AppHandle, game_slug: &str) -> String {
+    read_app_config(app_handle).ok()
+        .and_then(|config| config.definition_sets.get(game_slug).cloned())
+        .unwrap_or_else(|| game_slug.to_string())
 }
 
-#[command]
-async fn select_file() -> CmdResult<Option<PathBuf>> { // Removed AppHandle
-    // FIX: Use add_filter instead of dialog::Filter struct
-    let result = dialog::blocking::FileDialogBuilder::new() // FIX: Remove AppHandle
-        .set_title("Select Quick Launch Executable")
-        .add_filter("Executable", &["exe", "bat", "cmd", "sh", "app"]) // FIX: Use add_filter
-        .add_filter("All Files", &["*"]) // FIX: Use add_filter
-        .pick_file();
+// Parses one hashes.toml and returns the valid `hash -> entity slug` pairs for `game_slug`.
+// Malformed entries are logged and skipped instead of failing the whole file.
+fn parse_hash_definitions(toml_content: &str, game_slug: &str, source: &str) -> Vec<(String, String)> {
+    let table: toml::Table = match toml::from_str(toml_content) {
+        Ok(t) => t,
+        Err(e) => {
+            warn!("Failed to parse hash definitions from {}: {}", source, e);
+            return Vec::new();
+        }
+    };
+    let Some(game_table) = table.get(game_slug) else { return Vec::new(); };
+    let Some(game_table) = game_table.as_table() else {
+        warn!("[{}] in {} is not a table, ignoring it.", game_slug, source);
+        return Vec::new();
+    };
 
-    match result {
-        Some(path) => Ok(Some(path)),
-        None => Ok(None), // User cancelled
+    let mut pairs = Vec::new();
+    for (hash, value) in game_table {
+        let hash = hash.trim().to_lowercase();
+        if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            warn!("Skipping invalid hash '{}' in {}.", hash, source);
+            continue;
+        }
+        match value.as_str() {
+            Some(entity_slug) if !entity_slug.trim().is_empty() => pairs.push((hash, entity_slug.trim().to_string())),
+            _ => warn!("Skipping hash '{}' in {}: value must be an entity slug string.", hash, source),
+        }
     }
+    pairs
 }
 
-#[cfg(target_os = "windows")]
-#[command]
-fn launch_executable_elevated(path: String) -> Result<(), String> {
-    use std::os::windows::ffi::OsStrExt;
-    use windows::core::PCWSTR;
-    use windows::Win32::Foundation::HWND;
-    use windows::Win32::UI::Shell::ShellExecuteW;
-    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
-
-    println!("Attempting elevated launch for: {}", path);
-
-    // Convert the path and verb to Windows wide strings (UTF-16)
-    let path_wide: Vec<u16> = std::ffi::OsStr::new(&path)
-        .encode_wide()
-        .chain(std::iter::once(0)) // Null-terminate
-        .collect();
-    let operation_wide: Vec<u16> = std::ffi::OsStr::new("runas") // "runas" verb requests elevation
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect();
+// Rebuilds the entity_hashes table from definitions/hashes.toml, if bundled, and the optional
+// user file <app data>/hashes.toml (user entries win on conflicts). No bundled table ships yet:
+// hashes change with game updates and none have been verified against real mods, so only user
+// entries take effect. Both files use one table per game slug mapping a 3DMigoto hash (hex, as
+// written after `hash =` in the INI) to an entity slug of that game:
+//
+//   [genshin]
+//   "0123abcd" = "albedo"
+fn load_hash_definitions(conn: &Connection, app_handle: &AppHandle, game_slug: &str) -> Result<(), AppError> {
+    let mut sources: Vec<(PathBuf, String)> = Vec::new();
+    if let Some(path) = app_handle.path_resolver().resolve_resource("definitions/hashes.toml") {
+        sources.push((path, "bundled hashes.toml".to_string()));
+    }
+    let user_path = get_app_data_dir(app_handle)?.join("hashes.toml");
+    sources.push((user_path.clone(), user_path.display().to_string()));
+
+    let mut merged: HashMap<String, String> = HashMap::new();
+    for (path, label) in sources {
+        if !path.is_file() { continue; }
+        match fs::read_to_string(&path) {
+            Ok(content) => merged.extend(parse_hash_definitions(&content, game_slug, &label)),
+            Err(e) => warn!("Failed to read hash definitions from {}: {}", label, e),
+        }
+    }
 
-    // Call ShellExecuteW to request elevation
-    // HINSTANCE returned is actually an integer value; > 32 indicates success.
-    let result = unsafe {
-        ShellExecuteW(
-            Some(HWND::default()),               // Pass as Option<HWND>
-            PCWSTR(operation_wide.as_ptr()),     // Operation: "runas"
-            PCWSTR(path_wide.as_ptr()),          // File path
-            None,                                // No parameters
-            None,                                // Default working directory
-            SW_SHOWNORMAL,                       // Show command normal state
-        )
+    let known_entities: HashSet<String> = {
+        let mut stmt = conn.prepare("SELECT slug FROM entities")?;
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect::<SqlResult<HashSet<String>>>()?
     };
 
-    // --- FIX 1: Explicitly cast result.0 to isize BEFORE comparison ---
-    let result_value = result.0 as isize;
-
-    if result_value > 32 { // Compare the casted value
-        println!("Elevated launch initiated successfully via ShellExecuteW.");
-        Ok(())
-    } else {
-        // result.0 contains the error code as an isize, cast to i32 if needed elsewhere
-        let error_code = result_value as i32; // Cast for error reporting
-        let error_message = format!(
-            "Failed to request elevated launch for '{}'. ShellExecuteW error code: {}",
-            path, error_code
-        );
-        eprintln!("{}", error_message);
-
-        // --- FIX 2: Cast the ERROR_CANCELLED constant to i32 for comparison ---
-        if error_code == windows::Win32::Foundation::ERROR_CANCELLED.0 as i32 {
-             Err("Operation cancelled by user.".to_string())
-        } else {
-             Err(error_message)
+    conn.execute("DELETE FROM entity_hashes", [])?;
+    let mut loaded = 0;
+    for (hash, entity_slug) in merged {
+        if !known_entities.contains(&entity_slug) {
+            warn!("Hash '{}' points to unknown entity '{}', skipping.", hash, entity_slug);
+            continue;
         }
+        conn.execute("INSERT OR REPLACE INTO entity_hashes (hash, entity_slug) VALUES (?1, ?2)", params![hash, entity_slug])?;
+        loaded += 1;
     }
+    info!("Loaded {} hash definitions for '{}'.", loaded, game_slug);
+    Ok(())
 }
 
-#[command]
-async fn launch_executable(path: String, _app_handle: AppHandle) -> CmdResult<()> { // app_handle might not be needed now
-    println!("Attempting to launch (non-elevated) via Command::new: {}", path);
-
-    // FIX: Use Command::new for launching executables
-    let cmd = Command::new(path) // Use the path directly as the command
-        // .args([]) // Add arguments if needed later
+// --- Utility Functions ---
+fn get_app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> { // Internal error type
+    app_handle.path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| AppError::TauriPath("Failed to resolve app data directory".to_string()))
+}
+
+// --- Logging ---
+// Release builds on Windows have no console, so everything also goes to daily-rotated files in <app data>/logs.
+const LOG_DIR_NAME: &str = "logs";
+const LOG_FILE_PREFIX: &str = "gmm";
+const LOG_FILES_TO_KEEP: usize = 7;
+
+// Flushes buffered log lines when dropped; held until flush_logs() runs on the way out
+static LOG_GUARD: Lazy<Mutex<Option<tracing_appender::non_blocking::WorkerGuard>>> = Lazy::new(|| Mutex::new(None));
+
+// Statics are never dropped, so the guard is taken and dropped here. Lines logged afterwards are lost.
+fn flush_logs() {
+    drop(LOG_GUARD.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take());
+}
+
+// process::exit skips destructors, so buffered log lines would be lost without the flush
+fn exit_process(code: i32) -> ! {
+    flush_logs();
+    std::process::exit(code)
+}
+
+fn init_logging(log_dir: &Path) {
+    let file_appender = tracing_appender::rolling::Builder::new()
+        .rotation(tracing_appender::rolling::Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(LOG_FILES_TO_KEEP)
+        .build(log_dir);
+    let file_appender = match file_appender {
+        Ok(appender) => appender,
+        Err(e) => {
+            // Still log to stdout so dev builds keep working
+            tracing_subscriber::fmt().init();
+            error!("Failed to create log file in '{}': {}", log_dir.display(), e);
+            return;
+        }
+    };
+    let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
+    *LOG_GUARD.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(guard);
+    tracing_subscriber::fmt()
+        .with_writer(io::stdout.and(file_writer))
+        .with_ansi(false)
+        .init();
+    info!("Logging to '{}'", log_dir.display());
+}
+
+// Most recent log file, or the log folder if nothing has been written yet, for attaching to bug reports
+#[command]
+fn get_log_path(app_handle: AppHandle) -> CmdResult<String> {
+    let log_dir = get_app_data_dir(&app_handle)?.join(LOG_DIR_NAME);
+    let latest_log = fs::read_dir(&log_dir).ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(LOG_FILE_PREFIX))
+        .max_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok())
+        .map(|entry| entry.path());
+    Ok(latest_log.unwrap_or(log_dir).to_string_lossy().to_string())
+}
+
+// Names Windows refuses for files/folders, with or without an extension
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+fn is_windows_reserved_name(name: &str) -> bool {
+    let stem = name.split('.').next().unwrap_or("").trim_end();
+    WINDOWS_RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem))
+}
+
+// Helper to build a folder-safe slug, e.g. "Hu Tao" -> "hu-tao", "Lumière" -> "lumiere"
+// Underscores are kept since existing definition slugs use them (e.g. "rover_female")
+fn slugify(input: &str) -> String {
+    let mut slug = String::with_capacity(input.len());
+    // Accents are folded first so they don't turn into separators; other non-ASCII characters still do
+    for c in fold_name_for_matching(input.trim()).chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if is_windows_reserved_name(&slug) { format!("{}_", slug) } else { slug }
+}
+
+// Helper to turn a user-facing mod name into a single safe folder name component
+fn sanitize_folder_name(input: &str) -> String {
+    let mut name: String = input.trim().chars()
+        .filter(|c| *c != '\'' && *c != '"')
+        .map(|c| match c {
+            ' ' | '.' | '/' | '\\' | ':' | '*' | '?' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if is_windows_reserved_name(&name) { name.push('_'); }
+    name
+}
+
+// Windows MAX_PATH; longer paths need the \\?\ extended-length prefix
+const WINDOWS_MAX_PATH: usize = 260;
+
+// Helper to sanitize one path component taken from an archive entry (keeps dots/extensions)
+fn sanitize_path_component(component: &str) -> String {
+    let mut cleaned: String = component.chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Windows silently drops trailing dots and spaces
+    let trimmed_len = cleaned.trim_end_matches(['.', ' ']).len();
+    if trimmed_len == 0 { return "_".to_string(); }
+    cleaned.truncate(trimmed_len);
+    if is_windows_reserved_name(&cleaned) { cleaned.push('_'); }
+    cleaned
+}
+
+// Helper to add the \\?\ prefix to absolute paths that exceed MAX_PATH on Windows
+fn to_extended_length_path(path: &Path) -> PathBuf {
+    if !cfg!(windows) || !path.is_absolute() { return path.to_path_buf(); }
+    let path_str = path.to_string_lossy();
+    if path_str.len() < WINDOWS_MAX_PATH || path_str.starts_with(r"\\?\") { return path.to_path_buf(); }
+    let normalized = path_str.replace('/', "\\");
+    match normalized.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+        None => PathBuf::from(format!(r"\\?\{}", normalized)),
+    }
+}
+
+const IMPORT_STAGING_PREFIX: &str = ".gmm_import_";
+
+// Temporary extraction folder for import_archive, removed on drop unless finish() moved it into place
+struct ImportStaging {
+    path: PathBuf,
+    finished: bool,
+}
+
+impl ImportStaging {
+    // Created beside the destination so finish() is a same-volume rename
+    fn create(final_dest: &Path) -> io::Result<Self> {
+        let parent = final_dest.parent().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "destination has no parent folder"))?;
+        let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        let path = parent.join(format!("{}{}", IMPORT_STAGING_PREFIX, nanos));
+        fs::create_dir_all(&path)?;
+        Ok(ImportStaging { path, finished: false })
+    }
+
+    fn finish(mut self, final_dest: &Path) -> io::Result<()> {
+        fs::rename(&self.path, final_dest)?;
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl Drop for ImportStaging {
+    fn drop(&mut self) {
+        if self.finished { return; }
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            error!("[import_archive] Failed to clean up staging folder '{}': {}", self.path.display(), e);
+        }
+    }
+}
+
+// Leftovers from an import that was interrupted mid-extraction; never treat them as mods
+fn is_import_staging_dir(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name.to_string_lossy().starts_with(IMPORT_STAGING_PREFIX))
+}
+
+// Archive format from the file name: the lowercased extension, except that .tar.gz and .tgz are both "tar.gz"
+fn archive_type(archive_path: &Path) -> Option<String> {
+    let file_name = archive_path.file_name()?.to_string_lossy().to_lowercase();
+    if file_name.ends_with(".tar.gz") || file_name.ends_with(".tgz") { return Some("tar.gz".to_string()); }
+    archive_path.extension().and_then(OsStr::to_str).map(|s| s.to_lowercase())
+}
+
+// Tar has no central directory, so every reader streams the entries from the start
+fn open_tar_archive(archive_path: &Path, gzipped: bool) -> io::Result<tar::Archive<Box<dyn Read>>> {
+    let file = BufReader::new(File::open(archive_path)?);
+    let reader: Box<dyn Read> = if gzipped { Box::new(flate2::read::GzDecoder::new(file)) } else { Box::new(file) };
+    Ok(tar::Archive::new(reader))
+}
+
+// Path of a tar entry with '/' separators and any leading "./" dropped. Returns None for links and other
+// special entries, for absolute or `..` paths, and for the archive's "./" root itself.
+fn tar_entry_path<R: Read>(entry: &tar::Entry<R>) -> Option<String> {
+    let entry_type = entry.header().entry_type();
+    if !entry_type.is_file() && !entry_type.is_dir() { return None; }
+    let path = entry.path().ok()?;
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_string_lossy().to_string()),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+// Sums the uncompressed size of the files import_archive would extract, reading headers only.
+// An empty prefix counts the whole archive.
+fn archive_uncompressed_size(archive_path: &Path, prefix_path: &Path) -> Result<u64, AppError> {
+    let in_prefix = |internal_path: &str| {
+        prefix_path.as_os_str().is_empty() || Path::new(&internal_path.replace("\\", "/")).starts_with(prefix_path)
+    };
+    let extension = archive_type(archive_path);
+    let mut total = 0u64;
+    match extension.as_deref() {
+        Some("zip") => {
+            let mut archive = ZipArchive::new(File::open(archive_path)?)?;
+            for i in 0..archive.len() {
+                let file_entry = archive.by_index_raw(i)?;
+                if !file_entry.is_dir() && zip_entry_path(&file_entry).is_some_and(|path| in_prefix(&path)) { total += file_entry.size(); }
+            }
+        }
+        Some("7z") => {
+            let archive = sevenz_rust::SevenZReader::open(archive_path, Password::empty())?;
+            total = archive.archive().files.iter()
+                .filter(|entry| !entry.is_directory() && in_prefix(entry.name()))
+                .map(|entry| entry.size())
+                .sum();
+        }
+        Some("rar") => {
+            let list_archive = Archive::new(archive_path).open_for_listing()?;
+            for header in list_archive.flatten() {
+                if !header.is_directory() && in_prefix(&header.filename.to_string_lossy()) { total += header.unpacked_size; }
+            }
+        }
+        Some(kind @ ("tar" | "tar.gz")) => {
+            for entry in open_tar_archive(archive_path, kind == "tar.gz")?.entries()? {
+                let entry = entry?;
+                if entry.header().entry_type().is_file() && tar_entry_path(&entry).is_some_and(|path| in_prefix(&path)) { total += entry.size(); }
+            }
+        }
+        _ => return Err(AppError::UnsupportedArchive(format!("Unsupported archive type: {:?}", extension))),
+    }
+    Ok(total)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ArchiveLimits {
+    max_entries: usize,
+    max_uncompressed_bytes: u64,
+}
+
+impl ArchiveLimits {
+    fn from_settings(conn: &Connection) -> CmdResult<Self> {
+        let max_entries = get_setting_value(conn, SETTINGS_KEY_ARCHIVE_MAX_ENTRIES)?
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_ARCHIVE_MAX_ENTRIES);
+        let max_size_mb = get_setting_value(conn, SETTINGS_KEY_ARCHIVE_MAX_SIZE_MB)?
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(DEFAULT_ARCHIVE_MAX_SIZE_MB);
+        Ok(ArchiveLimits { max_entries, max_uncompressed_bytes: max_size_mb.saturating_mul(1024 * 1024) })
+    }
+}
+
+// Checks entry count and declared uncompressed size from the archive's directory alone, stopping as soon
+// as a limit is passed, so pathological archives are refused before anything is read or extracted.
+fn check_archive_limits(archive_path: &Path, limits: &ArchiveLimits) -> Result<(), AppError> {
+    let name = archive_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let too_many = || AppError::ModOperation(format!(
+        "'{}' has more than {} entries, which is over the archive_max_entries limit.", name, limits.max_entries
+    ));
+    let too_big = || AppError::ModOperation(format!(
+        "'{}' would unpack to more than {} MB, which is over the archive_max_size_mb limit.", name, limits.max_uncompressed_bytes / (1024 * 1024)
+    ));
+
+    let extension = archive_type(archive_path);
+    let mut total = 0u64;
+    match extension.as_deref() {
+        Some("zip") => {
+            let mut archive = ZipArchive::new(File::open(archive_path)?)?;
+            if archive.len() > limits.max_entries { return Err(too_many()); }
+            for i in 0..archive.len() {
+                total = total.saturating_add(archive.by_index_raw(i)?.size());
+                if total > limits.max_uncompressed_bytes { return Err(too_big()); }
+            }
+        }
+        Some("7z") => {
+            let archive = sevenz_rust::SevenZReader::open(archive_path, Password::empty())?;
+            let files = &archive.archive().files;
+            if files.len() > limits.max_entries { return Err(too_many()); }
+            total = files.iter().fold(0u64, |sum, entry| sum.saturating_add(entry.size()));
+            if total > limits.max_uncompressed_bytes { return Err(too_big()); }
+        }
+        Some("rar") => {
+            let list_archive = Archive::new(archive_path).open_for_listing()?;
+            for (count, header) in list_archive.flatten().enumerate() {
+                if count >= limits.max_entries { return Err(too_many()); }
+                total = total.saturating_add(header.unpacked_size);
+                if total > limits.max_uncompressed_bytes { return Err(too_big()); }
+            }
+        }
+        Some(kind @ ("tar" | "tar.gz")) => {
+            // Headers are interleaved with the data, so this streams (and for .tar.gz decompresses) the archive
+            for (count, entry) in open_tar_archive(archive_path, kind == "tar.gz")?.entries()?.enumerate() {
+                if count >= limits.max_entries { return Err(too_many()); }
+                total = total.saturating_add(entry?.size());
+                if total > limits.max_uncompressed_bytes { return Err(too_big()); }
+            }
+        }
+        _ => return Err(AppError::UnsupportedArchive(format!("Unsupported archive type: {:?}", extension))),
+    }
+    Ok(())
+}
+
+// Copies a zip entry, failing if it decompresses to more than the size its header declares.
+// check_archive_limits trusts those sizes, so this keeps a forged header from unpacking without bound.
+fn copy_zip_entry(entry: &mut zip::read::ZipFile, writer: &mut impl Write) -> io::Result<u64> {
+    let declared = entry.size();
+    let copied = io::copy(&mut entry.take(declared.saturating_add(1)), writer)?;
+    if copied > declared {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, format!("entry is larger than its declared {} bytes", declared)));
+    }
+    Ok(copied)
+}
+
+fn corrupt_archive_error(archive_path: &Path, detail: impl std::fmt::Display) -> AppError {
+    let name = archive_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    AppError::ModOperation(format!("'{}' is corrupt or incomplete (was the download interrupted?). Try downloading it again. Details: {}", name, detail))
+}
+
+// Reads the archive's directory (and, with `verify_contents`, decompresses every entry so CRCs get checked)
+// so truncated downloads fail with a clear message before anything is extracted.
+fn validate_archive(archive_path: &Path, verify_contents: bool) -> Result<(), AppError> {
+    let extension = archive_type(archive_path);
+    match extension.as_deref() {
+        Some("zip") => {
+            let archive_len = fs::metadata(archive_path)?.len();
+            let mut archive = ZipArchive::new(File::open(archive_path)?).map_err(|e| corrupt_archive_error(archive_path, e))?;
+            for i in 0..archive.len() {
+                let file_entry = archive.by_index_raw(i).map_err(|e| corrupt_archive_error(archive_path, e))?;
+                if file_entry.data_start() + file_entry.compressed_size() > archive_len {
+                    return Err(corrupt_archive_error(archive_path, format!("entry '{}' extends past the end of the file", file_entry.name())));
+                }
+            }
+            if verify_contents {
+                for i in 0..archive.len() {
+                    let mut file_entry = archive.by_index(i).map_err(|e| corrupt_archive_error(archive_path, e))?;
+                    // The zip reader checks the CRC once an entry is read to the end
+                    io::copy(&mut file_entry, &mut io::sink())
+                        .map_err(|e| corrupt_archive_error(archive_path, format!("entry '{}': {}", file_entry.name(), e)))?;
+                }
+            }
+        }
+        Some("7z") => {
+            let mut archive = sevenz_rust::SevenZReader::open(archive_path, Password::empty()).map_err(|e| corrupt_archive_error(archive_path, e))?;
+            if verify_contents {
+                archive.for_each_entries(|_, reader| {
+                    io::copy(reader, &mut io::sink())?;
+                    Ok(true)
+                }).map_err(|e| corrupt_archive_error(archive_path, e))?;
+            }
+        }
+        Some("rar") => {
+            let list_archive = Archive::new(archive_path).open_for_listing().map_err(|e| corrupt_archive_error(archive_path, e))?;
+            for header in list_archive {
+                header.map_err(|e| corrupt_archive_error(archive_path, e))?;
+            }
+            if verify_contents {
+                let mut processing_archive = Archive::new(archive_path).open_for_processing().map_err(|e| corrupt_archive_error(archive_path, e))?;
+                while let Some(header_state) = processing_archive.read_header().map_err(|e| corrupt_archive_error(archive_path, e))? {
+                    processing_archive = if header_state.entry().is_file() {
+                        header_state.test().map_err(|e| corrupt_archive_error(archive_path, e))?
+                    } else {
+                        header_state.skip().map_err(|e| corrupt_archive_error(archive_path, e))?
+                    };
+                }
+            }
+        }
+        Some(kind @ ("tar" | "tar.gz")) => {
+            // Walking the headers already reads through a truncated .tar.gz to the point where it breaks
+            let mut archive = open_tar_archive(archive_path, kind == "tar.gz")?;
+            for entry in archive.entries().map_err(|e| corrupt_archive_error(archive_path, e))? {
+                let mut entry = entry.map_err(|e| corrupt_archive_error(archive_path, e))?;
+                if verify_contents {
+                    io::copy(&mut entry, &mut io::sink()).map_err(|e| corrupt_archive_error(archive_path, e))?;
+                }
+            }
+        }
+        _ => return Err(AppError::UnsupportedArchive(format!("Unsupported archive type: {:?}", extension))),
+    }
+    Ok(())
+}
+
+// Fails early when the volume holding dest_dir can't fit required_bytes, instead of leaving a half-extracted mod.
+fn ensure_free_space(dest_dir: &Path, required_bytes: u64) -> Result<(), AppError> {
+    // available_space needs an existing path; walk up to the nearest one
+    let Some(existing_dir) = dest_dir.ancestors().find(|p| p.exists()) else { return Ok(()); };
+    let available = fs2::available_space(existing_dir)?;
+    if available < required_bytes {
+        return Err(AppError::ModOperation(format!(
+            "Not enough disk space to import: needs {:.1} MB but only {:.1} MB is free on the drive containing '{}'.",
+            required_bytes as f64 / 1_048_576.0, available as f64 / 1_048_576.0, existing_dir.display()
+        )));
+    }
+    Ok(())
+}
+
+// Path of a zip entry with '/' separators. Names without the UTF-8 flag are decoded as CP437 by the zip crate,
+// which mangles archives made on Japanese/Chinese Windows, so the raw bytes are retried as UTF-8, Shift_JIS
+// and GBK first. Returns None for absolute or `..` paths, like enclosed_name().
+fn zip_entry_path(file_entry: &zip::read::ZipFile) -> Option<String> {
+    let raw_name = file_entry.name_raw();
+    let decoded = if raw_name.is_ascii() {
+        None
+    } else {
+        std::str::from_utf8(raw_name).ok().map(str::to_string).or_else(|| {
+            [encoding_rs::SHIFT_JIS, encoding_rs::GBK].iter()
+                .find_map(|encoding| encoding.decode_without_bom_handling_and_without_replacement(raw_name))
+                .map(|name| name.into_owned())
+        })
+    };
+    let name = match decoded {
+        Some(name) if name != file_entry.name() => {
+            info!("[zip_entry_path] Re-decoded entry name '{}' as '{}'", file_entry.name(), name);
+            name
+        }
+        _ => file_entry.name().to_string(),
+    };
+
+    let name = name.replace("\\", "/");
+    if name.contains('\0') || !Path::new(&name).components().all(|c| matches!(c, Component::Normal(_) | Component::CurDir)) {
+        return None;
+    }
+    Some(name)
+}
+
+// Helper to build the on-disk destination of an archive entry inside the mod folder.
+// Rejects entries that would escape the folder and sanitizes each component.
+fn build_extraction_path(dest_root: &Path, entry_relative_path: &Path) -> CmdResult<PathBuf> {
+    let mut outpath = dest_root.to_path_buf();
+    for component in entry_relative_path.components() {
+        match component {
+            Component::Normal(part) => outpath.push(sanitize_path_component(&part.to_string_lossy())),
+            Component::CurDir => {}
+            _ => return Err(AppError::ModOperation(format!("Archive entry '{}' has an unsafe path (absolute or contains '..').", entry_relative_path.display()))),
+        }
+    }
+    Ok(to_extended_length_path(&outpath))
+}
+
+// Helper to get a setting value (Internal error type)
+fn get_setting_value(conn: &Connection, key: &str) -> Result<Option<String>, AppError> { // Internal error type
+    let mut stmt = conn.prepare("SELECT value FROM settings WHERE key = ?1")?;
+    let result = stmt.query_row(params![key], |row| row.get(0)).optional()?;
+    Ok(result)
+}
+
+// Helper to get the configured mods base path (Internal error type)
+fn get_mods_base_path_from_settings(db_state: &DbState) -> Result<PathBuf, AppError> { // Internal error type
+    let conn = lock_db(db_state);
+    get_setting_value(&conn, SETTINGS_KEY_MODS_FOLDER)?
+        .map(PathBuf::from)
+        .ok_or_else(|| AppError::Config("Mods folder path not set".to_string()))
+}
+
+// Helper to get entity mods path using settings (Internal error type)
+// FIX: Removed unused app_handle parameter
+fn get_entity_mods_path(db_state: &DbState, entity_slug: &str) -> Result<PathBuf, AppError> {
+    let base_path = get_mods_base_path_from_settings(db_state)?;
+    Ok(base_path.join(entity_slug))
+}
+
+// --- Tauri Commands (Return CmdResult<T> = Result<T, AppError>) ---
+
+// == Settings Commands ==
+
+#[command]
+fn reload_definitions(app_handle: AppHandle, db_state: State<DbState>) -> CmdResult<DefinitionSyncCounts> {
+    let conn = lock_db(&db_state);
+    let game_slug = match get_setting_value(&conn, DB_INTERNAL_GAME_SLUG_KEY)? {
+        Some(slug) => slug,
+        None => read_app_config(&app_handle)?.requested_active_game,
+    };
+    info!("[reload_definitions] Reloading definitions for '{}'", game_slug);
+    let counts = sync_definitions(&conn, &app_handle, &game_slug, true)?;
+    info!("[reload_definitions] Done: {} added, {} updated.", counts.added, counts.updated);
+    Ok(counts)
+}
+
+#[command]
+fn get_setting(key: String, db_state: State<DbState>) -> CmdResult<Option<String>> {
+    let conn = lock_db(&db_state);
+    get_setting_value(&conn, &key)
+}
+
+#[command]
+fn set_setting(key: String, value: String, db_state: State<DbState>) -> CmdResult<()> {
+    let conn = lock_db(&db_state);
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        params![key, value],
+    )?;
+    info!("Set setting '{}' to '{}'", key, value);
+    Ok(())
+}
+
+// Live preview for the name cleanup patterns setting: applies `patterns` (unsaved) to `sample`
+#[command]
+fn preview_name_cleanup(sample: String, patterns: Vec<String>) -> CmdResult<String> {
+    let regexes = compile_name_cleanup_patterns(&patterns).map_err(AppError::Config)?;
+    Ok(clean_mod_name(&sample, &regexes))
+}
+
+#[command]
+fn get_deduction_rules(db_state: State<DbState>) -> CmdResult<Vec<DeductionRule>> {
+    let conn = lock_db(&db_state);
+    Ok(load_deduction_rules(&conn)?)
+}
+
+#[command]
+fn add_deduction_rule(pattern: String, entity_slug: String, db_state: State<DbState>) -> CmdResult<DeductionRule> {
+    compile_deduction_rule(&pattern).map_err(AppError::Config)?;
+    let conn = lock_db(&db_state);
+    let entity_name: String = conn.query_row("SELECT name FROM entities WHERE slug = ?1", params![entity_slug], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Entity '{}' not found", entity_slug)))?;
+    let pattern = pattern.trim().to_string();
+    conn.execute("INSERT INTO deduction_rules (pattern, entity_slug) VALUES (?1, ?2)", params![pattern, entity_slug])?;
+    let id = conn.last_insert_rowid();
+    info!("[add_deduction_rule] Rule {}: '{}' -> {}", id, pattern, entity_slug);
+    Ok(DeductionRule { id, pattern, entity_slug, entity_name: Some(entity_name) })
+}
+
+#[command]
+fn delete_deduction_rule(rule_id: i64, db_state: State<DbState>) -> CmdResult<()> {
+    let conn = lock_db(&db_state);
+    if conn.execute("DELETE FROM deduction_rules WHERE id = ?1", params![rule_id])? == 0 {
+        return Err(AppError::NotFound(format!("Rule {} not found", rule_id)));
+    }
+    info!("[delete_deduction_rule] Removed rule {}.", rule_id);
+    Ok(())
+}
+
+// The saved rule a scan would apply to `sample` (a mod folder name, or its path relative to the mods folder)
+#[command]
+fn test_deduction_rules(sample: String, db_state: State<DbState>) -> CmdResult<Option<DeductionRule>> {
+    let conn = lock_db(&db_state);
+    // Same rules fetch_deduction_maps keeps: existing entity, pattern still compiles
+    let usable: Vec<(GlobMatcher, DeductionRule)> = load_deduction_rules(&conn)?.into_iter()
+        .filter(|rule| rule.entity_name.is_some())
+        .filter_map(|rule| compile_deduction_rule(&rule.pattern).ok().map(|matcher| (matcher, rule)))
+        .collect();
+    let matchers: Vec<(GlobMatcher, String)> = usable.iter().map(|(matcher, rule)| (matcher.clone(), rule.entity_slug.clone())).collect();
+    let sample = sample.trim().replace('\\', "/");
+    Ok(match_deduction_rule(&matchers, sample.trim_matches('/')).map(|index| usable[index].1.clone()))
+}
+
+#[command]
+fn get_ui_prefs(db_state: State<DbState>) -> CmdResult<UiPrefs> {
+    let conn = lock_db(&db_state);
+    let Some(json) = get_setting_value(&conn, SETTINGS_KEY_UI_PREFS)? else { return Ok(UiPrefs::default()); };
+    // A row written by an incompatible version shouldn't lock the user out of the UI
+    Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+        warn!("[get_ui_prefs] Stored UI preferences are invalid, using defaults: {}", e);
+        UiPrefs::default()
+    }))
+}
+
+#[command]
+fn set_ui_prefs(prefs: UiPrefs, db_state: State<DbState>) -> CmdResult<()> {
+    let json = serde_json::to_string(&prefs)?;
+    let conn = lock_db(&db_state);
+    conn.execute("INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)", params![SETTINGS_KEY_UI_PREFS, json])?;
+    Ok(())
+}
+
+// Validates a candidate mods folder before it is persisted: it must be a writable directory.
+// A missing folder is only created when `create_if_missing` is set (i.e. the user confirmed).
+fn validate_mods_folder(path: &str, create_if_missing: bool) -> CmdResult<PathBuf> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err(AppError::Config("Mods folder path cannot be empty.".to_string()));
+    }
+    let folder = PathBuf::from(trimmed);
+
+    if !folder.exists() {
+        if !create_if_missing {
+            return Err(AppError::NotFound(format!("Mods folder does not exist: {}", folder.display())));
+        }
+        fs::create_dir_all(&folder)
+            .map_err(|e| AppError::Config(format!("Failed to create mods folder '{}': {}", folder.display(), e)))?;
+        info!("[set_mods_folder] Created mods folder: {}", folder.display());
+    } else if !folder.is_dir() {
+        return Err(AppError::Config(format!("Mods folder path is not a directory: {}", folder.display())));
+    }
+
+    // Write-test: create and remove a probe file so permission problems surface now, not mid-scan.
+    let probe_path = folder.join(".gmm_write_test");
+    fs::write(&probe_path, b"gmm")
+        .map_err(|e| AppError::Config(format!("Mods folder is not writable '{}': {}", folder.display(), e)))?;
+    if let Err(e) = fs::remove_file(&probe_path) {
+        warn!("[set_mods_folder] failed to remove write-test file {}: {}", probe_path.display(), e);
+    }
+    Ok(folder)
+}
+
+#[command]
+fn set_mods_folder(path: String, create_if_missing: Option<bool>, db_state: State<DbState>) -> CmdResult<()> {
+    let folder = validate_mods_folder(&path, create_if_missing.unwrap_or(false))?;
+    let conn = lock_db(&db_state);
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        params![SETTINGS_KEY_MODS_FOLDER, folder.to_string_lossy().to_string()],
+    )?;
+    info!("[set_mods_folder] Mods folder set to '{}'", folder.display());
+    Ok(())
+}
+
+#[command]
+fn is_first_run(db_state: State<DbState>) -> CmdResult<bool> {
+    let conn = lock_db(&db_state);
+    let mods_folder = get_setting_value(&conn, SETTINGS_KEY_MODS_FOLDER)?;
+    Ok(mods_folder.is_none_or(|p| p.trim().is_empty()))
+}
+
+// Loader folder names commonly used by 3DMigoto-based model importers, per game slug.
+fn loader_folder_names(game_slug: &str) -> &'static [&'static str] {
+    match game_slug {
+        "genshin" => &["GIMI", "3dmigoto", "3DMigoto"],
+        "wuwa" => &["WWMI"],
+        "zzz" => &["ZZMI"],
+        _ => &["3dmigoto", "3DMigoto"],
+    }
+}
+
+// Probes common install locations for an existing `Mods` folder. Only returns suggestions,
+// nothing is persisted; the frontend lets the user pick one.
+#[command]
+fn guess_mods_folder(app_handle: AppHandle, db_state: State<DbState>) -> CmdResult<Vec<String>> {
+    let game_slug = read_app_config(&app_handle)
+        .map(|config| config.requested_active_game)
+        .unwrap_or_default();
+    let loader_names = loader_folder_names(&game_slug);
+
+    let mut probe_paths: Vec<PathBuf> = Vec::new();
+
+    // 1. Sibling of the configured quick launch executable (usually the loader itself)
+    let quick_launch = {
+        let conn = lock_db(&db_state);
+        get_setting_value(&conn, SETTINGS_KEY_QUICK_LAUNCH)?
+    };
+    if let Some(exe_parent) = quick_launch.as_deref().filter(|p| !p.trim().is_empty()).and_then(|p| Path::new(p).parent()) {
+        probe_paths.push(exe_parent.join("Mods"));
+    }
+
+    // 2. Common user/drive roots with loader folders underneath
+    let mut roots: Vec<PathBuf> = Vec::new();
+    if let Some(home) = std::env::var_os("USERPROFILE").or_else(|| std::env::var_os("HOME")).map(PathBuf::from) {
+        for sub in ["", "Desktop", "Documents", "Downloads", "Games"] {
+            roots.push(if sub.is_empty() { home.clone() } else { home.join(sub) });
+        }
+    }
+    if let Some(appdata) = std::env::var_os("APPDATA").map(PathBuf::from) {
+        roots.push(appdata.join("XXMI Launcher"));
+    }
+    if cfg!(target_os = "windows") {
+        for drive in ["C:\\", "D:\\", "E:\\"] {
+            roots.push(PathBuf::from(drive));
+            roots.push(PathBuf::from(drive).join("Games"));
+        }
+    }
+    for root in &roots {
+        for loader in loader_names {
+            probe_paths.push(root.join(loader).join("Mods"));
+            probe_paths.push(root.join("XXMI Launcher").join(loader).join("Mods"));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let candidates: Vec<String> = probe_paths.into_iter()
+        .filter(|p| p.is_dir())
+        .map(|p| p.to_string_lossy().to_string())
+        .filter(|p| seen.insert(p.to_lowercase()))
+        .collect();
+    info!("[guess_mods_folder] Found {} candidate(s) for game '{}': {:?}", candidates.len(), game_slug, candidates);
+    Ok(candidates)
+}
+
+#[command]
+async fn select_directory() -> CmdResult<Option<PathBuf>> { // Removed AppHandle
+    // FIX: Remove AppHandle from new(), use blocking dialog directly
+    let result = dialog::blocking::FileDialogBuilder::new()
+        .set_title("Select Mods Folder")
+        .pick_folder();
+
+    match result {
+        Some(path) => Ok(Some(path)),
+        None => Ok(None), // User cancelled
+    }
+}
+
+#[command]
+async fn select_file() -> CmdResult<Option<PathBuf>> { // Removed AppHandle
+    // FIX: Use add_filter instead of dialog::Filter struct
+    let result = dialog::blocking::FileDialogBuilder::new() // FIX: Remove AppHandle
+        .set_title("Select Quick Launch Executable")
+        .add_filter("Executable", &["exe", "bat", "cmd", "sh", "app"]) // FIX: Use add_filter
+        .add_filter("All Files", &["*"]) // FIX: Use add_filter
+        .pick_file();
+
+    match result {
+        Some(path) => Ok(Some(path)),
+        None => Ok(None), // User cancelled
+    }
+}
+
+#[cfg(target_os = "windows")]
+#[command]
+fn launch_executable_elevated(path: String) -> CmdResult<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Shell::ShellExecuteW;
+    use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    info!("Attempting elevated launch for: {}", path);
+
+    // Convert the path and verb to Windows wide strings (UTF-16)
+    let path_wide: Vec<u16> = std::ffi::OsStr::new(&path)
+        .encode_wide()
+        .chain(std::iter::once(0)) // Null-terminate
+        .collect();
+    let operation_wide: Vec<u16> = std::ffi::OsStr::new("runas") // "runas" verb requests elevation
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+
+    // Call ShellExecuteW to request elevation
+    // HINSTANCE returned is actually an integer value; > 32 indicates success.
+    let result = unsafe {
+        ShellExecuteW(
+            Some(HWND::default()),               // Pass as Option<HWND>
+            PCWSTR(operation_wide.as_ptr()),     // Operation: "runas"
+            PCWSTR(path_wide.as_ptr()),          // File path
+            None,                                // No parameters
+            None,                                // Default working directory
+            SW_SHOWNORMAL,                       // Show command normal state
+        )
+    };
+
+    // --- FIX 1: Explicitly cast result.0 to isize BEFORE comparison ---
+    let result_value = result.0 as isize;
+
+    if result_value > 32 { // Compare the casted value
+        info!("Elevated launch initiated successfully via ShellExecuteW.");
+        Ok(())
+    } else {
+        // result.0 contains the error code as an isize, cast to i32 if needed elsewhere
+        let error_code = result_value as i32; // Cast for error reporting
+        let error_message = format!(
+            "Failed to request elevated launch for '{}'. ShellExecuteW error code: {}",
+            path, error_code
+        );
+        error!("{}", error_message);
+
+        // --- FIX 2: Cast the ERROR_CANCELLED constant to i32 for comparison ---
+        if error_code == windows::Win32::Foundation::ERROR_CANCELLED.0 as i32 {
+             Err(AppError::UserCancelled)
+        } else {
+             Err(AppError::ShellCommand(error_message))
+        }
+    }
+}
+
+#[command]
+async fn launch_executable(path: String, _app_handle: AppHandle) -> CmdResult<()> { // app_handle might not be needed now
+    info!("Attempting to launch (non-elevated) via Command::new: {}", path);
+
+    // FIX: Use Command::new for launching executables
+    let cmd = Command::new(path) // Use the path directly as the command
+        // .args([]) // Add arguments if needed later
         .spawn(); // Spawn the process
 
     match cmd {
@@ -1504,30 +3598,30 @@ async fn launch_executable(path: String, _app_handle: AppHandle) -> CmdResult<()
              while let Some(event) = rx.recv().await {
                  match event {
                     tauri::api::process::CommandEvent::Stdout(line) => {
-                        println!("Launcher stdout: {}", line);
+                        info!("Launcher stdout: {}", line);
                     }
                     tauri::api::process::CommandEvent::Stderr(line) => {
-                        eprintln!("Launcher stderr: {}", line);
+                        error!("Launcher stderr: {}", line);
                     }
                     tauri::api::process::CommandEvent::Error(e) => {
-                         eprintln!("Launcher error event: {}", e);
+                         error!("Launcher error event: {}", e);
                          // If we get the elevation error here, we could suggest the elevated launch
                          if e.contains("os error 740") {
-                             return Err(format!("Failed to launch: The application requires administrator privileges. Try the 'Launch as Admin' button if available, or run GMM as administrator (not recommended). Original error: {}", e));
+                             return Err(AppError::ShellCommand(format!("Failed to launch: The application requires administrator privileges. Try the 'Launch as Admin' button if available, or run GMM as administrator (not recommended). Original error: {}", e)));
                          }
                          // Decide if other errors constitute a failure
                          // return Err(format!("Launcher process event error: {}", e));
                     }
                      tauri::api::process::CommandEvent::Terminated(payload) => {
-                        println!("Launcher terminated: {:?}", payload);
+                        info!("Launcher terminated: {:?}", payload);
                         if let Some(code) = payload.code {
                              if code != 0 {
-                                println!("Launcher exited with non-zero code: {}", code);
+                                info!("Launcher exited with non-zero code: {}", code);
                                 // Optionally return error based on exit code
                                 // return Err(format!("Launcher exited with code {}", code));
                              }
                          } else {
-                             println!("Launcher terminated without exit code (possibly killed).");
+                             info!("Launcher terminated without exit code (possibly killed).");
                          }
                          // Process terminated, break the loop
                          break;
@@ -1535,16 +3629,16 @@ async fn launch_executable(path: String, _app_handle: AppHandle) -> CmdResult<()
                     _ => {} // Ignore other events
                 }
              }
-             println!("Launcher process finished or detached.");
+             info!("Launcher process finished or detached.");
              Ok(()) // Assume success if spawn worked and process finished/detached
         }
         Err(e) => {
-             eprintln!("Failed to spawn launcher: {}", e);
+             error!("Failed to spawn launcher: {}", e);
              // Check for the specific error here too
              if e.to_string().contains("os error 740") {
-                 Err(format!("Failed to launch: The application requires administrator privileges. Try running GMM as administrator (not recommended). Error: {}", e))
+                 Err(AppError::ShellCommand(format!("Failed to launch: The application requires administrator privileges. Try running GMM as administrator (not recommended). Error: {}", e)))
              } else {
-                  Err(format!("Failed to spawn executable: {}", e)) // Convert error to string
+                  Err(AppError::ShellCommand(format!("Failed to spawn executable: {}", e))) // Convert error to string
              }
         }
     }
@@ -1555,27 +3649,26 @@ async fn launch_executable(path: String, _app_handle: AppHandle) -> CmdResult<()
 
 #[command]
 fn get_categories(db_state: State<DbState>) -> CmdResult<Vec<Category>> {
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
-    let mut stmt = conn.prepare("SELECT id, name, slug FROM categories ORDER BY name")
-        .map_err(|e| e.to_string())?; // Convert error
+    let conn = lock_db(&db_state);
+    let mut stmt = conn.prepare("SELECT id, name, slug FROM categories ORDER BY name")?;
     let category_iter = stmt.query_map([], |row| {
         Ok(Category {
             id: row.get(0)?, name: row.get(1)?, slug: row.get(2)?,
         })
-    }).map_err(|e| e.to_string())?; // Convert error
-    category_iter.collect::<SqlResult<Vec<Category>>>().map_err(|e| e.to_string()) // Convert error
+    })?;
+    category_iter.collect::<SqlResult<Vec<Category>>>().map_err(AppError::from)
 }
 
 #[command]
 fn get_category_entities(category_slug: String, db_state: State<DbState>) -> CmdResult<Vec<Entity>> {
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let conn = lock_db(&db_state);
      let category_id: i64 = conn.query_row(
         "SELECT id FROM categories WHERE slug = ?1",
         params![category_slug],
         |row| row.get(0),
     ).map_err(|e| match e {
-        rusqlite::Error::QueryReturnedNoRows => format!("Category '{}' not found", category_slug),
-        _ => e.to_string(),
+        rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(format!("Category '{}' not found", category_slug)),
+        _ => AppError::Sqlite(e),
     })?;
 
      // Fetch id, name, slug - ORDER BY to put 'Other' first
@@ -1586,7 +3679,7 @@ fn get_category_entities(category_slug: String, db_state: State<DbState>) -> Cmd
          ORDER BY
             CASE WHEN slug LIKE '%-other' THEN 0 ELSE 1 END ASC,
             name ASC"
-    ).map_err(|e| e.to_string())?; // Corrected SQL query
+    )?;
 
     let entity_iter = stmt.query_map(params![category_id], |row| {
         Ok(Entity {
@@ -1595,68 +3688,276 @@ fn get_category_entities(category_slug: String, db_state: State<DbState>) -> Cmd
             name: row.get(1)?,
             slug: row.get(2)?,
             description: None,
-            details: None,
+            details: EntityDetails::default(),
             base_image: None,
             mod_count: 0,
             enabled_mod_count: None,
+            disabled_mod_count: None,
             recent_mod_count: None,
             favorite_mod_count: None,
         })
-    }).map_err(|e| e.to_string())?;
-    entity_iter.collect::<SqlResult<Vec<Entity>>>().map_err(|e| e.to_string())
+    })?;
+    entity_iter.collect::<SqlResult<Vec<Entity>>>().map_err(AppError::from)
+}
+
+#[command]
+fn get_all_entities(db_state: State<DbState>) -> CmdResult<Vec<EntityWithCategory>> {
+    let conn = lock_db(&db_state);
+    let mut stmt = conn.prepare(
+        "SELECT e.id, e.name, e.slug, e.details, e.base_image, c.id, c.name, c.slug
+         FROM entities e
+         JOIN categories c ON e.category_id = c.id
+         ORDER BY
+            c.name ASC,
+            CASE WHEN e.slug LIKE '%-other' THEN 0 ELSE 1 END ASC,
+            e.name ASC"
+    )?;
+    let entity_iter = stmt.query_map([], |row| {
+        Ok(EntityWithCategory {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            slug: row.get(2)?,
+            details: row.get(3)?,
+            base_image: row.get(4)?,
+            category_id: row.get(5)?,
+            category_name: row.get(6)?,
+            category_slug: row.get(7)?,
+        })
+    })?;
+    entity_iter.collect::<SqlResult<Vec<EntityWithCategory>>>().map_err(AppError::from)
+}
+
+#[command]
+fn get_entity_default_tag(entity_slug: String, db_state: State<DbState>) -> CmdResult<Option<String>> {
+    let conn = lock_db(&db_state);
+    conn.query_row(
+        "SELECT default_category_tag FROM entities WHERE slug = ?1",
+        params![entity_slug],
+        |row| row.get(0),
+    ).optional()?
+        .ok_or_else(|| AppError::NotFound(format!("Entity '{}' not found.", entity_slug)))
 }
 
+// Sets (or with an empty/None tag, clears) the tag import_archive uses for this entity by default
 #[command]
-fn get_entities_by_category(category_slug: String, db_state: State<DbState>) -> CmdResult<Vec<Entity>> {
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+fn set_entity_default_tag(entity_slug: String, category_tag: Option<String>, db_state: State<DbState>) -> CmdResult<()> {
+    let category_tag = category_tag.map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty());
+    let conn = lock_db(&db_state);
+    let changes = conn.execute(
+        "UPDATE entities SET default_category_tag = ?1 WHERE slug = ?2",
+        params![category_tag, entity_slug],
+    )?;
+    if changes == 0 {
+        return Err(AppError::NotFound(format!("Entity '{}' not found.", entity_slug)));
+    }
+    info!("[set_entity_default_tag] '{}' default tag -> {:?}", entity_slug, category_tag);
+    Ok(())
+}
+
+// An entity created from the UI rather than the definitions (e.g. a character released after the last definitions update)
+#[derive(Deserialize, Debug, Clone)]
+struct NewEntitySpec {
+    category_slug: String,
+    category_name: Option<String>, // Creates the category (with its -other entity) when category_slug doesn't exist yet
+    name: String,
+    slug: Option<String>,          // Defaults to the slugified name
+    description: Option<String>,
+}
+
+impl NewEntitySpec {
+    fn entity_slug(&self) -> String {
+        slugify(self.slug.as_deref().filter(|s| !s.trim().is_empty()).unwrap_or(&self.name))
+    }
+}
+
+// Validates the spec and inserts the entity (and its category if requested). Returns the new entity's slug.
+fn insert_new_entity(conn: &Connection, spec: &NewEntitySpec) -> CmdResult<String> {
+    let name = spec.name.trim();
+    if name.is_empty() { return Err(AppError::ModOperation("Entity name cannot be empty.".to_string())); }
+    let entity_slug = spec.entity_slug();
+    if entity_slug.is_empty() { return Err(AppError::ModOperation(format!("'{}' does not produce a valid entity slug.", name))); }
+    if entity_slug.ends_with(OTHER_ENTITY_SUFFIX) {
+        return Err(AppError::ModOperation(format!("Entity slugs ending in '{}' are reserved.", OTHER_ENTITY_SUFFIX)));
+    }
+    let category_slug = slugify(&spec.category_slug);
+    if category_slug.is_empty() { return Err(AppError::ModOperation("Category must be selected.".to_string())); }
+
+    let exists: bool = conn.query_row("SELECT EXISTS(SELECT 1 FROM entities WHERE slug = ?1)", params![entity_slug], |row| row.get(0))?;
+    if exists { return Err(AppError::ModOperation(format!("An entity with slug '{}' already exists.", entity_slug))); }
+
+    let category_id = match conn.query_row("SELECT id FROM categories WHERE slug = ?1", params![category_slug], |row| row.get::<_, i64>(0)).optional()? {
+        Some(id) => id,
+        None => {
+            let Some(category_name) = spec.category_name.as_deref().map(str::trim).filter(|n| !n.is_empty()) else {
+                return Err(AppError::NotFound(format!("Category '{}' not found.", category_slug)));
+            };
+            conn.execute("INSERT INTO categories (name, slug) VALUES (?1, ?2)", params![category_name, category_slug])?;
+            let category_id = conn.last_insert_rowid();
+            insert_other_entity(conn, category_id, &category_slug)?;
+            info!("[insert_new_entity] Created category '{}' ({})", category_name, category_slug);
+            category_id
+        }
+    };
+
+    let description = spec.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+    conn.execute(
+        "INSERT INTO entities (category_id, name, slug, description, details, base_image) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![category_id, name, entity_slug, description, "{}", None::<String>],
+    )?;
+    info!("[insert_new_entity] Created entity '{}' ({}) in category '{}'", name, entity_slug, category_slug);
+    Ok(entity_slug)
+}
+
+#[command]
+fn create_entity(spec: NewEntitySpec, db_state: State<DbState>) -> CmdResult<String> {
+    let conn = lock_db(&db_state);
+    insert_new_entity(&conn, &spec)
+}
+
+// Counts how many of the given asset folders are enabled vs disabled on disk.
+// Assets hidden by a disabled parent folder count as disabled; missing folders count as neither.
+fn count_asset_states_on_disk(base_mods_path: &Path, clean_relative_paths: &[String]) -> (i32, i32) {
+    let mut enabled = 0;
+    let mut disabled = 0;
+    for clean_relative_path_str in clean_relative_paths {
+        let clean_relative_path = PathBuf::from(clean_relative_path_str.replace("\\", "/"));
+        match asset_state_on_disk(base_mods_path, &clean_relative_path) {
+            Some((_, true)) => enabled += 1,
+            Some((_, false)) => disabled += 1,
+            None if resolve_asset_under_disabled_parent(base_mods_path, &clean_relative_path).is_some() => disabled += 1,
+            None => {}
+        }
+    }
+    (enabled, disabled)
+}
+
+#[command]
+fn get_entities_by_category(
+    category_slug: String,
+    only_with_mods: Option<bool>,
+    sort_by: Option<String>,    // "custom" (default: user order, then name), "name" or "mod_count_desc"
+    pin_other: Option<bool>,    // Keep the '-other' entity first (default true)
+    db_state: State<DbState>
+) -> CmdResult<Vec<Entity>> {
+    let sort_clause = match sort_by.as_deref().unwrap_or("custom") {
+        "custom" => "e.sort_order IS NULL, e.sort_order ASC, e.name ASC",
+        "name" => "e.name ASC",
+        "mod_count_desc" => "mod_count DESC, e.name ASC",
+        other => return Err(AppError::Config(format!("Unknown entity sort '{}'", other))),
+    };
+    let pin_clause = if pin_other.unwrap_or(true) { "CASE WHEN e.slug LIKE '%-other' THEN 0 ELSE 1 END ASC," } else { "" };
+
+    // Without a mods folder the enabled/disabled counts are simply left empty
+    let base_mods_path = get_mods_base_path_from_settings(&db_state).ok();
+    let conn = lock_db(&db_state);
      let category_id: i64 = conn.query_row(
         "SELECT id FROM categories WHERE slug = ?1",
         params![category_slug],
         |row| row.get(0),
     ).map_err(|e| match e {
-        rusqlite::Error::QueryReturnedNoRows => format!("Category '{}' not found", category_slug),
-        _ => e.to_string(),
+        rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(format!("Category '{}' not found", category_slug)),
+        _ => AppError::Sqlite(e),
     })?;
 
-     // Fetch full entity details - ORDER BY to put 'Other' first
-     let mut stmt = conn.prepare(
+     // Fetch full entity details - ORDER BY puts 'Other' first unless unpinned
+     let mut stmt = conn.prepare(&format!(
         "SELECT e.id, e.category_id, e.name, e.slug, e.description, e.details, e.base_image, COUNT(a.id) as mod_count
          FROM entities e LEFT JOIN assets a ON e.id = a.entity_id
          WHERE e.category_id = ?1
          GROUP BY e.id
-         ORDER BY
-            CASE WHEN e.slug LIKE '%-other' THEN 0 ELSE 1 END ASC,
-            e.name ASC" // Corrected SQL query
-    ).map_err(|e| e.to_string())?;
+         HAVING ?2 = 0 OR COUNT(a.id) > 0
+         ORDER BY {} {}",
+        pin_clause, sort_clause
+    ))?;
 
-    let entity_iter = stmt.query_map(params![category_id], |row| {
+    let entity_iter = stmt.query_map(params![category_id, only_with_mods.unwrap_or(false)], |row| {
         Ok(Entity {
             id: row.get(0)?, category_id: row.get(1)?, name: row.get(2)?,
-            slug: row.get(3)?, description: row.get(4)?, details: row.get(5)?,
+            slug: row.get(3)?, description: row.get(4)?,
+            details: parse_entity_details(row.get::<_, Option<String>>(5)?.as_deref(), &row.get::<_, String>(3)?),
             base_image: row.get(6)?, mod_count: row.get(7)?,
             enabled_mod_count: None,
+            disabled_mod_count: None,
             recent_mod_count: None,
             favorite_mod_count: None
         })
-    }).map_err(|e| e.to_string())?;
-    entity_iter.collect::<SqlResult<Vec<Entity>>>().map_err(|e| e.to_string())
+    })?;
+    let mut entities = entity_iter.collect::<SqlResult<Vec<Entity>>>()?;
+
+    if let Some(base_mods_path) = base_mods_path {
+        let mut asset_folder_stmt = conn.prepare("SELECT folder_name FROM assets WHERE entity_id = ?1")?;
+        for entity in entities.iter_mut().filter(|e| e.mod_count > 0) {
+            let folders = asset_folder_stmt.query_map(params![entity.id], |row| row.get::<_, String>(0))?
+                .collect::<SqlResult<Vec<String>>>()?;
+            let (enabled, disabled) = count_asset_states_on_disk(&base_mods_path, &folders);
+            entity.enabled_mod_count = Some(enabled);
+            entity.disabled_mod_count = Some(disabled);
+        }
+    }
+    Ok(entities)
 }
 
 
+// Stores a user-arranged display order for an entity's mods (e.g. to match an in-game cycle). Unlisted mods sort after, by name.
+#[command]
+fn set_asset_order(entity_slug: String, ordered_ids: Vec<i64>, db_state: State<DbState>) -> CmdResult<()> {
+    let mut conn = lock_db(&db_state);
+    let entity_id: i64 = conn.query_row(
+        "SELECT id FROM entities WHERE slug = ?1", params![entity_slug], |row| row.get(0),
+    ).optional()?.ok_or_else(|| AppError::NotFound(format!("Entity '{}' not found", entity_slug)))?;
+
+    let tx = conn.transaction()?;
+    tx.execute("UPDATE assets SET sort_order = NULL WHERE entity_id = ?1", params![entity_id])?;
+    {
+        let mut stmt = tx.prepare("UPDATE assets SET sort_order = ?1 WHERE id = ?2 AND entity_id = ?3")?;
+        for (position, asset_id) in ordered_ids.iter().enumerate() {
+            if stmt.execute(params![position as i64, asset_id, entity_id])? == 0 {
+                warn!("[set_asset_order] Asset {} does not belong to '{}'; skipped.", asset_id, entity_slug);
+            }
+        }
+    }
+    tx.commit()?;
+    info!("[set_asset_order] Saved order of {} mods for '{}'.", ordered_ids.len(), entity_slug);
+    Ok(())
+}
+
+// Stores a user-arranged order for a category's entities. Entities left out go back to name order after the listed ones.
+#[command]
+fn set_entity_order(category_slug: String, ordered_slugs: Vec<String>, db_state: State<DbState>) -> CmdResult<()> {
+    let mut conn = lock_db(&db_state);
+    let category_id: i64 = conn.query_row(
+        "SELECT id FROM categories WHERE slug = ?1", params![category_slug], |row| row.get(0),
+    ).optional()?.ok_or_else(|| AppError::NotFound(format!("Category '{}' not found", category_slug)))?;
+
+    let tx = conn.transaction()?;
+    tx.execute("UPDATE entities SET sort_order = NULL WHERE category_id = ?1", params![category_id])?;
+    {
+        let mut stmt = tx.prepare("UPDATE entities SET sort_order = ?1 WHERE slug = ?2 AND category_id = ?3")?;
+        for (position, slug) in ordered_slugs.iter().enumerate() {
+            if stmt.execute(params![position as i64, slug, category_id])? == 0 {
+                warn!("[set_entity_order] Entity '{}' is not in category '{}'; skipped.", slug, category_slug);
+            }
+        }
+    }
+    tx.commit()?;
+    info!("[set_entity_order] Saved order of {} entities for '{}'.", ordered_slugs.len(), category_slug);
+    Ok(())
+}
+
 #[command]
 fn get_entity_details(entity_slug: String, db_state: State<DbState>) -> CmdResult<Entity> {
-    println!("[get_entity_details] Starting for entity: {}", entity_slug);
+    info!("[get_entity_details] Starting for entity: {}", entity_slug);
     
     // PART 1: Get base entity info with a brief lock
     let entity_info = {
-        let conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+        let conn_guard = lock_db(&db_state);
         let conn = &*conn_guard; // Dereference the guard
         
         let mut stmt = conn.prepare(
             "SELECT e.id, e.category_id, e.name, e.slug, e.description, e.details, e.base_image, COUNT(a.id) as mod_count
              FROM entities e LEFT JOIN assets a ON e.id = a.entity_id
              WHERE e.slug = ?1 GROUP BY e.id"
-        ).map_err(|e| format!("[get_entity_details] DB prepare error: {}", e))?;
+        )?;
         
         // Get basic entity details first
         stmt.query_row(params![entity_slug], |row| {
@@ -1666,16 +3967,17 @@ fn get_entity_details(entity_slug: String, db_state: State<DbState>) -> CmdResul
                 name: row.get(2)?,
                 slug: row.get(3)?, 
                 description: row.get(4)?, 
-                details: row.get(5)?,
+                details: parse_entity_details(row.get::<_, Option<String>>(5)?.as_deref(), &row.get::<_, String>(3)?),
                 base_image: row.get(6)?, 
                 mod_count: row.get(7)?,
                 enabled_mod_count: None,  // Will be populated later
+                disabled_mod_count: None, // Will be populated later
                 recent_mod_count: None,   // Will be populated later
                 favorite_mod_count: None  // Will be populated later
             })
         }).map_err(|e| match e { 
-            rusqlite::Error::QueryReturnedNoRows => format!("Entity '{}' not found", entity_slug),
-            _ => format!("[get_entity_details] DB row error: {}", e),
+            rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(format!("Entity '{}' not found", entity_slug)),
+            _ => AppError::Sqlite(e),
         })?
     }; // conn_guard is released here
     
@@ -1684,22 +3986,20 @@ fn get_entity_details(entity_slug: String, db_state: State<DbState>) -> CmdResul
     
     // PART 2: Get folder paths from DB with a separate brief lock
     let asset_folder_paths: Vec<String> = {
-        let conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+        let conn_guard = lock_db(&db_state);
         let conn = &*conn_guard;
         
         // Prepare statement and collect all folder paths while holding lock
-        let mut stmt = conn.prepare("SELECT folder_name FROM assets WHERE entity_id = ?1")
-            .map_err(|e| format!("[get_entity_details] Error preparing folder query: {}", e))?;
+        let mut stmt = conn.prepare("SELECT folder_name FROM assets WHERE entity_id = ?1")?;
             
-        let folder_iter = stmt.query_map(params![entity.id], |row| row.get::<_, String>(0))
-            .map_err(|e| format!("[get_entity_details] Error executing folder query: {}", e))?;
+        let folder_iter = stmt.query_map(params![entity.id], |row| row.get::<_, String>(0))?;
             
         // Collect all paths into a Vec to release the lock sooner
         let mut paths = Vec::new();
         for result in folder_iter {
             match result {
                 Ok(path) => paths.push(path.replace("\\", "/")),
-                Err(e) => println!("[get_entity_details] Warning: Error fetching path: {}", e),
+                Err(e) => warn!("[get_entity_details] Error fetching path: {}", e),
             }
         }
         paths
@@ -1709,38 +4009,24 @@ fn get_entity_details(entity_slug: String, db_state: State<DbState>) -> CmdResul
     let base_mods_path = match get_mods_base_path_from_settings(&db_state) {
         Ok(path) => path,
         Err(e) => {
-            println!("[get_entity_details] Warning: Error getting base mods path: {}", e);
+            warn!("[get_entity_details] Error getting base mods path: {}", e);
             // We'll proceed with empty counts since we can't check the disk
             entity.enabled_mod_count = Some(0);
+            entity.disabled_mod_count = Some(0);
             entity.recent_mod_count = Some(0);
             entity.favorite_mod_count = Some(0);
             return Ok(entity);
         }
     };
     
-    // PART 4: Count enabled mods by checking disk paths (NO DB LOCK NEEDED)
-    let mut enabled_count = 0;
-    for clean_relative_path_str in &asset_folder_paths {
-        let clean_relative_path = PathBuf::from(clean_relative_path_str);
-        let filename_osstr = match clean_relative_path.file_name() {
-            Some(name) => name,
-            None => continue, // Skip if we can't get filename
-        };
-        
-        let filename_str = filename_osstr.to_string_lossy();
-        if filename_str.is_empty() { continue; }
-        
-        // Check only enabled state path
-        let full_path_if_enabled = base_mods_path.join(&clean_relative_path);
-        if full_path_if_enabled.is_dir() {
-            enabled_count += 1;
-        }
-    }
+    // PART 4: Count enabled/disabled mods by checking disk paths (NO DB LOCK NEEDED)
+    let (enabled_count, disabled_count) = count_asset_states_on_disk(&base_mods_path, &asset_folder_paths);
     entity.enabled_mod_count = Some(enabled_count);
+    entity.disabled_mod_count = Some(disabled_count);
     
     // PART 5: Get recent mod count and favorite counts with a final lock
     {
-        let conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+        let conn_guard = lock_db(&db_state);
         let conn = &*conn_guard;
         
         // Count recent mods (approximation using ID sorting, assuming higher IDs are more recent)
@@ -1756,7 +4042,7 @@ fn get_entity_details(entity_slug: String, db_state: State<DbState>) -> CmdResul
                     entity.recent_mod_count = Some(count);
                 },
                 Err(e) => {
-                    println!("[get_entity_details] Warning: Error counting recent mods: {}", e);
+                    warn!("[get_entity_details] Error counting recent mods: {}", e);
                     entity.recent_mod_count = Some(0);
                 }
             }
@@ -1777,44 +4063,63 @@ fn get_entity_details(entity_slug: String, db_state: State<DbState>) -> CmdResul
                 entity.favorite_mod_count = Some(count);
             },
             Err(e) => {
-                println!("[get_entity_details] Warning: Error counting mods in favorite presets: {}", e);
+                warn!("[get_entity_details] Error counting mods in favorite presets: {}", e);
                 entity.favorite_mod_count = Some(0);
             }
         }
     } // Final conn_guard is released here
     
-    println!("[get_entity_details] Completed for entity: {}", entity_slug);
+    info!("[get_entity_details] Completed for entity: {}", entity_slug);
     Ok(entity)
 }
 
 #[command]
-fn get_assets_for_entity(entity_slug: String, db_state: State<DbState>, _app_handle: AppHandle) -> CmdResult<Vec<Asset>> {
-    let base_mods_path = get_mods_base_path_from_settings(&db_state)
-                             .map_err(|e| format!("[get_assets_for_entity {}] Error getting base mods path: {}", entity_slug, e))?;
+fn get_assets_for_entity(
+    entity_slug: String,
+    include_context: Option<bool>,
+    sort_by: Option<String>, // "name" (default) or "custom" (user order from set_asset_order, then name)
+    db_state: State<DbState>,
+    _app_handle: AppHandle
+) -> CmdResult<Vec<Asset>> {
+    let sort_clause = match sort_by.as_deref().unwrap_or("name") {
+        "name" => "name",
+        "custom" => "sort_order IS NULL, sort_order, name",
+        other => return Err(AppError::Config(format!("Unknown asset sort '{}'", other))),
+    };
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
 
-    let conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let conn_guard = lock_db(&db_state);
     let conn = &*conn_guard;
 
     // --- Entity ID Lookup ---
-    let entity_id: i64 = conn.query_row(
-        "SELECT id FROM entities WHERE slug = ?1",
+    let (entity_id, entity_name, category_slug, category_name): (i64, String, String, String) = conn.query_row(
+        "SELECT e.id, e.name, c.slug, c.name FROM entities e JOIN categories c ON e.category_id = c.id WHERE e.slug = ?1",
         params![entity_slug],
-        |row| row.get(0),
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
     ).map_err(|e| match e {
-        rusqlite::Error::QueryReturnedNoRows => format!("[get_assets_for_entity {}] Entity not found for assets lookup", entity_slug),
-        _ => format!("[get_assets_for_entity {}] DB Error getting entity ID: {}", entity_slug, e),
+        rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(format!("Entity '{}' not found", entity_slug)),
+        _ => AppError::Sqlite(e),
     })?;
+    // All assets share the same entity, so the breadcrumb context is built once
+    let context = include_context.unwrap_or(false).then(|| AssetContext {
+        entity_slug: entity_slug.clone(),
+        entity_name,
+        category_slug,
+        category_name,
+    });
 
     // --- Prepare Statement ---
-    let mut stmt = conn.prepare(
-        "SELECT id, entity_id, name, description, folder_name, image_filename, author, category_tag
-         FROM assets WHERE entity_id = ?1 ORDER BY name"
-    ).map_err(|e| format!("[get_assets_for_entity {}] DB Error preparing asset statement: {}", entity_slug, e))?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, entity_id, name, description, folder_name, image_filename, author, category_tag, created_at, is_enabled, source_url, sort_order, version, state_source
+         FROM assets WHERE entity_id = ?1 ORDER BY {}",
+        sort_clause
+    ))?;
 
     // --- Query Rows ---
     let asset_rows_result = stmt.query_map(params![entity_id], |row| {
         let folder_name_raw: String = row.get(4)?;
-        Ok(Asset {
+        let stored_is_enabled: Option<bool> = row.get(9)?;
+        Ok((stored_is_enabled, Asset {
             id: row.get(0)?,
             entity_id: row.get(1)?,
             name: row.get(2)?,
@@ -1825,161 +4130,639 @@ fn get_assets_for_entity(entity_slug: String, db_state: State<DbState>, _app_han
             author: row.get(6)?,
             category_tag: row.get(7)?,
             is_enabled: false, // Default, will be determined below
-        })
+            hidden_by_disabled_parent: false,
+            created_at: row.get(8)?,
+            source_url: row.get(10)?,
+            sort_order: row.get(11)?,
+            version: row.get(12)?,
+            state_source: row.get(13)?,
+            context: context.clone(),
+        }))
     });
 
     let mut assets_to_return = Vec::new();
+    let mut case_fixed_paths: Vec<(i64, String)> = Vec::new(); // (asset id, clean path as cased on disk)
+    let mut resolved_states: Vec<(i64, bool)> = Vec::new(); // Unknown states resolved from disk, to persist
 
     match asset_rows_result {
         Ok(asset_iter) => {
              for (index, asset_result) in asset_iter.enumerate() {
                  match asset_result {
-                     Ok(mut asset_from_db) => {
+                     Ok((stored_is_enabled, mut asset_from_db)) => {
                          // --- Corrected State Detection Logic ---
                          // `asset_from_db.folder_name` currently holds the CLEAN relative path from DB
                          let clean_relative_path_from_db = PathBuf::from(&asset_from_db.folder_name);
 
-                         // Construct potential paths based on the CLEAN relative path
-                         let filename_osstr = clean_relative_path_from_db.file_name().unwrap_or_default();
-                         let filename_str = filename_osstr.to_string_lossy();
-                         if filename_str.is_empty() {
-                             continue;
+                         // Trust the persisted state (no filesystem access); the background reconcile resyncs it with disk
+                         if let Some(is_enabled) = stored_is_enabled {
+                             if let Some((relative_if_enabled, relative_if_disabled)) = asset_disk_paths(Path::new(""), &clean_relative_path_from_db) {
+                                 let relative_on_disk = if is_enabled { relative_if_enabled } else { relative_if_disabled };
+                                 asset_from_db.is_enabled = is_enabled;
+                                 asset_from_db.folder_name = relative_on_disk.to_string_lossy().replace("\\", "/");
+                                 assets_to_return.push(asset_from_db);
+                                 continue;
+                             }
                          }
-                         let disabled_filename = format!("{}{}", DISABLED_PREFIX, filename_str);
-                         let relative_parent_path = clean_relative_path_from_db.parent();
-
-                         // Path if enabled = base / clean_relative_path
-                         let full_path_if_enabled = base_mods_path.join(&clean_relative_path_from_db);
-
-                         // Path if disabled = base / relative_parent / disabled_filename
-                         let full_path_if_disabled = match relative_parent_path {
-                            Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_filename),
-                            _ => base_mods_path.join(&disabled_filename), // No parent or parent is root
-                         };
-
-                         // Determine state based on which path exists
-                         if full_path_if_enabled.is_dir() {
-                             asset_from_db.is_enabled = true;
-                             // Set folder_name to the actual path found on disk
-                             asset_from_db.folder_name = clean_relative_path_from_db.to_string_lossy().replace("\\", "/");
-                         } else if full_path_if_disabled.is_dir() {
-                             asset_from_db.is_enabled = false;
-                             // Set folder_name to the actual path found on disk (the disabled one)
-                              let disabled_relative_path = match relative_parent_path {
-                                 Some(parent) if parent.as_os_str().len() > 0 => parent.join(&disabled_filename),
-                                 _ => PathBuf::from(&disabled_filename),
-                              };
-                             asset_from_db.folder_name = disabled_relative_path.to_string_lossy().replace("\\", "/");
-                         } else {
-                             // Mod folder doesn't exist in either state
-                             continue; // Skip this asset
+
+                         // Determine state based on which path exists (and, for an enabled folder, its INIs)
+                         match asset_state_on_disk(&base_mods_path, &clean_relative_path_from_db) {
+                             Some((found_path, is_enabled)) => {
+                                 asset_from_db.is_enabled = is_enabled;
+                                 resolved_states.push((asset_from_db.id, is_enabled));
+                                 // Set folder_name to the actual path found on disk (enabled or disabled)
+                                 let relative_on_disk = found_path.strip_prefix(&base_mods_path).unwrap_or(&clean_relative_path_from_db);
+                                 asset_from_db.folder_name = relative_on_disk.to_string_lossy().replace("\\", "/");
+                             }
+                             // Exact path missed, try ignoring case (case-sensitive filesystems only)
+                             None => match if cfg!(windows) { None } else { find_asset_path_ignoring_case(&base_mods_path, &clean_relative_path_from_db) } {
+                                 Some((disk_clean_path, is_enabled)) => {
+                                     let disk_clean_path_str = disk_clean_path.to_string_lossy().replace("\\", "/");
+                                     info!("[get_assets_for_entity {}] Path casing differs on disk: '{}' -> '{}'", entity_slug, asset_from_db.folder_name, disk_clean_path_str);
+                                     let (relative_if_enabled, relative_if_disabled) = asset_disk_paths(Path::new(""), &disk_clean_path).unwrap_or_default();
+                                     let relative_on_disk = if is_enabled { relative_if_enabled } else { relative_if_disabled };
+                                     asset_from_db.is_enabled = is_enabled;
+                                     asset_from_db.folder_name = relative_on_disk.to_string_lossy().replace("\\", "/");
+                                     case_fixed_paths.push((asset_from_db.id, disk_clean_path_str));
+                                 }
+                                 None => match resolve_asset_under_disabled_parent(&base_mods_path, &clean_relative_path_from_db) {
+                                     // A parent folder (e.g. the entity folder) is disabled, report it instead of dropping the mod
+                                     Some(found_path) => {
+                                         asset_from_db.is_enabled = false;
+                                         asset_from_db.hidden_by_disabled_parent = true;
+                                         let relative_on_disk = found_path.strip_prefix(&base_mods_path).unwrap_or(&clean_relative_path_from_db);
+                                         asset_from_db.folder_name = relative_on_disk.to_string_lossy().replace("\\", "/");
+                                     }
+                                     None => continue, // Mod folder doesn't exist in either state, skip this asset
+                                 },
+                             },
                          }
 
                          assets_to_return.push(asset_from_db);
                          // --- End Corrected State Detection ---
                      }
                      Err(e) => {
-                         eprintln!("[get_assets_for_entity {}] Error processing asset row index {}: {}", entity_slug, index, e);
+                         error!("[get_assets_for_entity {}] Error processing asset row index {}: {}", entity_slug, index, e);
                      }
                  }
              }
         }
         Err(e) => {
-             let err_msg = format!("[get_assets_for_entity {}] DB Error preparing asset iterator: {}", entity_slug, e);
-             return Err(err_msg);
+             error!("[get_assets_for_entity {}] DB Error preparing asset iterator: {}", entity_slug, e);
+             return Err(AppError::Sqlite(e));
         }
     }
 
+    // Store the on-disk casing so later lookups hit the exact path
+    for (asset_id, disk_clean_path_str) in case_fixed_paths {
+        if let Err(e) = conn.execute("UPDATE assets SET folder_name = ?1 WHERE id = ?2", params![disk_clean_path_str, asset_id]) {
+            error!("[get_assets_for_entity {}] Failed to update path casing for asset {}: {}", entity_slug, asset_id, e);
+        }
+    }
+    for (asset_id, is_enabled) in resolved_states {
+        if let Err(e) = conn.execute("UPDATE assets SET is_enabled = ?1 WHERE id = ?2", params![is_enabled, asset_id]) {
+            error!("[get_assets_for_entity {}] Failed to store enabled state for asset {}: {}", entity_slug, asset_id, e);
+        }
+    }
     Ok(assets_to_return)
 }
 
+// Enabled state of an asset as found directly on disk. None when the folder is missing, hidden by a
+// disabled parent or differs in casing; those cases are stored as unknown and resolved on next load.
+fn disk_enabled_state(base_mods_path: &Path, clean_relative_path: &Path) -> Option<bool> {
+    asset_state_on_disk(base_mods_path, clean_relative_path).map(|(_, is_enabled)| is_enabled)
+}
+
+// Persists a known (or with None, unknown) enabled state after a rename by the app, marking a known one as
+// set by the app. Failures only leave the column stale, which the next reconcile fixes, so they are
+// logged rather than returned.
+fn store_asset_enabled_state(db_state: &DbState, asset_id: i64, is_enabled: Option<bool>) {
+    let conn = lock_db(db_state);
+    if let Err(e) = conn.execute(
+        "UPDATE assets SET is_enabled = ?1, state_source = COALESCE(?3, state_source) WHERE id = ?2",
+        params![is_enabled, asset_id, is_enabled.map(|_| STATE_SOURCE_APP)],
+    ) {
+        error!("Failed to store enabled state for asset {}: {}", asset_id, e);
+    }
+}
+
+// (asset id, name, entity slug, clean folder path, stored is_enabled, state_source)
+type ReconcileRow = (i64, String, String, String, Option<bool>, Option<String>);
+
+// Single pass over every asset: syncs the persisted is_enabled column with disk, fixes path casing
+// and reports folders that are missing. With `prune_missing`, missing assets are deleted as well.
+fn reconcile_library_state(conn: &mut Connection, base_mods_path: &Path, prune_missing: bool) -> CmdResult<ReconcileReport> {
+    let assets: Vec<ReconcileRow> = {
+        let mut stmt = conn.prepare(
+            "SELECT a.id, a.name, e.slug, a.folder_name, a.is_enabled, a.state_source
+             FROM assets a JOIN entities e ON a.entity_id = e.id",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((
+            row.get(0)?,
+            row.get(1)?,
+            row.get(2)?,
+            row.get::<_, String>(3)?.replace("\\", "/"),
+            row.get(4)?,
+            row.get(5)?,
+        )))?;
+        rows.collect::<SqlResult<Vec<_>>>()?
+    };
+
+    let mut report = ReconcileReport { checked: assets.len(), ..Default::default() };
+    let tx = conn.transaction()?;
+    for (asset_id, name, entity_slug, clean_path, stored_state, state_source) in assets {
+        let clean_relative_path = Path::new(&clean_path);
+        let mut current_state = disk_enabled_state(base_mods_path, clean_relative_path);
+
+        if current_state.is_none() && !cfg!(windows) {
+            if let Some((disk_clean_path, is_enabled)) = find_asset_path_ignoring_case(base_mods_path, clean_relative_path) {
+                let disk_clean_path_str = disk_clean_path.to_string_lossy().replace("\\", "/");
+                tx.execute("UPDATE assets SET folder_name = ?1 WHERE id = ?2", params![disk_clean_path_str, asset_id])?;
+                report.case_fixed += 1;
+                current_state = Some(is_enabled);
+            }
+        }
+
+        let change = ReconcileChange {
+            asset_id, name, entity_slug, folder_name: clean_path.clone(),
+            previous_enabled: stored_state, current_enabled: current_state, previous_state_source: state_source,
+        };
+        if current_state.is_none() {
+            if resolve_asset_under_disabled_parent(base_mods_path, clean_relative_path).is_some() {
+                report.hidden_by_disabled_parent += 1;
+            } else {
+                if prune_missing {
+                    report.pruned += tx.execute("DELETE FROM assets WHERE id = ?1", params![asset_id])?;
+                } else if stored_state.is_some() {
+                    tx.execute("UPDATE assets SET is_enabled = NULL WHERE id = ?1", params![asset_id])?;
+                }
+                report.missing.push(change);
+                continue;
+            }
+        }
+        if current_state != stored_state {
+            // Resolving an unknown state isn't a change anyone made; flipping a known one happened outside the app
+            let changed_on_disk = stored_state.is_some();
+            tx.execute(
+                "UPDATE assets SET is_enabled = ?1, state_source = CASE WHEN ?3 THEN ?4 ELSE state_source END WHERE id = ?2",
+                params![current_state, asset_id, changed_on_disk, STATE_SOURCE_DISK],
+            )?;
+            report.state_changes.push(change);
+        }
+    }
+    tx.commit()?;
+    info!(
+        "[reconcile_state] Checked {} asset(s): {} state change(s), {} missing ({} pruned), {} hidden by a disabled parent, {} casing fix(es).",
+        report.checked, report.state_changes.len(), report.missing.len(), report.pruned, report.hidden_by_disabled_parent, report.case_fixed
+    );
+    Ok(report)
+}
+
+#[command]
+fn reconcile_state(prune_missing: Option<bool>, db_state: State<DbState>) -> CmdResult<ReconcileReport> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let mut conn = lock_db(&db_state);
+    reconcile_library_state(&mut conn, &base_mods_path, prune_missing.unwrap_or(false))
+}
+
+// Re-reads the on-disk state of every asset and updates the persisted is_enabled column.
+// Returns the number of assets whose stored state changed.
+#[command]
+fn verify_disk_state(db_state: State<DbState>) -> CmdResult<usize> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let mut conn = lock_db(&db_state);
+    let report = reconcile_library_state(&mut conn, &base_mods_path, false)?;
+    Ok(report.state_changes.len())
+}
+
+// Runs reconcile_library_state on a blocking worker so stored states catch up with renames made outside
+// the app, then emits ASSET_STATE_DRIFT_EVENT with the affected entities. Throttled, and skipped while a
+// scan or VACUUM runs or the mods folder is unreachable (an unplugged drive would otherwise mark
+// every asset missing).
+fn spawn_background_reconcile(app_handle: &AppHandle) {
+    {
+        let mut last_run = LAST_BACKGROUND_RECONCILE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if last_run.is_some_and(|started| started.elapsed() < BACKGROUND_RECONCILE_MIN_INTERVAL) { return; }
+        *last_run = Some(std::time::Instant::now());
+    }
+    let app_handle = app_handle.clone();
+    async_runtime::spawn_blocking(move || {
+        if BULK_DB_TASK_RUNNING.load(Ordering::SeqCst) { return; }
+        let db_state: State<DbState> = app_handle.state();
+        let Ok(base_mods_path) = get_mods_base_path_from_settings(&db_state) else { return; };
+        if !base_mods_path.is_dir() {
+            warn!("[background_reconcile] Mods folder {} is not reachable; skipping.", base_mods_path.display());
+            return;
+        }
+        let report = {
+            let mut conn = lock_db(&db_state);
+            match reconcile_library_state(&mut conn, &base_mods_path, false) {
+                Ok(report) => report,
+                Err(e) => { warn!("[background_reconcile] Failed: {}", e); return; }
+            }
+        };
+        let mut drifted_entities: Vec<String> = report.state_changes.iter()
+            .chain(report.missing.iter().filter(|change| change.previous_enabled.is_some()))
+            .map(|change| change.entity_slug.clone())
+            .collect();
+        drifted_entities.sort();
+        drifted_entities.dedup();
+        if !drifted_entities.is_empty() {
+            if let Err(e) = app_handle.emit_all(ASSET_STATE_DRIFT_EVENT, &drifted_entities) {
+                warn!("[background_reconcile] Failed to emit {}: {}", ASSET_STATE_DRIFT_EVENT, e);
+            }
+        }
+    });
+}
+
+// Canonical form of a folder/INI name with a malformed disable prefix, or None if it is already fine
+fn normalized_disabled_name(name: &str) -> Option<String> {
+    let prefix = DISABLED_PREFIX_RUN_REGEX.find(name)?;
+    let rest = &name[prefix.end()..];
+    if prefix.as_str() == DISABLED_PREFIX || rest.is_empty() { return None; }
+    Some(format!("{}{}", DISABLED_PREFIX, rest))
+}
+
+// Collapses repeated or oddly written DISABLED prefixes (on folders and mod INIs) to a single "DISABLED_",
+// then fixes stored paths that carried a prefix and resyncs enabled states with the DB.
+#[command]
+async fn normalize_disabled_prefixes(db_state: State<'_, DbState>) -> CmdResult<PrefixNormalizationReport> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    if !base_mods_path.is_dir() {
+        return Err(AppError::NotFound(format!("Mods folder not found: {}", base_mods_path.display())));
+    }
+
+    let walk_base = base_mods_path.clone();
+    let rename_task = async_runtime::spawn_blocking(move || {
+        let mut report = PrefixNormalizationReport::default();
+        // Children first, so renaming a folder never invalidates paths still to be visited
+        for entry in WalkDir::new(&walk_base).min_depth(1).contents_first(true).into_iter().filter_map(|e| e.ok()) {
+            let is_ini = entry.file_type().is_file() && entry.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ini"));
+            if !entry.file_type().is_dir() && !is_ini { continue; }
+            let Some(new_name) = normalized_disabled_name(&entry.file_name().to_string_lossy()) else { continue; };
+            let from = entry.path();
+            let to = from.with_file_name(&new_name);
+            let relative = |p: &Path| p.strip_prefix(&walk_base).unwrap_or(p).to_string_lossy().replace("\\", "/");
+            if to.exists() {
+                warn!("[normalize_disabled_prefixes] Skipping '{}': '{}' already exists.", from.display(), new_name);
+                report.conflicts.push(relative(from));
+                continue;
+            }
+            match rename_verified(from, &to) {
+                Ok(()) => {
+                    info!("[normalize_disabled_prefixes] '{}' -> '{}'", from.display(), new_name);
+                    report.renamed.push(PrefixRename { from: relative(from), to: relative(&to) });
+                }
+                Err(e) => report.errors.push(e.to_string()),
+            }
+        }
+        report
+    });
+    let mut report = rename_task.await
+        .map_err(|e| AppError::Other(format!("Prefix normalization failed: {}", e)))?;
+
+    let mut conn = lock_db(&db_state);
+    // Folders scanned as "DISABLED_DISABLED_X" were stored as ".../DISABLED_X"; store the clean ".../X" instead
+    let dirty_paths: Vec<(i64, String)> = {
+        let mut stmt = conn.prepare("SELECT id, folder_name FROM assets")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get::<_, String>(1)?.replace("\\", "/"))))?;
+        rows.collect::<SqlResult<Vec<_>>>()?
+    };
+    for (asset_id, folder_name) in dirty_paths {
+        let (parent, leaf) = folder_name.rsplit_once('/').map_or(("", folder_name.as_str()), |(p, l)| (p, l));
+        let Some(prefix) = DISABLED_PREFIX_RUN_REGEX.find(leaf) else { continue; };
+        let clean_leaf = &leaf[prefix.end()..];
+        if clean_leaf.is_empty() { continue; }
+        let clean_path = if parent.is_empty() { clean_leaf.to_string() } else { format!("{}/{}", parent, clean_leaf) };
+        match conn.execute("UPDATE OR IGNORE assets SET folder_name = ?1 WHERE id = ?2", params![clean_path, asset_id]) {
+            Ok(1) => report.db_paths_fixed += 1,
+            Ok(_) => report.errors.push(format!("Asset {} could not be moved to '{}': another asset already uses that path.", asset_id, clean_path)),
+            Err(e) => report.errors.push(format!("Asset {}: {}", asset_id, e)),
+        }
+    }
+
+    report.reconcile = reconcile_library_state(&mut conn, &base_mods_path, false)?;
+    info!(
+        "[normalize_disabled_prefixes] {} renamed, {} conflict(s), {} error(s), {} DB path(s) fixed.",
+        report.renamed.len(), report.conflicts.len(), report.errors.len(), report.db_paths_fixed
+    );
+    Ok(report)
+}
+
+// Creates the "-other" entity of every category that lost it. Returns the recreated slugs.
+fn recreate_other_entities(conn: &Connection) -> CmdResult<Vec<String>> {
+    let categories: Vec<(i64, String)> = conn.prepare("SELECT id, slug FROM categories")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<SqlResult<_>>()?;
+    let mut created = Vec::new();
+    for (category_id, category_slug) in &categories {
+        if insert_other_entity(conn, *category_id, category_slug)? > 0 {
+            let other_slug = format!("{}{}", category_slug, OTHER_ENTITY_SUFFIX);
+            info!("[recreate_other_entities] Recreated '{}'.", other_slug);
+            created.push(other_slug);
+        }
+    }
+    Ok(created)
+}
+
+// Moves assets pointing at an entity that no longer exists into the "-other" entity of the category folder
+// they live in, or the fallback category's when that folder isn't a known category. Returns how many moved.
+fn reassign_orphaned_assets(conn: &Connection) -> CmdResult<usize> {
+    let orphans: Vec<(i64, String)> = conn.prepare(
+        "SELECT a.id, a.folder_name FROM assets a LEFT JOIN entities e ON a.entity_id = e.id WHERE e.id IS NULL"
+    )?.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<SqlResult<_>>()?;
+    if orphans.is_empty() { return Ok(0); }
+
+    let maps = fetch_deduction_maps(conn)?;
+    let mut reassigned = 0;
+    for (asset_id, folder_name) in &orphans {
+        // Mods live at <category>/<entity>/<mod>
+        let folder_category = folder_name.replace('\\', "/").split('/').next().unwrap_or_default().to_string();
+        let category_slug = if maps.category_slug_to_id.contains_key(&folder_category) { folder_category } else { maps.fallback_category_slug.clone() };
+        let other_slug = format!("{}{}", category_slug, OTHER_ENTITY_SUFFIX);
+        let Some(other_id) = conn.query_row("SELECT id FROM entities WHERE slug = ?1", params![other_slug], |row| row.get::<_, i64>(0)).optional()? else {
+            warn!("[reassign_orphaned_assets] No '{}' entity to move orphaned asset {} into.", other_slug, asset_id);
+            continue;
+        };
+        reassigned += conn.execute("UPDATE assets SET entity_id = ?1 WHERE id = ?2", params![other_id, asset_id])?;
+        info!("[reassign_orphaned_assets] Moved orphaned asset {} ('{}') to '{}'.", asset_id, folder_name, other_slug);
+    }
+    Ok(reassigned)
+}
+
+// Repair tool for users who deleted an "-other" entity: recreates the missing ones and rehomes orphaned assets.
+#[command]
+fn ensure_other_entities(db_state: State<DbState>) -> CmdResult<OtherEntityRepairReport> {
+    let mut conn = lock_db(&db_state);
+    let tx = conn.transaction()?;
+    let report = OtherEntityRepairReport {
+        created: recreate_other_entities(&tx)?,
+        assets_reassigned: reassign_orphaned_assets(&tx)?,
+    };
+    tx.commit()?;
+    info!("[ensure_other_entities] Recreated {} entities, reassigned {} assets.", report.created.len(), report.assets_reassigned);
+    Ok(report)
+}
+
+// Rows whose foreign key points at a missing parent, grouped per relation ("assets -> entities")
+fn foreign_key_violations(conn: &Connection) -> CmdResult<Vec<ForeignKeyViolation>> {
+    let mut counts: HashMap<(String, String), usize> = HashMap::new();
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(2)?)))?;
+    for row in rows {
+        *counts.entry(row?).or_default() += 1;
+    }
+    let mut violations: Vec<ForeignKeyViolation> = counts.into_iter()
+        .map(|((table, parent), count)| ForeignKeyViolation { table, parent, count })
+        .collect();
+    violations.sort_by(|a, b| (&a.table, &a.parent).cmp(&(&b.table, &b.parent)));
+    Ok(violations)
+}
+
+// Checks every foreign key in the database. Existing databases may predate `PRAGMA foreign_keys = ON`
+// or have been edited by hand. With `repair`, assets without an entity move to an "-other" entity and
+// preset/hotkey rows for deleted assets or presets are dropped. Entities without a category are only reported,
+// since their mods' folders still sit under the old category.
+#[command]
+fn check_integrity(repair: Option<bool>, db_state: State<DbState>) -> CmdResult<IntegrityReport> {
+    let mut conn = lock_db(&db_state);
+    let mut report = IntegrityReport { found: foreign_key_violations(&conn)?, ..Default::default() };
+    info!("[check_integrity] Found {} kind(s) of foreign key violation.", report.found.len());
+
+    if repair.unwrap_or(false) && !report.found.is_empty() {
+        let tx = conn.transaction()?;
+        report.other_entities_created = recreate_other_entities(&tx)?;
+        report.assets_reassigned = reassign_orphaned_assets(&tx)?;
+        report.rows_deleted += tx.execute(
+            "DELETE FROM preset_assets WHERE asset_id NOT IN (SELECT id FROM assets) OR preset_id NOT IN (SELECT id FROM presets)", [],
+        )?;
+        report.rows_deleted += tx.execute("DELETE FROM hotkeys WHERE asset_id NOT IN (SELECT id FROM assets)", [])?;
+        tx.commit()?;
+        report.remaining = foreign_key_violations(&conn)?;
+        info!("[check_integrity] Reassigned {} assets, deleted {} dangling rows, {} kind(s) of violation left.",
+            report.assets_reassigned, report.rows_deleted, report.remaining.len());
+    } else {
+        report.remaining = report.found.clone();
+    }
+    Ok(report)
+}
+
 #[command]
 fn toggle_asset_enabled(entity_slug: String, asset: Asset, db_state: State<DbState>) -> CmdResult<bool> {
     // Note: asset.folder_name passed from frontend is the CURRENT name on disk.
     // We use the asset.id to get the CLEAN relative path from DB for robust path construction.
-    println!("[toggle_asset_enabled] Toggling asset: ID={}, Name={}, UI Folder='{}', UI Enabled State={}", asset.id, asset.name, asset.folder_name, asset.is_enabled);
+    info!("[toggle_asset_enabled] Toggling asset: ID={}, Name={}, UI Folder='{}', UI Enabled State={}", asset.id, asset.name, asset.folder_name, asset.is_enabled);
+    toggle_asset_on_disk(&db_state, asset.id, &asset.name)
+}
 
+// Flips an asset between enabled and disabled based on the state found on disk, using the configured
+// disable strategy. Shared by the toggle command and global hotkeys. Returns the new enabled state.
+fn toggle_asset_on_disk(db_state: &DbState, asset_id: i64, asset_name: &str) -> CmdResult<bool> {
     // Get BASE mods path
-    let base_mods_path = get_mods_base_path_from_settings(&db_state).map_err(|e| e.to_string())?;
+    let base_mods_path = get_mods_base_path_from_settings(db_state)?;
 
     // Fetch the CLEAN STORED relative path from DB using asset ID
-    let clean_relative_path_from_db_str = {
-         let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
-         conn.query_row::<String, _, _>(
+    let (clean_relative_path_from_db_str, use_ini_strategy) = {
+         let conn = lock_db(db_state);
+         let path = conn.query_row::<String, _, _>(
             "SELECT folder_name FROM assets WHERE id = ?1", // Expecting clean path here
-            params![asset.id],
+            params![asset_id],
             |row| row.get(0),
-         ).map_err(|e| format!("Failed to get relative path from DB for asset ID {}: {}", asset.id, e))?
+         )?;
+         (path, use_ini_disable_strategy(&conn))
     };
      // Ensure forward slashes for PathBuf consistency
      let clean_relative_path_from_db_str = clean_relative_path_from_db_str.replace("\\", "/");
      let clean_relative_path_from_db = PathBuf::from(&clean_relative_path_from_db_str);
-     println!("[toggle_asset_enabled] Clean relative path from DB: '{}'", clean_relative_path_from_db.display());
-
+     info!("[toggle_asset_enabled] Clean relative path from DB: '{}'", clean_relative_path_from_db.display());
 
-    // --- FIX: Construct potential paths correctly ---
-    let filename_osstr = clean_relative_path_from_db.file_name().ok_or_else(|| format!("Could not extract filename from DB path: {}", clean_relative_path_from_db.display()))?;
-    let filename_str = filename_osstr.to_string_lossy();
-    if filename_str.is_empty() {
-        return Err(format!("Filename extracted from DB path is empty: {}", clean_relative_path_from_db.display()));
-    }
-    let disabled_filename = format!("{}{}", DISABLED_PREFIX, filename_str);
-    let relative_parent_path = clean_relative_path_from_db.parent();
 
-    // Full path if enabled = base / clean_relative_path
-    let full_path_if_enabled = base_mods_path.join(&clean_relative_path_from_db);
+    // --- Construct potential paths (enabled / DISABLED_ prefixed) ---
+    let (full_path_if_enabled, full_path_if_disabled) = asset_disk_paths(&base_mods_path, &clean_relative_path_from_db)
+        .ok_or_else(|| AppError::ModOperation(format!("Filename extracted from DB path is empty: {}", clean_relative_path_from_db.display())))?;
 
-    // Full path if disabled = base / relative_parent / disabled_filename
-    let full_path_if_disabled = match relative_parent_path {
-       Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_filename),
-       _ => base_mods_path.join(&disabled_filename), // No parent or parent is root
-    };
-
-    println!("[toggle_asset_enabled] Constructed enabled path check: {}", full_path_if_enabled.display());
-    println!("[toggle_asset_enabled] Constructed disabled path check: {}", full_path_if_disabled.display());
+    info!("[toggle_asset_enabled] Constructed enabled path check: {}", full_path_if_enabled.display());
+    info!("[toggle_asset_enabled] Constructed disabled path check: {}", full_path_if_disabled.display());
 
 
-    // Determine the CURRENT full path and the TARGET full path based on the *actual* state on disk
-    let (current_full_path, target_full_path, new_enabled_state) =
-        if full_path_if_enabled.is_dir() { // Check if the ENABLED path exists
-            // It's currently enabled on disk, target is the disabled path
-             println!("[toggle_asset_enabled] Detected state on disk: ENABLED (found {})", full_path_if_enabled.display());
-            (full_path_if_enabled, full_path_if_disabled, false) // New state will be disabled
-        } else if full_path_if_disabled.is_dir() { // Check if the DISABLED path exists
-            // It's currently disabled on disk, target is the enabled path
-             println!("[toggle_asset_enabled] Detected state on disk: DISABLED (found {})", full_path_if_disabled.display());
-            (full_path_if_disabled, full_path_if_enabled, true) // New state will be enabled
-        } else {
+    // Determine the CURRENT state based on what is *actually* on disk
+    let new_enabled_state = match asset_state_on_disk(&base_mods_path, &clean_relative_path_from_db) {
+        Some((current_path, is_enabled)) => {
+            info!("[toggle_asset_enabled] Detected state on disk: {} (found {})", if is_enabled { "ENABLED" } else { "DISABLED" }, current_path.display());
+            !is_enabled
+        }
+        None => {
+            if let Some(hidden_path) = resolve_asset_under_disabled_parent(&base_mods_path, &clean_relative_path_from_db) {
+                return Err(AppError::ModOperation(format!(
+                    "Cannot toggle mod '{}': a parent folder of '{}' is disabled. Enable the parent folder first.",
+                    asset_name, hidden_path.display()
+                )));
+            }
             // Neither exists, something is wrong. Error based on DB path.
-             println!("[toggle_asset_enabled] Error: Mod folder not found on disk based on DB relative path!");
+             error!("[toggle_asset_enabled] Mod folder not found on disk based on DB relative path!");
             // Use the better error message from before
-             return Err(format!(
+             return Err(AppError::NotFound(format!(
                 "Cannot toggle mod '{}': Folder not found at expected locations derived from DB path '{}' (Checked {} and {}). Did the folder get moved or deleted?",
-                asset.name, // Use the display name from the asset object
+                asset_name, // Use the display name from the asset object
                 clean_relative_path_from_db.display(), // Show the clean path we checked against
                 full_path_if_enabled.display(),
                 full_path_if_disabled.display()
-            ));
-        };
+            )));
+        }
+    };
 
-    println!("[toggle_asset_enabled] Current actual path: {}", current_full_path.display());
-    println!("[toggle_asset_enabled] Target path for rename: {}", target_full_path.display());
+    // Perform the rename (folder or INIs)
+    let path_after = set_asset_state_on_disk(&base_mods_path, &clean_relative_path_from_db, new_enabled_state, use_ini_strategy)?;
 
-    // Perform the rename
-    fs::rename(&current_full_path, &target_full_path)
-        .map_err(|e| format!("Failed to rename '{}' to '{}': {}", current_full_path.display(), target_full_path.display(), e))?;
+    // Only record the new state once the disk actually shows it
+    let state_after = asset_state_on_disk(&base_mods_path, &clean_relative_path_from_db).map(|(_, is_enabled)| is_enabled);
+    if state_after != Some(new_enabled_state) {
+        error!("[toggle_asset_enabled] State on disk after toggle is {:?}, expected {}", state_after, new_enabled_state);
+        return Err(AppError::FileLocked(format!(
+            "Toggling '{}' did not take effect on disk. {}", asset_name, FILE_LOCKED_HINT
+        )));
+    }
 
-    println!("[toggle_asset_enabled] Renamed successfully. New logical state should be: {}", new_enabled_state);
+    info!("[toggle_asset_enabled] Renamed successfully ({}). New logical state should be: {}", path_after.display(), new_enabled_state);
+    store_asset_enabled_state(db_state, asset_id, Some(new_enabled_state));
 
     // Return the actual NEW state after the rename
     Ok(new_enabled_state)
 }
 
 
+fn use_external_previews(conn: &Connection) -> bool {
+    matches!(get_setting_value(conn, SETTINGS_KEY_PREVIEW_STORAGE), Ok(Some(v)) if v == PREVIEW_STORAGE_APP)
+}
+
+// One folder per game: each game has its own database, so asset ids repeat across games
+fn previews_dir(app_handle: &AppHandle) -> CmdResult<PathBuf> {
+    Ok(get_app_data_dir(app_handle)?.join(PREVIEWS_DIR_NAME).join(read_app_config(app_handle)?.last_active_game))
+}
+
+// File behind an "external:" image_filename; None for images inside the mod folder
+fn external_preview_path(app_handle: &AppHandle, image_filename: &str) -> CmdResult<Option<PathBuf>> {
+    let Some(file_name) = image_filename.strip_prefix(EXTERNAL_PREVIEW_PREFIX) else { return Ok(None); };
+    let mut components = Path::new(file_name).components();
+    if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+        return Err(AppError::ModOperation(format!("Invalid external preview name '{}'.", file_name)));
+    }
+    Ok(Some(previews_dir(app_handle)?.join(file_name)))
+}
+
+// Removes every stored preview of the asset (one per extension it may have been saved with)
+fn remove_external_previews(app_handle: &AppHandle, asset_id: i64) -> CmdResult<()> {
+    let Ok(read_dir) = fs::read_dir(previews_dir(app_handle)?) else { return Ok(()); };
+    let stem = asset_id.to_string();
+    for entry in read_dir.flatten() {
+        if entry.path().file_stem().is_some_and(|s| s == stem.as_str()) {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    Ok(())
+}
+
+// Saves an asset's custom preview under previews/, replacing any earlier one. Returns the image_filename value.
+fn save_external_preview(app_handle: &AppHandle, asset_id: i64, data: &[u8], extension: &str) -> CmdResult<String> {
+    let dir = previews_dir(app_handle)?;
+    fs::create_dir_all(&dir)?;
+    remove_external_previews(app_handle, asset_id)?;
+    let file_name = format!("{}.{}", asset_id, extension);
+    fs::write(dir.join(&file_name), data)?;
+    info!("[save_external_preview] Stored preview for asset {} as '{}'.", asset_id, file_name);
+    Ok(format!("{}{}", EXTERNAL_PREVIEW_PREFIX, file_name))
+}
+
+// Lowercase image extension of `path`, "png" when it has none we recognize
+fn image_extension(path: &Path) -> String {
+    path.extension().and_then(OsStr::to_str).map(str::to_lowercase)
+        .filter(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()))
+        .unwrap_or_else(|| "png".to_string())
+}
+
+#[derive(Serialize, Debug)]
+struct AssetImageFile {
+    filename: String, // Relative to the mod folder, '/' separated; the value stored in image_filename
+    path: String,
+    size: u64,
+    is_active: bool,
+}
+
+// Resolves the asset's folder on disk (enabled or disabled) along with its current image_filename
+fn resolve_asset_folder_and_image(conn: &Connection, asset_id: i64, base_mods_path: &Path) -> Result<(PathBuf, Option<String>), AppError> {
+    let asset_info = get_asset_location_info(conn, asset_id)?;
+    let image_filename: Option<String> = conn.query_row("SELECT image_filename FROM assets WHERE id = ?1", params![asset_id], |row| row.get(0))?;
+    let (mod_folder, _) = resolve_asset_on_disk(base_mods_path, Path::new(&asset_info.clean_relative_path))
+        .ok_or_else(|| AppError::NotFound(format!("Mod folder for asset ID {} not found on disk.", asset_id)))?;
+    Ok((mod_folder, image_filename))
+}
+
+// Every image in the mod folder (two levels deep, to catch a screenshots/ subfolder)
+#[command]
+fn list_asset_images(asset_id: i64, db_state: State<DbState>) -> CmdResult<Vec<AssetImageFile>> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let (mod_folder, active_image) = resolve_asset_folder_and_image(&lock_db(&db_state), asset_id, &base_mods_path)?;
+
+    let mut images: Vec<AssetImageFile> = WalkDir::new(&mod_folder).min_depth(1).max_depth(2).into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| e.path().extension().and_then(OsStr::to_str).is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str())))
+        .filter_map(|e| {
+            let filename = e.path().strip_prefix(&mod_folder).ok()?.to_string_lossy().replace("\\", "/");
+            Some(AssetImageFile {
+                is_active: active_image.as_deref().is_some_and(|active| active.replace("\\", "/").eq_ignore_ascii_case(&filename)),
+                path: e.path().to_string_lossy().to_string(),
+                size: e.metadata().map(|m| m.len()).unwrap_or(0),
+                filename,
+            })
+        })
+        .collect();
+    images.sort_by(|a, b| b.is_active.cmp(&a.is_active).then_with(|| a.filename.to_lowercase().cmp(&b.filename.to_lowercase())));
+    Ok(images)
+}
+
+// Points image_filename at another image already in the mod folder (nothing is copied or renamed)
+#[command]
+fn set_active_preview(asset_id: i64, filename: String, db_state: State<DbState>) -> CmdResult<()> {
+    let relative_image = Path::new(&filename);
+    let is_image = relative_image.extension().and_then(OsStr::to_str).is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()));
+    if !is_image || !relative_image.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(AppError::ModOperation(format!("'{}' is not an image inside the mod folder.", filename)));
+    }
+
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let conn = lock_db(&db_state);
+    let (mod_folder, _) = resolve_asset_folder_and_image(&conn, asset_id, &base_mods_path)?;
+    if !mod_folder.join(relative_image).is_file() {
+        return Err(AppError::NotFound(format!("Image '{}' not found in the mod folder.", filename)));
+    }
+    conn.execute("UPDATE assets SET image_filename = ?1 WHERE id = ?2", params![filename.replace("\\", "/"), asset_id])?;
+    info!("[set_active_preview] Asset {} now uses '{}' as its preview.", asset_id, filename);
+    Ok(())
+}
+
+#[derive(Serialize, Debug)]
+struct AssetImageInfo {
+    path: String,
+    modified: i64, // Unix milliseconds; with `size`, lets the frontend reuse image bytes it already read
+    size: u64,
+}
+
+#[command]
+fn get_asset_image_info(asset_id: i64, db_state: State<DbState>, app_handle: AppHandle) -> CmdResult<AssetImageInfo> {
+    let path = get_asset_image_path(asset_id, db_state, app_handle)?;
+    let metadata = fs::metadata(&path)?;
+    let modified = metadata.modified().ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_millis() as i64);
+    Ok(AssetImageInfo { path, modified, size: metadata.len() })
+}
+
 #[command]
 fn get_asset_image_path(
     asset_id: i64,
-    db_state: State<DbState>
+    db_state: State<DbState>,
+    app_handle: AppHandle
 ) -> CmdResult<String> {
     // --- Data needed from DB ---
     let base_mods_path_str: String;
@@ -1988,14 +4771,13 @@ fn get_asset_image_path(
 
     // --- Acquire lock *only* for DB reads ---
     { // Scope for the MutexGuard
-        println!("[get_asset_image_path ID: {}] Acquiring DB lock...", asset_id);
-        let conn_guard = db_state.0.lock().map_err(|_| format!("[get_asset_image_path ID: {}] DB lock poisoned", asset_id))?;
+        info!("[get_asset_image_path ID: {}] Acquiring DB lock...", asset_id);
+        let conn_guard = lock_db(&db_state);
         let conn = &*conn_guard;
 
         // 1. Get base mods path from settings
-        base_mods_path_str = get_setting_value(conn, SETTINGS_KEY_MODS_FOLDER)
-            .map_err(|e| format!("[get_asset_image_path ID: {}] DB Error getting base path: {}", asset_id, e))?
-            .ok_or_else(|| format!("[get_asset_image_path ID: {}] Mods folder path not set", asset_id))?;
+        base_mods_path_str = get_setting_value(conn, SETTINGS_KEY_MODS_FOLDER)?
+            .ok_or_else(|| AppError::Config("Mods folder path not set".to_string()))?;
 
         // 2. Fetch asset info (clean path and image filename) using asset_id
         let (fetched_path, fetched_image_opt): (String, Option<String>) = conn.query_row(
@@ -2003,8 +4785,8 @@ fn get_asset_image_path(
             params![asset_id],
             |row| Ok((row.get(0)?, row.get(1)?))
         ).map_err(|e| match e {
-            rusqlite::Error::QueryReturnedNoRows => format!("[get_asset_image_path ID: {}] Asset not found.", asset_id),
-            _ => format!("[get_asset_image_path ID: {}] DB Error getting asset info: {}", asset_id, e),
+            rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(format!("Asset with ID {} not found", asset_id)),
+            _ => AppError::Sqlite(e),
         })?;
 
         clean_relative_path_str = fetched_path.replace("\\", "/"); // Normalize path separators immediately
@@ -2012,179 +4794,601 @@ fn get_asset_image_path(
              Some(name) if !name.is_empty() => name,
              _ => {
                  // If no image filename in DB, we can stop early. Release lock implicitly.
-                 return Err(format!("[get_asset_image_path ID: {}] Asset does not have an associated image filename.", asset_id));
+                 return Err(AppError::NotFound(format!("[get_asset_image_path ID: {}] Asset does not have an associated image filename.", asset_id)));
              }
         };
 
-        println!("[get_asset_image_path ID: {}] Releasing DB lock.", asset_id);
+        info!("[get_asset_image_path ID: {}] Releasing DB lock.", asset_id);
         // MutexGuard `conn_guard` is dropped here, releasing the lock
     }
     // --- Lock is released ---
 
+    // Previews stored by the app live outside the mod folder, so check there first
+    if let Some(external_path) = external_preview_path(&app_handle, &image_filename)? {
+        if !external_path.is_file() {
+            return Err(AppError::NotFound(format!("Stored preview '{}' not found.", external_path.display())));
+        }
+        return Ok(external_path.to_string_lossy().into_owned());
+    }
+
     // --- Filesystem operations (No DB lock needed) ---
-    println!("[get_asset_image_path ID: {}] Performing filesystem checks...", asset_id);
+    info!("[get_asset_image_path ID: {}] Performing filesystem checks...", asset_id);
     let base_mods_path = PathBuf::from(base_mods_path_str);
     let clean_relative_path_buf = PathBuf::from(&clean_relative_path_str); // Already normalized
 
     // 3. Determine current folder path (enabled or disabled)
-    let mod_folder_filename_osstr = clean_relative_path_buf.file_name()
-        .ok_or_else(|| format!("[get_asset_image_path ID: {}] Cannot get folder filename from '{}'", asset_id, clean_relative_path_str))?;
-    let mod_folder_filename_str = mod_folder_filename_osstr.to_string_lossy();
-    let disabled_mod_folder_filename = format!("{}{}", DISABLED_PREFIX, mod_folder_filename_str);
-    let relative_parent_path = clean_relative_path_buf.parent();
-
-    let full_path_if_enabled = base_mods_path.join(&clean_relative_path_buf);
-    let full_path_if_disabled = match relative_parent_path {
-        Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_mod_folder_filename),
-        _ => base_mods_path.join(&disabled_mod_folder_filename),
-    };
+    let (full_path_if_enabled, full_path_if_disabled) = asset_disk_paths(&base_mods_path, &clean_relative_path_buf)
+        .ok_or_else(|| AppError::ModOperation(format!("Cannot get folder filename from '{}'", clean_relative_path_str)))?;
 
-    // Use is_dir which might be slightly faster than exists() if we only care about directories
-    let current_mod_folder_path = if full_path_if_enabled.is_dir() {
-        println!("[get_asset_image_path ID: {}] Found enabled path: {}", asset_id, full_path_if_enabled.display());
-        full_path_if_enabled
-    } else if full_path_if_disabled.is_dir() {
-        println!("[get_asset_image_path ID: {}] Found disabled path: {}", asset_id, full_path_if_disabled.display());
-        full_path_if_disabled
-    } else {
-        // Folder not found. This isn't necessarily an error for *this* function,
-        // but we can't construct the image path. Return an error.
-        println!("[get_asset_image_path ID: {}] Mod folder not found on disk.", asset_id);
-        return Err(format!("Mod folder for asset ID {} not found on disk (Checked '{}' and '{}').", asset_id, full_path_if_enabled.display(), full_path_if_disabled.display()));
+    let current_mod_folder_path = match resolve_asset_on_disk(&base_mods_path, &clean_relative_path_buf) {
+        Some((path, is_enabled)) => {
+            info!("[get_asset_image_path ID: {}] Found {} path: {}", asset_id, if is_enabled { "enabled" } else { "disabled" }, path.display());
+            path
+        }
+        None => {
+            // Folder not found. This isn't necessarily an error for *this* function,
+            // but we can't construct the image path. Return an error.
+            info!("[get_asset_image_path ID: {}] Mod folder not found on disk.", asset_id);
+            return Err(AppError::NotFound(format!("Mod folder for asset ID {} not found on disk (Checked '{}' and '{}').", asset_id, full_path_if_enabled.display(), full_path_if_disabled.display())));
+        }
     };
 
     // 4. Construct the FULL path to the image file within the found folder
     let image_full_path = current_mod_folder_path.join(&image_filename);
-    println!("[get_asset_image_path ID: {}] Checking image file: {}", asset_id, image_full_path.display());
+    info!("[get_asset_image_path ID: {}] Checking image file: {}", asset_id, image_full_path.display());
+
+    // 5. Check if the image file *itself* exists
+    if !image_full_path.is_file() {
+        info!("[get_asset_image_path ID: {}] Image file does not exist.", asset_id);
+        return Err(AppError::NotFound(format!("Image file '{}' not found in mod folder '{}'.", image_filename, current_mod_folder_path.display())));
+    }
+
+    // Return the absolute path string for the frontend
+    info!("[get_asset_image_path ID: {}] Success, returning path: {}", asset_id, image_full_path.display());
+    Ok(image_full_path.to_string_lossy().into_owned())
+}
+
+// Opens the OS file explorer at `path`. When `select` is set on Windows, the explorer
+// opens the parent folder with `path` highlighted (`explorer /select,<path>`).
+fn spawn_file_explorer(path: &Path, select: bool) -> CmdResult<()> {
+    let command_name;
+    let arg; // Variable to hold the single argument string
+
+    // Determine OS-specific command and prepare the argument
+    if cfg!(target_os = "windows") {
+        command_name = "explorer";
+        // Windows explorer doesn't handle forward slashes well, especially in UNC paths
+        let native_path = path.to_string_lossy().replace("/", "\\");
+        arg = if select { format!("/select,{}", native_path) } else { native_path };
+    } else if cfg!(target_os = "macos") {
+        command_name = "open";
+        arg = path.to_str().ok_or_else(|| AppError::ShellCommand("Invalid UTF-8 path string for macOS".to_string()))?.to_string();
+    } else { // Assume Linux/Unix-like
+        command_name = "xdg-open";
+        arg = path.to_str().ok_or_else(|| AppError::ShellCommand("Invalid UTF-8 path string for Linux".to_string()))?.to_string();
+    }
+
+    info!("Executing: {} \"{}\"", command_name, arg); // Log with quotes for clarity
+
+    match Command::new(command_name).args(&[arg]).spawn() {
+        Ok((_, _child)) => {
+             info!("File explorer command spawned successfully.");
+             Ok(())
+        },
+        Err(e) => {
+             error!("Failed to spawn file explorer command '{}': {}", command_name, e);
+             Err(AppError::ShellCommand(format!("Failed to open folder using '{}': {}", command_name, e)))
+        }
+    }
+}
+
+// Doesn't depend on the mods folder setting, so it works on a fresh install too
+#[command]
+fn open_app_data_folder(app_handle: AppHandle) -> CmdResult<()> {
+    let data_dir = get_app_data_dir(&app_handle)?;
+    info!("Opening app data folder: {}", data_dir.display());
+    fs::create_dir_all(&data_dir)?;
+    spawn_file_explorer(&data_dir, false)
+}
+
+#[command]
+fn open_mods_folder(_app_handle: AppHandle, db_state: State<DbState>) -> CmdResult<()> { // Mark app_handle unused
+    let mods_path = get_mods_base_path_from_settings(&db_state)?;
+    info!("Opening mods folder: {}", mods_path.display());
+
+    if !mods_path.exists() || !mods_path.is_dir() { // Check it's a directory
+        error!("Configured mods folder does not exist or is not a directory: {}", mods_path.display());
+        return Err(AppError::Config(format!("Configured mods folder does not exist or is not a directory: {}", mods_path.display())));
+    }
+
+    spawn_file_explorer(&mods_path, false)
+}
+
+// Globs from the scan_ignore_patterns setting plus <mods root>/.gmmignore. A pattern without a '/'
+// matches a folder name anywhere ("_tools", "*.backup"); one with a '/' matches the path relative to the mods root.
+fn load_scan_ignore_set(conn: &Connection, base_mods_path: &Path) -> Result<GlobSet, AppError> {
+    let setting_patterns = get_setting_value(conn, SETTINGS_KEY_SCAN_IGNORE_PATTERNS)?.unwrap_or_default();
+    let file_patterns = fs::read_to_string(base_mods_path.join(SCAN_IGNORE_FILENAME)).unwrap_or_default();
+
+    let mut builder = GlobSetBuilder::new();
+    let patterns = setting_patterns.split([',', '\n']).chain(file_patterns.lines())
+        .map(str::trim)
+        .filter(|p| !p.is_empty() && !p.starts_with('#'));
+    for pattern in patterns {
+        let pattern = pattern.replace('\\', "/");
+        let pattern = pattern.trim_matches('/');
+        let glob = if pattern.contains('/') { pattern.to_string() } else { format!("**/{}", pattern) };
+        match GlobBuilder::new(&glob).case_insensitive(true).literal_separator(true).build() {
+            Ok(glob) => { builder.add(glob); }
+            Err(e) => error!("[load_scan_ignore_set] Ignoring invalid pattern '{}': {}", pattern, e),
+        }
+    }
+    builder.build().map_err(|e| AppError::Config(format!("Failed to build scan ignore list: {}", e)))
+}
+
+// Whether the walker should skip this entry (and, for folders, everything under it)
+fn is_scan_ignored(ignore_set: &GlobSet, base_mods_path: &Path, path: &Path) -> bool {
+    if is_import_staging_dir(path) { return true; }
+    if ignore_set.is_empty() { return false; }
+    path.strip_prefix(base_mods_path)
+        .is_ok_and(|relative| ignore_set.is_match(relative.to_string_lossy().replace('\\', "/")))
+}
+
+// A mod folder found during a scan, resolved to the entity and DB path it would be stored under
+struct ScanCandidate {
+    entity_id: i64,
+    relative_path: String, // Clean (no DISABLED_ prefix), forward slashes
+    is_enabled: bool,
+    deduced: DeducedInfo,
+}
+
+// Shared by scan_mods_directory and preview_scan: deduce the entity for a mod folder and compute its DB path.
+fn resolve_scan_candidate(mod_path: &PathBuf, base_mods_path: &PathBuf, maps: &DeductionMaps) -> Result<ScanCandidate, String> {
+    let path_display = mod_path.display().to_string();
+    let deduced = deduce_mod_info_v2(mod_path, base_mods_path, maps)
+        .ok_or_else(|| format!("Failed to deduce mod info for path '{}'", path_display))?;
+    info!("[Scan Task] Deduced slug for '{}': {}", path_display, deduced.entity_slug);
+
+    let entity_id = *maps.entity_slug_to_id.get(&deduced.entity_slug).ok_or_else(|| format!(
+        "Deduced slug '{}' for path '{}' does NOT exist in the entity map! Check DB initialization and deduction logic.",
+        deduced.entity_slug, path_display
+    ))?;
+
+    let relative_path_buf = mod_path.strip_prefix(base_mods_path)
+        .map_err(|_| format!("Could not strip base path prefix from '{}'", path_display))?;
+    let filename_str = relative_path_buf.file_name().unwrap_or_default().to_string_lossy();
+    // Also strip a bare "DISABLED" so previews of not-yet-renamed folders get the same path the scan would store
+    let clean_filename = filename_str.strip_prefix(DISABLED_PREFIX)
+        .or_else(|| filename_str.strip_prefix("DISABLED"))
+        .unwrap_or(&filename_str);
+    let is_enabled = !filename_str.starts_with("DISABLED");
+    let relative_path = match relative_path_buf.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.join(clean_filename).to_string_lossy().to_string(),
+        _ => clean_filename.to_string(),
+    }.replace("\\", "/");
+
+    Ok(ScanCandidate { entity_id, relative_path, is_enabled, deduced })
+}
+
+// Inserts a scanned mod folder as a new asset under the candidate's entity
+fn insert_scanned_asset(conn: &Connection, candidate: &ScanCandidate, mod_path: &Path) -> SqlResult<usize> {
+    let deduced = &candidate.deduced;
+    conn.execute(
+        "INSERT INTO assets (entity_id, name, description, folder_name, image_filename, author, category_tag, created_at, is_enabled, version) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            candidate.entity_id,
+            deduced.mod_name,
+            deduced.description,
+            candidate.relative_path,
+            deduced.image_filename,
+            deduced.author,
+            deduced.mod_type_tag,
+            folder_created_at(mod_path),
+            candidate.is_enabled,
+            deduced.version
+        ]
+    )
+}
+
+#[derive(Serialize, Debug)]
+struct PlannedAssetChange {
+    action: String, // "add" | "remove" | "unresolved"
+    folder_path: String, // Absolute path on disk (or stored relative path for "remove")
+    relative_path: Option<String>,
+    mod_name: Option<String>,
+    entity_slug: Option<String>,
+    entity_name: Option<String>,
+    category_slug: Option<String>,
+    is_enabled: Option<bool>,
+    error: Option<String>,
+}
+
+// Runs the scan walk and deduction without touching the disk or DB, returning what scan_mods_directory would change.
+#[command]
+async fn preview_scan(db_state: State<'_, DbState>) -> CmdResult<Vec<PlannedAssetChange>> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    if !base_mods_path.is_dir() {
+        return Err(AppError::Config(format!("Mods directory path is not a valid directory: {}", base_mods_path.display())));
+    }
+
+    let (maps, existing_assets, entity_names, ignore_set) = {
+        let conn = lock_db(&db_state);
+        let maps = fetch_deduction_maps(&conn)?;
+        let ignore_set = load_scan_ignore_set(&conn, &base_mods_path)?;
+        // (entity_id, clean folder path) -> (asset name, entity slug)
+        let mut stmt = conn.prepare("SELECT a.entity_id, a.folder_name, a.name, e.slug FROM assets a JOIN entities e ON a.entity_id = e.id")?;
+        let existing_assets = stmt.query_map([], |row| Ok((
+            (row.get::<_, i64>(0)?, row.get::<_, String>(1)?.replace("\\", "/")),
+            (row.get::<_, String>(2)?, row.get::<_, String>(3)?),
+        )))?.collect::<SqlResult<HashMap<_, _>>>()?;
+        let mut stmt = conn.prepare("SELECT slug, name FROM entities")?;
+        let entity_names = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<SqlResult<HashMap<_, _>>>()?;
+        (maps, existing_assets, entity_names, ignore_set)
+    };
+
+    let plan_task = async_runtime::spawn_blocking(move || {
+        let mut changes = Vec::new();
+        let mut found_keys = HashSet::new();
+        let mut walker = WalkDir::new(&base_mods_path).min_depth(1).into_iter().filter_entry(|e| !is_scan_ignored(&ignore_set, &base_mods_path, e.path()));
+
+        while let Some(entry_result) = walker.next() {
+            let entry = match entry_result {
+                Ok(entry) => entry,
+                Err(e) => { error!("[preview_scan] Error accessing path: {}", e); continue; }
+            };
+            if !entry.file_type().is_dir() { continue; }
+            let mod_path = entry.path().to_path_buf();
+            if !is_mod_root_folder(&mod_path) { continue; }
+            walker.skip_current_dir();
+
+            let folder_path = mod_path.display().to_string();
+            match resolve_scan_candidate(&mod_path, &base_mods_path, &maps) {
+                Ok(candidate) => {
+                    let key = (candidate.entity_id, candidate.relative_path.clone());
+                    if existing_assets.contains_key(&key) {
+                        found_keys.insert(key);
+                        continue;
+                    }
+                    let entity_slug = candidate.deduced.entity_slug;
+                    changes.push(PlannedAssetChange {
+                        action: "add".to_string(),
+                        folder_path,
+                        relative_path: Some(candidate.relative_path),
+                        mod_name: Some(candidate.deduced.mod_name),
+                        entity_name: entity_names.get(&entity_slug).cloned(),
+                        category_slug: maps.entity_slug_to_category_slug.get(&entity_slug).cloned(),
+                        entity_slug: Some(entity_slug),
+                        is_enabled: Some(candidate.is_enabled),
+                        error: None,
+                    });
+                }
+                Err(e) => changes.push(PlannedAssetChange {
+                    action: "unresolved".to_string(),
+                    folder_path,
+                    relative_path: None,
+                    mod_name: None,
+                    entity_slug: None,
+                    entity_name: None,
+                    category_slug: None,
+                    is_enabled: None,
+                    error: Some(e),
+                }),
+            }
+        }
+
+        // Anything in the DB that the walk didn't find would be pruned
+        for (key, (name, entity_slug)) in existing_assets.iter() {
+            if found_keys.contains(key) { continue; }
+            changes.push(PlannedAssetChange {
+                action: "remove".to_string(),
+                folder_path: key.1.clone(),
+                relative_path: Some(key.1.clone()),
+                mod_name: Some(name.clone()),
+                entity_name: entity_names.get(entity_slug).cloned(),
+                category_slug: maps.entity_slug_to_category_slug.get(entity_slug).cloned(),
+                entity_slug: Some(entity_slug.clone()),
+                is_enabled: None,
+                error: None,
+            });
+        }
+        changes
+    });
+
+    let changes = plan_task.await.map_err(|e| AppError::Other(format!("Scan preview task failed: {}", e)))?;
+    info!("[preview_scan] {} planned changes.", changes.len());
+    Ok(changes)
+}
+
+// Mod folders on disk with no asset row at all (e.g. dropped in by hand since the last scan), as "add"/"unresolved"
+// entries like preview_scan's. Matches on the stored path alone, so a mod filed under another entity isn't reported.
+// Import the chosen ones with apply_scan_decisions.
+#[command]
+async fn find_orphaned_mods(db_state: State<'_, DbState>) -> CmdResult<Vec<PlannedAssetChange>> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    if !base_mods_path.is_dir() {
+        return Err(AppError::Config(format!("Mods directory path is not a valid directory: {}", base_mods_path.display())));
+    }
+
+    let (maps, known_paths, entity_names, ignore_set) = {
+        let conn = lock_db(&db_state);
+        let maps = fetch_deduction_maps(&conn)?;
+        let ignore_set = load_scan_ignore_set(&conn, &base_mods_path)?;
+        let mut stmt = conn.prepare("SELECT folder_name FROM assets")?;
+        let known_paths = stmt.query_map([], |row| row.get::<_, String>(0))?
+            .map(|path| path.map(|p| p.replace("\\", "/")))
+            .collect::<SqlResult<HashSet<_>>>()?;
+        let mut stmt = conn.prepare("SELECT slug, name FROM entities")?;
+        let entity_names = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<SqlResult<HashMap<_, _>>>()?;
+        (maps, known_paths, entity_names, ignore_set)
+    };
+
+    let find_task = async_runtime::spawn_blocking(move || {
+        let mut orphans = Vec::new();
+        let mut walker = WalkDir::new(&base_mods_path).min_depth(1).into_iter().filter_entry(|e| !is_scan_ignored(&ignore_set, &base_mods_path, e.path()));
+
+        while let Some(entry_result) = walker.next() {
+            let entry = match entry_result {
+                Ok(entry) => entry,
+                Err(e) => { error!("[find_orphaned_mods] Error accessing path: {}", e); continue; }
+            };
+            if !entry.file_type().is_dir() { continue; }
+            let mod_path = entry.path().to_path_buf();
+            if !is_mod_root_folder(&mod_path) { continue; }
+            walker.skip_current_dir();
+
+            let folder_path = mod_path.display().to_string();
+            match resolve_scan_candidate(&mod_path, &base_mods_path, &maps) {
+                Ok(candidate) => {
+                    if known_paths.contains(&candidate.relative_path) { continue; }
+                    let entity_slug = candidate.deduced.entity_slug;
+                    orphans.push(PlannedAssetChange {
+                        action: "add".to_string(),
+                        folder_path,
+                        relative_path: Some(candidate.relative_path),
+                        mod_name: Some(candidate.deduced.mod_name),
+                        entity_name: entity_names.get(&entity_slug).cloned(),
+                        category_slug: maps.entity_slug_to_category_slug.get(&entity_slug).cloned(),
+                        entity_slug: Some(entity_slug),
+                        is_enabled: Some(candidate.is_enabled),
+                        error: None,
+                    });
+                }
+                Err(e) => orphans.push(PlannedAssetChange {
+                    action: "unresolved".to_string(),
+                    folder_path,
+                    relative_path: None,
+                    mod_name: None,
+                    entity_slug: None,
+                    entity_name: None,
+                    category_slug: None,
+                    is_enabled: None,
+                    error: Some(e),
+                }),
+            }
+        }
+        orphans
+    });
 
-    // 5. Check if the image file *itself* exists
-    if !image_full_path.is_file() {
-        println!("[get_asset_image_path ID: {}] Image file does not exist.", asset_id);
-        return Err(format!("Image file '{}' not found in mod folder '{}'.", image_filename, current_mod_folder_path.display()));
-    }
+    let orphans = find_task.await.map_err(|e| AppError::Other(format!("Orphaned mod search failed: {}", e)))?;
+    info!("[find_orphaned_mods] Found {} folders not in the database.", orphans.len());
+    Ok(orphans)
+}
 
-    // Return the absolute path string for the frontend
-    println!("[get_asset_image_path ID: {}] Success, returning path: {}", asset_id, image_full_path.display());
-    Ok(image_full_path.to_string_lossy().into_owned())
+#[derive(Serialize, Debug)]
+struct ScanReportEntry {
+    relative_path: String,
+    status: String, // "mod" | "unresolved" | "grouping" | "ignored" | "folder"
+    has_ini: bool,
+    entity_slug: Option<String>,
+    mod_name: Option<String>,
+    detail: Option<String>, // Why it was skipped/unresolved, or the DB path it would use
 }
 
+// Read-only walk of the mods folder explaining how a scan sees each directory, for pasting into bug reports.
 #[command]
-fn open_mods_folder(_app_handle: AppHandle, db_state: State<DbState>) -> CmdResult<()> { // Mark app_handle unused
-    let mods_path = get_mods_base_path_from_settings(&db_state).map_err(|e| e.to_string())?;
-    println!("Opening mods folder: {}", mods_path.display());
-
-    if !mods_path.exists() || !mods_path.is_dir() { // Check it's a directory
-        eprintln!("Configured mods folder does not exist or is not a directory: {}", mods_path.display());
-        return Err(format!("Configured mods folder does not exist or is not a directory: {}", mods_path.display()));
+async fn debug_scan_report(db_state: State<'_, DbState>) -> CmdResult<Vec<ScanReportEntry>> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    if !base_mods_path.is_dir() {
+        return Err(AppError::Config(format!("Mods directory path is not a valid directory: {}", base_mods_path.display())));
     }
+    let (maps, ignore_set) = {
+        let conn = lock_db(&db_state);
+        (fetch_deduction_maps(&conn)?, load_scan_ignore_set(&conn, &base_mods_path)?)
+    };
 
-    let command_name;
-    let arg; // Variable to hold the single argument string
+    let report_task = async_runtime::spawn_blocking(move || {
+        let mut report = Vec::new();
+        let mut walker = WalkDir::new(&base_mods_path).min_depth(1).into_iter();
+        while let Some(entry_result) = walker.next() {
+            let entry = match entry_result {
+                Ok(entry) => entry,
+                Err(e) => {
+                    report.push(ScanReportEntry {
+                        relative_path: e.path().and_then(|p| p.strip_prefix(&base_mods_path).ok()).map(|p| p.to_string_lossy().replace('\\', "/")).unwrap_or_default(),
+                        status: "folder".to_string(), has_ini: false, entity_slug: None, mod_name: None,
+                        detail: Some(format!("Could not be read: {}", e)),
+                    });
+                    continue;
+                }
+            };
+            if !entry.file_type().is_dir() { continue; }
+            let dir_path = entry.path().to_path_buf();
+            let relative_path = dir_path.strip_prefix(&base_mods_path).unwrap_or(&dir_path).to_string_lossy().replace('\\', "/");
+            let has_ini = has_ini_file(&dir_path);
+            let mut report_entry = ScanReportEntry { relative_path, status: "folder".to_string(), has_ini, entity_slug: None, mod_name: None, detail: None };
+
+            if is_scan_ignored(&ignore_set, &base_mods_path, &dir_path) {
+                walker.skip_current_dir();
+                report_entry.status = "ignored".to_string();
+                report_entry.detail = Some(if is_import_staging_dir(&dir_path) { "Leftover import staging folder".to_string() } else { "Matches the scan ignore list".to_string() });
+            } else if is_mod_root_folder(&dir_path) {
+                walker.skip_current_dir();
+                match resolve_scan_candidate(&dir_path, &base_mods_path, &maps) {
+                    Ok(candidate) => {
+                        report_entry.status = "mod".to_string();
+                        report_entry.entity_slug = Some(candidate.deduced.entity_slug);
+                        report_entry.mod_name = Some(candidate.deduced.mod_name);
+                        report_entry.detail = Some(format!("Stored as '{}' ({})", candidate.relative_path, if candidate.is_enabled { "enabled" } else { "disabled" }));
+                    }
+                    Err(e) => {
+                        report_entry.status = "unresolved".to_string();
+                        report_entry.detail = Some(e);
+                    }
+                }
+            } else if has_ini {
+                report_entry.status = "grouping".to_string();
+                report_entry.detail = Some("Has an INI but no mod resources of its own; subfolders are scanned as mods".to_string());
+            }
+            report.push(report_entry);
+        }
+        report
+    });
 
-    // Determine OS-specific command and prepare the argument
-    if cfg!(target_os = "windows") {
-        command_name = "explorer";
-        // Windows explorer doesn't always handle forward slashes well, especially in UNC paths, canonicalize might help sometimes
-        // Or just ensure it's a string representation
-         arg = mods_path.to_string_lossy().to_string();
-    } else if cfg!(target_os = "macos") {
-        command_name = "open";
-         arg = mods_path.to_str().ok_or("Invalid path string for macOS")?.to_string();
-    } else { // Assume Linux/Unix-like
-        command_name = "xdg-open";
-         arg = mods_path.to_str().ok_or("Invalid path string for Linux")?.to_string();
-    }
+    let report = report_task.await.map_err(|e| AppError::Other(format!("Scan report task failed: {}", e)))?;
+    info!("[debug_scan_report] Reported on {} folders.", report.len());
+    Ok(report)
+}
 
-    println!("Executing: {} \"{}\"", command_name, arg); // Log with quotes for clarity
+// Inserts reviewed mod folders (absolute path, entity slug) under the entity the user picked instead of the deduced one.
+#[command]
+fn apply_scan_decisions(decisions: Vec<(String, String)>, db_state: State<DbState>) -> CmdResult<ScanDecisionResult> {
+    info!("[apply_scan_decisions] Applying {} reviewed decisions.", decisions.len());
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let conn_guard = lock_db(&db_state);
+    let conn = &*conn_guard;
+    let maps = fetch_deduction_maps(conn)?;
 
-    // FIX: Use .args() with a slice containing the single argument
-    match Command::new(command_name).args(&[arg]).spawn() {
-        Ok((_, _child)) => {
-             println!("File explorer command spawned successfully.");
-             Ok(())
-        },
-        Err(e) => {
-             eprintln!("Failed to spawn file explorer command '{}': {}", command_name, e);
-             Err(format!("Failed to open folder using '{}': {}", command_name, e))
+    let mut result = ScanDecisionResult::default();
+    for (folder_path, entity_slug) in decisions {
+        let mod_path = PathBuf::from(&folder_path);
+        if !mod_path.is_dir() {
+            result.errors.push(format!("'{}': folder not found on disk.", folder_path));
+            continue;
+        }
+        let Some(&entity_id) = maps.entity_slug_to_id.get(&entity_slug) else {
+            result.errors.push(format!("'{}': entity '{}' not found.", folder_path, entity_slug));
+            continue;
+        };
+        let mut candidate = match resolve_scan_candidate(&mod_path, &base_mods_path, &maps) {
+            Ok(candidate) => candidate,
+            Err(e) => { result.errors.push(e); continue; }
+        };
+        candidate.entity_id = entity_id;
+        candidate.deduced.entity_slug = entity_slug;
+
+        let existing: Option<(i64, i64)> = conn.query_row(
+            "SELECT id, entity_id FROM assets WHERE folder_name = ?1",
+            params![candidate.relative_path],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?;
+
+        match existing {
+            Some((_, current_entity_id)) if current_entity_id == entity_id => {
+                info!("[apply_scan_decisions] '{}' is already assigned to '{}'.", candidate.relative_path, candidate.deduced.entity_slug);
+            }
+            Some((asset_id, _)) => {
+                conn.execute("UPDATE assets SET entity_id = ?1 WHERE id = ?2", params![entity_id, asset_id])?;
+                info!("[apply_scan_decisions] Reassigned asset {} to '{}'.", asset_id, candidate.deduced.entity_slug);
+                result.reassigned += 1;
+            }
+            None => match insert_scanned_asset(conn, &candidate, &mod_path) {
+                Ok(_) => {
+                    info!("[apply_scan_decisions] Inserted '{}' under '{}' (ID: {}).", candidate.relative_path, candidate.deduced.entity_slug, conn.last_insert_rowid());
+                    result.inserted += 1;
+                }
+                Err(e) => result.errors.push(format!("'{}': {}", candidate.relative_path, e)),
+            },
         }
     }
+
+    info!("[apply_scan_decisions] Inserted {}, reassigned {}, {} errors.", result.inserted, result.reassigned, result.errors.len());
+    Ok(result)
 }
 
 #[command]
 async fn scan_mods_directory(db_state: State<'_, DbState>, app_handle: AppHandle) -> CmdResult<()> {
-    println!("Starting robust mod directory scan with pruning...");
-    let base_mods_path = get_mods_base_path_from_settings(&db_state).map_err(|e| e.to_string())?;
-    println!("Scanning base path: {}", base_mods_path.display());
+    let _bulk_task_guard = BulkDbTaskGuard::acquire("a scan")?;
+    info!("Starting robust mod directory scan with pruning...");
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    info!("Scanning base path: {}", base_mods_path.display());
 
     if !base_mods_path.is_dir() {
         let err_msg = format!("Mods directory path is not a valid directory: {}", base_mods_path.display());
-        app_handle.emit_all(SCAN_ERROR_EVENT, &err_msg).unwrap_or_else(|e| eprintln!("Failed to emit scan error event: {}", e));
-        return Err(err_msg);
+        app_handle.emit_all(SCAN_ERROR_EVENT, &err_msg).unwrap_or_else(|e| error!("Failed to emit scan error event: {}", e));
+        return Err(AppError::Config(err_msg));
     }
 
     // --- Preparation ---
-    let deduction_maps = {
-        let conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let (deduction_maps, ignore_set, scan_threads) = {
+        let conn_guard = lock_db(&db_state);
         let conn = &*conn_guard;
-        fetch_deduction_maps(conn).map_err(|e| format!("Failed to pre-fetch deduction maps: {}", e))?
+        let maps = fetch_deduction_maps(conn)?;
+        // 0 (the default) lets rayon use one thread per CPU
+        let scan_threads = get_setting_value(conn, SETTINGS_KEY_SCAN_THREADS)?
+            .and_then(|value| value.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        (maps, load_scan_ignore_set(conn, &base_mods_path)?, scan_threads)
     };
-    println!("[Scan Prep] Deduction maps loaded.");
+    info!("[Scan Prep] Deduction maps loaded.");
 
     let db_path = {
-        let data_dir = get_app_data_dir(&app_handle).map_err(|e| e.to_string())?;
+        let data_dir = get_app_data_dir(&app_handle)?;
         data_dir.join(DB_NAME)
     };
     let db_path_str = db_path.to_string_lossy().to_string();
     let base_mods_path_clone = base_mods_path.clone();
     let app_handle_clone = app_handle.clone();
     let maps_clone = deduction_maps.clone();
+    let ignore_set_clone = ignore_set.clone();
 
-    println!("[Scan Prep] Calculating total potential mod folders...");
+    info!("[Scan Prep] Calculating total potential mod folders...");
     let potential_mod_folders_for_count: Vec<PathBuf> = WalkDir::new(&base_mods_path)
         .min_depth(1)
         .into_iter()
+        .filter_entry(|e| !is_scan_ignored(&ignore_set, &base_mods_path, e.path()))
         .filter_map(|e| e.ok().filter(|entry| entry.file_type().is_dir()))
         .filter(|e| {
              // Temporary check for rename condition as well for count (might be slightly inaccurate if rename fails later)
              let path = e.path();
              let filename = path.file_name().unwrap_or_default().to_string_lossy();
              // Check for INI OR if it needs renaming (so it's counted)
-             has_ini_file(&path.to_path_buf()) || (filename.starts_with("DISABLED") && !filename.starts_with(DISABLED_PREFIX))
+             is_mod_root_folder(path) || (filename.starts_with("DISABLED") && !filename.starts_with(DISABLED_PREFIX))
          })
         .map(|e| e.path().to_path_buf())
         .collect();
     let total_to_process = potential_mod_folders_for_count.len();
-    println!("[Scan Prep] Found {} potential mod folders for progress total (includes folders needing rename).", total_to_process);
+    info!("[Scan Prep] Found {} potential mod folders for progress total (includes folders needing rename).", total_to_process);
 
     app_handle.emit_all(SCAN_PROGRESS_EVENT, ScanProgress {
             processed: 0, total: total_to_process, current_path: None, message: "Starting scan...".to_string()
-        }).unwrap_or_else(|e| eprintln!("Failed to emit initial scan progress: {}", e));
+        }).unwrap_or_else(|e| error!("Failed to emit initial scan progress: {}", e));
 
 
     // --- Process folders and collect FOUND asset IDs in a blocking task ---
     let scan_task = async_runtime::spawn_blocking(move || {
         // Open a new connection inside the blocking task
-        let conn = Connection::open(&db_path_str).map_err(|e| format!("Failed to open DB connection in scan task: {}", e))?;
+        let conn = open_db_connection(Path::new(&db_path_str))?;
 
         // --- Fetch ALL asset IDs and their CLEAN relative paths from DB first ---
         let mut initial_db_assets = HashMap::<i64, String>::new(); // asset_id -> clean_relative_path
         { // Scope for the statement
-            let mut stmt = conn.prepare("SELECT id, folder_name FROM assets")
-                .map_err(|e| format!("Failed to prepare asset fetch statement: {}", e))?;
+            let mut stmt = conn.prepare("SELECT id, folder_name FROM assets")?;
             let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)));
-             let row_iter = rows.map_err(|e| format!("Error creating asset query iterator: {}", e))?;
+             let row_iter = rows?;
             for row_result in row_iter {
                  match row_result {
                      Ok((id, folder_name)) => {
                          initial_db_assets.insert(id, folder_name.replace("\\", "/"));
                      }
                      Err(e) => {
-                          eprintln!("[Scan Task Prep] Error fetching asset row from DB: {}", e);
+                          error!("[Scan Task Prep] Error fetching asset row from DB: {}", e);
                      }
                  }
             }
         }
-        println!("[Scan Task Prep] Fetched {} assets from DB initially.", initial_db_assets.len());
+        info!("[Scan Task Prep] Fetched {} assets from DB initially.", initial_db_assets.len());
 
         let mut processed_count = 0; // Counts folders *identified* as mods and processed
         let mut mods_added_count = 0;
@@ -2194,14 +5398,14 @@ async fn scan_mods_directory(db_state: State<'_, DbState>, app_handle: AppHandle
         let mut found_asset_ids = HashSet::<i64>::new(); // Track IDs found on disk
         let mut renamed_count = 0; // Count renamed folders
 
-        // --- Iterate using WalkDir ---
-        let mut walker = WalkDir::new(&base_mods_path_clone).min_depth(1).into_iter();
+        // --- Pass 1: walk the tree, fixing "DISABLED" prefixes and collecting mod folders ---
+        let mut mod_folders: Vec<PathBuf> = Vec::new();
+        let mut walker = WalkDir::new(&base_mods_path_clone).min_depth(1).into_iter().filter_entry(|e| !is_scan_ignored(&ignore_set_clone, &base_mods_path_clone, e.path()));
 
         while let Some(entry_result) = walker.next() {
             match entry_result {
                 Ok(entry) => {
-                    // Use mutable path as it might be changed by rename logic
-                    let mut current_path = entry.path().to_path_buf();
+                    let current_path = entry.path().to_path_buf();
                     let is_directory = entry.file_type().is_dir(); // Check type once
 
                     if is_directory && !processed_mod_paths.contains(&current_path) {
@@ -2216,24 +5420,24 @@ async fn scan_mods_directory(db_state: State<'_, DbState>, app_handle: AppHandle
                             let new_filename = format!("{}{}", DISABLED_PREFIX, filename_str.strip_prefix("DISABLED").unwrap_or(&filename_str));
                             if let Some(parent_path) = current_path.parent() {
                                 let new_path = parent_path.join(&new_filename);
-                                println!("[Scan Task - Rename] Found incorrect prefix: '{}'. Renaming to '{}'", current_path.display(), new_path.display());
+                                info!("[Scan Task - Rename] Found incorrect prefix: '{}'. Renaming to '{}'", current_path.display(), new_path.display());
 
                                 // Emit progress before rename attempt
                                 app_handle_clone.emit_all(SCAN_PROGRESS_EVENT, ScanProgress {
-                                     processed: processed_count, // Don't increment processed count for rename yet
+                                     processed: 0,
                                      total: total_to_process,
                                      current_path: Some(current_path.display().to_string()),
                                      message: format!("Renaming: {}", filename_str)
-                                }).unwrap_or_else(|e| eprintln!("Failed to emit rename progress: {}", e));
+                                }).unwrap_or_else(|e| error!("Failed to emit rename progress: {}", e));
 
                                 match fs::rename(&current_path, &new_path) {
                                     Ok(_) => {
-                                        println!("[Scan Task - Rename] Successfully renamed.");
+                                        info!("[Scan Task - Rename] Successfully renamed.");
                                         current_path_for_processing = new_path; // Use the NEW path for further processing
                                         renamed_count += 1;
                                     }
                                     Err(e) => {
-                                        eprintln!("[Scan Task - Rename] ERROR: Failed to rename folder '{}': {}. Skipping folder.", current_path.display(), e);
+                                        error!("[Scan Task - Rename] Failed to rename folder '{}': {}. Skipping folder.", current_path.display(), e);
                                         errors_count += 1;
                                         // Don't process this folder if rename failed
                                         walker.skip_current_dir(); // Skip children as well
@@ -2241,7 +5445,7 @@ async fn scan_mods_directory(db_state: State<'_, DbState>, app_handle: AppHandle
                                     }
                                 }
                             } else {
-                                eprintln!("[Scan Task - Rename] ERROR: Cannot get parent path for '{}'. Skipping rename and folder.", current_path.display());
+                                error!("[Scan Task - Rename] Cannot get parent path for '{}'. Skipping rename and folder.", current_path.display());
                                 errors_count += 1;
                                 walker.skip_current_dir(); // Skip children
                                 continue; // Move to the next entry
@@ -2249,113 +5453,10 @@ async fn scan_mods_directory(db_state: State<'_, DbState>, app_handle: AppHandle
                         }
                         // --- END: Rename Check ---
 
-                        // Now check if the (potentially renamed) folder has an INI file
-                        if has_ini_file(&current_path_for_processing) {
-                            // This is a mod folder (or was successfully renamed to be treated as one)
-                            processed_count += 1; // Increment processed count *here*
-                            processed_mod_paths.insert(current_path_for_processing.clone()); // Add the path we actually processed
-                            let path_display = current_path_for_processing.display().to_string();
-                            let folder_name_only = current_path_for_processing.file_name().unwrap_or_default().to_string_lossy();
-
-                            // Emit progress for actual mod processing
-                            app_handle_clone.emit_all(SCAN_PROGRESS_EVENT, ScanProgress {
-                                processed: processed_count,
-                                total: total_to_process,
-                                current_path: Some(path_display.clone()),
-                                message: format!("Processing: {}", folder_name_only)
-                            }).unwrap_or_else(|e| eprintln!("Failed to emit scan progress: {}", e));
-
-                            // --- Start Original Deduction/DB Logic (using current_path_for_processing) ---
-                            match deduce_mod_info_v2(&current_path_for_processing, &base_mods_path_clone, &maps_clone) {
-                                Some(deduced) => {
-                                    println!("[Scan Task] Deduced slug for '{}': {}", path_display, deduced.entity_slug);
-                                    let target_entity_id_result: Option<i64> = maps_clone.entity_slug_to_id.get(&deduced.entity_slug).copied();
-
-                                    if let Some(target_entity_id) = target_entity_id_result {
-                                        println!("[Scan Task] Found entity ID {} for slug '{}'", target_entity_id, deduced.entity_slug);
-
-                                        let relative_path_buf = match current_path_for_processing.strip_prefix(&base_mods_path_clone) {
-                                            Ok(p) => p.to_path_buf(),
-                                            Err(_) => {
-                                                eprintln!("[Scan Task] Error: Could not strip base path prefix from '{}'. Skipping.", path_display);
-                                                errors_count += 1;
-                                                continue; // Skip only this mod folder deduction/DB part
-                                            }
-                                        };
-
-                                        let filename_osstr = relative_path_buf.file_name().unwrap_or_default();
-                                        let filename_str = filename_osstr.to_string_lossy();
-                                        // --- Critical: Ensure stripping the CORRECT prefix after potential rename ---
-                                        let clean_filename = filename_str.strip_prefix(DISABLED_PREFIX).unwrap_or(&filename_str);
-                                        // ---
-                                        let relative_parent_path = relative_path_buf.parent();
-                                        let relative_path_to_store = match relative_parent_path {
-                                            Some(parent) if parent.as_os_str().len() > 0 => parent.join(clean_filename).to_string_lossy().to_string(),
-                                            _ => clean_filename.to_string(),
-                                        };
-                                        let relative_path_to_store = relative_path_to_store.replace("\\", "/");
-                                        println!("[Scan Task] Calculated DB path: '{}'", relative_path_to_store);
-
-                                        let existing_db_asset_id: Option<i64> = conn.query_row(
-                                            "SELECT id FROM assets WHERE entity_id = ?1 AND folder_name = ?2",
-                                            params![target_entity_id, relative_path_to_store],
-                                            |row| row.get(0),
-                                        ).optional().map_err(|e| format!("DB error checking for existing asset '{}': {}", relative_path_to_store, e))?;
-
-                                        if let Some(asset_id) = existing_db_asset_id {
-                                            println!("[Scan Task] Asset already in DB (ID: {}), path '{}'. Marking as found.", asset_id, relative_path_to_store);
-                                            found_asset_ids.insert(asset_id);
-                                            // mods_updated_count += 1; // Optional update logic here
-                                        } else {
-                                            println!("[Scan Task] Inserting new asset: EntityID={}, Name='{}', Path='{}'", target_entity_id, deduced.mod_name, relative_path_to_store);
-                                            let insert_result = conn.execute(
-                                                "INSERT INTO assets (entity_id, name, description, folder_name, image_filename, author, category_tag) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                                                params![
-                                                    target_entity_id,
-                                                    deduced.mod_name,
-                                                    deduced.description,
-                                                    relative_path_to_store,
-                                                    deduced.image_filename,
-                                                    deduced.author,
-                                                    deduced.mod_type_tag
-                                                ]
-                                            );
-
-                                            match insert_result {
-                                                Ok(changes) => {
-                                                    if changes > 0 {
-                                                        mods_added_count += 1;
-                                                        let new_id = conn.last_insert_rowid();
-                                                        found_asset_ids.insert(new_id);
-                                                        println!("[Scan Task]   -> Insert successful (New ID: {})", new_id);
-                                                    } else {
-                                                        eprintln!("[Scan Task]   -> Insert reported 0 changes for '{}'.", relative_path_to_store);
-                                                        errors_count += 1;
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    if e.to_string().contains("UNIQUE constraint failed: assets.folder_name") {
-                                                        eprintln!("[Scan Task]   -> Insert failed due to UNIQUE constraint on folder_name '{}'. Asset might exist under a different entity or needs pruning. Skipping insert.", relative_path_to_store);
-                                                        // Maybe don't count as error if pruning will fix it?
-                                                    } else {
-                                                        eprintln!("[Scan Task]   -> DB error inserting new asset '{}': {}", relative_path_to_store, e);
-                                                        errors_count += 1;
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    } else {
-                                        eprintln!("[Scan Task] CRITICAL ERROR: Deduced slug '{}' for path '{}' does NOT exist in the entity map! Skipping mod. Check DB initialization and deduction logic.", deduced.entity_slug, path_display);
-                                        errors_count += 1;
-                                    }
-                                }
-                                None => {
-                                    eprintln!("[Scan Task] Error: Failed to deduce mod info for path '{}'", path_display);
-                                    errors_count += 1;
-                                }
-                            }
-                            // --- End Original Deduction/DB Logic ---
-                            walker.skip_current_dir(); // Skip children after processing a mod folder
+                        if is_mod_root_folder(&current_path_for_processing) {
+                            processed_mod_paths.insert(current_path_for_processing.clone());
+                            mod_folders.push(current_path_for_processing);
+                            walker.skip_current_dir(); // Skip children of a mod folder
                         }
                         // If it's a directory but doesn't have an INI (and wasn't renamed+processed),
                         // we just let WalkDir continue into its children.
@@ -2363,12 +5464,99 @@ async fn scan_mods_directory(db_state: State<'_, DbState>, app_handle: AppHandle
                     // If it's not a directory, or already processed, ignore.
                 }
                 Err(e) => {
-                     eprintln!("[Scan Task] Error accessing path during scan: {}", e);
+                     error!("[Scan Task] Error accessing path during scan: {}", e);
                      errors_count += 1;
                 }
             }
         }
 
+        // --- Pass 2: deduce every folder in parallel (filesystem/CPU only, no DB access) ---
+        // About 0.2 ms per folder on one core (5000 generated folders: 1.1 s warm, 1.4 s cold cache) and CPU-bound,
+        // so it should scale with cores; on a single core extra threads only add overhead.
+        let deduction_started = std::time::Instant::now();
+        let progress_counter = std::sync::atomic::AtomicUsize::new(0);
+        let deduce_folder = |mod_path: &PathBuf| {
+            let processed = progress_counter.fetch_add(1, Ordering::Relaxed) + 1;
+            app_handle_clone.emit_all(SCAN_PROGRESS_EVENT, ScanProgress {
+                processed,
+                total: total_to_process,
+                current_path: Some(mod_path.display().to_string()),
+                message: format!("Processing: {}", mod_path.file_name().unwrap_or_default().to_string_lossy())
+            }).unwrap_or_else(|e| error!("Failed to emit scan progress: {}", e));
+            resolve_scan_candidate(mod_path, &base_mods_path_clone, &maps_clone)
+        };
+        let candidates: Vec<Result<ScanCandidate, String>> = match rayon::ThreadPoolBuilder::new().num_threads(scan_threads).build() {
+            Ok(pool) => pool.install(|| mod_folders.par_iter().map(deduce_folder).collect()),
+            Err(e) => {
+                warn!("[Scan Task] Failed to build deduction thread pool ({}); deducing on one thread.", e);
+                mod_folders.iter().map(deduce_folder).collect()
+            }
+        };
+        info!("[Scan Task] Deduced {} folders in {:?} ({} threads).", mod_folders.len(), deduction_started.elapsed(), if scan_threads == 0 { rayon::current_num_threads() } else { scan_threads });
+
+        // --- Pass 3: sync the results to the DB on this task's single connection, in walk order ---
+        for (current_path_for_processing, candidate_result) in mod_folders.iter().zip(candidates) {
+            processed_count += 1;
+            match candidate_result {
+                Ok(candidate) => {
+                    let (target_entity_id, relative_path_to_store, is_enabled_on_disk) = (candidate.entity_id, &candidate.relative_path, candidate.is_enabled);
+                    info!("[Scan Task] Calculated DB path: '{}'", relative_path_to_store);
+
+                    let existing_db_asset_id: Option<i64> = conn.query_row(
+                        "SELECT id FROM assets WHERE entity_id = ?1 AND folder_name = ?2",
+                        params![target_entity_id, relative_path_to_store],
+                        |row| row.get(0),
+                    ).optional()?;
+
+                    if let Some(asset_id) = existing_db_asset_id {
+                        info!("[Scan Task] Asset already in DB (ID: {}), path '{}'. Marking as found.", asset_id, relative_path_to_store);
+                        found_asset_ids.insert(asset_id);
+                        // Backfill created_at for assets added before the column existed
+                        conn.execute(
+                            "UPDATE assets SET created_at = ?1 WHERE id = ?2 AND created_at IS NULL",
+                            params![folder_created_at(current_path_for_processing), asset_id],
+                        )?;
+                        // A known state that no longer matches the folder was changed outside the app
+                        conn.execute(
+                            "UPDATE assets SET state_source = CASE WHEN is_enabled IS NOT NULL AND is_enabled != ?1 THEN ?3 ELSE state_source END, is_enabled = ?1 WHERE id = ?2",
+                            params![is_enabled_on_disk, asset_id, STATE_SOURCE_DISK],
+                        )?;
+                        // mods_updated_count += 1; // Optional update logic here
+                    } else {
+                        info!("[Scan Task] Inserting new asset: EntityID={}, Name='{}', Path='{}'", target_entity_id, candidate.deduced.mod_name, relative_path_to_store);
+                        let insert_result = insert_scanned_asset(&conn, &candidate, current_path_for_processing);
+
+                        match insert_result {
+                            Ok(changes) => {
+                                if changes > 0 {
+                                    mods_added_count += 1;
+                                    let new_id = conn.last_insert_rowid();
+                                    found_asset_ids.insert(new_id);
+                                    info!("[Scan Task]   -> Insert successful (New ID: {})", new_id);
+                                } else {
+                                    error!("[Scan Task]   -> Insert reported 0 changes for '{}'.", relative_path_to_store);
+                                    errors_count += 1;
+                                }
+                            }
+                            Err(e) => {
+                                if e.to_string().contains("UNIQUE constraint failed: assets.folder_name") {
+                                    error!("[Scan Task]   -> Insert failed due to UNIQUE constraint on folder_name '{}'. Asset might exist under a different entity or needs pruning. Skipping insert.", relative_path_to_store);
+                                    // Maybe don't count as error if pruning will fix it?
+                                } else {
+                                    error!("[Scan Task]   -> DB error inserting new asset '{}': {}", relative_path_to_store, e);
+                                    errors_count += 1;
+                                }
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("[Scan Task] {}", e);
+                    errors_count += 1;
+                }
+            }
+        }
+
         // --- Pruning Logic (Remains the same) ---
         let mut mods_to_prune_ids = Vec::new();
         for (asset_id, _clean_path) in initial_db_assets.iter() {
@@ -2381,7 +5569,7 @@ async fn scan_mods_directory(db_state: State<'_, DbState>, app_handle: AppHandle
         let mut pruning_errors_count = 0;
 
         if !mods_to_prune_ids.is_empty() {
-            println!("[Scan Task Pruning] Found {} mods in DB missing from disk. Pruning...", prune_count);
+            info!("[Scan Task Pruning] Found {} mods in DB missing from disk. Pruning...", prune_count);
             app_handle_clone.emit_all(PRUNING_START_EVENT, prune_count).ok();
 
              let ids_to_delete_sql: Vec<Box<dyn rusqlite::ToSql>> = mods_to_prune_ids
@@ -2401,27 +5589,27 @@ async fn scan_mods_directory(db_state: State<'_, DbState>, app_handle: AppHandle
                 match delete_result {
                     Ok(count) => {
                          pruned_count = count;
-                         println!("[Scan Task Pruning] Successfully pruned {} asset entries.", pruned_count);
+                         info!("[Scan Task Pruning] Successfully pruned {} asset entries.", pruned_count);
                          app_handle_clone.emit_all(PRUNING_COMPLETE_EVENT, pruned_count).ok();
                     },
                     Err(e) => {
-                        eprintln!("[Scan Task Pruning] {}", e);
+                        error!("[Scan Task Pruning] {}", e);
                          pruning_errors_count += 1;
                          app_handle_clone.emit_all(PRUNING_ERROR_EVENT, e).ok();
                     }
                 }
             } else {
-                 println!("[Scan Task Pruning] No valid IDs to prune after conversion.");
+                 info!("[Scan Task Pruning] No valid IDs to prune after conversion.");
                  app_handle_clone.emit_all(PRUNING_COMPLETE_EVENT, 0).ok();
             }
         } else {
-             println!("[Scan Task Pruning] No missing mods found. Skipping pruning.");
+             info!("[Scan Task Pruning] No missing mods found. Skipping pruning.");
         }
         // --- End Pruning Logic ---
 
         let total_errors = errors_count + pruning_errors_count;
         // Return renamed_count as well
-        Ok::<_, String>((processed_count, mods_added_count, mods_updated_count, total_errors, pruned_count, renamed_count))
+        Ok::<_, AppError>((processed_count, mods_added_count, mods_updated_count, total_errors, pruned_count, renamed_count))
     });
 
     // --- Handle Task Result ---
@@ -2432,66 +5620,162 @@ async fn scan_mods_directory(db_state: State<'_, DbState>, app_handle: AppHandle
                  "Scan complete. Processed {} mod folders. Added {} new mods. Pruned {} missing mods.{} {} errors occurred.",
                  processed, added, pruned, rename_msg, errors
             );
-             println!("{}", summary);
-             app_handle.emit_all(SCAN_COMPLETE_EVENT, summary.clone()).unwrap_or_else(|e| eprintln!("Failed to emit scan complete event: {}", e));
+             info!("{}", summary);
+             app_handle.emit_all(SCAN_COMPLETE_EVENT, summary.clone()).unwrap_or_else(|e| error!("Failed to emit scan complete event: {}", e));
              Ok(())
          }
          Ok(Err(e)) => {
-             eprintln!("Scan task failed internally: {}", e);
-              app_handle.emit_all(SCAN_ERROR_EVENT, e.clone()).unwrap_or_else(|e| eprintln!("Failed to emit scan error event: {}", e));
+             error!("Scan task failed internally: {}", e);
+              app_handle.emit_all(SCAN_ERROR_EVENT, e.to_string()).unwrap_or_else(|e| error!("Failed to emit scan error event: {}", e));
              Err(e)
          }
          Err(e) => {
              let err_msg = format!("Scan task panicked or failed to join: {}", e);
-             eprintln!("{}", err_msg);
-             app_handle.emit_all(SCAN_ERROR_EVENT, err_msg.clone()).unwrap_or_else(|e| eprintln!("Failed to emit scan error event: {}", e));
-             Err(err_msg)
+             error!("{}", err_msg);
+             app_handle.emit_all(SCAN_ERROR_EVENT, err_msg.clone()).unwrap_or_else(|e| error!("Failed to emit scan error event: {}", e));
+             Err(err_msg.into())
          }
      }
 }
 
+// Rescans only <category>/<entity> under the mods folder and inserts mod folders with no asset row yet.
+// Everything found there is filed under this entity, whatever deduction would pick. Nothing is pruned or renamed;
+// run scan_mods_directory for that.
+#[command]
+async fn scan_entity(entity_slug: String, db_state: State<'_, DbState>) -> CmdResult<EntityScanResult> {
+    let _bulk_task_guard = BulkDbTaskGuard::acquire("a scan")?;
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+
+    let (entity_id, entity_root, maps, known_paths, ignore_set) = {
+        let conn = lock_db(&db_state);
+        let (entity_id, category_slug): (i64, String) = conn.query_row(
+            "SELECT e.id, c.slug FROM entities e JOIN categories c ON e.category_id = c.id WHERE e.slug = ?1",
+            params![entity_slug],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?.ok_or_else(|| AppError::NotFound(format!("Entity '{}' not found", entity_slug)))?;
+        let maps = fetch_deduction_maps(&conn)?;
+        let ignore_set = load_scan_ignore_set(&conn, &base_mods_path)?;
+        let mut stmt = conn.prepare("SELECT folder_name FROM assets")?;
+        let known_paths = stmt.query_map([], |row| row.get::<_, String>(0))?
+            .map(|path| path.map(|p| p.replace("\\", "/")))
+            .collect::<SqlResult<HashSet<_>>>()?;
+        (entity_id, base_mods_path.join(category_slug).join(&entity_slug), maps, known_paths, ignore_set)
+    };
+
+    let mut result = EntityScanResult::default();
+    if !entity_root.is_dir() {
+        info!("[scan_entity] No folder for '{}' at {}; nothing to scan.", entity_slug, entity_root.display());
+        return Ok(result);
+    }
+    info!("[scan_entity] Scanning {}", entity_root.display());
+
+    let walk_root = entity_root.clone();
+    let walk_base = base_mods_path.clone();
+    let walk_task = async_runtime::spawn_blocking(move || {
+        let mut candidates = Vec::new();
+        let mut walker = WalkDir::new(&walk_root).min_depth(1).into_iter().filter_entry(|e| !is_scan_ignored(&ignore_set, &walk_base, e.path()));
+
+        while let Some(entry_result) = walker.next() {
+            let entry = match entry_result {
+                Ok(entry) => entry,
+                Err(e) => { candidates.push(Err(format!("Error accessing path: {}", e))); continue; }
+            };
+            if !entry.file_type().is_dir() { continue; }
+            let mod_path = entry.path().to_path_buf();
+            if !is_mod_root_folder(&mod_path) { continue; }
+            walker.skip_current_dir();
+            candidates.push(resolve_scan_candidate(&mod_path, &walk_base, &maps).map(|candidate| (mod_path, candidate)));
+        }
+        candidates
+    });
+    let candidates = walk_task.await.map_err(|e| AppError::Other(format!("Entity scan task failed: {}", e)))?;
+
+    let conn = lock_db(&db_state);
+    for candidate_result in candidates {
+        let (mod_path, mut candidate) = match candidate_result {
+            Ok(found) => found,
+            Err(e) => { error!("[scan_entity] {}", e); result.errors.push(e); continue; }
+        };
+        if known_paths.contains(&candidate.relative_path) {
+            result.already_known += 1;
+            continue;
+        }
+        candidate.entity_id = entity_id;
+        candidate.deduced.entity_slug = entity_slug.clone();
+        match insert_scanned_asset(&conn, &candidate, &mod_path) {
+            Ok(_) => {
+                info!("[scan_entity] Inserted '{}' (ID: {}).", candidate.relative_path, conn.last_insert_rowid());
+                result.inserted += 1;
+            }
+            Err(e) => result.errors.push(format!("'{}': {}", candidate.relative_path, e)),
+        }
+    }
+
+    info!("[scan_entity] '{}': inserted {}, {} already known, {} errors.", entity_slug, result.inserted, result.already_known, result.errors.len());
+    Ok(result)
+}
+
 #[command]
 fn get_total_asset_count(db_state: State<DbState>) -> CmdResult<i64> {
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let conn = lock_db(&db_state);
     conn.query_row("SELECT COUNT(*) FROM assets", [], |row| row.get(0))
-        .map_err(|e| e.to_string())
+        .map_err(AppError::from)
+}
+
+// Blank URLs are stored as NULL
+fn normalize_source_url(source_url: Option<String>) -> Option<String> {
+    source_url.map(|url| url.trim().to_string()).filter(|url| !url.is_empty())
+}
+
+// Optional parts of an update_asset_info request, sent by the frontend as one `options` object.
+// Omitted text fields are stored as NULL, like an explicit null.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct AssetUpdateOptions {
+    description: Option<String>,
+    author: Option<String>,
+    category_tag: Option<String>,
+    selected_image_absolute_path: Option<String>, // New preview picked from disk
+    image_data: Option<Vec<u8>>, // Pasted preview image; wins over selected_image_absolute_path
+    new_target_entity_slug: Option<String>, // Move the mod under this entity
+    source_url: Option<String>,
+    relocate_enabled: Option<bool>, // State of the folder after a relocation; None keeps its current state
 }
 
 #[command]
 fn update_asset_info(
     asset_id: i64,
     name: String,
-    description: Option<String>,
-    author: Option<String>,
-    category_tag: Option<String>,
-    selected_image_absolute_path: Option<String>,
-    image_data: Option<Vec<u8>>,
-    new_target_entity_slug: Option<String>,
-    db_state: State<DbState>
-) -> CmdResult<()> { // Returns Result<(), String>
-    println!("[update_asset_info] Start for asset ID: {}. Relocate to: {:?}. Image Data Provided: {}",
+    options: AssetUpdateOptions,
+    db_state: State<DbState>,
+    app_handle: AppHandle
+) -> CmdResult<()> {
+    let AssetUpdateOptions {
+        description, author, category_tag, selected_image_absolute_path, image_data,
+        new_target_entity_slug, source_url, relocate_enabled,
+    } = options;
+    info!("[update_asset_info] Start for asset ID: {}. Relocate to: {:?}. Image Data Provided: {}",
         asset_id, new_target_entity_slug, image_data.is_some());
 
-    let conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let conn_guard = lock_db(&db_state);
     let conn = &*conn_guard;
 
     // --- 1. Get Current Asset Location Info ---
-    let current_info = get_asset_location_info(conn, asset_id)
-        .map_err(|e| format!("Failed get current asset info: {}", e))?;
-    println!("[update_asset_info] Current Info: {:?}", current_info);
+    let current_info = get_asset_location_info(conn, asset_id)?;
+    info!("[update_asset_info] Current Info: {:?}", current_info);
 
     // --- 2. Relocation Logic ---
     let needs_relocation = new_target_entity_slug.is_some() && new_target_entity_slug.as_deref() != Some(&current_info.entity_slug);
     let mut final_entity_id = current_info.entity_id;
     let mut final_relative_path_str = current_info.clean_relative_path.clone();
-    let mut final_path_on_disk: Option<PathBuf> = None;
+    // Moves the folder back if anything below fails before the DB points at the new location
+    let mut relocation: Option<FolderRelocation> = None;
 
     let base_mods_path = PathBuf::from(
-        get_setting_value(conn, SETTINGS_KEY_MODS_FOLDER)
-           .map_err(|e|e.to_string())?
-           .ok_or_else(|| "Mods folder path not set".to_string())?
+        get_setting_value(conn, SETTINGS_KEY_MODS_FOLDER)?
+           .ok_or_else(|| AppError::Config("Mods folder path not set".to_string()))?
     );
-    println!("[update_asset_info] Base mods path: {}", base_mods_path.display());
+    info!("[update_asset_info] Base mods path: {}", base_mods_path.display());
 
     if needs_relocation {
         // ... (setup for relocation: target_slug, new_entity_id, etc.) ...
@@ -2500,127 +5784,91 @@ fn update_asset_info(
             "SELECT e.id, c.slug FROM entities e JOIN categories c ON e.category_id = c.id WHERE e.slug = ?1",
             params![target_slug],
             |row| Ok((row.get(0)?, row.get(1)?)),
-        ).map_err(|e| format!("DB Error getting new target entity info: {}", e))?;
+        )?;
 
-        // --- Determine Current Full Path on Disk (Check Enabled/Disabled) ---
-        let current_relative_path_buf = PathBuf::from(&current_info.clean_relative_path);
-        let current_filename_osstr = current_relative_path_buf.file_name().ok_or("Cannot get current filename")?;
-        let current_filename_str = current_filename_osstr.to_string_lossy();
-        let disabled_filename = format!("{}{}", DISABLED_PREFIX, current_filename_str);
-        let relative_parent_path = current_relative_path_buf.parent();
-        let full_path_if_enabled = base_mods_path.join(&current_relative_path_buf);
-        let full_path_if_disabled = match relative_parent_path {
-           Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_filename),
-           _ => base_mods_path.join(&disabled_filename),
-        };
-        let current_full_path = if full_path_if_enabled.is_dir() { full_path_if_enabled }
-            else if full_path_if_disabled.is_dir() { full_path_if_disabled }
-            else { return Err(format!("Cannot relocate: Source folder not found at '{}' or disabled variant.", full_path_if_enabled.display())); };
-        println!("[update_asset_info] Current full path on disk: {}", current_full_path.display());
-
-        // --- Construct New Relative (for DB) and Full (for Disk) Paths ---
-        let mod_base_name = current_filename_str.trim_start_matches(DISABLED_PREFIX);
-        let new_relative_path_buf = PathBuf::new().join(&new_category_slug).join(target_slug).join(mod_base_name);
-        final_relative_path_str = new_relative_path_buf.to_string_lossy().replace("\\", "/"); // For DB
-
-        // Determine the name to use on disk (keep disabled prefix if present)
-        let new_filename_to_use_on_disk = if current_full_path.file_name().map_or(false, |name| name.to_string_lossy().starts_with(DISABLED_PREFIX)) {
-             disabled_filename // Keep disabled prefix
-        } else {
-             mod_base_name.to_string() // Use clean name
-        };
-        let new_full_dest_path_on_disk = base_mods_path.join(&new_category_slug).join(target_slug).join(&new_filename_to_use_on_disk);
-        println!("[update_asset_info] New relative path for DB: {}", final_relative_path_str);
-        println!("[update_asset_info] New full destination path on disk: {}", new_full_dest_path_on_disk.display());
-
-        // --- Create Parent Directory & Perform Move ---
-        if let Some(parent) = new_full_dest_path_on_disk.parent() {
-            fs::create_dir_all(parent).map_err(|e| e.to_string())? // Add map_err
-       } else {
-            return Err("Could not determine parent for new path".into());
-       }
-        if new_full_dest_path_on_disk.exists() { return Err(format!("Cannot relocate: Target path '{}' already exists.", new_full_dest_path_on_disk.display())); }
-        fs::rename(&current_full_path, &new_full_dest_path_on_disk)
-            .map_err(|e| e.to_string())?; // Add map_err
-        // --- END FIX 2 ---
+        let moved = relocate_asset_folder(&base_mods_path, &current_info.clean_relative_path, &new_category_slug, target_slug, relocate_enabled)?;
+        final_relative_path_str = moved.relative_path.clone(); // For DB
+        info!("[update_asset_info] New relative path for DB: {}", final_relative_path_str);
+        info!("[update_asset_info] New full destination path on disk: {}", moved.to.display());
 
-        println!("[update_asset_info] Successfully moved mod folder.");
+        info!("[update_asset_info] Successfully moved mod folder.");
 
         final_entity_id = new_entity_id;
-        final_path_on_disk = Some(new_full_dest_path_on_disk);
+        relocation = Some(moved);
     }
 
     // --- 4. Handle Image Saving (Handles Paste > File Path > Existing) ---
 
     // Determine the mod folder path ON DISK where the image should be saved
     // This uses the path *after* potential relocation if it happened.
-    let mod_folder_on_disk = if let Some(relocated_path) = final_path_on_disk {
-        relocated_path
+    let mod_folder_on_disk = if let Some(moved) = &relocation {
+        moved.to.clone()
     } else {
         // If no relocation, determine current path (enabled/disabled) based on current_info
         let current_relative_path_buf = PathBuf::from(&current_info.clean_relative_path);
-        let current_filename_osstr = current_relative_path_buf.file_name().ok_or("Cannot get current filename")?;
-        let current_filename_str = current_filename_osstr.to_string_lossy();
-        let disabled_filename = format!("{}{}", DISABLED_PREFIX, current_filename_str);
-        let relative_parent_path = current_relative_path_buf.parent();
-        let full_path_if_enabled = base_mods_path.join(&current_relative_path_buf);
-        let full_path_if_disabled = match relative_parent_path {
-            Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_filename),
-            _ => base_mods_path.join(&disabled_filename),
-        };
-        if full_path_if_enabled.is_dir() { full_path_if_enabled }
-        else if full_path_if_disabled.is_dir() { full_path_if_disabled }
-        else { return Err(format!("Mod folder not found on disk at '{}' or disabled variant.", full_path_if_enabled.display())); }
+        match resolve_asset_on_disk(&base_mods_path, &current_relative_path_buf) {
+            Some((path, _)) => path,
+            None => return Err(AppError::NotFound(format!("Mod folder not found on disk at '{}' or disabled variant.", base_mods_path.join(&current_relative_path_buf).display()))),
+        }
     };
-    println!("[update_asset_info] Confirmed mod path on disk for image: {}", mod_folder_on_disk.display());
+    info!("[update_asset_info] Confirmed mod path on disk for image: {}", mod_folder_on_disk.display());
 
     // Ensure the target directory exists (it should, but double-check)
     if !mod_folder_on_disk.is_dir() {
         // This might happen if the folder got deleted between checks, try creating it.
-        println!("[update_asset_info] Warning: Target mod folder {} does not exist, attempting to create.", mod_folder_on_disk.display());
-        fs::create_dir_all(&mod_folder_on_disk).map_err(|e| e.to_string())?;
+        warn!("[update_asset_info] Target mod folder {} does not exist, attempting to create.", mod_folder_on_disk.display());
+        fs::create_dir_all(&mod_folder_on_disk)?;
     }
 
     let mut image_filename_to_save: Option<String> = None; // Default to None
+    let store_externally = use_external_previews(conn);
 
     // --- Priority 1: Handle pasted/provided image data ---
-    if let Some(data) = image_data {
-        println!("[update_asset_info] Handling provided image data ({} bytes)", data.len());
+    if let (Some(data), true) = (&image_data, store_externally) {
+        image_filename_to_save = Some(save_external_preview(&app_handle, asset_id, data, "png")?);
+    }
+    else if let (Some(source_path_str), true) = (&selected_image_absolute_path, store_externally) {
+        let source_path = PathBuf::from(source_path_str);
+        if !source_path.is_file() { return Err(AppError::NotFound(format!("Selected image file does not exist: {}", source_path.display()))); }
+        image_filename_to_save = Some(save_external_preview(&app_handle, asset_id, &fs::read(&source_path)?, &image_extension(&source_path))?);
+    }
+    else if let Some(data) = image_data {
+        info!("[update_asset_info] Handling provided image data ({} bytes)", data.len());
         let target_image_path = mod_folder_on_disk.join(TARGET_IMAGE_FILENAME);
         // Use fs::write which creates/truncates the file
         fs::write(&target_image_path, data)
-            .map_err(|e| format!("Failed to save pasted image data to '{}': {}", target_image_path.display(), e))?;
-        println!("[update_asset_info] Image data written successfully.");
+            .map_err(|e| file_operation_error("save pasted image to", &target_image_path, &e))?;
+        info!("[update_asset_info] Image data written successfully.");
         image_filename_to_save = Some(TARGET_IMAGE_FILENAME.to_string());
     }
     // --- Priority 2: Handle selected file path (only if no data was provided) ---
     else if let Some(source_path_str) = selected_image_absolute_path {
-        println!("[update_asset_info] Handling selected image file path: {}", source_path_str);
+        info!("[update_asset_info] Handling selected image file path: {}", source_path_str);
         let source_path = PathBuf::from(&source_path_str);
-        if !source_path.is_file() { return Err(format!("Selected image file does not exist: {}", source_path.display())); }
+        if !source_path.is_file() { return Err(AppError::NotFound(format!("Selected image file does not exist: {}", source_path.display()))); }
         let target_image_path = mod_folder_on_disk.join(TARGET_IMAGE_FILENAME);
         fs::copy(&source_path, &target_image_path)
-             .map_err(|e| format!("Failed to copy selected image to '{}': {}", target_image_path.display(), e))?;
-        println!("[update_asset_info] Image file copied successfully.");
+             .map_err(|e| file_operation_error("copy selected image to", &target_image_path, &e))?;
+        info!("[update_asset_info] Image file copied successfully.");
         image_filename_to_save = Some(TARGET_IMAGE_FILENAME.to_string());
     }
     // --- Priority 3: No new image provided, fetch existing filename from DB ---
     else {
-         println!("[update_asset_info] No new image data or path provided. Fetching existing filename.");
+         info!("[update_asset_info] No new image data or path provided. Fetching existing filename.");
          // Query existing filename. Ok if it doesn't exist (returns None)
          image_filename_to_save = conn.query_row::<Option<String>, _, _>(
             "SELECT image_filename FROM assets WHERE id=?1",
              params![asset_id],
              |r|r.get(0)
-         ).optional().map_err(|e| format!("DB error fetching existing image name: {}", e))?.flatten(); // flatten Option<Option<String>>
+         ).optional()?.flatten(); // flatten Option<Option<String>>
     }
-    println!("[update_asset_info] Image handling complete. Filename to save in DB: {:?}", image_filename_to_save);
+    info!("[update_asset_info] Image handling complete. Filename to save in DB: {:?}", image_filename_to_save);
 
 
     // --- 5. Update Database ---
-    println!("[update_asset_info] Attempting DB update for asset ID {}...", asset_id);
+    info!("[update_asset_info] Attempting DB update for asset ID {}...", asset_id);
     let changes = conn.execute(
-        "UPDATE assets SET name = ?1, description = ?2, author = ?3, category_tag = ?4, image_filename = ?5, entity_id = ?6, folder_name = ?7 WHERE id = ?8",
+        "UPDATE assets SET name = ?1, description = ?2, author = ?3, category_tag = ?4, image_filename = ?5, entity_id = ?6, folder_name = ?7, source_url = ?8, is_enabled = COALESCE(?10, is_enabled) WHERE id = ?9",
         params![
             name, // Use name from arguments
             description,
@@ -2629,186 +5877,583 @@ fn update_asset_info(
             image_filename_to_save, // Use the determined filename
             final_entity_id,        // Use potentially updated entity ID
             final_relative_path_str, // Use potentially updated relative path (for DB only)
-            asset_id
+            normalize_source_url(source_url),
+            asset_id,
+            relocation.as_ref().map(|moved| moved.enabled) // Keep is_enabled in line with the relocated folder's prefix
         ]
-    ).map_err(|e| format!("Failed update asset info in DB for ID {}: {}", asset_id, e))?;
+    )?;
 
-    println!("[update_asset_info] DB update executed. Changes: {}", changes);
-    if changes == 0 { eprintln!("[update_asset_info] Warning: DB update affected 0 rows for asset ID {}.", asset_id); }
+    info!("[update_asset_info] DB update executed. Changes: {}", changes);
+    if changes == 0 { warn!("[update_asset_info] DB update affected 0 rows for asset ID {}.", asset_id); }
+    if let Some(moved) = relocation { moved.commit(); }
 
-    println!("[update_asset_info] Asset ID {} updated successfully. END", asset_id);
+    info!("[update_asset_info] Asset ID {} updated successfully. END", asset_id);
     Ok(())
 }
 
-#[command]
-fn delete_asset(asset_id: i64, db_state: State<DbState>) -> CmdResult<()> {
-     println!("[delete_asset] Attempting to delete asset ID: {}", asset_id);
-
-    let conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
-    let conn = &*conn_guard;
-    println!("[delete_asset] DB lock acquired.");
+// Total size of the files under a folder; unreadable entries count as 0
+fn folder_size_bytes(path: &Path) -> u64 {
+    WalkDir::new(path).into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.metadata().map(|m| m.len()).unwrap_or(0))
+        .sum()
+}
 
+// Removes an asset's folder (enabled or disabled) and its DB row. Returns the bytes freed on disk.
+fn remove_asset_and_folder(conn: &Connection, app_handle: &AppHandle, base_mods_path: &Path, asset_id: i64) -> CmdResult<u64> {
     // --- 1. Get Asset Info ---
-    let asset_info = get_asset_location_info(conn, asset_id)
-        .map_err(|e| format!("Failed to get asset info for deletion: {}", e))?;
-    println!("[delete_asset] Asset info found: {:?}", asset_info);
-
-    // --- 2. Get Base Mods Path ---
-    let base_mods_path_str = get_setting_value(conn, SETTINGS_KEY_MODS_FOLDER)
-        .map_err(|e| format!("Failed to query mods folder setting: {}", e))?
-        .ok_or_else(|| "Mods folder path not set".to_string())?;
-    let base_mods_path = PathBuf::from(base_mods_path_str);
+    let asset_info = get_asset_location_info(conn, asset_id)?;
+    info!("[delete_asset] Asset info found: {:?}", asset_info);
 
-    // --- 3. Determine Full Path on Disk (Check Enabled/Disabled) ---
-     let relative_path_buf = PathBuf::from(&asset_info.clean_relative_path);
-     let filename_osstr = relative_path_buf.file_name().ok_or_else(|| format!("Could not extract filename from DB path: {}", asset_info.clean_relative_path))?;
-     let filename_str = filename_osstr.to_string_lossy();
-     let disabled_filename = format!("{}{}", DISABLED_PREFIX, filename_str);
-     let relative_parent_path = relative_path_buf.parent();
-
-     let full_path_if_enabled = base_mods_path.join(&relative_path_buf);
-     let full_path_if_disabled = match relative_parent_path {
-        Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_filename),
-        _ => base_mods_path.join(&disabled_filename),
-     };
+    // --- 2. Determine Full Path on Disk (Check Enabled/Disabled) ---
+    let relative_path_buf = PathBuf::from(&asset_info.clean_relative_path);
+    let (full_path_if_enabled, full_path_if_disabled) = asset_disk_paths(base_mods_path, &relative_path_buf)
+        .ok_or_else(|| AppError::ModOperation(format!("Could not extract filename from DB path: {}", asset_info.clean_relative_path)))?;
 
-    let path_to_delete = if full_path_if_enabled.is_dir() {
-        Some(full_path_if_enabled)
-    } else if full_path_if_disabled.is_dir() {
-        Some(full_path_if_disabled)
-    } else {
+    let path_to_delete = resolve_asset_on_disk(base_mods_path, &relative_path_buf).map(|(path, _)| path);
+    if path_to_delete.is_none() {
          // Folder not found, maybe already deleted? Log a warning but proceed to DB deletion.
-         eprintln!("[delete_asset] Warning: Mod folder not found on disk for asset ID {}. Checked {} and {}. Proceeding with DB deletion.",
+         warn!("[delete_asset] Mod folder not found on disk for asset ID {}. Checked {} and {}. Proceeding with DB deletion.",
              asset_id, full_path_if_enabled.display(), full_path_if_disabled.display());
-         None
-    };
+    }
 
-    // --- 4. Delete Folder from Filesystem ---
+    // --- 3. Delete Folder from Filesystem ---
+    let mut bytes_freed = 0;
     if let Some(path) = path_to_delete {
-         println!("[delete_asset] Deleting folder: {}", path.display());
-         fs::remove_dir_all(&path)
-            .map_err(|e| format!("Failed to delete mod folder '{}': {}", path.display(), e))?;
-         println!("[delete_asset] Folder deleted successfully.");
+         bytes_freed = folder_size_bytes(&path);
+         info!("[delete_asset] Deleting folder: {} ({} bytes)", path.display(), bytes_freed);
+         retry_if_locked(|| fs::remove_dir_all(&path))
+            .map_err(|e| file_operation_error("delete mod folder", &path, &e))?;
+         info!("[delete_asset] Folder deleted successfully.");
+    }
+
+    // --- 4. Release any global hotkey bound to this asset (row is removed by cascade) ---
+    let bound_hotkey: Option<String> = conn.query_row("SELECT accelerator FROM hotkeys WHERE asset_id = ?1", params![asset_id], |row| row.get(0)).optional()?;
+    if let Some(accelerator) = bound_hotkey {
+        if let Err(e) = app_handle.global_shortcut_manager().unregister(&accelerator) {
+            error!("[delete_asset] Failed to unregister hotkey '{}': {}", accelerator, e);
+        }
     }
 
     // --- 5. Delete from Database ---
-    println!("[delete_asset] Deleting asset ID {} from database.", asset_id);
-    let changes = conn.execute("DELETE FROM assets WHERE id = ?1", params![asset_id])
-        .map_err(|e| format!("Failed to delete asset ID {} from database: {}", asset_id, e))?;
+    info!("[delete_asset] Deleting asset ID {} from database.", asset_id);
+    let changes = conn.execute("DELETE FROM assets WHERE id = ?1", params![asset_id])?;
 
      if changes == 0 {
          // This shouldn't happen if get_asset_location_info succeeded, but good to log.
-         eprintln!("[delete_asset] Warning: Database delete affected 0 rows for asset ID {}.", asset_id);
+         warn!("[delete_asset] Database delete affected 0 rows for asset ID {}.", asset_id);
      } else {
-         println!("[delete_asset] Database entry deleted successfully.");
+         info!("[delete_asset] Database entry deleted successfully.");
      }
+    if let Err(e) = remove_external_previews(app_handle, asset_id) {
+        warn!("[delete_asset] Failed to remove stored preview for asset ID {}: {}", asset_id, e);
+    }
+    Ok(bytes_freed)
+}
+
+#[command]
+fn delete_asset(asset_id: i64, app_handle: AppHandle, db_state: State<DbState>) -> CmdResult<()> {
+     info!("[delete_asset] Attempting to delete asset ID: {}", asset_id);
+
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let conn_guard = lock_db(&db_state);
+    remove_asset_and_folder(&conn_guard, &app_handle, &base_mods_path, asset_id)?;
+
+    info!("[delete_asset] Asset ID {} deleted successfully. END", asset_id);
+    Ok(())
+}
+
+// Deletes several mods in one call, continuing past failures
+#[command]
+fn delete_assets(asset_ids: Vec<i64>, app_handle: AppHandle, db_state: State<DbState>) -> CmdResult<BulkDeleteResult> {
+    info!("[delete_assets] Deleting {} assets: {:?}", asset_ids.len(), asset_ids);
+
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let conn_guard = lock_db(&db_state);
+    let mut result = BulkDeleteResult::default();
+    for asset_id in asset_ids {
+        match remove_asset_and_folder(&conn_guard, &app_handle, &base_mods_path, asset_id) {
+            Ok(bytes) => {
+                result.deleted += 1;
+                result.bytes_freed += bytes;
+                result.results.push(BulkDeleteItem { asset_id, success: true, error: None });
+            }
+            Err(e) => {
+                warn!("[delete_assets] Failed to delete asset ID {}: {}", asset_id, e);
+                result.results.push(BulkDeleteItem { asset_id, success: false, error: Some(e.to_string()) });
+            }
+        }
+    }
+
+    info!("[delete_assets] Deleted {} of {} assets, {} bytes freed.", result.deleted, result.results.len(), result.bytes_freed);
+    Ok(result)
+}
+
+#[command]
+fn rename_asset_folder(asset_id: i64, new_folder_name: String, db_state: State<DbState>) -> CmdResult<String> {
+    info!("[rename_asset_folder] Renaming folder of asset ID {} to '{}'", asset_id, new_folder_name);
+    let sanitized_name = sanitize_folder_name(&new_folder_name);
+    if sanitized_name.trim_matches('_').is_empty() {
+        return Err(AppError::ModOperation("New folder name is empty after sanitizing.".to_string()));
+    }
+
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let conn_guard = lock_db(&db_state);
+    let conn = &*conn_guard;
+
+    let asset_info = get_asset_location_info(conn, asset_id)?;
+    let current_relative_path = PathBuf::from(&asset_info.clean_relative_path);
+    let (current_full_path, is_enabled) = resolve_asset_on_disk(&base_mods_path, &current_relative_path)
+        .ok_or_else(|| AppError::NotFound(format!("Mod folder not found on disk for '{}' (enabled or disabled).", asset_info.clean_relative_path)))?;
+
+    let new_relative_path = current_relative_path.with_file_name(&sanitized_name);
+    let new_relative_path_str = new_relative_path.to_string_lossy().replace("\\", "/");
+    if new_relative_path_str == asset_info.clean_relative_path {
+        return Ok(new_relative_path_str); // Nothing to do
+    }
+
+    // Keep the DISABLED_ prefix on disk if the mod is currently disabled
+    let (new_path_if_enabled, new_path_if_disabled) = asset_disk_paths(&base_mods_path, &new_relative_path)
+        .ok_or_else(|| AppError::ModOperation(format!("Invalid new folder name '{}'.", sanitized_name)))?;
+    let new_full_path = if is_enabled { new_path_if_enabled } else { new_path_if_disabled };
+    // Case-only renames point at the same folder on case-insensitive filesystems
+    let is_case_only_rename = new_relative_path_str.eq_ignore_ascii_case(&asset_info.clean_relative_path);
+    if new_full_path.exists() && !is_case_only_rename {
+        return Err(AppError::ModOperation(format!("A folder named '{}' already exists.", new_full_path.display())));
+    }
+    let existing_db_asset: Option<i64> = conn.query_row(
+        "SELECT id FROM assets WHERE folder_name = ?1 AND id != ?2", params![new_relative_path_str, asset_id], |row| row.get(0),
+    ).optional()?;
+    if existing_db_asset.is_some() {
+        return Err(AppError::ModOperation(format!("Another mod is already stored at '{}'.", new_relative_path_str)));
+    }
+
+    fs::rename(&current_full_path, &new_full_path)?;
+    if let Err(e) = conn.execute("UPDATE assets SET folder_name = ?1 WHERE id = ?2", params![new_relative_path_str, asset_id]) {
+        // Put the folder back so disk and DB stay in sync
+        fs::rename(&new_full_path, &current_full_path).ok();
+        return Err(AppError::Sqlite(e));
+    }
+
+    info!("[rename_asset_folder] Renamed '{}' -> '{}'", current_full_path.display(), new_full_path.display());
+    Ok(new_relative_path_str)
+}
+
+#[command]
+fn merge_entities(from_slug: String, into_slug: String, db_state: State<DbState>) -> CmdResult<MergeEntitiesResult> {
+    info!("[merge_entities] Merging '{}' into '{}'", from_slug, into_slug);
+    if from_slug == into_slug {
+        return Err(AppError::ModOperation("Cannot merge an entity into itself.".to_string()));
+    }
+    if from_slug.ends_with(OTHER_ENTITY_SUFFIX) {
+        return Err(AppError::ModOperation(format!("Cannot merge the '{}' entity away; move its mods individually instead.", from_slug)));
+    }
+
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let conn_guard = lock_db(&db_state);
+    let conn = &*conn_guard;
+
+    let entity_info = |slug: &str| -> CmdResult<(i64, String)> {
+        conn.query_row(
+            "SELECT e.id, c.slug FROM entities e JOIN categories c ON e.category_id = c.id WHERE e.slug = ?1",
+            params![slug], |row| Ok((row.get(0)?, row.get(1)?)),
+        ).map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(format!("Entity '{}' not found.", slug)),
+            _ => AppError::Sqlite(e),
+        })
+    };
+    let (from_entity_id, from_category_slug) = entity_info(&from_slug)?;
+    let (into_entity_id, into_category_slug) = entity_info(&into_slug)?;
+
+    let assets_to_move: Vec<(i64, String)> = {
+        let mut stmt = conn.prepare("SELECT id, folder_name FROM assets WHERE entity_id = ?1")?;
+        let rows = stmt.query_map(params![from_entity_id], |row| Ok((row.get(0)?, row.get::<_, String>(1)?.replace("\\", "/"))))?;
+        rows.collect::<SqlResult<Vec<_>>>()?
+    };
+    info!("[merge_entities] {} assets to move.", assets_to_move.len());
+
+    let mut moved = 0;
+    let mut conflicts: Vec<String> = Vec::new();
+    for (asset_id, clean_relative_path) in assets_to_move {
+        match relocate_asset_folder(&base_mods_path, &clean_relative_path, &into_category_slug, &into_slug, None) {
+            Ok(relocation) => {
+                match conn.execute(
+                    "UPDATE assets SET entity_id = ?1, folder_name = ?2 WHERE id = ?3",
+                    params![into_entity_id, relocation.relative_path, asset_id],
+                ) {
+                    Ok(_) => { relocation.commit(); moved += 1; }
+                    Err(e) => conflicts.push(format!("'{}': DB update failed, folder moved back: {}", clean_relative_path, e)),
+                }
+            }
+            Err(e) => conflicts.push(format!("'{}': {}", clean_relative_path, e)),
+        }
+    }
+
+    // Only remove the source entity once nothing is left in it
+    let deleted_source = conflicts.is_empty();
+    if deleted_source {
+        conn.execute("DELETE FROM entities WHERE id = ?1", params![from_entity_id])?;
+        // Remove the now empty entity folder; ignore failure if something else is still in it
+        fs::remove_dir(base_mods_path.join(&from_category_slug).join(&from_slug)).ok();
+    } else {
+        error!("[merge_entities] Keeping '{}' because of {} conflict(s).", from_slug, conflicts.len());
+    }
+
+    info!("[merge_entities] Moved {} assets. Conflicts: {}. Source deleted: {}", moved, conflicts.len(), deleted_source);
+    Ok(MergeEntitiesResult { moved, conflicts, deleted_source })
+}
+
+// Files moved by merge_assets as (from, to) pairs. They are moved back on drop unless commit() is
+// called, so a failed move or DB update leaves both mods as they were.
+struct MergedFiles {
+    moved: Vec<(PathBuf, PathBuf)>,
+    created_dirs: Vec<PathBuf>, // Folders created at the destination, outermost first
+    committed: bool,
+}
+
+impl MergedFiles {
+    fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for MergedFiles {
+    fn drop(&mut self) {
+        if self.committed { return; }
+        warn!("[merge_assets] Rolling back {} moved file(s).", self.moved.len());
+        for (from, to) in self.moved.iter().rev() {
+            if let Err(e) = retry_if_locked(|| fs::rename(to, from)) {
+                error!("[merge_assets] Failed to move '{}' back to '{}': {}", to.display(), from.display(), e);
+            }
+        }
+        // remove_dir only removes empty folders, so nothing that failed to move back is lost
+        for dir in self.created_dirs.iter().rev() {
+            fs::remove_dir(dir).ok();
+        }
+    }
+}
+
+// Moves the secondary asset's files into the primary's folder (or a subfolder of it with `nest`),
+// then deletes the secondary. The primary keeps its metadata. Any file that already exists at the
+// destination aborts the merge before anything is moved; a failure after that moves the files back.
+#[command]
+fn merge_assets(primary_id: i64, secondary_id: i64, nest: Option<bool>, app_handle: AppHandle, db_state: State<DbState>) -> CmdResult<MergeAssetsResult> {
+    info!("[merge_assets] Merging asset {} into {} (nest: {:?})", secondary_id, primary_id, nest);
+    if primary_id == secondary_id {
+        return Err(AppError::ModOperation("Cannot merge a mod into itself.".to_string()));
+    }
+
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let conn_guard = lock_db(&db_state);
+    let conn = &*conn_guard;
+
+    let locate = |asset_id: i64| -> CmdResult<PathBuf> {
+        let info = get_asset_location_info(conn, asset_id)?;
+        resolve_asset_on_disk(&base_mods_path, Path::new(&info.clean_relative_path))
+            .map(|(path, _)| path)
+            .ok_or_else(|| AppError::NotFound(format!("Mod folder for asset {} ('{}') not found on disk.", asset_id, info.clean_relative_path)))
+    };
+    let primary_path = locate(primary_id)?;
+    let secondary_path = locate(secondary_id)?;
+    if primary_path.starts_with(&secondary_path) || secondary_path.starts_with(&primary_path) {
+        return Err(AppError::ModOperation("Cannot merge mods whose folders are nested inside each other.".to_string()));
+    }
+
+    let nested_folder = if nest.unwrap_or(false) {
+        let folder_name = secondary_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let clean_name = folder_name.strip_prefix(DISABLED_PREFIX).unwrap_or(&folder_name).to_string();
+        Some(clean_name)
+    } else {
+        None
+    };
+    let dest_root = match &nested_folder {
+        Some(name) => primary_path.join(name),
+        None => primary_path.clone(),
+    };
+
+    // --- Collision check before touching anything ---
+    let files: Vec<PathBuf> = WalkDir::new(&secondary_path).into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.path().strip_prefix(&secondary_path).ok().map(Path::to_path_buf))
+        .collect();
+    let collisions: Vec<String> = files.iter()
+        .filter(|relative| dest_root.join(relative).exists())
+        .map(|relative| relative.to_string_lossy().replace("\\", "/"))
+        .collect();
+    if !collisions.is_empty() {
+        let preview = collisions.iter().take(10).cloned().collect::<Vec<_>>().join(", ");
+        let more = if collisions.len() > 10 { format!(" and {} more", collisions.len() - 10) } else { String::new() };
+        error!("[merge_assets] Aborting, {} file(s) would be overwritten.", collisions.len());
+        return Err(AppError::ModOperation(format!("Merge aborted, these files already exist in the target folder: {}{}. Try merging into a subfolder instead.", preview, more)));
+    }
+
+    // --- Move files ---
+    // Returning an error drops `merged`, which moves every file already moved back
+    let mut merged = MergedFiles { moved: Vec::new(), created_dirs: Vec::new(), committed: false };
+    for relative in &files {
+        let from = secondary_path.join(relative);
+        let to = dest_root.join(relative);
+        if let Some(parent) = to.parent() {
+            let missing: Vec<PathBuf> = parent.ancestors().take_while(|dir| !dir.exists()).map(Path::to_path_buf).collect();
+            fs::create_dir_all(parent).map_err(|e| file_operation_error("create folder", parent, &e))?;
+            merged.created_dirs.extend(missing.into_iter().rev());
+        }
+        fs::rename(&from, &to)
+            .map_err(|e| AppError::ModOperation(format!("Failed to move '{}' after {} file(s) were moved; the merge was rolled back: {}", from.display(), merged.moved.len(), e)))?;
+        merged.moved.push((from, to));
+    }
+    let moved_files = merged.moved.len();
+
+    // --- Drop the secondary entry (presets/hotkey rows go with it via cascade) ---
+    let bound_hotkey: Option<String> = conn.query_row("SELECT accelerator FROM hotkeys WHERE asset_id = ?1", params![secondary_id], |row| row.get(0)).optional()?;
+    conn.execute("DELETE FROM assets WHERE id = ?1", params![secondary_id])?;
+    merged.commit();
+
+    if let Some(accelerator) = bound_hotkey {
+        if let Err(e) = app_handle.global_shortcut_manager().unregister(&accelerator) {
+            error!("[merge_assets] Failed to unregister hotkey '{}': {}", accelerator, e);
+        }
+    }
+    // Only (now empty) directories are left behind
+    if let Err(e) = fs::remove_dir_all(&secondary_path) {
+        error!("[merge_assets] Failed to remove leftover folder '{}': {}", secondary_path.display(), e);
+    }
+
+    info!("[merge_assets] Moved {} file(s) from asset {} into asset {}.", moved_files, secondary_id, primary_id);
+    Ok(MergeAssetsResult { moved_files, nested_folder })
+}
+
+#[command]
+fn rededuce_other_assets(category_slug: Option<String>, db_state: State<DbState>) -> CmdResult<RededuceResult> {
+    info!("[rededuce_other_assets] Re-deducing 'Other' assets for category: {:?}", category_slug);
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let conn_guard = lock_db(&db_state);
+    let conn = &*conn_guard;
+
+    // Drop the *-other entities from the maps so the current parent folder can't match itself
+    let mut maps = fetch_deduction_maps(conn)?;
+    maps.entity_slug_to_id.retain(|slug, _| !slug.ends_with(OTHER_ENTITY_SUFFIX));
+    maps.lowercase_entity_name_to_slug.retain(|_, slug| !slug.ends_with(OTHER_ENTITY_SUFFIX));
+    maps.lowercase_entity_firstname_to_slug.retain(|_, slug| !slug.ends_with(OTHER_ENTITY_SUFFIX));
+    maps.lowercase_entity_first_two_words_to_slug.retain(|_, slug| !slug.ends_with(OTHER_ENTITY_SUFFIX));
+
+    let other_assets: Vec<(i64, String, String, String)> = {
+        let mut stmt = conn.prepare(
+            "SELECT a.id, a.name, a.folder_name, e.slug
+             FROM assets a JOIN entities e ON a.entity_id = e.id JOIN categories c ON e.category_id = c.id
+             WHERE e.slug LIKE '%' || ?1 AND (?2 IS NULL OR c.slug = ?2)"
+        )?;
+        let rows = stmt.query_map(params![OTHER_ENTITY_SUFFIX, category_slug], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get::<_, String>(2)?.replace("\\", "/"), row.get(3)?))
+        })?;
+        rows.collect::<SqlResult<Vec<_>>>()?
+    };
+
+    let mut result = RededuceResult { checked: other_assets.len(), moved: Vec::new(), errors: Vec::new() };
+    for (asset_id, name, clean_relative_path, from_entity_slug) in other_assets {
+        let Some((mod_folder_path, _)) = resolve_asset_on_disk(&base_mods_path, Path::new(&clean_relative_path)) else {
+            result.errors.push(format!("'{}': folder not found on disk.", clean_relative_path));
+            continue;
+        };
+        let Some(deduced) = deduce_mod_info_v2(&mod_folder_path, &base_mods_path, &maps) else { continue; };
+        if deduced.entity_slug.ends_with(OTHER_ENTITY_SUFFIX) { continue; } // Still no specific match
+
+        let (Some(&to_entity_id), Some(to_category_slug)) = (
+            maps.entity_slug_to_id.get(&deduced.entity_slug),
+            maps.entity_slug_to_category_slug.get(&deduced.entity_slug),
+        ) else { continue; };
+
+        match relocate_asset_folder(&base_mods_path, &clean_relative_path, to_category_slug, &deduced.entity_slug, None) {
+            Ok(relocation) => {
+                if let Err(e) = conn.execute(
+                    "UPDATE assets SET entity_id = ?1, folder_name = ?2 WHERE id = ?3",
+                    params![to_entity_id, relocation.relative_path, asset_id],
+                ) {
+                    result.errors.push(format!("'{}': DB update failed, folder moved back: {}", clean_relative_path, e));
+                    continue;
+                }
+                relocation.commit();
+                info!("[rededuce_other_assets] Moved '{}' from {} to {}", name, from_entity_slug, deduced.entity_slug);
+                result.moved.push(RededuceMove { asset_id, name, from_entity_slug, to_entity_slug: deduced.entity_slug });
+            }
+            Err(e) => result.errors.push(format!("'{}': {}", clean_relative_path, e)),
+        }
+    }
 
-    println!("[delete_asset] Asset ID {} deleted successfully. END", asset_id);
-    Ok(())
+    info!("[rededuce_other_assets] Checked {}, moved {}, errors {}.", result.checked, result.moved.len(), result.errors.len());
+    Ok(result)
 }
 
 #[command]
-async fn read_binary_file(path: String) -> Result<Vec<u8>, String> {
-    println!("[read_binary_file] Reading path: {}", path);
-    // Keep the original path for potential error reporting
-    let path_for_error = path.clone(); // Clone the path *before* it's moved
-
-    read_binary(PathBuf::from(path)) // 'path' is moved here
-        .map_err(|e| {
-            // Use the cloned path 'path_for_error' in the error message
-            eprintln!("[read_binary_file] Error reading file '{}': {}", path_for_error, e);
-            format!("Failed to read file: {}", e)
-        })
+async fn read_binary_file(path: String) -> CmdResult<Vec<u8>> {
+    info!("[read_binary_file] Reading path: {}", path);
+    let path = PathBuf::from(path);
+    fs::read(&path).map_err(|e| {
+        error!("[read_binary_file] Error reading file '{}': {}", path.display(), e);
+        if e.kind() == io::ErrorKind::NotFound {
+            AppError::NotFound(format!("File '{}' does not exist", path.display()))
+        } else {
+            file_operation_error("read", &path, &e)
+        }
+    })
 }
 
 #[command]
 async fn select_archive_file() -> CmdResult<Option<PathBuf>> {
-    println!("[select_archive_file] Opening file dialog...");
+    info!("[select_archive_file] Opening file dialog...");
     let result = dialog::blocking::FileDialogBuilder::new()
         .set_title("Select Mod Archive")
         // --- Update Filter ---
-        .add_filter("Archives", &["zip", "7z", "rar"])
+        .add_filter("Archives", &["zip", "7z", "rar", "tar", "gz", "tgz"])
         .add_filter("All Files", &["*"])
         .pick_file();
 
     match result {
         Some(path) => {
-            println!("[select_archive_file] File selected: {}", path.display());
+            info!("[select_archive_file] File selected: {}", path.display());
             Ok(Some(path))
         },
         None => {
-            println!("[select_archive_file] Dialog cancelled.");
+            info!("[select_archive_file] Dialog cancelled.");
             Ok(None)
         }, // User cancelled
     }
 }
 
+// Full integrity check (decompresses every entry), for when an import fails or a download looks suspect
+#[command]
+async fn verify_archive(file_path_str: String) -> CmdResult<()> {
+    async_runtime::spawn_blocking(move || validate_archive(Path::new(&file_path_str), true))
+        .await
+        .map_err(|e| AppError::Other(format!("Archive verification task failed: {}", e)))?
+}
+
+// Adds the folders an archive only implies through its file paths (zips often store no directory
+// entries), sorts by path and links every entry to its parent directory.
+fn link_archive_entries(entries: &mut Vec<ArchiveEntry>) {
+    let known_dirs: HashSet<String> = entries.iter()
+        .filter(|e| e.is_dir)
+        .map(|e| e.path.trim_end_matches('/').to_string())
+        .collect();
+    let mut implied_dirs = HashSet::new();
+    for entry in entries.iter() {
+        let mut path = entry.path.trim_end_matches('/');
+        while let Some((parent, _)) = path.rsplit_once('/') {
+            if !parent.is_empty() && !known_dirs.contains(parent) {
+                implied_dirs.insert(parent.to_string());
+            }
+            path = parent;
+        }
+    }
+    entries.extend(implied_dirs.into_iter().map(|path| ArchiveEntry {
+        path, is_dir: true, is_likely_mod_root: false, uncompressed_size: 0, parent_index: None, depth: 0,
+    }));
+
+    entries.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+    let dir_indices: HashMap<String, usize> = entries.iter().enumerate()
+        .filter(|(_, e)| e.is_dir)
+        .map(|(index, e)| (e.path.trim_end_matches('/').to_string(), index))
+        .collect();
+    for entry in entries.iter_mut() {
+        let path = entry.path.trim_end_matches('/');
+        entry.depth = path.matches('/').count();
+        entry.parent_index = path.rsplit_once('/').and_then(|(parent, _)| dir_indices.get(parent).copied());
+    }
+}
+
 #[command]
-fn analyze_archive(
+async fn analyze_archive(
     file_path_str: String,
-    // *** ADDED: Inject DB State ***
-    db_state: State<DbState>
+    db_state: State<'_, DbState>,
+    app_handle: AppHandle
 ) -> CmdResult<ArchiveAnalysisResult> {
-    println!("[analyze_archive] Analyzing: {}", file_path_str);
+    info!("[analyze_archive] Analyzing: {}", file_path_str);
+
+    // --- Fetch Deduction Maps ---
+    let (maps, limits) = {
+        // Use a block to limit the scope of the lock guard
+        let conn_guard = lock_db(&db_state);
+        let conn = &*conn_guard; // Dereference the guard
+        let maps = fetch_deduction_maps(conn)?;
+        (maps, ArchiveLimits::from_settings(conn)?)
+    };
+    info!("[analyze_archive] Deduction maps loaded.");
+    // --- End Fetch ---
+
+    // Archives with thousands of entries take a while to read; do it on a blocking thread and report progress
+    async_runtime::spawn_blocking(move || analyze_archive_contents(file_path_str, maps, limits, app_handle)).await
+        .map_err(|e| AppError::Other(format!("Archive analysis failed: {}", e)))?
+}
+
+fn analyze_archive_contents(file_path_str: String, maps: DeductionMaps, limits: ArchiveLimits, app_handle: AppHandle) -> CmdResult<ArchiveAnalysisResult> {
     let file_path = PathBuf::from(&file_path_str);
-    if !file_path.is_file() { return Err(format!("Archive file not found: {}", file_path.display())); }
+    if !file_path.is_file() { return Err(AppError::NotFound(format!("Archive file not found: {}", file_path.display()))); }
 
-    let extension = file_path.extension().and_then(|os| os.to_str()).map(|s| s.to_lowercase());
-    println!("[analyze_archive] Detected extension: {:?}", extension);
+    let extension = archive_type(&file_path);
+    info!("[analyze_archive] Detected extension: {:?}", extension);
+    validate_archive(&file_path, false)?;
+    check_archive_limits(&file_path, &limits)?;
 
     let mut entries = Vec::new();
     let mut ini_contents: HashMap<String, String> = HashMap::new();
     let preview_candidates = ["preview.png", "icon.png", "thumbnail.png", "preview.jpg", "icon.jpg", "thumbnail.jpg"];
 
-    // --- Fetch Deduction Maps ---
-    let maps = {
-        // Use a block to limit the scope of the lock guard
-        let conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
-        let conn = &*conn_guard; // Dereference the guard
-        fetch_deduction_maps(conn)
-             .map_err(|e| format!("Analyze: Failed to fetch deduction maps: {}", e))?
+    let emit_progress = |processed: usize, total: usize, current_file: &str| {
+        // Every entry would flood the frontend on huge archives
+        if !processed.is_multiple_of(ANALYZE_PROGRESS_INTERVAL) && processed != total { return; }
+        app_handle.emit_all(ANALYZE_PROGRESS_EVENT, AnalyzeProgress {
+            processed, total, current_file: Some(current_file.to_string())
+        }).unwrap_or_else(|e| error!("Failed to emit analyze progress: {}", e));
     };
-    println!("[analyze_archive] Deduction maps loaded.");
-    // --- End Fetch ---
 
     match extension.as_deref() {
         Some("zip") => {
-            println!("[analyze_archive] Processing as ZIP...");
+            info!("[analyze_archive] Processing as ZIP...");
             let file = fs::File::open(&file_path)
-                .map_err(|e| format!("Failed to open zip file {}: {}", file_path.display(), e))?;
-            let mut archive = ZipArchive::new(file)
-                .map_err(|e| format!("Failed to read zip archive {}: {}", file_path.display(), e))?;
-
-            for i in 0..archive.len() {
-                let mut file_entry = archive.by_index(i)
-                     .map_err(|e| format!("Failed to read zip entry #{}: {}", i, e))?;
-                let path_str_opt = file_entry.enclosed_name().map(|p| p.to_string_lossy().replace("\\", "/"));
-                if path_str_opt.is_none() { continue; }
-                // --- FIX: Just clone the String if needed, or use directly ---
-                let path_str = path_str_opt.unwrap().to_string(); // Use to_string() to ensure it's owned String
+                .map_err(|e| file_operation_error("open archive", &file_path, &e))?;
+            let mut archive = ZipArchive::new(file)?;
+
+            let total_entries = archive.len();
+            for i in 0..total_entries {
+                let mut file_entry = archive.by_index(i)?;
+                let Some(path_str) = zip_entry_path(&file_entry) else {
+                    warn!("[analyze_archive] Skipping zip entry with an unsafe path: {}", file_entry.name());
+                    continue;
+                };
                 let is_dir = file_entry.is_dir();
+                let uncompressed_size = file_entry.size();
+                emit_progress(i + 1, total_entries, &path_str);
 
                 if !is_dir && path_str.to_lowercase().ends_with(".ini") {
-                    let mut content = String::new();
-                    if file_entry.read_to_string(&mut content).is_ok() {
-                        ini_contents.insert(path_str.clone(), content);
+                    if uncompressed_size > MAX_ANALYZE_INI_BYTES {
+                        warn!("[analyze_archive] Skipping oversized INI '{}' ({} bytes).", path_str, uncompressed_size);
+                    } else {
+                        let mut content = String::new();
+                        if file_entry.read_to_string(&mut content).is_ok() {
+                            ini_contents.insert(path_str.clone(), content);
+                        }
                     }
                 }
-                entries.push(ArchiveEntry { path: path_str, is_dir, is_likely_mod_root: false });
+                entries.push(ArchiveEntry { path: path_str, is_dir, is_likely_mod_root: false, uncompressed_size, parent_index: None, depth: 0 });
             }
         }
         Some("7z") => {
-            println!("[analyze_archive] Processing as 7z...");
+            info!("[analyze_archive] Processing as 7z...");
             // --- FIX: Use Password::empty() ---
-            let mut archive = sevenz_rust::SevenZReader::open(&file_path_str, Password::empty())
-                .map_err(|e| format!("Failed to open/read 7z archive {}: {}", file_path.display(), e))?;
+            let mut archive = sevenz_rust::SevenZReader::open(&file_path_str, Password::empty())?;
 
+             let total_entries = archive.archive().files.len();
              // --- FIX: Use for_each_entries ---
              archive.for_each_entries(|entry, reader| {
                 let path_str = entry.name().replace("\\", "/");
                 let is_dir = entry.is_directory();
+                emit_progress(entries.len() + 1, total_entries, &path_str);
 
-                if !is_dir && path_str.to_lowercase().ends_with(".ini") {
+                if !is_dir && path_str.to_lowercase().ends_with(".ini") && entry.size() > MAX_ANALYZE_INI_BYTES {
+                    warn!("[analyze_archive] Skipping oversized INI '{}' ({} bytes).", path_str, entry.size());
+                } else if !is_dir && path_str.to_lowercase().ends_with(".ini") {
                      let mut content_bytes = Vec::new();
                      let mut buffer = [0u8; 4096];
                      loop {
@@ -2819,17 +6464,16 @@ fn analyze_archive(
                      let content = String::from_utf8_lossy(&content_bytes).to_string();
                      ini_contents.insert(path_str.clone(), content);
                 }
-                entries.push(ArchiveEntry { path: path_str, is_dir, is_likely_mod_root: false });
+                entries.push(ArchiveEntry { path: path_str, is_dir, is_likely_mod_root: false, uncompressed_size: entry.size(), parent_index: None, depth: 0 });
                 Ok(true) // Continue processing entries
              })
              // --- Map the specific error type from the closure if needed ---
              .map_err(|e: sevenz_rust::Error| format!("Error iterating 7z entries: {}", e))?;
         }
         Some("rar") => {
-            println!("[analyze_archive] Processing as RAR...");
+            info!("[analyze_archive] Processing as RAR...");
             let mut list_archive = Archive::new(&file_path_str)
-                .open_for_listing()
-                .map_err(|e| e.to_string())?;
+                .open_for_listing()?;
 
             let mut header_infos = Vec::new();
             // Iterate through headers
@@ -2838,13 +6482,19 @@ fn analyze_archive(
                     Ok(header) => {
                         let path_str = header.filename.to_string_lossy().replace("\\", "/").to_string();
                         let is_dir = header.is_directory();
+                        emit_progress(entries.len() + 1, 0, &path_str); // RAR listing has no up-front entry count
+                        if !is_dir && path_str.to_lowercase().ends_with(".ini") && header.unpacked_size > MAX_ANALYZE_INI_BYTES {
+                            warn!("[analyze_archive] Skipping oversized INI '{}' ({} bytes).", path_str, header.unpacked_size);
+                            entries.push(ArchiveEntry { path: path_str, is_dir, is_likely_mod_root: false, uncompressed_size: header.unpacked_size, parent_index: None, depth: 0 });
+                            continue;
+                        }
                         // --- FIX 1: Clone path_str for the first push ---
                         header_infos.push((path_str.clone(), is_dir, header.filename.clone()));
                         // --- End Fix 1 ---
-                        entries.push(ArchiveEntry { path: path_str, is_dir, is_likely_mod_root: false });
+                        entries.push(ArchiveEntry { path: path_str, is_dir, is_likely_mod_root: false, uncompressed_size: header.unpacked_size, parent_index: None, depth: 0 });
                     }
                     Err(e) => {
-                        eprintln!("[analyze_archive] Warning: Skipping RAR entry due to header read error: {}", e);
+                        warn!("[analyze_archive] Skipping RAR entry due to header read error: {}", e);
                         // --- FIX 2: Remove force_heal call ---
                         // list_archive.force_heal(); // Cannot call this here
                         // --- End Fix 2 ---
@@ -2862,11 +6512,10 @@ fn analyze_archive(
                .collect();
 
             if !ini_files_to_read.is_empty() {
-               let mut processing_archive = Archive::new(&file_path_str).open_for_processing()
-                    .map_err(|e| e.to_string())?;
+               let mut processing_archive = Archive::new(&file_path_str).open_for_processing()?;
                let mut read_count = 0;
                loop {
-                   match processing_archive.read_header().map_err(|e| e.to_string())? {
+                   match processing_archive.read_header()? {
                        Some(header_state) => {
                            let current_filename = header_state.entry().filename.clone();
                            let path_str = current_filename.to_string_lossy().replace("\\", "/").to_string();
@@ -2878,10 +6527,10 @@ fn analyze_archive(
                                        read_count += 1;
                                        if read_count == ini_files_to_read.len() { break; }
                                    }
-                                   Err(e) => { return Err(format!("Error reading content of RAR INI '{}': {}", path_str, e)); }
+                                   Err(e) => { return Err(AppError::ModOperation(format!("Error reading content of RAR INI '{}': {}", path_str, e))); }
                                }
                            } else {
-                               processing_archive = header_state.skip().map_err(|e| e.to_string())?;
+                               processing_archive = header_state.skip()?;
                            }
                        }
                        None => break,
@@ -2889,13 +6538,40 @@ fn analyze_archive(
                }
             }
         }
+        Some(kind @ ("tar" | "tar.gz")) => {
+            info!("[analyze_archive] Processing as {}...", kind);
+            let mut archive = open_tar_archive(&file_path, kind == "tar.gz")
+                .map_err(|e| file_operation_error("open archive", &file_path, &e))?;
+            for entry_result in archive.entries()? {
+                let mut entry = entry_result?;
+                let is_dir = entry.header().entry_type().is_dir();
+                let Some(path_str) = tar_entry_path(&entry) else {
+                    if !is_dir { warn!("[analyze_archive] Skipping tar entry that is a link or has an unsafe path: {}", entry.path().map(|p| p.display().to_string()).unwrap_or_default()); }
+                    continue;
+                };
+                let uncompressed_size = entry.size();
+                emit_progress(entries.len() + 1, 0, &path_str); // Tar has no up-front entry count
+
+                if !is_dir && path_str.to_lowercase().ends_with(".ini") {
+                    if uncompressed_size > MAX_ANALYZE_INI_BYTES {
+                        warn!("[analyze_archive] Skipping oversized INI '{}' ({} bytes).", path_str, uncompressed_size);
+                    } else {
+                        let mut content = String::new();
+                        if entry.read_to_string(&mut content).is_ok() {
+                            ini_contents.insert(path_str.clone(), content);
+                        }
+                    }
+                }
+                entries.push(ArchiveEntry { path: path_str, is_dir, is_likely_mod_root: false, uncompressed_size, parent_index: None, depth: 0 });
+            }
+        }
         _ => {
-            return Err(format!("Unsupported archive type: {:?}", extension));
+            return Err(AppError::UnsupportedArchive(format!("Unsupported archive type: {:?}", extension)));
         }
     }
-    println!("[analyze_archive] Pass 1: Found {} entries. Found {} INI files.", entries.len(), ini_contents.len());
+    info!("[analyze_archive] Pass 1: Found {} entries. Found {} INI files.", entries.len(), ini_contents.len());
 
-    entries.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+    link_archive_entries(&mut entries);
 
     // ... (Pass 2: Find roots) ...
     let mut likely_root_indices = HashSet::new();
@@ -2916,6 +6592,27 @@ fn analyze_archive(
             }
         }
     }
+    // Texture-only replacement mods ship without any INI; fall back to the topmost folders holding mod assets
+    if likely_root_indices.is_empty() && !entries.iter().any(|e| !e.is_dir && e.path.to_lowercase().ends_with(".ini")) {
+        let asset_dirs: HashSet<String> = entries.iter()
+            .filter(|e| !e.is_dir)
+            .filter(|e| Path::new(&e.path).extension().and_then(OsStr::to_str)
+                .is_some_and(|ext| MOD_ASSET_EXTENSIONS.contains(&ext.to_lowercase().as_str())))
+            .filter_map(|e| Path::new(&e.path).parent().map(|p| p.to_string_lossy().replace("\\", "/")))
+            .filter(|p| !p.is_empty())
+            .collect();
+        for (dir_index, dir_entry) in entries.iter().enumerate() {
+            if !dir_entry.is_dir { continue; }
+            let dir_path_norm = dir_entry.path.strip_suffix('/').unwrap_or(&dir_entry.path);
+            let has_asset_ancestor = asset_dirs.iter().any(|other| dir_path_norm.starts_with(&format!("{}/", other)));
+            if asset_dirs.contains(dir_path_norm) && !has_asset_ancestor {
+                likely_root_indices.insert(dir_index);
+            }
+        }
+        if !likely_root_indices.is_empty() {
+            info!("[analyze_archive] No INI found; using {} folder(s) with mod assets as likely roots.", likely_root_indices.len());
+        }
+    }
     // ... (Pass 3: Find previews) ...
      let mut root_to_preview_map: HashMap<usize, String> = HashMap::new();
      for root_index in likely_root_indices.iter() {
@@ -2930,31 +6627,59 @@ fn analyze_archive(
               }
           }
      }
+    // Collect every image so the user can pick a different preview (frontend filters by selected root)
+    let mut preview_candidate_paths: Vec<String> = entries.iter()
+        .filter(|e| !e.is_dir)
+        .filter(|e| Path::new(&e.path).extension().and_then(OsStr::to_str)
+            .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str())))
+        .map(|e| e.path.clone())
+        .collect();
+    preview_candidate_paths.sort_by_key(|path| {
+        let file_name = Path::new(path).file_name().and_then(OsStr::to_str).unwrap_or("").to_lowercase();
+        (!preview_candidates.contains(&file_name.as_str()), path.to_lowercase())
+    });
     // ... (Pass 4: Deduction) ...
     let mut deduced_mod_name: Option<String> = None;
     let mut deduced_author: Option<String> = None;
+    let mut deduced_version: Option<String> = None;
     // Initialize final deduced slugs
     let mut final_deduced_category_slug: Option<String> = None;
     let mut final_deduced_entity_slug: Option<String> = None;
+    let mut deduction_source: Option<DeductionSource> = None;
     // Raw hints extracted from INI
     let mut raw_ini_type_found: Option<String> = None;
     let mut raw_ini_target_found: Option<String> = None;
+    let mut raw_ini_hash_hints: Option<IniHashHints> = None;
     // Preview path detected within archive
     let mut detected_preview_internal_path : Option<String> = None;
     let mut first_likely_root_processed = false;
 
+    // --- 0. User entity rules, against the first mod root's folder name, then the archive's own name ---
+    if !maps.entity_rules.is_empty() {
+        let first_root_name = likely_root_indices.iter().min()
+            .and_then(|&index| entries[index].path.trim_end_matches('/').rsplit('/').next().map(str::to_string));
+        let archive_stem = file_path.file_stem().and_then(OsStr::to_str).map(str::to_string);
+        if let Some((name, index)) = first_root_name.into_iter().chain(archive_stem)
+            .find_map(|name| match_deduction_rule(&maps.entity_rules, &name).map(|index| (name, index))) {
+            final_deduced_entity_slug = Some(maps.entity_rules[index].1.clone());
+            deduction_source = Some(DeductionSource::UserRule);
+            info!("[analyze_archive]   -> Found entity via rule #{} for '{}' -> {}", index + 1, name, maps.entity_rules[index].1);
+        }
+    }
+
     // --- 1. Deduce from INI in First Likely Root ---
-    println!("[analyze_archive] Starting Pass 4: Deduction...");
+    info!("[analyze_archive] Starting Pass 4: Deduction...");
     for (index, entry) in entries.iter_mut().enumerate() {
         if likely_root_indices.contains(&index) {
             entry.is_likely_mod_root = true; // Mark the entry
-            println!("[analyze_archive] Found likely root: {}", entry.path);
+            info!("[analyze_archive] Found likely root: {}", entry.path);
             if !first_likely_root_processed {
                 first_likely_root_processed = true;
                 let root_prefix = if entry.path.ends_with('/') { entry.path.clone() } else { format!("{}/", entry.path) };
                 // Find the first INI file *directly* inside this root
                 if let Some((_ini_path, ini_content)) = ini_contents.iter().find(|(p, _)| p.starts_with(&root_prefix) && p.trim_start_matches(&root_prefix).find('/') == None) {
-                    println!("[analyze_archive] Found INI in root {}: {}", root_prefix, _ini_path);
+                    info!("[analyze_archive] Found INI in root {}: {}", root_prefix, _ini_path);
+                    raw_ini_hash_hints = Some(extract_ini_hash_hints(ini_content));
                     if let Ok(ini) = Ini::load_from_str(ini_content) {
                         // --- Temporary storage for extracted hints ---
                         let mut extracted_target: Option<String> = None;
@@ -2966,13 +6691,14 @@ fn analyze_archive(
                                 let name_val = section.get("Name").or_else(|| section.get("ModName"));
                                 // Use the INI name if found, otherwise keep the initial filename guess
                                 if let Some(name) = name_val {
-                                    let cleaned_ini_name = MOD_NAME_CLEANUP_REGEX.replace_all(name, "").trim().to_string();
+                                    let cleaned_ini_name = clean_mod_name(name, &maps.name_cleanup_patterns);
                                     if !cleaned_ini_name.is_empty() {
                                         deduced_mod_name = Some(cleaned_ini_name);
                                     }
                                 }
                                 let author_val = section.get("Author");
                                 if author_val.is_some() { deduced_author = author_val.map(String::from); }
+                                if let Some(version) = section.get("Version").and_then(normalize_mod_version) { deduced_version = Some(version); }
 
                                 // Extract Raw Hints
                                 let target_val = section.get("Target").or_else(|| section.get("Entity")).or_else(|| section.get("Character"));
@@ -2982,27 +6708,36 @@ fn analyze_archive(
                             }
                         }
                         // Log extracted hints and assign to outer scope
-                        println!("[analyze_archive] INI Extracted Hints: Target='{:?}', Type='{:?}'", extracted_target, extracted_type);
+                        info!("[analyze_archive] INI Extracted Hints: Target='{:?}', Type='{:?}'", extracted_target, extracted_type);
                         raw_ini_target_found = extracted_target;
                         raw_ini_type_found = extracted_type;
                     } else {
-                        eprintln!("[analyze_archive] Warning: Failed to parse INI content from {}", _ini_path);
+                        warn!("[analyze_archive] Failed to parse INI content from {}", _ini_path);
                     }
                 } else {
-                    println!("[analyze_archive] No INI found directly in root: {}", root_prefix);
+                    info!("[analyze_archive] No INI found directly in root: {}", root_prefix);
                 }
 
                 // --- Try matching INI Target Hint (USE HELPER) ---
                 if final_deduced_entity_slug.is_none() { // Only run if not already found
                     if let Some(target_hint) = &raw_ini_target_found {
-                        println!("[analyze_archive] Trying INI target hint matching...");
+                        info!("[analyze_archive] Trying INI target hint matching...");
                         // Use the reusable helper function
                         if let Some(slug) = find_entity_slug_from_hint(target_hint, &maps) {
                             final_deduced_entity_slug = Some(slug);
-                            println!("[analyze_archive]   -> Found entity via INI target hint: '{}' -> {}", target_hint, final_deduced_entity_slug.as_ref().unwrap());
+                            deduction_source = Some(DeductionSource::IniTarget);
+                            info!("[analyze_archive]   -> Found entity via INI target hint: '{}' -> {}", target_hint, final_deduced_entity_slug.as_ref().unwrap());
                         }
                     } else {
-                        println!("[analyze_archive] No INI target hint found.");
+                        info!("[analyze_archive] No INI target hint found.");
+                    }
+                }
+
+                // --- Try known hashes / namespace / TextureOverride names ---
+                if final_deduced_entity_slug.is_none() {
+                    if let Some(hints) = &raw_ini_hash_hints {
+                        final_deduced_entity_slug = find_entity_slug_from_ini_hash_hints(hints, &maps);
+                        if final_deduced_entity_slug.is_some() { deduction_source = Some(DeductionSource::IniHash); }
                     }
                 }
 
@@ -3010,24 +6745,24 @@ fn analyze_archive(
                 if final_deduced_category_slug.is_none() { // Only run if not already found
                     if let Some(type_hint) = &raw_ini_type_found {
                         let lower_type_hint = type_hint.to_lowercase();
-                        println!("[analyze_archive] Trying INI type hint: '{}' (lowercase: '{}')", type_hint, lower_type_hint);
+                        info!("[analyze_archive] Trying INI type hint: '{}' (lowercase: '{}')", type_hint, lower_type_hint);
 
                         // Prio 1: Exact slug
                         if maps.category_slug_to_id.contains_key(type_hint) {
                             final_deduced_category_slug = Some(type_hint.clone());
-                            println!("[analyze_archive]   -> Matched category via INI exact slug: {}", type_hint);
+                            info!("[analyze_archive]   -> Matched category via INI exact slug: {}", type_hint);
                         }
                         // Prio 2: Exact lowercase name -> original slug
                         else if let Some(slug) = maps.lowercase_category_name_to_slug.get(&lower_type_hint) {
                             final_deduced_category_slug = Some(slug.clone());
-                            println!("[analyze_archive]   -> Matched category via INI exact lowercase name: {} -> {}", lower_type_hint, slug);
+                            info!("[analyze_archive]   -> Matched category via INI exact lowercase name: {} -> {}", lower_type_hint, slug);
                         }
                         // Prio 3: Known name starts with hint
                         else {
                             for (cat_name_lower, cat_slug) in &maps.lowercase_category_name_to_slug {
                                 if cat_name_lower.starts_with(&lower_type_hint) {
                                     final_deduced_category_slug = Some(cat_slug.clone());
-                                    println!("[analyze_archive]   -> Matched category via INI name prefix: '{}' starts with '{}' -> {}", cat_name_lower, lower_type_hint, cat_slug);
+                                    info!("[analyze_archive]   -> Matched category via INI name prefix: '{}' starts with '{}' -> {}", cat_name_lower, lower_type_hint, cat_slug);
                                     break;
                                 }
                             }
@@ -3037,27 +6772,27 @@ fn analyze_archive(
                             for (cat_name_lower, cat_slug) in &maps.lowercase_category_name_to_slug {
                                 if lower_type_hint.len() > 2 && cat_name_lower.contains(&lower_type_hint) {
                                     final_deduced_category_slug = Some(cat_slug.clone());
-                                    println!("[analyze_archive]   -> Matched category via INI name contains: '{}' contains '{}' -> {}", cat_name_lower, lower_type_hint, cat_slug);
+                                    info!("[analyze_archive]   -> Matched category via INI name contains: '{}' contains '{}' -> {}", cat_name_lower, lower_type_hint, cat_slug);
                                     break;
                                 }
                             }
                         }
                         if final_deduced_category_slug.is_none() {
-                            println!("[analyze_archive]   -> No category match found from INI type hint.");
+                            info!("[analyze_archive]   -> No category match found from INI type hint.");
                         }
                     } else {
-                        println!("[analyze_archive] No INI type hint found.");
+                        info!("[analyze_archive] No INI type hint found.");
                     }
                 }
 
                 // Use detected preview if available for this root
                 if let Some(preview_path) = root_to_preview_map.get(&index) {
                     detected_preview_internal_path = Some(preview_path.clone());
-                    println!("[analyze_archive] Detected preview for this root: {}", preview_path);
+                    info!("[analyze_archive] Detected preview for this root: {}", preview_path);
                 }
 
                 // --- Break after processing the first root's INI ---
-                println!("[analyze_archive] Finished processing first likely root INI.");
+                info!("[analyze_archive] Finished processing first likely root INI.");
                 break;
             }
         }
@@ -3067,7 +6802,7 @@ fn analyze_archive(
 
     // --- 2. Deduce from Internal Filenames ---
     if final_deduced_entity_slug.is_none() {
-        println!("[analyze_archive] Trying internal filename matching...");
+        info!("[analyze_archive] Trying internal filename matching...");
         let mut file_match_found = false;
         // Iterate through ALL file entries in the archive
         for entry in &entries {
@@ -3079,7 +6814,8 @@ fn analyze_archive(
                         // Use the helper to check if the stem matches an entity
                         if let Some(slug) = find_entity_slug_from_hint(stem, &maps) {
                             final_deduced_entity_slug = Some(slug);
-                            println!("[analyze_archive]   -> Found entity via internal filename stem: '{}' -> {}", stem, final_deduced_entity_slug.as_ref().unwrap());
+                            deduction_source = Some(DeductionSource::InternalFilename);
+                            info!("[analyze_archive]   -> Found entity via internal filename stem: '{}' -> {}", stem, final_deduced_entity_slug.as_ref().unwrap());
                             file_match_found = true;
                             break; // Found a match from a file, stop searching files
                         }
@@ -3088,43 +6824,44 @@ fn analyze_archive(
             }
         }
         if !file_match_found {
-            println!("[analyze_archive]   -> No entity match found from internal filenames.");
+            info!("[analyze_archive]   -> No entity match found from internal filenames.");
         }
     } else {
-        println!("[analyze_archive] Skipping internal filename check (entity already found).")
+        info!("[analyze_archive] Skipping internal filename check (entity already found).")
     }
     // --- End Internal Filename Deduction ---
 
 
     // --- 3. Deduce from Archive Filename (USE HELPER - Lower Priority) ---
     if final_deduced_entity_slug.is_none() || final_deduced_category_slug.is_none() {
-        println!("[analyze_archive] Attempting deduction from archive filename...");
+        info!("[analyze_archive] Attempting deduction from archive filename...");
         if let Some(stem) = file_path.file_stem().and_then(OsStr::to_str) {
             // Try matching stem against Entities (USE HELPER)
             if final_deduced_entity_slug.is_none() {
-                println!("[analyze_archive] Trying archive filename stem for Entity: '{}'", stem);
+                info!("[analyze_archive] Trying archive filename stem for Entity: '{}'", stem);
                 if let Some(slug) = find_entity_slug_from_hint(stem, &maps) {
                     final_deduced_entity_slug = Some(slug);
-                    println!("[analyze_archive]   -> Found entity via filename.");
+                    deduction_source = Some(DeductionSource::ArchiveFilename);
+                    info!("[analyze_archive]   -> Found entity via filename.");
                 } else {
-                    println!("[analyze_archive]   -> No entity match found from filename.");
+                    info!("[analyze_archive]   -> No entity match found from filename.");
                 }
             }
 
             // Try matching stem against Categories
             if final_deduced_category_slug.is_none() {
                 let cleaned_stem = clean_and_extract_name(stem);
-                println!("[analyze_archive] Trying archive filename stem for Category: '{}' (cleaned lowercase: '{}')", stem, cleaned_stem);
+                info!("[analyze_archive] Trying archive filename stem for Category: '{}' (cleaned lowercase: '{}')", stem, cleaned_stem);
 
                 // Prio 1: Exact slug (original stem)
                 if maps.category_slug_to_id.contains_key(stem) {
                     final_deduced_category_slug = Some(stem.to_string());
-                    println!("[analyze_archive]   -> Matched category from filename via exact slug: {}", stem);
+                    info!("[analyze_archive]   -> Matched category from filename via exact slug: {}", stem);
                 }
                 // Prio 2: Exact lowercase cleaned name match -> original slug
                 else if let Some(slug) = maps.lowercase_category_name_to_slug.get(&cleaned_stem) {
                     final_deduced_category_slug = Some(slug.clone());
-                    println!("[analyze_archive]   -> Matched category from filename via exact cleaned name: {} -> {}", cleaned_stem, slug);
+                    info!("[analyze_archive]   -> Matched category from filename via exact cleaned name: {} -> {}", cleaned_stem, slug);
                 }
                 // Prio 3: Known name contains cleaned stem part
                 else {
@@ -3134,7 +6871,7 @@ fn analyze_archive(
                             for word in &stem_words {
                                 if cat_name_lower.contains(word) {
                                     final_deduced_category_slug = Some(cat_slug.clone());
-                                    println!("[analyze_archive]   -> Matched category from filename via word contains: '{}' contains '{}' -> {}", cat_name_lower, word, cat_slug);
+                                    info!("[analyze_archive]   -> Matched category from filename via word contains: '{}' contains '{}' -> {}", cat_name_lower, word, cat_slug);
                                     break 'cat_loop;
                                 }
                             }
@@ -3142,39 +6879,67 @@ fn analyze_archive(
                     }
                 }
                 if final_deduced_category_slug.is_none() {
-                    println!("[analyze_archive]   -> No category match found from filename.");
+                    info!("[analyze_archive]   -> No category match found from filename.");
                 }
             }
         } else {
-            println!("[analyze_archive] Could not get filename stem.");
+            info!("[analyze_archive] Could not get filename stem.");
         }
     } else {
-        println!("[analyze_archive] Skipping filename deduction (already found entity and category).");
+        info!("[analyze_archive] Skipping filename deduction (already found entity and category).");
     }
     // --- End Filename Deduction ---
 
+    // --- 3b. Tokenize Descriptive Mod Root / Archive Names ---
+    let mut deduced_entity_token: Option<String> = None;
+    if final_deduced_entity_slug.is_none() {
+        info!("[analyze_archive] Trying name token matching on mod root folders and archive filename...");
+        let root_names = entries.iter()
+            .filter(|e| e.is_dir && e.is_likely_mod_root)
+            .filter_map(|e| e.path.trim_end_matches('/').rsplit('/').next().map(String::from));
+        let stem = file_path.file_stem().and_then(OsStr::to_str).map(String::from);
+        for name in root_names.chain(stem) {
+            if let Some((slug, token)) = find_entity_slug_from_name_tokens(&name, &maps) {
+                info!("[analyze_archive]   -> Found entity via name token '{}' in '{}' -> {}", token, name, slug);
+                final_deduced_entity_slug = Some(slug);
+                deduction_source = Some(DeductionSource::NameToken);
+                deduced_entity_token = Some(token);
+                break;
+            }
+        }
+    }
+
 
     // --- 4. Final Category Lookup (If needed) ---
     if final_deduced_entity_slug.is_some() && final_deduced_category_slug.is_none() {
         let entity_slug = final_deduced_entity_slug.as_ref().unwrap();
-        println!("[analyze_archive] Entity slug '{}' found, but category slug is missing. Looking up category...", entity_slug);
+        info!("[analyze_archive] Entity slug '{}' found, but category slug is missing. Looking up category...", entity_slug);
         if let Some(cat_slug) = maps.entity_slug_to_category_slug.get(entity_slug) {
             final_deduced_category_slug = Some(cat_slug.clone());
-            println!("[analyze_archive]   -> Found category slug '{}' from entity map.", cat_slug);
+            info!("[analyze_archive]   -> Found category slug '{}' from entity map.", cat_slug);
         } else {
-            eprintln!("[analyze_archive]   -> Warning: Could not find category slug for deduced entity slug '{}' in maps!", entity_slug);
+            warn!("[analyze_archive]   -> Could not find category slug for deduced entity slug '{}' in maps!", entity_slug);
         }
     }
     // --- End Final Category Lookup ---
 
 
+    // --- Version from a "_v1.2" suffix when the INI declared none (before the name cleanup strips it) ---
+    if deduced_version.is_none() {
+        let root_names = entries.iter()
+            .filter(|e| e.is_dir && e.is_likely_mod_root)
+            .filter_map(|e| e.path.trim_end_matches('/').rsplit('/').next().map(String::from));
+        let stem = file_path.file_stem().and_then(OsStr::to_str).map(String::from);
+        deduced_version = root_names.chain(stem).find_map(|name| version_from_name(&name));
+    }
+
     // --- Fallback name deduction & final cleanup ---
     // Use cleaned archive name if INI name wasn't found or was empty after cleaning
     if deduced_mod_name.is_none() || deduced_mod_name.as_deref() == Some("") {
         deduced_mod_name = file_path.file_stem()
             .and_then(OsStr::to_str)
             .map(|s| clean_and_extract_name(s)); // Use cleaner here too
-        println!("[analyze_archive] Used archive filename for deduced name: {:?}", deduced_mod_name);
+        info!("[analyze_archive] Used archive filename for deduced name: {:?}", deduced_mod_name);
     }
     // Final cleanup on whatever name we ended up with
     if let Some(name) = &deduced_mod_name {
@@ -3185,54 +6950,65 @@ fn analyze_archive(
         } else {
             // If cleaning resulted in empty, revert to original file stem as last resort
             deduced_mod_name = file_path.file_stem().and_then(OsStr::to_str).map(String::from);
-            println!("[analyze_archive] Warning: Name cleanup resulted in empty string, using raw file stem: {:?}", deduced_mod_name);
+            warn!("[analyze_archive] Name cleanup resulted in empty string, using raw file stem: {:?}", deduced_mod_name);
         }
     }
 
 
     // --- Final Log ---
-    println!("[analyze_archive] Final Deductions: Name={:?}, Author={:?}, Category={:?}, Entity={:?}, Preview={:?}, RawINI Target={:?}, RawINI Type={:?}",
+    info!("[analyze_archive] Final Deductions: Name={:?}, Author={:?}, Category={:?}, Entity={:?}, Preview={:?}, RawINI Target={:?}, RawINI Type={:?}",
         deduced_mod_name, deduced_author, final_deduced_category_slug, final_deduced_entity_slug, detected_preview_internal_path, raw_ini_target_found, raw_ini_type_found);
 
     // --- Return Result ---
+    let total_uncompressed_size = entries.iter().filter(|e| !e.is_dir).map(|e| e.uncompressed_size).sum();
     Ok(ArchiveAnalysisResult {
         file_path: file_path_str,
+        total_uncompressed_size,
         entries,
         deduced_mod_name,
         deduced_author,
+        deduced_version,
         deduced_category_slug: final_deduced_category_slug,
         deduced_entity_slug: final_deduced_entity_slug,
         raw_ini_type: raw_ini_type_found,
         raw_ini_target: raw_ini_target_found,
+        deduced_entity_token,
+        confidence: deduction_source.map_or(0.0, DeductionSource::confidence),
+        deduction_source,
         detected_preview_internal_path,
+        preview_candidate_paths,
     })
 }
 
 #[command]
 fn read_archive_file_content(archive_path_str: String, internal_file_path: String) -> CmdResult<Vec<u8>> {
-    println!("[read_archive_file_content] Reading '{}' from archive '{}'", internal_file_path, archive_path_str);
+    info!("[read_archive_file_content] Reading '{}' from archive '{}'", internal_file_path, archive_path_str);
     let archive_path = PathBuf::from(&archive_path_str);
-    if !archive_path.is_file() { return Err(format!("Archive file not found: {}", archive_path.display())); }
+    if !archive_path.is_file() { return Err(AppError::NotFound(format!("Archive file not found: {}", archive_path.display()))); }
 
-    let extension = archive_path.extension().and_then(|os| os.to_str()).map(|s| s.to_lowercase());
+    let extension = archive_type(&archive_path);
     let internal_path_normalized = internal_file_path.replace("\\", "/");
 
     match extension.as_deref() {
         Some("zip") => {
-            let file = fs::File::open(&archive_path).map_err(|e| format!("Zip Read: Failed open: {}", e))?;
-            let mut archive = ZipArchive::new(file).map_err(|e| format!("Zip Read: Failed read archive: {}", e))?;
+            let file = fs::File::open(&archive_path).map_err(|e| file_operation_error("open archive", &archive_path, &e))?;
+            let mut archive = ZipArchive::new(file)?;
 
             // --- FIX: Assign match result to variable and return it ---
-            let result = match archive.by_name(&internal_path_normalized) {
+            // Match on the decoded path analyze_archive reported, which may differ from the stored name
+            let entry_index = (0..archive.len()).find(|&i| {
+                archive.by_index_raw(i).ok().and_then(|entry| zip_entry_path(&entry)).as_deref() == Some(internal_path_normalized.as_str())
+            });
+            let result = match entry_index.map_or(Err(ZipError::FileNotFound), |i| archive.by_index(i)) {
                 Ok(mut file_in_zip) => {
                     let mut buffer = Vec::with_capacity(file_in_zip.size() as usize);
                     match file_in_zip.read_to_end(&mut buffer) {
                         Ok(_) => Ok(buffer), // Successful read
-                        Err(e) => Err(format!("Zip Read: Failed read content: {}", e)),
+                        Err(e) => Err(AppError::ModOperation(format!("Zip Read: Failed read content: {}", e))),
                     }
                 },
-                Err(ZipError::FileNotFound) => Err(format!("Zip Read: Internal file '{}' not found.", internal_file_path)),
-                Err(e) => Err(format!("Zip Read: Error accessing internal file '{}': {}", internal_file_path, e)),
+                Err(ZipError::FileNotFound) => Err(AppError::NotFound(format!("Zip Read: Internal file '{}' not found.", internal_file_path))),
+                Err(e) => Err(AppError::ModOperation(format!("Zip Read: Error accessing internal file '{}': {}", internal_file_path, e))),
             };
             result // Return the result stored in the variable
             // --- END FIX ---
@@ -3241,8 +7017,7 @@ fn read_archive_file_content(archive_path_str: String, internal_file_path: Strin
             // --- 7z logic remains the same as previously corrected ---
             let mut found_content: Option<Vec<u8>> = None;
             let mut found_error: Option<String> = None;
-            let mut archive = sevenz_rust::SevenZReader::open(&archive_path_str, Password::empty())
-                .map_err(|e| format!("7z Read: Failed open: {}", e))?;
+            let mut archive = sevenz_rust::SevenZReader::open(&archive_path_str, Password::empty())?;
 
             archive.for_each_entries(|entry, reader| {
                 if found_content.is_some() || found_error.is_some() { return Ok(false); }
@@ -3270,13 +7045,12 @@ fn read_archive_file_content(archive_path_str: String, internal_file_path: Strin
             .map_err(|e: sevenz_rust::Error| format!("7z Read: Error iterating entries: {}", e))?;
 
             if let Some(content) = found_content { Ok(content) }
-            else if let Some(err) = found_error { Err(err) }
-            else { Err(format!("7z Read: Internal file '{}' not found.", internal_file_path)) }
+            else if let Some(err) = found_error { Err(err.into()) }
+            else { Err(AppError::NotFound(format!("7z Read: Internal file '{}' not found.", internal_file_path))) }
         }
         Some("rar") => {
             let mut archive = Archive::new(&archive_path_str)
-                .open_for_processing() // Need Process mode to read content
-                .map_err(|e| e.to_string())?;
+                .open_for_processing()?; // Need Process mode to read content
             let mut found_content: Option<Vec<u8>> = None;
 
             loop {
@@ -3293,26 +7067,189 @@ fn read_archive_file_content(archive_path_str: String, internal_file_path: Strin
                                     break; // Found and read, exit loop
                                 }
                                 Err(e) => { // Error during reading
-                                    return Err(format!("Rar Read: Error reading content '{}': {}", internal_file_path, e));
+                                    return Err(AppError::ModOperation(format!("Rar Read: Error reading content '{}': {}", internal_file_path, e)));
                                 }
                             }
                         } else {
                             // Not the file we want, skip it
-                            archive = header_state.skip().map_err(|e| e.to_string())?; // Skip and update archive state
+                            archive = header_state.skip()?; // Skip and update archive state
                         }
                     }
                     Ok(None) => break, // End of archive, file not found
-                    Err(e) => return Err(format!("Rar Read: Error reading header: {}", e)),
+                    Err(e) => return Err(AppError::ModOperation(format!("Rar Read: Error reading header: {}", e))),
                 }
             }
-            found_content.ok_or_else(|| format!("Rar Read: Internal file '{}' not found.", internal_file_path))
+            found_content.ok_or_else(|| AppError::NotFound(format!("Rar Read: Internal file '{}' not found.", internal_file_path)))
+        }
+        Some(kind @ ("tar" | "tar.gz")) => {
+            let mut archive = open_tar_archive(&archive_path, kind == "tar.gz").map_err(|e| file_operation_error("open archive", &archive_path, &e))?;
+            for entry_result in archive.entries()? {
+                let mut entry = entry_result?;
+                if !entry.header().entry_type().is_file() || tar_entry_path(&entry).as_deref() != Some(internal_path_normalized.as_str()) { continue; }
+                let mut buffer = Vec::with_capacity(entry.size() as usize);
+                entry.read_to_end(&mut buffer).map_err(|e| AppError::ModOperation(format!("Tar Read: Failed read content: {}", e)))?;
+                return Ok(buffer);
+            }
+            Err(AppError::NotFound(format!("Tar Read: Internal file '{}' not found.", internal_file_path)))
+        }
+        _ => Err(AppError::UnsupportedArchive(format!("Unsupported archive type for reading: {:?}", extension))),
+    }
+}
+
+// Mime type for a file inside an archive, from its extension
+fn archive_entry_mime_type(internal_path: &str) -> &'static str {
+    match Path::new(internal_path).extension().and_then(OsStr::to_str).map(str::to_lowercase).as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("bmp") => "image/bmp",
+        Some("ini" | "txt" | "md") => "text/plain",
+        Some("json") => "application/json",
+        _ => "application/octet-stream",
+    }
+}
+
+// Same as read_archive_file_content, but as a data URL the frontend can use directly as an <img> src
+#[command]
+fn read_archive_file_data_url(archive_path_str: String, internal_file_path: String) -> CmdResult<String> {
+    let mime_type = archive_entry_mime_type(&internal_file_path);
+    let bytes = read_archive_file_content(archive_path_str, internal_file_path)?;
+    Ok(format!("data:{};base64,{}", mime_type, base64::engine::general_purpose::STANDARD.encode(bytes)))
+}
+
+// Returned by import_archive so the frontend can jump straight to the new mod
+#[derive(Serialize, Debug, Clone)]
+struct ImportedAsset {
+    asset_id: i64,
+    name: String, // Final name, which may carry a suffix from conflict handling
+    folder_name: String, // Clean relative path as stored in the DB
+    entity_slug: String, // Differs from the request's target when the entity was created by the import
+}
+
+// What already occupies an import destination: its DB row and/or its folder (enabled or DISABLED_)
+struct ImportConflict {
+    asset_id: Option<i64>,
+    folder_path: Option<PathBuf>,
+}
+
+impl ImportConflict {
+    fn describe(&self) -> String {
+        match &self.folder_path {
+            Some(path) => format!("A folder already exists at '{}'.", path.display()),
+            None => "A database entry already exists for this mod folder.".to_string(),
+        }
+    }
+}
+
+// Extracts the tar entries under `prefix_path` (everything when it is empty) into `dest_root`, calling
+// `on_entry` with each entry's 1-based index and path. Links and unsafe paths are skipped. Returns the file count.
+fn extract_tar_archive(archive_path: &Path, gzipped: bool, dest_root: &Path, prefix_path: &Path, mut on_entry: impl FnMut(usize, &str)) -> CmdResult<usize> {
+    let mut archive = open_tar_archive(archive_path, gzipped).map_err(|e| file_operation_error("open archive", archive_path, &e))?;
+    let mut processed_entries = 0;
+    let mut files_extracted_count = 0;
+    for entry_result in archive.entries()? {
+        let mut entry = entry_result?;
+        processed_entries += 1;
+        let is_dir = entry.header().entry_type().is_dir();
+        let Some(internal_path_str) = tar_entry_path(&entry) else {
+            if !is_dir { warn!("[import_archive] Skipping tar entry that is a link or has an unsafe path: {}", entry.path().map(|p| p.display().to_string()).unwrap_or_default()); }
+            continue;
+        };
+        on_entry(processed_entries, &internal_path_str);
+
+        // An empty prefix strips nothing, so extract-all keeps the full internal path
+        let Ok(relative_path_to_dest_obj) = Path::new(&internal_path_str).strip_prefix(prefix_path) else { continue; };
+        if relative_path_to_dest_obj.as_os_str().is_empty() { continue; }
+        let outpath = build_extraction_path(dest_root, relative_path_to_dest_obj)?;
+
+        if is_dir {
+            fs::create_dir_all(&outpath).map_err(|e| file_operation_error("create folder", &outpath, &e))?;
+        } else {
+            if let Some(p) = outpath.parent() { if !p.exists() { fs::create_dir_all(p).map_err(|e| file_operation_error("create folder", p, &e))?; }}
+            let mut outfile = fs::File::create(&outpath).map_err(|e| file_operation_error("create file", &outpath, &e))?;
+            io::copy(&mut entry, &mut outfile).map_err(|e| file_operation_error("write", &outpath, &e))?;
+            // Same as zip: keep helper scripts executable
+            #[cfg(unix)]
+            if let Ok(mode) = entry.header().mode() {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&outpath, fs::Permissions::from_mode((mode & 0o777) | 0o600))
+                    .map_err(|e| file_operation_error("set permissions on", &outpath, &e))?;
+            }
+            files_extracted_count += 1;
+        }
+    }
+    Ok(files_extracted_count)
+}
+
+fn import_destination_conflict(conn: &Connection, base_mods_path: &Path, clean_relative_path: &Path) -> CmdResult<Option<ImportConflict>> {
+    let relative_path_str = clean_relative_path.to_string_lossy().replace("\\", "/");
+    let asset_id: Option<i64> = conn.query_row(
+        "SELECT id FROM assets WHERE folder_name = ?1", params![relative_path_str], |row| row.get(0)
+    ).optional()?;
+    let folder_path = asset_disk_paths(base_mods_path, clean_relative_path)
+        .and_then(|(enabled, disabled)| [enabled, disabled].into_iter().find(|p| p.exists()));
+    if asset_id.is_none() && folder_path.is_none() { return Ok(None); }
+    Ok(Some(ImportConflict { asset_id, folder_path }))
+}
+
+// Extracts the zip entries under `prefix_path` (everything when it is empty) into `dest_root`, calling
+// `on_entry` with each entry's 1-based index, the entry count and its path. Returns the file count.
+fn extract_zip_archive(archive_path: &Path, dest_root: &Path, prefix_path: &Path, mut on_entry: impl FnMut(usize, usize, &str)) -> CmdResult<usize> {
+    let file = fs::File::open(archive_path).map_err(|e| file_operation_error("open archive", archive_path, &e))?;
+    let mut archive = ZipArchive::new(file)?;
+    let total_entries = archive.len();
+    let mut files_extracted_count = 0;
+    for i in 0..total_entries {
+        let mut file_in_zip = archive.by_index(i)?;
+        let Some(internal_path_str) = zip_entry_path(&file_in_zip) else {
+            warn!("[import_archive] Skipping zip entry with an unsafe path: {}", file_in_zip.name());
+            continue;
+        };
+        on_entry(i + 1, total_entries, &internal_path_str);
+
+        // An empty prefix strips nothing, so extract-all keeps the full internal path
+        let Ok(relative_path_to_dest_obj) = Path::new(&internal_path_str).strip_prefix(prefix_path) else { continue; };
+        if relative_path_to_dest_obj.as_os_str().is_empty() { continue; }
+        let outpath = build_extraction_path(dest_root, relative_path_to_dest_obj)?;
+
+        if file_in_zip.is_dir() {
+            fs::create_dir_all(&outpath).map_err(|e| file_operation_error("create folder", &outpath, &e))?;
+        } else {
+            if let Some(p) = outpath.parent() { if !p.exists() { fs::create_dir_all(p).map_err(|e| file_operation_error("create folder", p, &e))?; } }
+            let mut outfile = fs::File::create(&outpath).map_err(|e| file_operation_error("create file", &outpath, &e))?;
+            copy_zip_entry(&mut file_in_zip, &mut outfile).map_err(|e| file_operation_error("write", &outpath, &e))?;
+            // Keep helper scripts executable; entries without a mode keep the umask default
+            #[cfg(unix)]
+            if let Some(mode) = file_in_zip.unix_mode() {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&outpath, fs::Permissions::from_mode((mode & 0o777) | 0o600))
+                    .map_err(|e| file_operation_error("set permissions on", &outpath, &e))?;
+            }
+            files_extracted_count += 1;
         }
-        _ => Err(format!("Unsupported archive type for reading: {:?}", extension)),
     }
+    Ok(files_extracted_count)
+}
+
+// Optional parts of an import_archive request, sent by the frontend as one `options` object
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+struct ImportOptions {
+    image_data: Option<Vec<u8>>, // Pasted preview image
+    selected_preview_absolute_path: Option<String>, // Preview picked from disk
+    selected_internal_preview_path: Option<String>, // Image inside the archive to use as preview
+    archive_index: Option<usize>, // Set by the frontend for batch imports
+    delete_archive_after_import: Option<bool>, // Remove the source archive once the import is committed
+    source_url: Option<String>, // Where the mod was downloaded from, if known
+    import_disabled: Option<bool>, // Stage the mod without activating it (DISABLED_ folder, is_enabled = 0)
+    on_conflict: Option<String>, // "abort" (default), "suffix" or "overwrite" when the mod folder or DB entry already exists
+    create_entity: Option<NewEntitySpec>, // Import into a new entity instead; target_entity_slug is ignored
+    version: Option<String>, // Declared mod version, usually analyze_archive's deduced_version
 }
 
 #[command]
-fn import_archive(
+async fn import_archive(
     archive_path_str: String,
     target_entity_slug: String,
     selected_internal_root: String, // Frontend still provides this, empty means "extract all"
@@ -3320,12 +7257,16 @@ fn import_archive(
     description: Option<String>,
     author: Option<String>,
     category_tag: Option<String>,
-    image_data: Option<Vec<u8>>,
-    selected_preview_absolute_path: Option<String>,
     preset_ids: Option<Vec<i64>>,
-    db_state: State<DbState>
-) -> CmdResult<()> {
-    println!("[import_archive] Importing '{}', internal path '{}' for entity '{}'. Image Data Provided: {}. Add to presets: {:?}",
+    options: Option<ImportOptions>,
+    db_state: State<'_, DbState>,
+    app_handle: AppHandle
+) -> CmdResult<ImportedAsset> {
+    let ImportOptions {
+        image_data, selected_preview_absolute_path, selected_internal_preview_path, archive_index,
+        delete_archive_after_import, source_url, import_disabled, on_conflict, create_entity, version,
+    } = options.unwrap_or_default();
+    info!("[import_archive] Importing '{}', internal path '{}' for entity '{}'. Image Data Provided: {}. Add to presets: {:?}",
         archive_path_str,
         if selected_internal_root.is_empty() { "(Extract All)" } else { &selected_internal_root }, // Indicate if extracting all
         target_entity_slug,
@@ -3333,91 +7274,135 @@ fn import_archive(
         preset_ids);
 
     // --- Basic Validation & Setup ---
-    if mod_name.trim().is_empty() { return Err("Mod Name cannot be empty.".to_string()); }
-    if target_entity_slug.trim().is_empty() { return Err("Target Entity must be selected.".to_string()); }
+    if mod_name.trim().is_empty() { return Err(AppError::ModOperation("Mod Name cannot be empty.".to_string())); }
+    if target_entity_slug.trim().is_empty() && create_entity.is_none() { return Err(AppError::ModOperation("Target Entity must be selected.".to_string())); }
     let archive_path = PathBuf::from(&archive_path_str);
-    if !archive_path.is_file() { return Err(format!("Archive file not found: {}", archive_path.display())); }
-
-    let mut conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    if !archive_path.is_file() { return Err(AppError::NotFound(format!("Archive file not found: {}", archive_path.display()))); }
+
+    let mut conn_guard = lock_db(&db_state);
+
+    // A retried import reuses the entity its failed first attempt already created
+    let target_entity_slug = match &create_entity {
+        Some(spec) => {
+            let slug = spec.entity_slug();
+            let exists: bool = conn_guard.query_row("SELECT EXISTS(SELECT 1 FROM entities WHERE slug = ?1)", params![slug], |row| row.get(0))?;
+            if exists {
+                info!("[import_archive] Entity '{}' already exists, importing into it.", slug);
+                slug
+            } else {
+                insert_new_entity(&conn_guard, spec)?
+            }
+        }
+        None => target_entity_slug,
+    };
 
-    let base_mods_path_str = get_setting_value(&conn_guard, SETTINGS_KEY_MODS_FOLDER)
-        .map_err(|e| e.to_string())?
-        .ok_or_else(|| "Mods folder path not set".to_string())?;
+    let base_mods_path_str = get_setting_value(&conn_guard, SETTINGS_KEY_MODS_FOLDER)?
+        .ok_or_else(|| AppError::Config("Mods folder path not set".to_string()))?;
     let base_mods_path = PathBuf::from(base_mods_path_str);
 
-    let (target_category_slug, target_entity_id): (String, i64) = conn_guard.query_row(
-        "SELECT c.slug, e.id FROM entities e JOIN categories c ON e.category_id = c.id WHERE e.slug = ?1",
-        params![target_entity_slug], |row| Ok((row.get(0)?, row.get(1)?)),
+    let (target_category_slug, target_entity_id, entity_default_tag): (String, i64, Option<String>) = conn_guard.query_row(
+        "SELECT c.slug, e.id, e.default_category_tag FROM entities e JOIN categories c ON e.category_id = c.id WHERE e.slug = ?1",
+        params![target_entity_slug], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
     ).map_err(|e| match e {
-        rusqlite::Error::QueryReturnedNoRows => format!("Target entity '{}' not found.", target_entity_slug),
-        _ => format!("DB Error get target entity: {}", e)
+        rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(format!("Target entity '{}' not found.", target_entity_slug)),
+        _ => AppError::Sqlite(e),
     })?;
+    // Fall back to the entity's default tag when none was given
+    let category_tag = category_tag.filter(|tag| !tag.trim().is_empty()).or(entity_default_tag);
+
+    let on_conflict = on_conflict.as_deref().unwrap_or("abort");
+    if !matches!(on_conflict, "abort" | "suffix" | "overwrite") {
+        return Err(AppError::ModOperation(format!("Unknown conflict mode '{}'.", on_conflict)));
+    }
+    let base_folder_name = sanitize_folder_name(&mod_name);
+    if base_folder_name.trim_matches('_').is_empty() { return Err(AppError::ModOperation("Mod Name results in invalid folder name.".to_string())); }
+    let import_disabled = import_disabled.unwrap_or(false);
+
+    // --- Destination Conflicts ---
+    let entity_relative_path = Path::new(&target_category_slug).join(&target_entity_slug);
+    let mut mod_name = mod_name.trim().to_string();
+    let mut target_mod_folder_name = base_folder_name.clone();
+    let mut conflict = import_destination_conflict(&conn_guard, &base_mods_path, &entity_relative_path.join(&target_mod_folder_name))?;
+    if let Some(existing) = conflict.as_ref().filter(|_| on_conflict == "abort") {
+        return Err(AppError::ModOperation(format!("{} Choose a different mod name.", existing.describe())));
+    }
+    if on_conflict == "suffix" {
+        // Like duplicate browser downloads: ModName_2, ModName_3, ...
+        let mut suffix = 2;
+        while conflict.is_some() {
+            if suffix > 999 {
+                return Err(AppError::ModOperation(format!("Could not find a free folder name for '{}'.", base_folder_name)));
+            }
+            target_mod_folder_name = format!("{}_{}", base_folder_name, suffix);
+            conflict = import_destination_conflict(&conn_guard, &base_mods_path, &entity_relative_path.join(&target_mod_folder_name))?;
+            suffix += 1;
+        }
+        if target_mod_folder_name != base_folder_name {
+            mod_name = format!("{} ({})", mod_name, suffix - 1);
+            info!("[import_archive] Destination taken, importing as '{}' in folder '{}'.", mod_name, target_mod_folder_name);
+        }
+    }
+    // With "overwrite", whatever is left in `conflict` is removed right before the new row is inserted
+
+    let clean_relative_path = entity_relative_path.join(&target_mod_folder_name);
+    let (enabled_dest_path, disabled_dest_path) = asset_disk_paths(&base_mods_path, &clean_relative_path)
+        .ok_or_else(|| AppError::ModOperation("Mod Name results in invalid folder name.".to_string()))?;
+    // A disabled import lands directly in the DISABLED_ folder so the mod never loads in-game
+    let final_mod_dest_path = if import_disabled { disabled_dest_path.clone() } else { enabled_dest_path.clone() };
+
+    validate_archive(&archive_path, false)?;
+    check_archive_limits(&archive_path, &ArchiveLimits::from_settings(&conn_guard)?)?;
 
-    let target_mod_folder_name = mod_name.trim().replace(" ", "_").replace(".", "_").replace("'", "").replace("\"", "");
-    if target_mod_folder_name.is_empty() { return Err("Mod Name results in invalid folder name.".to_string()); }
-    let final_mod_dest_path = base_mods_path.join(&target_category_slug).join(&target_entity_slug).join(&target_mod_folder_name);
+    // --- Free Space Check ---
+    let selected_root_norm = selected_internal_root.replace("\\", "/");
+    let required_bytes = archive_uncompressed_size(&archive_path, Path::new(selected_root_norm.trim_end_matches('/')))?;
+    ensure_free_space(&final_mod_dest_path, required_bytes)?;
+    info!("[import_archive] Free space check passed ({} bytes to extract).", required_bytes);
 
-    fs::create_dir_all(&final_mod_dest_path)
-        .map_err(|e| format!("Failed create dest directory '{}': {}", final_mod_dest_path.display(), e))?;
-    println!("[import_archive] Target destination folder created/ensured: {}", final_mod_dest_path.display());
+    // Extract into a staging folder next to the destination; it is only renamed into place once the DB row is in,
+    // and is removed on any failure, so a failed import never leaves a half-populated mod folder behind.
+    let staging = ImportStaging::create(&final_mod_dest_path)
+        .map_err(|e| file_operation_error("create a staging folder for", &final_mod_dest_path, &e))?;
+    let staging_path = staging.path.clone();
+    info!("[import_archive] Extracting into staging folder: {}", staging_path.display());
 
-    let tx = conn_guard.transaction().map_err(|e| format!("Failed start import transaction: {}", e))?;
+    let tx = conn_guard.transaction()?;
 
     // --- Extraction Logic ---
-    println!("[import_archive] Starting extraction...");
-    let extension = archive_path.extension().and_then(|os| os.to_str()).map(|s| s.to_lowercase());
+    info!("[import_archive] Starting extraction...");
+    let extension = archive_type(&archive_path);
     // Normalize and prepare the prefix path IF a root was selected
     let prefix_to_extract_norm = selected_internal_root.replace("\\", "/");
     let prefix_to_extract = prefix_to_extract_norm.strip_suffix('/').unwrap_or(&prefix_to_extract_norm);
     let prefix_path = Path::new(prefix_to_extract);
     let extract_all = prefix_to_extract.is_empty(); // Flag to determine if extracting all
-    println!("[import_archive] Extract All Mode: {}", extract_all);
+    info!("[import_archive] Extract All Mode: {}", extract_all);
     let mut files_extracted_count = 0;
 
-    let extraction_result: Result<usize, String> = (|| {
+    let last_progress_emit = std::cell::Cell::new(None::<std::time::Instant>);
+    let emit_progress = |extracted: usize, total: usize, current_file: &str| {
+        let due = extracted.is_multiple_of(IMPORT_PROGRESS_INTERVAL) || extracted == total
+            || last_progress_emit.get().is_none_or(|at| at.elapsed() >= IMPORT_PROGRESS_MAX_DELAY);
+        if !due { return; }
+        last_progress_emit.set(Some(std::time::Instant::now()));
+        app_handle.emit_all(IMPORT_PROGRESS_EVENT, ImportProgress {
+            extracted, total, current_file: Some(current_file.to_string()), archive_index
+        }).unwrap_or_else(|e| error!("Failed to emit import progress: {}", e));
+    };
+
+    let extraction_result: Result<usize, AppError> = (|| {
         match extension.as_deref() {
         Some("zip") => {
-             let file = fs::File::open(&archive_path).map_err(|e| format!("Zip Extract: Failed open: {}", e))?;
-             let mut archive = ZipArchive::new(file).map_err(|e| format!("Zip Extract: Failed read archive: {}", e))?;
-             for i in 0..archive.len() {
-                  let mut file_in_zip = archive.by_index(i).map_err(|e| format!("Zip Extract: Failed read entry #{}: {}", i, e))?;
-                  let internal_path_obj_opt = file_in_zip.enclosed_name().map(|p| p.to_path_buf());
-                  if internal_path_obj_opt.is_none() { continue; }
-                  let internal_path_obj = internal_path_obj_opt.unwrap();
-
-                  let (should_extract, relative_path_to_dest_obj) = if extract_all {
-                      // Extracting all: always extract, relative path is the full internal path
-                      (true, internal_path_obj.clone()) // Clone needed as we check it later
-                  } else {
-                      // Specific root selected: check prefix
-                      let should = internal_path_obj.starts_with(prefix_path);
-                      let relative_path = if should {
-                          // Strip the prefix if it matches
-                          internal_path_obj.strip_prefix(prefix_path).map(|p| p.to_path_buf()).ok()
-                      } else {
-                          None
-                      };
-                      (should && relative_path.is_some(), relative_path.unwrap_or_default())
-                  };
-
-                  if !should_extract || relative_path_to_dest_obj.as_os_str().is_empty() { continue; }
-                  let outpath = final_mod_dest_path.join(&relative_path_to_dest_obj);
-
-                  if file_in_zip.is_dir() {
-                      fs::create_dir_all(&outpath).map_err(|e| format!("Zip Extract: Failed create dir '{}': {}", outpath.display(), e))?;
-                  } else {
-                      if let Some(p) = outpath.parent() { if !p.exists() { fs::create_dir_all(&p).map_err(|e| format!("Zip Extract: Failed create parent '{}': {}", p.display(), e))?; } }
-                      let mut outfile = fs::File::create(&outpath).map_err(|e| format!("Zip Extract: Failed create file '{}': {}", outpath.display(), e))?;
-                      std::io::copy(&mut file_in_zip, &mut outfile).map_err(|e| format!("Zip Extract: Failed copy content '{}': {}", outpath.display(), e))?;
-                      files_extracted_count += 1;
-                  }
-             }
+            files_extracted_count = extract_zip_archive(&archive_path, &staging_path, prefix_path, emit_progress)?;
         }
         Some("7z") => {
-            let mut archive = sevenz_rust::SevenZReader::open(&archive_path_str, Password::empty())
-                .map_err(|e| format!("7z Extract: Failed open: {}", e))?;
+            let mut archive = sevenz_rust::SevenZReader::open(&archive_path_str, Password::empty())?;
+             let total_entries = archive.archive().files.len();
+             let mut processed_entries = 0;
              archive.for_each_entries(|entry, reader| {
                  let internal_path_str = entry.name().replace("\\", "/");
+                 processed_entries += 1;
+                 emit_progress(processed_entries, total_entries, &internal_path_str);
                  let internal_path_obj = PathBuf::from(&internal_path_str);
 
                  let (should_extract, relative_path_to_dest_obj) = if extract_all {
@@ -3428,18 +7413,19 @@ fn import_archive(
                       (should && relative_path.is_some(), relative_path.unwrap_or_default())
                  };
                  if !should_extract || relative_path_to_dest_obj.as_os_str().is_empty() { return Ok(true); } // Skip to next
-                 let outpath = final_mod_dest_path.join(&relative_path_to_dest_obj);
+                 let outpath = build_extraction_path(&staging_path, &relative_path_to_dest_obj).map_err(|e| sevenz_rust::Error::other(e.to_string()))?;
+                 let entry_err = |e: std::io::Error| sevenz_rust::Error::other(format!("Entry '{}' ('{}'): {}", internal_path_str, outpath.display(), e));
 
                  if entry.is_directory() {
-                    fs::create_dir_all(&outpath)?;
+                    fs::create_dir_all(&outpath).map_err(entry_err)?;
                  } else {
-                    if let Some(p) = outpath.parent() { if !p.exists() { fs::create_dir_all(&p)?; }}
-                    let mut outfile = fs::File::create(&outpath)?;
+                    if let Some(p) = outpath.parent() { if !p.exists() { fs::create_dir_all(p).map_err(entry_err)?; }}
+                    let mut outfile = fs::File::create(&outpath).map_err(entry_err)?;
                     let mut buffer = [0u8; 4096];
                     loop {
-                        let bytes_read = reader.read(&mut buffer)?;
+                        let bytes_read = reader.read(&mut buffer).map_err(entry_err)?;
                         if bytes_read == 0 { break; }
-                        outfile.write_all(&buffer[..bytes_read])?;
+                        outfile.write_all(&buffer[..bytes_read]).map_err(entry_err)?;
                     }
                     files_extracted_count += 1;
                  }
@@ -3448,14 +7434,20 @@ fn import_archive(
              .map_err(|e: sevenz_rust::Error| format!("7z Extract: Error processing entries: {}", e))?;
         }
         Some("rar") => {
-            let mut archive = Archive::new(&archive_path_str).open_for_processing()
-                .map_err(|e| e.to_string())?;
+            // RAR headers are streamed, so count them with a listing pass first
+            let total_entries = Archive::new(&archive_path_str).open_for_listing()
+                .map(|list| list.filter(|entry| entry.is_ok()).count())
+                .unwrap_or(0);
+            let mut processed_entries = 0;
+            let mut archive = Archive::new(&archive_path_str).open_for_processing()?;
             loop {
-                match archive.read_header().map_err(|e| e.to_string())? {
+                match archive.read_header()? {
                     Some(header_state) => {
                         let entry_filename = &header_state.entry().filename;
                         let internal_path_str = entry_filename.to_string_lossy().replace("\\", "/").to_string();
                         let internal_path_obj = PathBuf::from(&internal_path_str);
+                        processed_entries += 1;
+                        emit_progress(processed_entries, total_entries.max(processed_entries), &internal_path_str);
 
                         let (should_extract, relative_path_to_dest_obj) = if extract_all {
                             (true, internal_path_obj.clone())
@@ -3465,17 +7457,17 @@ fn import_archive(
                             (should && relative_path.is_some(), relative_path.unwrap_or_default())
                         };
                         if !should_extract || relative_path_to_dest_obj.as_os_str().is_empty() {
-                            archive = header_state.skip().map_err(|e| e.to_string())?;
+                            archive = header_state.skip()?;
                             continue; // Skip to next
                         }
-                        let outpath = final_mod_dest_path.join(&relative_path_to_dest_obj);
+                        let outpath = build_extraction_path(&staging_path, &relative_path_to_dest_obj)?;
 
                         if header_state.entry().is_directory() {
-                            fs::create_dir_all(&outpath).map_err(|e| format!("Rar Extract: Failed create dir '{}': {}", outpath.display(), e))?;
-                            archive = header_state.skip().map_err(|e| e.to_string())?;
+                            fs::create_dir_all(&outpath).map_err(|e| file_operation_error("create folder", &outpath, &e))?;
+                            archive = header_state.skip()?;
                         } else {
-                            if let Some(p) = outpath.parent() { if !p.exists() { fs::create_dir_all(&p).map_err(|e| format!("Rar Extract: Failed create parent '{}': {}", p.display(), e))?; }}
-                            archive = header_state.extract_to(&outpath).map_err(|e| e.to_string())?;
+                            if let Some(p) = outpath.parent() { if !p.exists() { fs::create_dir_all(p).map_err(|e| file_operation_error("create folder", p, &e))?; }}
+                            archive = header_state.extract_to(&outpath)?;
                             files_extracted_count += 1;
                         }
                     }
@@ -3483,157 +7475,645 @@ fn import_archive(
                 }
             }
         }
-        _ => return Err(format!("Unsupported archive type for extraction: {:?}", extension)),
+        Some(kind @ ("tar" | "tar.gz")) => {
+            // Tar has no up-front entry count
+            files_extracted_count = extract_tar_archive(&archive_path, kind == "tar.gz", &staging_path, prefix_path,
+                |index, internal_path| emit_progress(index, 0, internal_path))?;
+        }
+        _ => return Err(AppError::UnsupportedArchive(format!("Unsupported archive type for extraction: {:?}", extension))),
         }
         Ok(files_extracted_count) // Return count on success
     })();
 
     // Handle extraction result
-    let files_extracted_count = extraction_result.map_err(|e| {
-         fs::remove_dir_all(&final_mod_dest_path).ok();
-         e
+    let files_extracted_count = extraction_result?;
+    info!("[import_archive] Extracted {} files.", files_extracted_count);
+
+    // --- Handle Preview Image ---
+    let mut image_filename_for_db: Option<String> = None;
+    // A user-chosen preview kept outside the mod folder is written once the asset ID exists
+    let mut external_preview: Option<(Vec<u8>, String)> = None;
+    let store_externally = use_external_previews(&tx);
+    if let (Some(data), true) = (&image_data, store_externally) {
+        external_preview = Some((data.clone(), "png".to_string()));
+    }
+    else if let (Some(path_str), true) = (&selected_preview_absolute_path, store_externally) {
+        let source_path = PathBuf::from(path_str);
+        match fs::read(&source_path) {
+            Ok(data) => external_preview = Some((data, image_extension(&source_path))),
+            Err(e) => warn!("[import_archive] Could not read selected preview '{}': {}. Preview will be missing.", path_str, e),
+        }
+    }
+    else if let (Some(internal_path), true) = (&selected_internal_preview_path, store_externally) {
+        match read_archive_file_content(archive_path_str.clone(), internal_path.clone()) {
+            Ok(data) => external_preview = Some((data, image_extension(Path::new(internal_path)))),
+            Err(e) => error!("[import_archive] Failed to read archive preview '{}': {}. Preview will be missing.", internal_path, e),
+        }
+    }
+    else if let Some(data) = image_data {
+        info!("[import_archive] Handling provided image data ({} bytes)", data.len());
+        let target_image_path = staging_path.join(TARGET_IMAGE_FILENAME);
+        match fs::write(&target_image_path, data) {
+            Ok(_) => {
+                info!("[import_archive] Image data written successfully to '{}'.", target_image_path.display());
+                image_filename_for_db = Some(TARGET_IMAGE_FILENAME.to_string());
+            }
+            Err(e) => {
+                error!("[import_archive] Failed to save pasted image data to '{}': {}. Preview will be missing.", target_image_path.display(), e);
+            }
+        }
+    }
+    else if let Some(user_preview_path_str) = selected_preview_absolute_path {
+        info!("[import_archive] Handling selected image file path: {}", user_preview_path_str);
+        let source_path = PathBuf::from(&user_preview_path_str);
+        if source_path.is_file() {
+            let target_image_path = staging_path.join(TARGET_IMAGE_FILENAME);
+            match fs::copy(&source_path, &target_image_path) {
+                Ok(_) => {
+                    info!("[import_archive] Image file copied successfully to '{}'.", target_image_path.display());
+                    image_filename_for_db = Some(TARGET_IMAGE_FILENAME.to_string());
+                }
+                Err(e) => {
+                    error!("[import_archive] Failed copy user preview to '{}': {}. Preview will be missing.", target_image_path.display(), e);
+                }
+            }
+        } else {
+             warn!("[import_archive] Selected preview file '{}' not found, skipping.", user_preview_path_str);
+        }
+    }
+    else if let Some(internal_path) = selected_internal_preview_path {
+        info!("[import_archive] Handling preview from inside archive: {}", internal_path);
+        // Keep the original extension for non-png images (e.g. preview.jpg)
+        let target_image_filename = match Path::new(&internal_path).extension().and_then(OsStr::to_str).map(|ext| ext.to_lowercase()) {
+            Some(ext) if ext != "png" => Path::new(TARGET_IMAGE_FILENAME).with_extension(ext).to_string_lossy().to_string(),
+            _ => TARGET_IMAGE_FILENAME.to_string(),
+        };
+        let target_image_path = staging_path.join(&target_image_filename);
+        match read_archive_file_content(archive_path_str.clone(), internal_path.clone()).and_then(|data| fs::write(&target_image_path, data).map_err(AppError::from)) {
+            Ok(_) => {
+                info!("[import_archive] Archive preview written successfully to '{}'.", target_image_path.display());
+                image_filename_for_db = Some(target_image_filename);
+            }
+            Err(e) => {
+                error!("[import_archive] Failed to copy archive preview '{}': {}. Preview will be missing.", internal_path, e);
+            }
+        }
+    }
+    else {
+        let potential_extracted_image_path = staging_path.join(TARGET_IMAGE_FILENAME);
+        if potential_extracted_image_path.is_file() {
+            info!("[import_archive] Using extracted {} as preview.", TARGET_IMAGE_FILENAME);
+            image_filename_for_db = Some(TARGET_IMAGE_FILENAME.to_string());
+        } else {
+             info!("[import_archive] No pasted, selected, or extracted preview found.");
+        }
+    }
+    info!("[import_archive] Image handling complete. Filename to save in DB: {:?}", image_filename_for_db);
+
+    // --- Add to Database ---
+    let relative_path_for_db_str = clean_relative_path.to_string_lossy().replace("\\", "/");
+
+    if let Some(existing) = conflict {
+        info!("[import_archive] Overwriting existing mod at '{}'.", relative_path_for_db_str);
+        match existing.asset_id {
+            // Row deletion is part of the transaction; the folder removal can't be rolled back
+            Some(existing_id) => { remove_asset_and_folder(&tx, &app_handle, &base_mods_path, existing_id)?; }
+            None => if let Some(path) = &existing.folder_path {
+                fs::remove_dir_all(path).map_err(|e| file_operation_error("delete existing mod folder", path, &e))?;
+            },
+        }
+        // A DB row without its folder may have left the other enabled/disabled variant behind
+        for leftover in [&enabled_dest_path, &disabled_dest_path] {
+            if leftover.exists() {
+                fs::remove_dir_all(leftover).map_err(|e| file_operation_error("delete existing mod folder", leftover, &e))?;
+            }
+        }
+    }
+
+    let check_existing: Option<i64> = tx.query_row(
+        "SELECT id FROM assets WHERE entity_id = ?1 AND folder_name = ?2",
+        params![target_entity_id, relative_path_for_db_str], |row| row.get(0)
+    ).optional()?;
+
+    if check_existing.is_some() {
+        return Err(AppError::ModOperation(format!("Database entry already exists for '{}'. Aborting.", relative_path_for_db_str)));
+    }
+
+    let is_enabled_value = if import_disabled { 0 } else { 1 };
+    info!("[import_archive] Adding asset to DB: entity_id={}, name={}, path={}, image={:?}, enabled={}", target_entity_id, mod_name, relative_path_for_db_str, image_filename_for_db, !import_disabled);
+    tx.execute(
+        "INSERT INTO assets (entity_id, name, description, folder_name, image_filename, author, category_tag, created_at, is_enabled, source_url, version) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            target_entity_id, mod_name.trim(),
+            description, relative_path_for_db_str,
+            image_filename_for_db, author, category_tag,
+            unix_now(), is_enabled_value, normalize_source_url(source_url),
+            version.as_deref().and_then(normalize_mod_version)
+        ]
+    )?;
+
+    let new_asset_id = tx.last_insert_rowid();
+    info!("[import_archive] Asset inserted with ID: {}", new_asset_id);
+
+    // --- Add to Presets ---
+    if let Some(ids) = preset_ids {
+        if !ids.is_empty() {
+            info!("[import_archive] Adding new asset {} to presets: {:?}", new_asset_id, ids);
+            let mut insert_preset_stmt = tx.prepare_cached(
+                "INSERT OR IGNORE INTO preset_assets (preset_id, asset_id, is_enabled) VALUES (?1, ?2, ?3)"
+            )?;
+            for preset_id in ids {
+                 insert_preset_stmt.execute(params![preset_id, new_asset_id, is_enabled_value])?; // Presets start out matching the imported state
+            }
+            info!("[import_archive] Finished adding asset {} to presets.", new_asset_id);
+        }
+    }
+
+    // --- Move Into Place & Commit ---
+    // Dropping the uncommitted transaction on a failed rename rolls the DB row back
+    staging.finish(&final_mod_dest_path)
+        .map_err(|e| file_operation_error("move imported files into", &final_mod_dest_path, &e))?;
+    tx.commit().map_err(|e| {
+        fs::remove_dir_all(&final_mod_dest_path).ok();
+        AppError::Sqlite(e)
     })?;
-    println!("[import_archive] Extracted {} files.", files_extracted_count);
 
-    // --- Handle Preview Image ---
-    let mut image_filename_for_db: Option<String> = None;
-    if let Some(data) = image_data {
-        println!("[import_archive] Handling provided image data ({} bytes)", data.len());
-        let target_image_path = final_mod_dest_path.join(TARGET_IMAGE_FILENAME);
-        match fs::write(&target_image_path, data) {
-            Ok(_) => {
-                println!("[import_archive] Image data written successfully to '{}'.", target_image_path.display());
-                image_filename_for_db = Some(TARGET_IMAGE_FILENAME.to_string());
-            }
-            Err(e) => {
-                eprintln!("[import_archive] ERROR: Failed to save pasted image data to '{}': {}. Preview will be missing.", target_image_path.display(), e);
+   info!("[import_archive] Import successful for '{}'", mod_name);
+
+   if let Some((data, extension)) = external_preview {
+       // The mod is already imported; a failed preview only leaves it without an image
+       match save_external_preview(&app_handle, new_asset_id, &data, &extension) {
+           Ok(image_filename) => {
+               conn_guard.execute("UPDATE assets SET image_filename = ?1 WHERE id = ?2", params![image_filename, new_asset_id])?;
+           }
+           Err(e) => warn!("[import_archive] Failed to store preview outside the mod folder: {}", e),
+       }
+   }
+
+   // Only now that the mod is on disk and in the DB is it safe to drop the source archive.
+   // A failure here doesn't undo the import, so it's logged instead of returned.
+   if is_temporary_download(&archive_path) {
+       remove_temporary_download(&archive_path);
+   } else if delete_archive_after_import.unwrap_or(false) {
+       match fs::remove_file(&archive_path) {
+           Ok(_) => info!("[import_archive] Deleted source archive '{}'.", archive_path.display()),
+           Err(e) => warn!("[import_archive] Imported successfully but failed to delete source archive '{}': {}", archive_path.display(), e),
+       }
+   }
+   Ok(ImportedAsset { asset_id: new_asset_id, name: mod_name, folder_name: relative_path_for_db_str, entity_slug: target_entity_slug })
+}
+
+// --- URL Downloads ---
+// Archives fetched by import_from_url live in their own subfolder of the system temp dir, so the
+// file keeps its original name (used for deduction) and can be cleaned up as a unit.
+const DOWNLOADS_DIR_NAME: &str = "gmm_downloads";
+const STALE_DOWNLOAD_SECS: u64 = 24 * 60 * 60;
+
+fn downloads_dir() -> PathBuf {
+    std::env::temp_dir().join(DOWNLOADS_DIR_NAME)
+}
+
+// Fresh, uniquely named folder for one download
+fn new_download_folder() -> io::Result<PathBuf> {
+    let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let folder = downloads_dir().join(nanos.to_string());
+    fs::create_dir_all(&folder)?;
+    Ok(folder)
+}
+
+// Downloads are always `<downloads_dir>/<download folder>/<file>`. starts_with alone compares components,
+// so a path like `<downloads_dir>/x/../../elsewhere` would pass it; the exact shape is required instead.
+fn is_temporary_download(path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(downloads_dir()) else { return false; };
+    let mut components = relative.components();
+    matches!(
+        (components.next(), components.next(), components.next()),
+        (Some(Component::Normal(_)), Some(Component::Normal(_)), None)
+    )
+}
+
+// Removes a downloaded archive together with its per-download folder
+fn remove_temporary_download(path: &Path) {
+    if !is_temporary_download(path) { return; }
+    let Some(folder) = path.parent() else { return; };
+    if let Err(e) = fs::remove_dir_all(folder) {
+        if e.kind() != io::ErrorKind::NotFound {
+            error!("[remove_temporary_download] Failed to remove '{}': {}", folder.display(), e);
+        }
+    }
+}
+
+// Leftovers from imports that were abandoned or crashed
+fn cleanup_stale_downloads() {
+    let Ok(entries) = fs::read_dir(downloads_dir()) else { return; };
+    for entry in entries.flatten() {
+        let is_stale = entry.metadata().and_then(|m| m.modified()).ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age.as_secs() > STALE_DOWNLOAD_SECS);
+        if is_stale {
+            info!("[cleanup_stale_downloads] Removing stale download '{}'", entry.path().display());
+            fs::remove_dir_all(entry.path()).or_else(|_| fs::remove_file(entry.path())).ok();
+        }
+    }
+}
+
+// Archive extension from the file's magic bytes; servers often send generic names/content types
+fn sniff_archive_extension(path: &Path) -> Option<&'static str> {
+    let mut header = [0u8; 262];
+    let read = File::open(path).and_then(|mut f| f.read(&mut header)).ok()?;
+    let header = &header[..read];
+    if header.starts_with(b"PK\x03\x04") || header.starts_with(b"PK\x05\x06") { Some("zip") }
+    else if header.starts_with(b"7z\xBC\xAF\x27\x1C") { Some("7z") }
+    else if header.starts_with(b"Rar!\x1A\x07") { Some("rar") }
+    else if header.starts_with(b"\x1F\x8B") { Some("tar.gz") } // Any gzip; tarballs are the only kind imported
+    else if header.get(257..262) == Some(&b"ustar"[..]) { Some("tar") }
+    else { None }
+}
+
+// Extracts `filename="..."` from a Content-Disposition header
+fn content_disposition_filename(header: &str) -> Option<String> {
+    header.split(';')
+        .map(str::trim)
+        .find_map(|part| part.strip_prefix("filename="))
+        .map(|name| name.trim_matches('"').to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn build_download_client(app_handle: &AppHandle) -> CmdResult<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .user_agent(format!("GMM/{}", app_handle.package_info().version))
+        .redirect(reqwest::redirect::Policy::limited(10))
+        .build()?)
+}
+
+// Accepts a bare mod id ("123456") or a GameBanana mod page URL
+fn parse_gamebanana_mod_id(mod_id_or_url: &str) -> Option<String> {
+    let input = mod_id_or_url.trim();
+    if !input.is_empty() && input.chars().all(|c| c.is_ascii_digit()) {
+        return Some(input.to_string());
+    }
+    let url = reqwest::Url::parse(input).ok()?;
+    let is_gamebanana = url.host_str().is_some_and(|host| host == "gamebanana.com" || host.ends_with(".gamebanana.com"));
+    if !is_gamebanana { return None; }
+    GAMEBANANA_MOD_URL_REGEX.captures(url.path()).and_then(|caps| caps.get(1)).map(|m| m.as_str().to_string())
+}
+
+// Full profile of a GameBanana mod (files, submitter, preview media, ...)
+async fn fetch_gamebanana_profile(client: &reqwest::Client, mod_id: &str) -> CmdResult<serde_json::Value> {
+    let api_url = format!("https://gamebanana.com/apiv11/Mod/{}/ProfilePage", mod_id);
+    info!("[fetch_gamebanana_profile] Querying {}", api_url);
+    let response = client.get(&api_url).send().await?;
+    if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = response.headers().get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .map(|secs| format!(" Try again in {} seconds.", secs))
+            .unwrap_or_else(|| " Try again later.".to_string());
+        return Err(AppError::Other(format!("GameBanana is rate limiting requests.{}", retry_after)));
+    }
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(AppError::NotFound(format!("GameBanana mod {} not found.", mod_id)));
+    }
+    Ok(response.error_for_status()?.json().await?)
+}
+
+// Resolves a GameBanana mod page to the download URL and file name of its newest file
+async fn resolve_gamebanana_download(client: &reqwest::Client, mod_id: &str) -> CmdResult<(String, Option<String>)> {
+    let profile = fetch_gamebanana_profile(client, mod_id).await?;
+    let newest_file = profile.get("_aFiles")
+        .and_then(|files| files.as_array())
+        .and_then(|files| files.iter().max_by_key(|f| f.get("_tsDateAdded").and_then(|t| t.as_i64()).unwrap_or(0)))
+        .ok_or_else(|| AppError::NotFound(format!("GameBanana mod {} has no downloadable files.", mod_id)))?;
+    let download_url = newest_file.get("_sDownloadUrl").and_then(|u| u.as_str())
+        .ok_or_else(|| AppError::NotFound(format!("GameBanana mod {} file has no download URL.", mod_id)))?;
+    let file_name = newest_file.get("_sFile").and_then(|n| n.as_str()).map(str::to_string);
+    Ok((download_url.to_string(), file_name))
+}
+
+// Streams `url` into a fresh folder under downloads_dir(), emitting DOWNLOAD_PROGRESS_EVENT
+async fn download_archive(client: &reqwest::Client, url: &str, suggested_name: Option<String>, app_handle: &AppHandle) -> CmdResult<PathBuf> {
+    let mut response = client.get(url).send().await?.error_for_status()?;
+
+    let content_type = response.headers().get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok()).unwrap_or("").to_lowercase();
+    if content_type.starts_with("text/html") {
+        return Err(AppError::UnsupportedArchive(format!("'{}' is a web page, not an archive download.", url)));
+    }
+
+    // Name preference: server-provided, then resolver-provided, then the last segment of the final (post-redirect) URL
+    let file_name = response.headers().get(reqwest::header::CONTENT_DISPOSITION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(content_disposition_filename)
+        .or(suggested_name)
+        .or_else(|| response.url().path_segments().and_then(|mut segments| segments.next_back()).map(str::to_string))
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| "download".to_string());
+    let file_name = Path::new(&file_name).file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "download".to_string());
+
+    let download_folder = new_download_folder()?;
+    let mut download_path = download_folder.join(&file_name);
+    info!("[download_archive] Downloading '{}' to '{}'", response.url(), download_path.display());
+
+    let total = response.content_length();
+    let mut downloaded: u64 = 0;
+    let mut last_emit = std::time::Instant::now();
+    let write_result: CmdResult<()> = async {
+        let mut file = File::create(&download_path)?;
+        while let Some(chunk) = response.chunk().await? {
+            file.write_all(&chunk)?;
+            downloaded += chunk.len() as u64;
+            if last_emit.elapsed().as_millis() >= 100 {
+                last_emit = std::time::Instant::now();
+                app_handle.emit_all(DOWNLOAD_PROGRESS_EVENT, DownloadProgress { downloaded, total, file_name: file_name.clone() })
+                    .unwrap_or_else(|e| error!("Failed to emit download progress: {}", e));
             }
         }
+        file.flush()?;
+        Ok(())
+    }.await;
+    if let Err(e) = write_result {
+        remove_temporary_download(&download_path);
+        return Err(e);
     }
-    else if let Some(user_preview_path_str) = selected_preview_absolute_path {
-        println!("[import_archive] Handling selected image file path: {}", user_preview_path_str);
-        let source_path = PathBuf::from(&user_preview_path_str);
-        if source_path.is_file() {
-            let target_image_path = final_mod_dest_path.join(TARGET_IMAGE_FILENAME);
-            match fs::copy(&source_path, &target_image_path) {
-                Ok(_) => {
-                    println!("[import_archive] Image file copied successfully to '{}'.", target_image_path.display());
-                    image_filename_for_db = Some(TARGET_IMAGE_FILENAME.to_string());
-                }
-                Err(e) => {
-                    eprintln!("[import_archive] ERROR: Failed copy user preview to '{}': {}. Preview will be missing.", target_image_path.display(), e);
-                }
+    app_handle.emit_all(DOWNLOAD_PROGRESS_EVENT, DownloadProgress { downloaded, total: Some(downloaded), file_name: file_name.clone() })
+        .unwrap_or_else(|e| error!("Failed to emit download progress: {}", e));
+
+    // analyze/import dispatch on the extension, so make sure it matches the actual content
+    match sniff_archive_extension(&download_path) {
+        Some(ext) => {
+            let current_ext = archive_type(&download_path);
+            if current_ext.as_deref() != Some(ext) {
+                let renamed = download_path.with_extension(ext);
+                fs::rename(&download_path, &renamed)?;
+                download_path = renamed;
             }
-        } else {
-             println!("[import_archive] Warning: Selected preview file '{}' not found, skipping.", user_preview_path_str);
         }
-    }
-    else {
-        let potential_extracted_image_path = final_mod_dest_path.join(TARGET_IMAGE_FILENAME);
-        if potential_extracted_image_path.is_file() {
-            println!("[import_archive] Using extracted {} as preview.", TARGET_IMAGE_FILENAME);
-            image_filename_for_db = Some(TARGET_IMAGE_FILENAME.to_string());
-        } else {
-             println!("[import_archive] No pasted, selected, or extracted preview found.");
+        None => {
+            remove_temporary_download(&download_path);
+            return Err(AppError::UnsupportedArchive(format!("Downloaded file '{}' is not a zip, 7z, rar or tar archive.", file_name)));
         }
     }
-    println!("[import_archive] Image handling complete. Filename to save in DB: {:?}", image_filename_for_db);
+    info!("[download_archive] Downloaded {} bytes to '{}'", downloaded, download_path.display());
+    Ok(download_path)
+}
 
-    // --- Add to Database ---
-    let relative_path_for_db = Path::new(&target_category_slug).join(&target_entity_slug).join(&target_mod_folder_name);
-    let relative_path_for_db_str = relative_path_for_db.to_string_lossy().replace("\\", "/");
+// Downloads an archive (direct link or GameBanana mod page) and analyzes it like a local file.
+// The returned file_path points into the temp downloads folder; import_archive removes it after
+// a successful import, and discard_downloaded_archive removes it if the import is abandoned.
+#[command]
+async fn import_from_url(url: String, db_state: State<'_, DbState>, app_handle: AppHandle) -> CmdResult<ArchiveAnalysisResult> {
+    info!("[import_from_url] Requested: {}", url);
+    let parsed_url = reqwest::Url::parse(url.trim())
+        .map_err(|e| AppError::Config(format!("Invalid URL '{}': {}", url, e)))?;
+    if !matches!(parsed_url.scheme(), "http" | "https") {
+        return Err(AppError::Config(format!("Only http(s) URLs are supported, got '{}'.", parsed_url.scheme())));
+    }
+    cleanup_stale_downloads();
 
-    let check_existing: Option<i64> = tx.query_row(
-        "SELECT id FROM assets WHERE entity_id = ?1 AND folder_name = ?2",
-        params![target_entity_id, relative_path_for_db_str], |row| row.get(0)
-    ).optional().map_err(|e| format!("DB error check existing import '{}': {}", relative_path_for_db_str, e))?;
+    let client = build_download_client(&app_handle)?;
+    let (download_url, suggested_name) = match parse_gamebanana_mod_id(parsed_url.as_str()) {
+        Some(mod_id) => resolve_gamebanana_download(&client, &mod_id).await?,
+        None => (parsed_url.to_string(), None),
+    };
 
-    if check_existing.is_some() {
-        fs::remove_dir_all(&final_mod_dest_path).ok();
-        return Err(format!("Database entry already exists for '{}'. Aborting.", relative_path_for_db_str));
+    let archive_path = download_archive(&client, &download_url, suggested_name, &app_handle).await?;
+    analyze_archive(archive_path.to_string_lossy().to_string(), db_state, app_handle).await
+        .inspect_err(|_| remove_temporary_download(&archive_path))
+}
+
+#[derive(Serialize, Debug, Clone)]
+struct GameBananaMetadata {
+    mod_id: String,
+    name: Option<String>,
+    author: Option<String>,
+    description: Option<String>,
+    preview_image_url: Option<String>,
+    preview_image_path: Option<String>, // Downloaded copy, usable as selected_preview_absolute_path
+}
+
+// Fetches name/author/preview for a GameBanana mod to prefill the import dialog. Missing fields
+// come back as None; a failed preview download only leaves preview_image_path empty.
+#[command]
+async fn fetch_gamebanana_metadata(mod_id_or_url: String, app_handle: AppHandle) -> CmdResult<GameBananaMetadata> {
+    let mod_id = parse_gamebanana_mod_id(&mod_id_or_url)
+        .ok_or_else(|| AppError::Config(format!("'{}' is not a GameBanana mod id or URL.", mod_id_or_url)))?;
+    let client = build_download_client(&app_handle)?;
+    let profile = fetch_gamebanana_profile(&client, &mod_id).await?;
+
+    let non_empty_str = |value: Option<&serde_json::Value>| value
+        .and_then(|v| v.as_str())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    let name = non_empty_str(profile.get("_sName"));
+    let author = non_empty_str(profile.pointer("/_aSubmitter/_sName"));
+    let description = non_empty_str(profile.get("_sDescription"));
+    let preview_image_url = profile.pointer("/_aPreviewMedia/_aImages/0").and_then(|image| {
+        let base_url = non_empty_str(image.get("_sBaseUrl"))?;
+        let file = non_empty_str(image.get("_sFile"))?;
+        Some(format!("{}/{}", base_url.trim_end_matches('/'), file))
+    });
+
+    let mut preview_image_path = None;
+    if let Some(image_url) = &preview_image_url {
+        let download: CmdResult<PathBuf> = async {
+            let bytes = client.get(image_url).send().await?.error_for_status()?.bytes().await?;
+            let extension = Path::new(image_url).extension().and_then(OsStr::to_str).unwrap_or("jpg").to_lowercase();
+            let path = new_download_folder()?.join(format!("gamebanana_preview.{}", extension));
+            fs::write(&path, &bytes)?;
+            Ok(path)
+        }.await;
+        match download {
+            Ok(path) => preview_image_path = Some(path.to_string_lossy().to_string()),
+            Err(e) => error!("[fetch_gamebanana_metadata] Failed to download preview '{}': {}", image_url, e),
+        }
     }
 
-    println!("[import_archive] Adding asset to DB: entity_id={}, name={}, path={}, image={:?}", target_entity_id, mod_name, relative_path_for_db_str, image_filename_for_db);
-    tx.execute(
-        "INSERT INTO assets (entity_id, name, description, folder_name, image_filename, author, category_tag) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        params![
-            target_entity_id, mod_name.trim(),
-            description, relative_path_for_db_str,
-            image_filename_for_db, author, category_tag
-        ]
-    ).map_err(|e| {
-        fs::remove_dir_all(&final_mod_dest_path).ok();
-        format!("Failed add imported mod to database: {}", e)
-    })?;
+    info!("[fetch_gamebanana_metadata] Mod {}: name={:?}, author={:?}, preview={:?}", mod_id, name, author, preview_image_url);
+    Ok(GameBananaMetadata { mod_id, name, author, description, preview_image_url, preview_image_path })
+}
 
-    let new_asset_id = tx.last_insert_rowid();
-    println!("[import_archive] Asset inserted with ID: {}", new_asset_id);
+// --- Update Checks ---
+const UPDATE_CHECK_CONCURRENCY: usize = 4;
 
-    // --- Add to Presets ---
-    if let Some(ids) = preset_ids {
-        if !ids.is_empty() {
-            println!("[import_archive] Adding new asset {} to presets: {:?}", new_asset_id, ids);
-            let mut insert_preset_stmt = tx.prepare_cached(
-                "INSERT OR IGNORE INTO preset_assets (preset_id, asset_id, is_enabled) VALUES (?1, ?2, ?3)"
-            ).map_err(|e| format!("Failed prepare preset asset insert: {}", e))?;
-            for preset_id in ids {
-                 insert_preset_stmt.execute(params![preset_id, new_asset_id, 1]) // Default to enabled state 1 when importing
-                    .map_err(|e| format!("Failed insert new asset {} into preset {}: {}", new_asset_id, preset_id, e))?;
+#[derive(Serialize, Debug, Clone)]
+struct ModUpdateStatus {
+    asset_id: i64,
+    asset_name: String,
+    entity_slug: String,
+    source_url: String,
+    local_date: Option<i64>,        // When the mod was imported (assets.created_at)
+    remote_updated_at: Option<i64>, // Latest update/file upload on GameBanana
+    local_version: Option<String>,  // Version declared by the installed mod (assets.version)
+    remote_version: Option<String>, // Version shown on the GameBanana page, if the author set one
+    is_outdated: bool,
+    error: Option<String>,
+}
+
+// Newest of the mod's own update/add dates and its files' upload dates
+fn gamebanana_latest_update(profile: &serde_json::Value) -> Option<i64> {
+    let profile_dates = ["_tsDateUpdated", "_tsDateModified", "_tsDateAdded"].into_iter()
+        .filter_map(|key| profile.get(key).and_then(|v| v.as_i64()));
+    let file_dates = profile.get("_aFiles").and_then(|files| files.as_array()).into_iter().flatten()
+        .filter_map(|file| file.get("_tsDateAdded").and_then(|v| v.as_i64()));
+    profile_dates.chain(file_dates).max()
+}
+
+// (latest update timestamp, declared version) for one GameBanana mod id
+type RemoteModInfo = (Option<i64>, Option<String>);
+// (asset id, name, entity slug, source url, local date, local version)
+type UpdateCandidateRow = (i64, String, String, String, Option<i64>, Option<String>);
+
+// Compares every mod with a GameBanana source_url against the mod's latest update date there.
+// Lookups are cached per mod id for the update_check_ttl_hours setting; `force` bypasses the cache.
+#[command]
+async fn check_for_updates(force: Option<bool>, db_state: State<'_, DbState>, app_handle: AppHandle) -> CmdResult<Vec<ModUpdateStatus>> {
+    let force = force.unwrap_or(false);
+    let now = unix_now();
+
+    // Collect candidates and cached lookups without holding the lock across network calls
+    let (candidates, mut remote_dates, ttl_secs) = {
+        let conn = lock_db(&db_state);
+        let ttl_hours = get_setting_value(&conn, SETTINGS_KEY_UPDATE_CHECK_TTL_HOURS)?
+            .and_then(|v| v.trim().parse::<i64>().ok())
+            .unwrap_or(DEFAULT_UPDATE_CHECK_TTL_HOURS);
+        let ttl_secs = ttl_hours.max(0) * 60 * 60;
+
+        let mut stmt = conn.prepare(
+            "SELECT a.id, a.name, e.slug, a.source_url, a.created_at, a.version
+             FROM assets a JOIN entities e ON a.entity_id = e.id
+             WHERE a.source_url IS NOT NULL AND a.source_url != ''
+             ORDER BY a.name"
+        )?;
+        let candidates: Vec<_> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)))?
+            .collect::<SqlResult<Vec<UpdateCandidateRow>>>()?
+            .into_iter()
+            .filter_map(|(id, name, entity_slug, url, created_at, version)| {
+                parse_gamebanana_mod_id(&url).map(|mod_id| (id, name, entity_slug, url, created_at, version, mod_id))
+            })
+            .collect();
+
+        let mut cached: HashMap<String, RemoteModInfo> = HashMap::new();
+        if !force {
+            let mut cache_stmt = conn.prepare("SELECT mod_id, remote_updated_at, remote_version FROM mod_update_cache WHERE checked_at >= ?1")?;
+            let rows = cache_stmt.query_map(params![now - ttl_secs], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?, row.get::<_, Option<String>>(2)?)))?;
+            for (mod_id, remote_updated_at, remote_version) in rows.flatten() {
+                cached.insert(mod_id, (remote_updated_at, remote_version));
+            }
+        }
+        (candidates, cached, ttl_secs)
+    };
+    info!("[check_for_updates] {} GameBanana-sourced mod(s), {} cached lookup(s) (TTL {}s).", candidates.len(), remote_dates.len(), ttl_secs);
+
+    // Several assets can point at the same mod page; query each mod id once
+    let mut to_fetch: Vec<String> = candidates.iter()
+        .map(|c| c.6.clone())
+        .filter(|mod_id| !remote_dates.contains_key(mod_id))
+        .collect();
+    to_fetch.sort();
+    to_fetch.dedup();
+
+    let client = build_download_client(&app_handle)?;
+    let mut fetch_errors: HashMap<String, String> = HashMap::new();
+    let mut fetched: Vec<(String, RemoteModInfo)> = Vec::new();
+    for batch in to_fetch.chunks(UPDATE_CHECK_CONCURRENCY) {
+        let handles: Vec<_> = batch.iter().map(|mod_id| {
+            let client = client.clone();
+            let mod_id = mod_id.clone();
+            async_runtime::spawn(async move {
+                let result = fetch_gamebanana_profile(&client, &mod_id).await.map(|profile| {
+                    let version = profile.get("_sVersion").and_then(|v| v.as_str()).and_then(normalize_mod_version);
+                    (gamebanana_latest_update(&profile), version)
+                });
+                (mod_id, result)
+            })
+        }).collect();
+        for handle in handles {
+            match handle.await {
+                Ok((mod_id, Ok(latest))) => fetched.push((mod_id, latest)),
+                Ok((mod_id, Err(e))) => {
+                    error!("[check_for_updates] Lookup failed for GameBanana mod {}: {}", mod_id, e);
+                    fetch_errors.insert(mod_id, e.to_string());
+                }
+                Err(e) => error!("[check_for_updates] Lookup task failed: {}", e),
             }
-            println!("[import_archive] Finished adding asset {} to presets.", new_asset_id);
         }
     }
 
-    // --- Commit Transaction ---
-    tx.commit().map_err(|e| {
-        fs::remove_dir_all(&final_mod_dest_path).ok();
-        format!("Failed to commit import transaction: {}", e)
-    })?;
+    if !fetched.is_empty() {
+        let conn = lock_db(&db_state);
+        for (mod_id, (latest, version)) in &fetched {
+            conn.execute(
+                "INSERT OR REPLACE INTO mod_update_cache (mod_id, remote_updated_at, checked_at, remote_version) VALUES (?1, ?2, ?3, ?4)",
+                params![mod_id, latest, now, version],
+            )?;
+        }
+    }
+    remote_dates.extend(fetched);
+
+    let statuses: Vec<ModUpdateStatus> = candidates.into_iter()
+        .map(|(asset_id, asset_name, entity_slug, source_url, local_date, local_version, mod_id)| {
+            let (remote_updated_at, remote_version) = remote_dates.get(&mod_id).cloned().unwrap_or_default();
+            let is_outdated = matches!((local_date, remote_updated_at), (Some(local), Some(remote)) if remote > local);
+            ModUpdateStatus {
+                asset_id, asset_name, entity_slug, source_url, local_date, remote_updated_at, local_version, remote_version, is_outdated,
+                error: fetch_errors.get(&mod_id).cloned(),
+            }
+        })
+        .collect();
+    info!("[check_for_updates] {} outdated mod(s).", statuses.iter().filter(|s| s.is_outdated).count());
+    Ok(statuses)
+}
 
-   println!("[import_archive] Import successful for '{}'", mod_name);
-   Ok(())
+// Called by the frontend when an import started from a URL is cancelled. Anything that is not a file
+// directly inside one of the temp download folders is ignored, so this can't delete a user's own archive.
+#[command]
+fn discard_downloaded_archive(archive_path_str: String) -> CmdResult<()> {
+    let archive_path = PathBuf::from(&archive_path_str);
+    if !is_temporary_download(&archive_path) {
+        info!("[discard_downloaded_archive] Ignoring non-download path '{}'", archive_path_str);
+        return Ok(());
+    }
+    remove_temporary_download(&archive_path);
+    Ok(())
 }
 
 #[command]
 fn create_preset(name: String, db_state: State<DbState>) -> CmdResult<Preset> {
     let name = name.trim();
     if name.is_empty() {
-        return Err("Preset name cannot be empty.".to_string());
+        return Err(AppError::ModOperation("Preset name cannot be empty.".to_string()));
     }
-    println!("[create_preset] Attempting to create preset: '{}'", name);
+    info!("[create_preset] Attempting to create preset: '{}'", name);
 
-    let base_mods_path = get_mods_base_path_from_settings(&db_state)
-        .map_err(|e| format!("Cannot create preset: {}", e))?;
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
 
-    let conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let conn_guard = lock_db(&db_state);
     let mut conn = conn_guard;
 
     // Use a block scope for the transaction
     let preset_id = { // Start block scope for tx
-        let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+        let tx = conn.transaction()?;
 
         // Check if name exists
         let existing_count: i64 = tx.query_row(
             "SELECT COUNT(*) FROM presets WHERE LOWER(name) = LOWER(?1)",
             params![name],
             |row| row.get(0),
-        ).map_err(|e| format!("DB error checking preset name: {}", e))?;
+        )?;
 
         if existing_count > 0 {
             // Rollback happens automatically when tx is dropped on error return
-            return Err(format!("Preset name '{}' already exists.", name));
+            return Err(AppError::ModOperation(format!("Preset name '{}' already exists.", name)));
         }
 
         // Insert new preset
-        tx.execute("INSERT INTO presets (name) VALUES (?1)", params![name])
-            .map_err(|e| format!("Failed to insert preset: {}", e))?;
+        tx.execute("INSERT INTO presets (name) VALUES (?1)", params![name])?;
         let new_preset_id = tx.last_insert_rowid();
-        println!("[create_preset] Inserted preset with ID: {}", new_preset_id);
+        info!("[create_preset] Inserted preset with ID: {}", new_preset_id);
 
         // Use another block scope for the statement and iteration
         { // Start block scope for stmt
-            let mut stmt = tx.prepare("SELECT id, folder_name FROM assets")
-                .map_err(|e| format!("Failed to prepare asset fetch: {}", e))?;
+            let mut stmt = tx.prepare("SELECT id, folder_name FROM assets")?;
             let asset_iter_result = stmt.query_map([], |row| {
                 Ok((
                     row.get::<_, i64>(0)?,
@@ -3647,46 +8127,35 @@ fn create_preset(name: String, db_state: State<DbState>) -> CmdResult<Preset> {
                         match asset_result {
                             Ok((asset_id, clean_relative_path_str)) => {
                                 let clean_relative_path = PathBuf::from(&clean_relative_path_str);
-                                let filename_osstr = clean_relative_path.file_name().unwrap_or_default();
-                                let filename_str = filename_osstr.to_string_lossy();
-                                if filename_str.is_empty() { continue; }
-
-                                let disabled_filename = format!("{}{}", DISABLED_PREFIX, filename_str);
-                                let relative_parent_path = clean_relative_path.parent();
-
-                                let full_path_if_enabled = base_mods_path.join(&clean_relative_path);
-                                let full_path_if_disabled = match relative_parent_path {
-                                    Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_filename),
-                                    _ => base_mods_path.join(&disabled_filename),
+                                let is_currently_enabled = match resolve_asset_on_disk(&base_mods_path, &clean_relative_path) {
+                                    Some((_, true)) => 1,
+                                    Some((_, false)) => 0,
+                                    None => {
+                                        warn!("[create_preset] Asset ID {} folder not found on disk during preset save (path: {}). Skipping.", asset_id, clean_relative_path_str);
+                                        continue;
+                                    }
                                 };
 
-                                let is_currently_enabled = if full_path_if_enabled.is_dir() { 1 }
-                                                            else if full_path_if_disabled.is_dir() { 0 }
-                                                            else {
-                                                                println!("[create_preset] Warning: Asset ID {} folder not found on disk during preset save (path: {}). Skipping.", asset_id, clean_relative_path_str);
-                                                                continue;
-                                                            };
-
                                 tx.execute(
                                     "INSERT INTO preset_assets (preset_id, asset_id, is_enabled) VALUES (?1, ?2, ?3)",
                                     params![new_preset_id, asset_id, is_currently_enabled],
-                                ).map_err(|e| format!("Failed to save state for asset {}: {}", asset_id, e))?;
+                                )?;
                             }
-                            Err(e) => return Err(format!("Error fetching asset row: {}", e)), // Rollbacks on return
+                            Err(e) => return Err(AppError::Sqlite(e)), // Rollbacks on return
                         }
                     }
                 }
-                Err(e) => return Err(format!("Error preparing asset iterator: {}", e)), // Rollbacks on return
+                Err(e) => return Err(AppError::Sqlite(e)), // Rollbacks on return
             }
         } // End block scope for stmt - stmt is dropped here, releasing borrow on tx
 
         // Commit the transaction
-        tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        tx.commit()?;
 
         new_preset_id // Return the ID from the block
     }; // End block scope for tx
 
-    println!("[create_preset] Preset '{}' created successfully.", name);
+    info!("[create_preset] Preset '{}' created successfully.", name);
 
     Ok(Preset { id: preset_id, name: name.to_string(), is_favorite: false })
 }
@@ -3694,55 +8163,54 @@ fn create_preset(name: String, db_state: State<DbState>) -> CmdResult<Preset> {
 
 #[command]
 fn get_presets(db_state: State<DbState>) -> CmdResult<Vec<Preset>> {
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
-    let mut stmt = conn.prepare("SELECT id, name, is_favorite FROM presets ORDER BY name ASC")
-        .map_err(|e| e.to_string())?;
+    let conn = lock_db(&db_state);
+    let mut stmt = conn.prepare("SELECT id, name, is_favorite FROM presets ORDER BY name ASC")?;
     let preset_iter = stmt.query_map([], |row| {
         Ok(Preset {
             id: row.get(0)?,
             name: row.get(1)?,
             is_favorite: row.get::<_, i64>(2)? == 1,
         })
-    }).map_err(|e| e.to_string())?;
-    preset_iter.collect::<SqlResult<Vec<Preset>>>().map_err(|e| e.to_string())
+    })?;
+    preset_iter.collect::<SqlResult<Vec<Preset>>>().map_err(AppError::from)
 }
 
 #[command]
 fn get_favorite_presets(db_state: State<DbState>) -> CmdResult<Vec<Preset>> {
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let conn = lock_db(&db_state);
     let mut stmt = conn.prepare(
         "SELECT id, name, is_favorite FROM presets WHERE is_favorite = 1 ORDER BY name ASC LIMIT 3"
-    ).map_err(|e| e.to_string())?;
+    )?;
     let preset_iter = stmt.query_map([], |row| {
         Ok(Preset {
             id: row.get(0)?,
             name: row.get(1)?,
             is_favorite: row.get::<_, i64>(2)? == 1,
         })
-    }).map_err(|e| e.to_string())?;
-    preset_iter.collect::<SqlResult<Vec<Preset>>>().map_err(|e| e.to_string())
+    })?;
+    preset_iter.collect::<SqlResult<Vec<Preset>>>().map_err(AppError::from)
 }
 
 #[command]
 async fn apply_preset(preset_id: i64, db_state: State<'_, DbState>, app_handle: AppHandle) -> CmdResult<()> {
-    println!("[apply_preset] Applying preset ID: {}", preset_id);
+    info!("[apply_preset] Applying preset ID: {}", preset_id);
 
     // Clone app_handle for potential use in error emission later
     let app_handle_clone = app_handle.clone();
 
     // --- Get base path first ---
-    let base_mods_path = get_mods_base_path_from_settings(&db_state)
-        .map_err(|e| format!("Cannot apply preset: {}", e))?;
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
 
     // --- Fetch preset assets ---
-    let preset_assets_to_apply = { // Use block scope for connection lock
-        let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let (preset_assets_to_apply, use_ini_strategy) = { // Use block scope for connection lock
+        let conn = lock_db(&db_state);
+        let use_ini_strategy = use_ini_disable_strategy(&conn);
         let mut stmt = conn.prepare(
             "SELECT pa.asset_id, pa.is_enabled, a.folder_name, a.name
              FROM preset_assets pa
              JOIN assets a ON pa.asset_id = a.id
              WHERE pa.preset_id = ?1"
-        ).map_err(|e| format!("Failed to prepare fetch for preset assets: {}", e))?;
+        )?;
 
         let preset_assets_iter_result = stmt.query_map(params![preset_id], |row| {
             Ok((
@@ -3753,15 +8221,15 @@ async fn apply_preset(preset_id: i64, db_state: State<'_, DbState>, app_handle:
             ))
         });
 
-        match preset_assets_iter_result {
-             Ok(iter) => iter.collect::<SqlResult<Vec<(i64, bool, String, String)>>>() // Include name
-                              .map_err(|e| format!("Failed to collect preset assets: {}", e))?,
-             Err(e) => return Err(format!("Error preparing preset asset iterator: {}", e)),
-        }
+        let assets = match preset_assets_iter_result {
+             Ok(iter) => iter.collect::<SqlResult<Vec<(i64, bool, String, String)>>>()?, // Include name
+             Err(e) => return Err(AppError::Sqlite(e)),
+        };
+        (assets, use_ini_strategy)
     }; // Connection lock released here
 
     let total_assets = preset_assets_to_apply.len();
-    println!("[apply_preset] Found {} assets in preset.", total_assets);
+    info!("[apply_preset] Found {} assets in preset.", total_assets);
 
     // --- Emit START event ---
     app_handle.emit_all(PRESET_APPLY_START_EVENT, total_assets).ok();
@@ -3780,62 +8248,31 @@ async fn apply_preset(preset_id: i64, db_state: State<'_, DbState>, app_handle:
             current_asset_id: Some(asset_id),
             message: progress_message.clone(),
         }).ok();
-        println!("[apply_preset] {}", progress_message); // Also log to console
+        info!("[apply_preset] {}", progress_message); // Also log to console
 
         // --- Filesystem logic ---
         let clean_relative_path = PathBuf::from(&clean_relative_path_str);
-        let filename_osstr = clean_relative_path.file_name().unwrap_or_default();
-        let filename_str = filename_osstr.to_string_lossy();
-        if filename_str.is_empty() {
+        if asset_disk_paths(&base_mods_path, &clean_relative_path).is_none() {
             let err_msg = format!("Skipping asset ID {}: Invalid folder name '{}'.", asset_id, clean_relative_path_str);
-            println!("[apply_preset] {}", err_msg);
+            info!("[apply_preset] {}", err_msg);
             errors.push(err_msg);
             continue;
         }
 
-        let enabled_filename = filename_str.to_string();
-        let disabled_filename = format!("{}{}", DISABLED_PREFIX, filename_str);
-        let relative_parent_path = clean_relative_path.parent();
-
-        let construct_full_path = |name: &str| -> PathBuf {
-            match relative_parent_path {
-                Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(name),
-                _ => base_mods_path.join(name),
-            }
-        };
-
-        let full_path_if_enabled = construct_full_path(&enabled_filename);
-        let full_path_if_disabled = construct_full_path(&disabled_filename);
-
-        let current_path_on_disk: Option<PathBuf>;
-        let current_is_enabled: bool;
-
-        if full_path_if_enabled.is_dir() {
-            current_path_on_disk = Some(full_path_if_enabled);
-            current_is_enabled = true;
-        } else if full_path_if_disabled.is_dir() {
-            current_path_on_disk = Some(full_path_if_disabled);
-            current_is_enabled = false;
-        } else {
+        let Some((current_path_on_disk, current_is_enabled)) = asset_state_on_disk(&base_mods_path, &clean_relative_path) else {
             let err_msg = format!("Skipping asset '{}' (ID {}): Folder not found on disk (path: '{}').", asset_name, asset_id, clean_relative_path_str);
-            println!("[apply_preset] {}", err_msg);
+            info!("[apply_preset] {}", err_msg);
             errors.push(err_msg);
             continue;
-        }
+        };
 
         if current_is_enabled != desired_is_enabled {
-            let target_path = if desired_is_enabled {
-                construct_full_path(&enabled_filename)
-            } else {
-                construct_full_path(&disabled_filename)
-            };
-            let source_path = current_path_on_disk.unwrap();
-            println!("[apply_preset] Renaming '{}' -> '{}' (Desired Enabled: {})", source_path.display(), target_path.display(), desired_is_enabled);
-            match fs::rename(&source_path, &target_path) {
-                Ok(_) => { /* Success */ }
+            info!("[apply_preset] Switching '{}' (Desired Enabled: {})", current_path_on_disk.display(), desired_is_enabled);
+            match set_asset_state_on_disk(&base_mods_path, &clean_relative_path, desired_is_enabled, use_ini_strategy) {
+                Ok(_) => store_asset_enabled_state(&db_state, asset_id, Some(desired_is_enabled)),
                 Err(e) => {
                      let err_msg = format!("Failed to rename asset '{}' (ID {}): {}", asset_name, asset_id, e);
-                     println!("[apply_preset] Error: {}", err_msg);
+                     error!("[apply_preset] {}", err_msg);
                      errors.push(err_msg);
                 }
             }
@@ -3844,7 +8281,7 @@ async fn apply_preset(preset_id: i64, db_state: State<'_, DbState>, app_handle:
         // tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
     } // End loop
 
-    println!("[apply_preset] Finished applying preset ID {}. Errors: {}", preset_id, errors.len());
+    info!("[apply_preset] Finished applying preset ID {}. Errors: {}", preset_id, errors.len());
 
     if errors.is_empty() {
         // --- Emit COMPLETE event ---
@@ -3857,137 +8294,258 @@ async fn apply_preset(preset_id: i64, db_state: State<'_, DbState>, app_handle:
         let error_summary = format!("Preset application completed with {} error(s).", errors.len());
         // You might want to send the full errors separately or just the summary
         app_handle_clone.emit_all(PRESET_APPLY_ERROR_EVENT, &error_summary).ok();
-        Err(format!("{}\nDetails:\n{}", error_summary, combined_errors)) // Return error details too
+        Err(AppError::ModOperation(format!("{}\nDetails:\n{}", error_summary, combined_errors))) // Return error details too
+    }
+}
+
+
+#[derive(Serialize, Debug, Clone)]
+struct StagedChange {
+    asset_id: i64,
+    name: String,
+    entity_slug: String,
+    folder_name: String,
+    current_enabled: Option<bool>, // On disk right now; None if the folder is missing
+    staged_enabled: bool,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct StagedCommitResult {
+    applied: usize,
+    errors: Vec<String>, // Failed changes stay staged so they can be retried
+}
+
+// Stages a wanted state. Staging the state the asset already has on disk drops its pending change instead.
+fn stage_asset_state(asset_id: i64, enabled: bool, db_state: &DbState, staged_state: &StagedChangesState) -> CmdResult<usize> {
+    let base_mods_path = get_mods_base_path_from_settings(db_state)?;
+    let clean_relative_path: String = {
+        let conn = lock_db(db_state);
+        conn.query_row("SELECT folder_name FROM assets WHERE id = ?1", params![asset_id], |row| row.get(0)).optional()?
+            .ok_or_else(|| AppError::NotFound(format!("Asset {} not found.", asset_id)))?
+    };
+    let current = asset_state_on_disk(&base_mods_path, Path::new(&clean_relative_path.replace("\\", "/"))).map(|(_, is_enabled)| is_enabled);
+    let mut staged = staged_state.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if current == Some(enabled) {
+        staged.remove(&asset_id);
+    } else {
+        staged.insert(asset_id, enabled);
+    }
+    Ok(staged.len())
+}
+
+// Returns the number of pending changes
+#[command]
+fn stage_enable(asset_id: i64, db_state: State<DbState>, staged_state: State<StagedChangesState>) -> CmdResult<usize> {
+    stage_asset_state(asset_id, true, &db_state, &staged_state)
+}
+
+#[command]
+fn stage_disable(asset_id: i64, db_state: State<DbState>, staged_state: State<StagedChangesState>) -> CmdResult<usize> {
+    stage_asset_state(asset_id, false, &db_state, &staged_state)
+}
+
+#[command]
+fn clear_staged(staged_state: State<StagedChangesState>) {
+    staged_state.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).clear();
+}
+
+// The pending view: every staged change with the asset's current state on disk. Assets deleted since staging are dropped.
+#[command]
+fn get_staged_state(db_state: State<DbState>, staged_state: State<StagedChangesState>) -> CmdResult<Vec<StagedChange>> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let mut staged = staged_state.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let conn = lock_db(&db_state);
+    let mut stmt = conn.prepare(
+        "SELECT a.name, e.slug, a.folder_name FROM assets a JOIN entities e ON a.entity_id = e.id WHERE a.id = ?1",
+    )?;
+    let mut changes = Vec::new();
+    let mut gone = Vec::new();
+    for (&asset_id, &staged_enabled) in staged.iter() {
+        let Some((name, entity_slug, folder_name)) = stmt.query_row(params![asset_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?.replace("\\", "/")))
+        }).optional()? else {
+            gone.push(asset_id);
+            continue;
+        };
+        let current_enabled = asset_state_on_disk(&base_mods_path, Path::new(&folder_name)).map(|(_, is_enabled)| is_enabled);
+        changes.push(StagedChange { asset_id, name, entity_slug, folder_name, current_enabled, staged_enabled });
     }
+    for asset_id in gone { staged.remove(&asset_id); }
+    changes.sort_by(|a, b| a.entity_slug.cmp(&b.entity_slug).then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase())));
+    Ok(changes)
 }
 
+// Applies every staged change with the configured disable strategy, reporting progress like apply_preset
+#[command]
+async fn commit_staged(db_state: State<'_, DbState>, staged_state: State<'_, StagedChangesState>, app_handle: AppHandle) -> CmdResult<StagedCommitResult> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let pending: Vec<(i64, bool)> = staged_state.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter().map(|(&id, &enabled)| (id, enabled)).collect();
+    let (assets, use_ini_strategy) = {
+        let conn = lock_db(&db_state);
+        let mut stmt = conn.prepare("SELECT name, folder_name FROM assets WHERE id = ?1")?;
+        let mut assets = Vec::with_capacity(pending.len());
+        for (asset_id, enabled) in pending {
+            let row = stmt.query_row(params![asset_id], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?.replace("\\", "/")))).optional()?;
+            assets.push((asset_id, enabled, row));
+        }
+        (assets, use_ini_disable_strategy(&conn))
+    };
+
+    let total = assets.len();
+    info!("[commit_staged] Applying {} staged change(s).", total);
+    app_handle.emit_all(PRESET_APPLY_START_EVENT, total).ok();
+    let mut result = StagedCommitResult::default();
+    for (index, (asset_id, enabled, row)) in assets.into_iter().enumerate() {
+        let Some((name, folder_name)) = row else {
+            // Deleted since it was staged; nothing left to apply
+            staged_state.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(&asset_id);
+            continue;
+        };
+        app_handle.emit_all(PRESET_APPLY_PROGRESS_EVENT, &ApplyProgress {
+            processed: index + 1, total, current_asset_id: Some(asset_id),
+            message: format!("Processing: {} ({}/{})", name, index + 1, total),
+        }).ok();
+        match set_asset_state_on_disk(&base_mods_path, Path::new(&folder_name), enabled, use_ini_strategy) {
+            Ok(_) => {
+                store_asset_enabled_state(&db_state, asset_id, Some(enabled));
+                staged_state.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(&asset_id);
+                result.applied += 1;
+            }
+            Err(e) => {
+                error!("[commit_staged] Failed to {} '{}': {}", if enabled { "enable" } else { "disable" }, name, e);
+                result.errors.push(format!("{}: {}", name, e));
+            }
+        }
+    }
+    let summary = format!("Applied {} staged change(s), {} failed.", result.applied, result.errors.len());
+    info!("[commit_staged] {}", summary);
+    app_handle.emit_all(PRESET_APPLY_COMPLETE_EVENT, &summary).ok();
+    Ok(result)
+}
 
 #[command]
 fn toggle_preset_favorite(preset_id: i64, is_favorite: bool, db_state: State<DbState>) -> CmdResult<()> {
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let conn = lock_db(&db_state);
     let fav_value = if is_favorite { 1 } else { 0 };
     conn.execute(
         "UPDATE presets SET is_favorite = ?1 WHERE id = ?2",
         params![fav_value, preset_id],
-    )
-    .map_err(|e| format!("Failed to update favorite status: {}", e))?;
+    )?;
     Ok(())
 }
 
 #[command]
 fn delete_preset(preset_id: i64, db_state: State<DbState>) -> CmdResult<()> {
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let conn = lock_db(&db_state);
     // Foreign key cascade should delete from preset_assets automatically
-    let changes = conn.execute("DELETE FROM presets WHERE id = ?1", params![preset_id])
-                      .map_err(|e| format!("Failed to delete preset: {}", e))?;
+    let changes = conn.execute("DELETE FROM presets WHERE id = ?1", params![preset_id])?;
     if changes == 0 {
-        Err(format!("Preset with ID {} not found.", preset_id))
+        Err(AppError::NotFound(format!("Preset with ID {} not found.", preset_id)))
     } else {
         Ok(())
     }
 }
 
 // --- Command to get Dashboard Stats ---
+// All home screen aggregates in one call: the SQL counts run under the lock, the disk check
+// (enabled/disabled/missing and total size) runs on a blocking thread afterwards
 #[command]
-fn get_dashboard_stats(db_state: State<DbState>) -> CmdResult<DashboardStats> {
+async fn get_dashboard_stats(db_state: State<'_, DbState>) -> CmdResult<DashboardStats> {
     let base_mods_path = match get_mods_base_path_from_settings(&db_state) {
         Ok(p) => p,
         Err(_) => {
              // If base path isn't set, return default zeroed stats
-            return Ok(DashboardStats {
-                total_mods: 0,
-                enabled_mods: 0,
-                disabled_mods: 0,
-                uncategorized_mods: 0,
-                category_counts: HashMap::new(),
-            });
+            return Ok(DashboardStats::default());
         }
     };
 
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let (mut stats, asset_folders) = {
+        let conn = lock_db(&db_state);
 
-    // 1. Total Mods
-    let total_mods = conn.query_row("SELECT COUNT(*) FROM assets", [], |row| row.get::<_, i64>(0))
-                         .map_err(|e| format!("Failed to get total mod count: {}", e))?;
+        // 1. Totals
+        let (total_categories, total_entities, total_mods): (i64, i64, i64) = conn.query_row(
+            "SELECT (SELECT COUNT(*) FROM categories), (SELECT COUNT(*) FROM entities), (SELECT COUNT(*) FROM assets)",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        )?;
 
-    // 2. Uncategorized Mods
-    let uncategorized_mods = conn.query_row(
-        "SELECT COUNT(a.id) FROM assets a JOIN entities e ON a.entity_id = e.id WHERE e.slug LIKE '%-other'",
-        [],
-        |row| row.get::<_, i64>(0)
-    ).map_err(|e| format!("Failed to get uncategorized mod count: {}", e))?;
-
-    // 3. Category Counts
-    let mut category_counts = HashMap::new();
-    let mut cat_stmt = conn.prepare(
-        "SELECT c.name, COUNT(a.id)
-         FROM categories c
-         JOIN entities e ON c.id = e.category_id
-         JOIN assets a ON e.id = a.entity_id
-         GROUP BY c.name
-         HAVING COUNT(a.id) > 0" // Only include categories with mods
-    ).map_err(|e| format!("Failed to prepare category count query: {}", e))?;
-
-    let cat_rows = cat_stmt.query_map([], |row| {
-        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
-    }).map_err(|e| format!("Failed to execute category count query: {}", e))?;
-
-    for row_result in cat_rows {
-        match row_result {
-            Ok((name, count)) => { category_counts.insert(name, count); }
-            Err(e) => { eprintln!("[get_dashboard_stats] Error processing category count row: {}", e); }
-        }
-    }
-
-    // 4. Enabled/Disabled Count (Disk Check)
-    let mut enabled_mods = 0;
-    let mut disabled_mods = 0;
-    let mut disk_check_errors = 0;
-
-    // Fetch folder names for checking
-    let mut asset_folders_stmt = conn.prepare("SELECT folder_name FROM assets")
-        .map_err(|e| format!("Failed to prepare asset folder fetch: {}", e))?;
-    let asset_folder_rows = asset_folders_stmt.query_map([], |row| row.get::<_, String>(0))
-        .map_err(|e| format!("Failed to query asset folders: {}", e))?;
-
-    for folder_result in asset_folder_rows {
-        match folder_result {
-            Ok(clean_relative_path_str) => {
-                 let clean_relative_path = PathBuf::from(clean_relative_path_str.replace("\\", "/"));
-                 let filename_osstr = clean_relative_path.file_name().unwrap_or_default();
-                 let filename_str = filename_osstr.to_string_lossy();
-                 if filename_str.is_empty() { continue; }
-
-                 let disabled_filename = format!("{}{}", DISABLED_PREFIX, filename_str);
-                 let relative_parent_path = clean_relative_path.parent();
-
-                 let full_path_if_enabled = base_mods_path.join(&clean_relative_path);
-                 let full_path_if_disabled = match relative_parent_path {
-                    Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_filename),
-                    _ => base_mods_path.join(&disabled_filename),
-                 };
+        // 2. Uncategorized Mods
+        let uncategorized_mods = conn.query_row(
+            "SELECT COUNT(a.id) FROM assets a JOIN entities e ON a.entity_id = e.id WHERE e.slug LIKE '%-other'",
+            [],
+            |row| row.get::<_, i64>(0)
+        )?;
 
-                 if full_path_if_enabled.is_dir() {
-                     enabled_mods += 1;
-                 } else if full_path_if_disabled.is_dir() {
-                     disabled_mods += 1;
-                 } else {
-                     // Folder not found in either state - might have been deleted since last scan
-                     // We don't count it as enabled or disabled.
-                     disk_check_errors += 1;
-                 }
+        // 3. Category Counts
+        let mut category_counts = HashMap::new();
+        let mut cat_stmt = conn.prepare(
+            "SELECT c.name, COUNT(a.id)
+             FROM categories c
+             JOIN entities e ON c.id = e.category_id
+             JOIN assets a ON e.id = a.entity_id
+             GROUP BY c.name
+             HAVING COUNT(a.id) > 0" // Only include categories with mods
+        )?;
+
+        let cat_rows = cat_stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+
+        for row_result in cat_rows {
+            match row_result {
+                Ok((name, count)) => { category_counts.insert(name, count); }
+                Err(e) => { error!("[get_dashboard_stats] Error processing category count row: {}", e); }
             }
-            Err(e) => { eprintln!("[get_dashboard_stats] Error fetching asset folder row: {}", e); }
         }
+
+        // Fetch folder names for the disk check
+        let mut asset_folders_stmt = conn.prepare("SELECT folder_name FROM assets")?;
+        let asset_folders = asset_folders_stmt.query_map([], |row| row.get::<_, String>(0))?
+            .filter_map(|folder_result| folder_result.map_err(|e| error!("[get_dashboard_stats] Error fetching asset folder row: {}", e)).ok())
+            .collect::<Vec<_>>();
+
+        let stats = DashboardStats {
+            total_categories,
+            total_entities,
+            total_mods,
+            uncategorized_mods,
+            category_counts,
+            ..DashboardStats::default()
+        };
+        (stats, asset_folders)
+    };
+
+    // 4. Enabled/Disabled Count and Disk Usage (Disk Check)
+    let disk_task = async_runtime::spawn_blocking(move || {
+        let (mut enabled_mods, mut disabled_mods, mut missing_mods, mut disk_usage_bytes) = (0, 0, 0, 0);
+        for clean_relative_path_str in asset_folders {
+            let clean_relative_path = PathBuf::from(clean_relative_path_str.replace("\\", "/"));
+            let found = match asset_state_on_disk(&base_mods_path, &clean_relative_path) {
+                Some((path, true)) => { enabled_mods += 1; Some(path) }
+                Some((path, false)) => { disabled_mods += 1; Some(path) }
+                // Hidden by a disabled parent folder counts as disabled
+                None => match resolve_asset_under_disabled_parent(&base_mods_path, &clean_relative_path) {
+                    Some(path) => { disabled_mods += 1; Some(path) }
+                    // Folder not found in either state - might have been deleted since last scan
+                    None => { missing_mods += 1; None }
+                },
+            };
+            if let Some(path) = found { disk_usage_bytes += folder_size_bytes(&path); }
+        }
+        (enabled_mods, disabled_mods, missing_mods, disk_usage_bytes)
+    });
+    let (enabled_mods, disabled_mods, missing_mods, disk_usage_bytes) = disk_task.await
+        .map_err(|e| AppError::Other(format!("Dashboard disk check failed: {}", e)))?;
+    if missing_mods > 0 {
+        warn!("[get_dashboard_stats] {} mods in the database were not found on disk.", missing_mods);
     }
 
-    Ok(DashboardStats {
-        total_mods,
-        enabled_mods,
-        disabled_mods,
-        uncategorized_mods,
-        category_counts,
-    })
+    stats.enabled_mods = enabled_mods;
+    stats.disabled_mods = disabled_mods;
+    stats.missing_mods = missing_mods;
+    stats.disk_usage_bytes = disk_usage_bytes;
+    Ok(stats)
 }
 
 
@@ -3998,19 +8556,47 @@ fn get_app_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
 }
 
+#[derive(Serialize, Debug)]
+struct AppInfo {
+    app_version: String,
+    schema_version: i64,
+    active_game: Option<String>,
+    database_path: String,
+    mods_folder: Option<String>,
+    log_path: String,
+}
+
+// Everything worth pasting into a bug report, in one call
+#[command]
+fn get_app_info(app_handle: AppHandle, db_state: State<DbState>) -> CmdResult<AppInfo> {
+    let (schema_version, active_game, mods_folder) = {
+        let conn = lock_db(&db_state);
+        let schema_version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        (schema_version, get_setting_value(&conn, DB_INTERNAL_GAME_SLUG_KEY)?, get_setting_value(&conn, SETTINGS_KEY_MODS_FOLDER)?)
+    };
+    Ok(AppInfo {
+        app_version: get_app_version(),
+        schema_version,
+        active_game,
+        database_path: get_app_data_dir(&app_handle)?.join(ACTIVE_DB_FILENAME).to_string_lossy().to_string(),
+        mods_folder,
+        log_path: get_log_path(app_handle)?,
+    })
+}
+
 #[command]
-fn get_entities_by_category_with_counts(category_slug: String, db_state: State<DbState>) -> CmdResult<Vec<EntityWithCounts>> {
-    println!("[get_entities_with_counts] Fetching for category: {}", category_slug);
+fn get_entities_by_category_with_counts(category_slug: String, only_with_mods: Option<bool>, db_state: State<DbState>) -> CmdResult<Vec<EntityWithCounts>> {
+    info!("[get_entities_with_counts] Fetching for category: {}", category_slug);
 
     let base_mods_path = match get_mods_base_path_from_settings(&db_state) {
         Ok(p) => p,
         Err(_) => {
-            println!("[get_entities_with_counts] Mods folder not set. Returning empty list.");
+            info!("[get_entities_with_counts] Mods folder not set. Returning empty list.");
             return Ok(Vec::new());
         }
     };
 
-    let conn = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let conn = lock_db(&db_state);
 
     // 1. Get Category ID
     let category_id: i64 = conn.query_row(
@@ -4018,17 +8604,17 @@ fn get_entities_by_category_with_counts(category_slug: String, db_state: State<D
         params![category_slug],
         |row| row.get(0),
     ).map_err(|e| match e {
-        rusqlite::Error::QueryReturnedNoRows => format!("Category '{}' not found", category_slug),
-        _ => format!("DB Error getting category ID: {}", e),
+        rusqlite::Error::QueryReturnedNoRows => AppError::NotFound(format!("Category '{}' not found", category_slug)),
+        _ => AppError::Sqlite(e),
     })?;
 
     // 2. Get Entities for the Category
     let mut entity_stmt = conn.prepare(
-         "SELECT e.id, e.category_id, e.name, e.slug, e.details, e.base_image
+         "SELECT e.id, e.category_id, e.name, e.slug, e.details, e.base_image, e.sort_order
           FROM entities e
           WHERE e.category_id = ?1
-          ORDER BY CASE WHEN e.slug LIKE '%-other' THEN 0 ELSE 1 END ASC, e.name ASC"
-     ).map_err(|e| format!("Failed to prepare entity query: {}", e))?;
+          ORDER BY CASE WHEN e.slug LIKE '%-other' THEN 0 ELSE 1 END ASC, e.sort_order IS NULL, e.sort_order ASC, e.name ASC"
+     )?;
 
     let entity_rows_iter = entity_stmt.query_map(params![category_id], |row| {
         Ok((
@@ -4038,50 +8624,34 @@ fn get_entities_by_category_with_counts(category_slug: String, db_state: State<D
             row.get::<_, String>(3)?,
             row.get::<_, Option<String>>(4)?,
             row.get::<_, Option<String>>(5)?,
+            row.get::<_, Option<i64>>(6)?,
         ))
-    }).map_err(|e| format!("Failed to query entities: {}", e))?;
+    })?;
 
     let mut results: Vec<EntityWithCounts> = Vec::new();
 
     // *** FIX: Apply .map_err() to the prepare call ***
-    let mut asset_folder_stmt = conn.prepare("SELECT folder_name FROM assets WHERE entity_id = ?1")
-                                     .map_err(|e| format!("Failed to prepare asset folder query: {}", e))?; // Prepare asset query once
+    let mut asset_folder_stmt = conn.prepare("SELECT folder_name FROM assets WHERE entity_id = ?1")?; // Prepare asset query once
 
     for entity_result in entity_rows_iter {
         match entity_result {
-            Ok((id, cat_id, name, slug, details, base_image)) => {
+            Ok((id, cat_id, name, slug, details, base_image, sort_order)) => {
                 // 3. For each entity, get its assets and check disk status
-                let mut total_mods_for_entity = 0;
-                let mut enabled_mods_for_entity = 0;
-
-                // Map potential errors when querying assets for *this specific* entity
-                let asset_folder_rows_result = asset_folder_stmt.query_map(params![id], |row| row.get::<_, String>(0));
-
-                match asset_folder_rows_result {
-                     Ok(rows) => {
-                        for folder_result in rows {
-                            match folder_result {
-                                Ok(clean_relative_path_str) => {
-                                    total_mods_for_entity += 1;
-
-                                    let clean_relative_path = PathBuf::from(clean_relative_path_str.replace("\\", "/"));
-                                    let filename_osstr = clean_relative_path.file_name().unwrap_or_default();
-                                    let filename_str = filename_osstr.to_string_lossy();
-                                    if filename_str.is_empty() { continue; }
-
-                                    // Check only enabled state path
-                                    let full_path_if_enabled = base_mods_path.join(&clean_relative_path);
-                                    if full_path_if_enabled.is_dir() {
-                                        enabled_mods_for_entity += 1;
-                                    }
-                                }
-                                Err(e) => eprintln!("[get_entities_with_counts] Error fetching asset folder row for entity {}: {}", id, e),
-                            }
-                        }
-                    }
+                let asset_folders: Vec<String> = match asset_folder_stmt.query_map(params![id], |row| row.get::<_, String>(0)) {
+                    Ok(rows) => rows.filter_map(|folder_result| folder_result
+                        .map_err(|e| error!("[get_entities_with_counts] Error fetching asset folder row for entity {}: {}", id, e))
+                        .ok())
+                        .collect(),
                     // Log the error but don't stop the whole process for one entity's assets failing
-                    Err(e) => eprintln!("[get_entities_with_counts] Error querying asset folders for entity {}: {}", id, e),
-                }
+                    Err(e) => {
+                        error!("[get_entities_with_counts] Error querying asset folders for entity {}: {}", id, e);
+                        Vec::new()
+                    }
+                };
+                let total_mods_for_entity = asset_folders.len() as i64;
+                let (enabled_mods_for_entity, disabled_mods_for_entity) = count_asset_states_on_disk(&base_mods_path, &asset_folders);
+
+                if only_with_mods.unwrap_or(false) && total_mods_for_entity == 0 { continue; }
 
                 results.push(EntityWithCounts {
                     id,
@@ -4091,43 +8661,41 @@ fn get_entities_by_category_with_counts(category_slug: String, db_state: State<D
                     details,
                     base_image,
                     total_mods: total_mods_for_entity,
-                    enabled_mods: enabled_mods_for_entity,
+                    enabled_mods: enabled_mods_for_entity as i64,
+                    disabled_mods: disabled_mods_for_entity as i64,
+                    sort_order,
                 });
             }
-            Err(e) => eprintln!("[get_entities_with_counts] Error processing entity row: {}", e),
+            Err(e) => error!("[get_entities_with_counts] Error processing entity row: {}", e),
         }
     }
 
-    println!("[get_entities_with_counts] Found {} entities with counts for category '{}'", results.len(), category_slug);
+    info!("[get_entities_with_counts] Found {} entities with counts for category '{}'", results.len(), category_slug);
     Ok(results)
 }
 
 #[command]
 fn overwrite_preset(preset_id: i64, db_state: State<DbState>) -> CmdResult<()> {
-    println!("[overwrite_preset] Attempting to overwrite preset ID: {}", preset_id);
+    info!("[overwrite_preset] Attempting to overwrite preset ID: {}", preset_id);
 
-    let base_mods_path = get_mods_base_path_from_settings(&db_state)
-        .map_err(|e| format!("Cannot overwrite preset (failed to get mods path): {}", e))?;
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
 
-    let conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let conn_guard = lock_db(&db_state);
     let mut conn = conn_guard; // Get mutable access to the MutexGuard content
 
     // Use a transaction for atomicity
-    let tx = conn.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let tx = conn.transaction()?;
 
     // 1. Delete existing asset states for this preset
-    println!("[overwrite_preset] Deleting old asset states for preset {}", preset_id);
-    let delete_count = tx.execute("DELETE FROM preset_assets WHERE preset_id = ?1", params![preset_id])
-        .map_err(|e| format!("Failed to delete old preset asset states: {}", e))?;
-    println!("[overwrite_preset] Deleted {} old entries.", delete_count);
+    info!("[overwrite_preset] Deleting old asset states for preset {}", preset_id);
+    let delete_count = tx.execute("DELETE FROM preset_assets WHERE preset_id = ?1", params![preset_id])?;
+    info!("[overwrite_preset] Deleted {} old entries.", delete_count);
 
     // 2. Fetch all current assets from the main assets table
     let mut assets_to_save = Vec::<(i64, String)>::new(); // (asset_id, clean_relative_path)
     { // Scope for the statement
-        let mut stmt = tx.prepare("SELECT id, folder_name FROM assets")
-           .map_err(|e| format!("Failed to prepare asset fetch statement: {}", e))?;
-        let asset_iter = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
-                             .map_err(|e| format!("Failed to create asset query iterator: {}", e))?;
+        let mut stmt = tx.prepare("SELECT id, folder_name FROM assets")?;
+        let asset_iter = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
 
         for row_result in asset_iter {
             match row_result {
@@ -4136,7 +8704,7 @@ fn overwrite_preset(preset_id: i64, db_state: State<DbState>) -> CmdResult<()> {
                 }
                 Err(e) => {
                     // Log error for the specific row but continue fetching others
-                    eprintln!("[overwrite_preset] Error fetching asset row from DB: {}", e);
+                    error!("[overwrite_preset] Error fetching asset row from DB: {}", e);
                     // Optionally rollback transaction and return error
                     // tx.rollback().ok(); // Attempt rollback // Don't rollback here, let the error propagate
                     // return Err(format!("Error fetching asset row from DB: {}", e)); // Let caller handle rollback on error
@@ -4144,7 +8712,7 @@ fn overwrite_preset(preset_id: i64, db_state: State<DbState>) -> CmdResult<()> {
             }
         }
     }
-    println!("[overwrite_preset] Fetched {} assets to check for saving.", assets_to_save.len());
+    info!("[overwrite_preset] Fetched {} assets to check for saving.", assets_to_save.len());
 
 
     // --- FIX: Add block scope for insert_stmt ---
@@ -4154,109 +8722,89 @@ fn overwrite_preset(preset_id: i64, db_state: State<DbState>) -> CmdResult<()> {
         // 3. Iterate through fetched assets, check disk state, and insert into preset_assets
         let mut insert_stmt = tx.prepare(
             "INSERT INTO preset_assets (preset_id, asset_id, is_enabled) VALUES (?1, ?2, ?3)"
-        ).map_err(|e| format!("Failed to prepare insert statement for preset assets: {}", e))?;
+        )?;
 
 
         for (asset_id, clean_relative_path_str) in assets_to_save {
             let clean_relative_path = PathBuf::from(&clean_relative_path_str);
-            let filename_osstr = clean_relative_path.file_name().unwrap_or_default();
-            let filename_str = filename_osstr.to_string_lossy();
-            if filename_str.is_empty() { continue; }
 
             // Check enabled state on disk
-            let full_path_if_enabled = base_mods_path.join(&clean_relative_path);
-            let is_currently_enabled_on_disk = if full_path_if_enabled.is_dir() {
-                1 // Enabled
-            } else {
-                // Check disabled state only to confirm it exists somewhere, otherwise skip saving
-                let disabled_filename = format!("{}{}", DISABLED_PREFIX, filename_str);
-                let relative_parent_path = clean_relative_path.parent();
-                let full_path_if_disabled = match relative_parent_path {
-                    Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_filename),
-                    _ => base_mods_path.join(&disabled_filename),
-                };
-                if full_path_if_disabled.is_dir() {
-                    0 // Disabled
-                } else {
+            let is_currently_enabled_on_disk = match resolve_asset_on_disk(&base_mods_path, &clean_relative_path) {
+                Some((_, true)) => 1, // Enabled
+                Some((_, false)) => 0, // Disabled
+                None => {
                     // Folder not found in either state - skip saving its state for this preset
-                    println!("[overwrite_preset] Warning: Asset ID {} folder not found on disk during preset save (path: {}). Skipping.", asset_id, clean_relative_path_str);
+                    warn!("[overwrite_preset] Asset ID {} folder not found on disk during preset save (path: {}). Skipping.", asset_id, clean_relative_path_str);
                     not_found_count += 1;
                     continue; // Skip to next asset
                 }
             };
 
             // Insert the current state into the preset
-            insert_stmt.execute(params![preset_id, asset_id, is_currently_enabled_on_disk])
-                .map_err(|e| format!("Failed to save state for asset {}: {}", asset_id, e))?;
+            insert_stmt.execute(params![preset_id, asset_id, is_currently_enabled_on_disk])?;
             saved_count += 1;
         }
     } // --- End scope for insert_stmt --- `insert_stmt` is dropped here, releasing the borrow on `tx`
 
 
     // 4. Commit the transaction (Now safe as insert_stmt is out of scope)
-    tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    tx.commit()?;
 
-    println!("[overwrite_preset] Preset ID {} overwritten successfully. Saved state for {} assets (skipped {} not found).", preset_id, saved_count, not_found_count);
+    info!("[overwrite_preset] Preset ID {} overwritten successfully. Saved state for {} assets (skipped {} not found).", preset_id, saved_count, not_found_count);
     Ok(())
 }
 
 #[command]
-fn get_ini_keybinds(asset_id: i64, db_state: State<DbState>) -> Result<Vec<KeybindInfo>, String> { // CmdResult is Result<T, String>
-    println!("[get_ini_keybinds] COMMAND START for asset ID: {}", asset_id);
-
-    let result: Result<Vec<KeybindInfo>, String> = (|| {
-        println!("[get_ini_keybinds] Attempting to acquire DB lock...");
-        let conn_guard = db_state.0.lock().map_err(|_| {
-            eprintln!("[get_ini_keybinds] ERROR: DB lock poisoned!");
-            "DB lock poisoned".to_string()
-        })?;
-        println!("[get_ini_keybinds] DB lock acquired.");
+fn get_ini_keybinds(asset_id: i64, db_state: State<DbState>) -> CmdResult<Vec<KeybindInfo>> {
+    info!("[get_ini_keybinds] COMMAND START for asset ID: {}", asset_id);
+
+    let result: CmdResult<Vec<KeybindInfo>> = (|| {
+        info!("[get_ini_keybinds] Attempting to acquire DB lock...");
+        let conn_guard = lock_db(&db_state);
+        info!("[get_ini_keybinds] DB lock acquired.");
         let conn = &*conn_guard; // Dereference the guard to get the connection
 
-        println!("[get_ini_keybinds] Attempting to get base mods path setting directly...");
+        info!("[get_ini_keybinds] Attempting to get base mods path setting directly...");
         // --- FIX: Provide arguments to query_row ---
         let mods_folder_path_str_opt: Option<String> = conn.query_row(
             "SELECT value FROM settings WHERE key = ?1", // SQL query
             params![SETTINGS_KEY_MODS_FOLDER],          // Parameters
             |row| row.get(0),                           // Mapping closure
-        ).optional().map_err(|e| format!("DB error fetching mods folder setting: {}", e))?;
+        ).optional()?;
         // --- End Fix ---
 
         let base_mods_path = mods_folder_path_str_opt
             .map(PathBuf::from)
             .ok_or_else(|| {
-                eprintln!("[get_ini_keybinds] ERROR: Mods folder path not set in settings.");
-                "Mods folder path not set".to_string()
+                error!("[get_ini_keybinds] Mods folder path not set in settings.");
+                AppError::Config("Mods folder path not set".to_string())
             })?;
-        println!("[get_ini_keybinds] Base mods path obtained directly: {}", base_mods_path.display());
+        info!("[get_ini_keybinds] Base mods path obtained directly: {}", base_mods_path.display());
 
 
-        println!("[get_ini_keybinds] Calling find_asset_ini_paths...");
+        info!("[get_ini_keybinds] Calling find_asset_ini_paths...");
         let ini_paths = find_asset_ini_paths(conn, asset_id, &base_mods_path)
-            .map_err(|e| {
-                eprintln!("[get_ini_keybinds] ERROR from find_asset_ini_paths: {}", e);
-                format!("Error finding INI paths: {}", e)
-            })?;
-        println!("[get_ini_keybinds] find_asset_ini_paths returned {} paths.", ini_paths.len());
+            .inspect_err(|e| error!("[get_ini_keybinds] find_asset_ini_paths failed: {}", e))?;
+        info!("[get_ini_keybinds] find_asset_ini_paths returned {} paths.", ini_paths.len());
 
         // --- Release the lock explicitly before file I/O ---
         drop(conn_guard);
-        println!("[get_ini_keybinds] DB lock released before file parsing.");
+        info!("[get_ini_keybinds] DB lock released before file parsing.");
 
         if ini_paths.is_empty() {
-             println!("[get_ini_keybinds] No INI files found for asset ID {}", asset_id);
+             info!("[get_ini_keybinds] No INI files found for asset ID {}", asset_id);
              return Ok(Vec::new()); // Return empty early if no INIs exist
         }
 
         let mut found_keybinds: Vec<KeybindInfo> = Vec::new();
 
         for ini_path in ini_paths {
-            println!("[get_ini_keybinds] Parsing INI at: {}", ini_path.display());
+            info!("[get_ini_keybinds] Parsing INI at: {}", ini_path.display());
 
             let file = match File::open(&ini_path) {
                  Ok(f) => f,
                  Err(e) => {
-                     eprintln!("[get_ini_keybinds] ERROR: Failed to open INI file {}: {}. Skipping.", ini_path.display(), e);
+                     error!("[get_ini_keybinds] Failed to open INI file {}: {}. Skipping.", ini_path.display(), e);
                      continue; // Skip to the next file if this one can't be opened
                  }
             };
@@ -4269,7 +8817,7 @@ fn get_ini_keybinds(asset_id: i64, db_state: State<DbState>) -> Result<Vec<Keybi
                 let line_raw = match line_result {
                     Ok(l) => l,
                     Err(_) => {
-                         println!("[get_ini_keybinds] Warning: Skipping unreadable line in {}", ini_path.display());
+                         warn!("[get_ini_keybinds] Skipping unreadable line in {}", ini_path.display());
                          continue;
                     }
                 };
@@ -4277,7 +8825,7 @@ fn get_ini_keybinds(asset_id: i64, db_state: State<DbState>) -> Result<Vec<Keybi
 
                 // Check for '; Constants' tag
                  if !found_constants_tag && line.starts_with(';') && line[1..].trim_start().to_lowercase().contains("constants") {
-                    println!("[get_ini_keybinds] Found '; Constants' marker in {}", ini_path.display());
+                    info!("[get_ini_keybinds] Found '; Constants' marker in {}", ini_path.display());
                     found_constants_tag = true;
                     continue; // Move to next line after finding the marker
                 }
@@ -4310,49 +8858,191 @@ fn get_ini_keybinds(asset_id: i64, db_state: State<DbState>) -> Result<Vec<Keybi
 
              // Check if keybinds were found in *this* file (after Constants)
              if !found_keybinds.is_empty() {
-                println!("[get_ini_keybinds] SUCCESS: Found {} keybinds (after Constants) in {}. Returning.", found_keybinds.len(), ini_path.display());
+                info!("[get_ini_keybinds] SUCCESS: Found {} keybinds (after Constants) in {}. Returning.", found_keybinds.len(), ini_path.display());
                 return Ok(found_keybinds); // Found binds, return them
             } else {
-                 println!("[get_ini_keybinds] No keybinds found (after Constants) in {}. Checking next file.", ini_path.display());
+                 info!("[get_ini_keybinds] No keybinds found (after Constants) in {}. Checking next file.", ini_path.display());
              }
         } // --- End loop through INI paths ---
 
         // If loop finishes, no keybinds were found in any file
-        println!("[get_ini_keybinds] No keybinds found (after Constants) after checking all INI files for asset ID {}", asset_id);
+        info!("[get_ini_keybinds] No keybinds found (after Constants) after checking all INI files for asset ID {}", asset_id);
         Ok(Vec::new()) // Return empty Vec<KeybindInfo>
 
     })(); // Execute the closure
 
-    println!("[get_ini_keybinds] COMMAND END for asset ID: {}", asset_id);
-    result // Return the result of the closure (Result<Vec<KeybindInfo>, String>)
+    info!("[get_ini_keybinds] COMMAND END for asset ID: {}", asset_id);
+    result // Return the result of the closure
+}
+
+// Swap keys declared in the asset's active INIs, e.g. "3 variants, cycles with F8"
+#[command]
+fn get_asset_variants(asset_id: i64, db_state: State<DbState>) -> CmdResult<AssetVariantInfo> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let ini_paths = find_asset_ini_paths(&lock_db(&db_state), asset_id, &base_mods_path)?;
+
+    let disabled_prefix_lower = DISABLED_PREFIX.to_lowercase();
+    let mut info = AssetVariantInfo { variant_count: 1, bindings: Vec::new() };
+    for ini_path in ini_paths {
+        let file_name = ini_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let file_name_lower = file_name.to_lowercase();
+        if file_name_lower.starts_with(&disabled_prefix_lower) || EXCLUDED_INI_FILENAMES.contains(&file_name_lower) { continue; }
+
+        match fs::read(&ini_path) {
+            Ok(bytes) => info.bindings.extend(parse_ini_variant_bindings(&String::from_utf8_lossy(&bytes), &file_name)),
+            Err(e) => warn!("[get_asset_variants] Failed to read {}: {}", ini_path.display(), e),
+        }
+    }
+    info.variant_count = info.bindings.iter().map(|b| b.states).max().unwrap_or(1);
+    Ok(info)
+}
+
+fn ini_section_header(line: &str) -> Option<&str> {
+    line.trim().trim_start_matches('\u{feff}').strip_prefix('[')?.strip_suffix(']').map(str::trim)
+}
+
+// Line without its disable marker, or None if the line isn't one we disabled. Keeps a leading BOM out of the way.
+fn strip_ini_disabled_marker(line: &str) -> Option<String> {
+    let (bom, rest) = line.strip_prefix('\u{feff}').map_or(("", line), |r| ("\u{feff}", r));
+    rest.strip_prefix(INI_SECTION_DISABLED_MARKER).map(|r| format!("{}{}", bom, r))
+}
+
+fn add_ini_disabled_marker(line: &str) -> String {
+    match line.strip_prefix('\u{feff}') {
+        Some(rest) => format!("\u{feff}{}{}", INI_SECTION_DISABLED_MARKER, rest),
+        None => format!("{}{}", INI_SECTION_DISABLED_MARKER, line),
+    }
+}
+
+fn list_ini_sections(ini_content: &str) -> Vec<IniSectionState> {
+    ini_content.lines().filter_map(|line| match strip_ini_disabled_marker(line) {
+        Some(stripped) => ini_section_header(&stripped).map(|name| IniSectionState { name: name.to_string(), enabled: false }),
+        None => ini_section_header(line).map(|name| IniSectionState { name: name.to_string(), enabled: true }),
+    }).collect()
+}
+
+// Comments out (or restores) a section header and every line up to the next header. Only touches the
+// affected lines and keeps their original line endings, so everything else round-trips byte for byte.
+// Returns Ok(None) when the section is already in the requested state.
+fn set_ini_section_enabled(ini_content: &str, section: &str, enabled: bool) -> Result<Option<String>, AppError> {
+    let mut lines: Vec<String> = ini_content.split_inclusive('\n').map(str::to_string).collect();
+    let is_header_of = |line: &str| ini_section_header(line).is_some_and(|name| name.eq_ignore_ascii_case(section));
+    let enabled_idx = lines.iter().position(|l| is_header_of(l));
+    let disabled_idx = lines.iter().position(|l| strip_ini_disabled_marker(l).is_some_and(|s| is_header_of(&s)));
+
+    match (enabled, enabled_idx, disabled_idx) {
+        (false, Some(start), _) => {
+            let end = lines.iter().skip(start + 1)
+                .position(|l| ini_section_header(l).is_some() || strip_ini_disabled_marker(l).is_some_and(|s| ini_section_header(&s).is_some()))
+                .map_or(lines.len(), |offset| start + 1 + offset);
+            for line in &mut lines[start..end] { *line = add_ini_disabled_marker(line); }
+        }
+        (true, _, Some(start)) => {
+            for (offset, line) in lines.iter_mut().skip(start).enumerate() {
+                let Some(stripped) = strip_ini_disabled_marker(line) else { break };
+                if offset > 0 && ini_section_header(&stripped).is_some() { break; }
+                *line = stripped;
+            }
+        }
+        (false, None, Some(_)) | (true, Some(_), None) => return Ok(None),
+        _ => return Err(AppError::NotFound(format!("Section [{}] not found in INI.", section))),
+    }
+    Ok(Some(lines.concat()))
+}
+
+// Resolves an INI path relative to the asset's folder, refusing anything that escapes it
+fn resolve_asset_ini_file(conn: &Connection, asset_id: i64, base_mods_path: &Path, ini_path: &str) -> Result<PathBuf, AppError> {
+    let relative_ini = Path::new(ini_path);
+    let is_ini = relative_ini.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("ini"));
+    if !is_ini || !relative_ini.components().all(|c| matches!(c, std::path::Component::Normal(_))) {
+        return Err(AppError::ModOperation(format!("Invalid INI path '{}'.", ini_path)));
+    }
+    let asset_info = get_asset_location_info(conn, asset_id)?;
+    let (mod_folder, _) = resolve_asset_on_disk(base_mods_path, Path::new(&asset_info.clean_relative_path))
+        .ok_or_else(|| AppError::NotFound(format!("Mod folder for asset ID {} not found on disk.", asset_id)))?;
+    let full_path = mod_folder.join(relative_ini);
+    if !full_path.is_file() {
+        return Err(AppError::NotFound(format!("INI file '{}' not found in mod folder.", ini_path)));
+    }
+    Ok(full_path)
+}
+
+fn read_ini_text(path: &Path) -> Result<String, AppError> {
+    String::from_utf8(fs::read(path)?)
+        .map_err(|_| AppError::ModOperation(format!("'{}' is not UTF-8 text; refusing to edit it.", path.display())))
+}
+
+#[command]
+fn get_ini_sections(asset_id: i64, ini_path: String, db_state: State<DbState>) -> CmdResult<Vec<IniSectionState>> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let full_path = resolve_asset_ini_file(&lock_db(&db_state), asset_id, &base_mods_path, &ini_path)?;
+    Ok(list_ini_sections(&read_ini_text(&full_path)?))
+}
+
+// Keeps the untouched original next to the INI (`<name>.ini.gmm.bak`, ignored by 3DMigoto) on the first edit
+#[command]
+fn toggle_ini_section(asset_id: i64, ini_path: String, section: String, enabled: bool, db_state: State<DbState>) -> CmdResult<()> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let full_path = resolve_asset_ini_file(&lock_db(&db_state), asset_id, &base_mods_path, &ini_path)?;
+
+    let content = read_ini_text(&full_path)?;
+    let Some(new_content) = set_ini_section_enabled(&content, section.trim(), enabled)? else {
+        info!("[toggle_ini_section] [{}] in {} is already {}.", section, full_path.display(), if enabled { "enabled" } else { "disabled" });
+        return Ok(());
+    };
+
+    let file_name = full_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let backup_path = full_path.with_file_name(format!("{}{}", file_name, INI_BACKUP_SUFFIX));
+    if !backup_path.exists() {
+        fs::copy(&full_path, &backup_path)?;
+    }
+    // Write beside the original and rename over it so a failed write can't leave a truncated INI
+    let temp_path = full_path.with_file_name(format!("{}.gmm_tmp", file_name));
+    fs::write(&temp_path, new_content)?;
+    if let Err(e) = fs::rename(&temp_path, &full_path) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(AppError::Io(e));
+    }
+    info!("[toggle_ini_section] {} [{}] in {}", if enabled { "Enabled" } else { "Disabled" }, section, full_path.display());
+    Ok(())
+}
+
+// Absolute on-disk folder of an asset, whichever of the enabled/DISABLED_ variants exists
+#[command]
+fn get_asset_path(asset_id: i64, db_state: State<DbState>) -> CmdResult<String> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let conn_guard = lock_db(&db_state);
+    let asset_info = get_asset_location_info(&conn_guard, asset_id)?;
+    let (full_path, _) = resolve_asset_on_disk(&base_mods_path, Path::new(&asset_info.clean_relative_path))
+        .ok_or_else(|| AppError::NotFound(format!("Mod folder not found on disk for '{}' (enabled or disabled).", asset_info.clean_relative_path)))?;
+    Ok(full_path.to_string_lossy().to_string())
 }
 
 #[command]
 fn open_asset_folder(asset_id: i64, db_state: State<DbState>) -> CmdResult<()> {
-    println!("[open_asset_folder] COMMAND START for asset ID: {}", asset_id);
-    let result = (|| {
+    info!("[open_asset_folder] COMMAND START for asset ID: {}", asset_id);
+    let result: CmdResult<()> = (|| {
         // ... (Lock acquisition, base path fetch, asset info fetch - remain the same) ...
-        println!("[open_asset_folder] Attempting to acquire DB lock...");
-        let conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
-        println!("[open_asset_folder] DB lock acquired.");
+        info!("[open_asset_folder] Attempting to acquire DB lock...");
+        let conn_guard = lock_db(&db_state);
+        info!("[open_asset_folder] DB lock acquired.");
         let conn = &*conn_guard;
 
-        println!("[open_asset_folder] Attempting to get base mods path setting directly...");
+        info!("[open_asset_folder] Attempting to get base mods path setting directly...");
         let mods_folder_path_str_opt: Option<String> = conn.query_row(
             "SELECT value FROM settings WHERE key = ?1",
             params![SETTINGS_KEY_MODS_FOLDER],
             |row| row.get(0),
-        ).optional().map_err(|e| format!("DB error fetching mods folder setting: {}", e))?;
+        ).optional()?;
 
         let base_mods_path = mods_folder_path_str_opt
             .map(PathBuf::from)
-            .ok_or_else(|| "Mods folder path not set".to_string())?;
-        println!("[open_asset_folder] Base path obtained directly: {}", base_mods_path.display());
+            .ok_or_else(|| AppError::Config("Mods folder path not set".to_string()))?;
+        info!("[open_asset_folder] Base path obtained directly: {}", base_mods_path.display());
 
-        println!("[open_asset_folder] Getting asset location info...");
-        let asset_info = get_asset_location_info(conn, asset_id)
-         .map_err(|e| format!("Failed to get asset info for opening folder: {}", e))?;
-         println!("[open_asset_folder] Asset info found: {:?}", asset_info);
+        info!("[open_asset_folder] Getting asset location info...");
+        let asset_info = get_asset_location_info(conn, asset_id)?;
+         info!("[open_asset_folder] Asset info found: {:?}", asset_info);
 
 
         // --- Determine the actual mod folder path on disk ---
@@ -4361,126 +9051,218 @@ fn open_asset_folder(asset_id: i64, db_state: State<DbState>) -> CmdResult<()> {
         // For now, assume PathBuf construction is correct.
         let relative_path_buf = PathBuf::from(&asset_info.clean_relative_path.replace("/", std::path::MAIN_SEPARATOR_STR)); // Ensure DB path uses native sep before join? Less critical usually.
 
-        let filename_osstr = relative_path_buf.file_name().ok_or_else(|| format!("Could not extract filename from DB path: {}", asset_info.clean_relative_path))?;
-        let filename_str = filename_osstr.to_string_lossy();
-        let disabled_filename = format!("{}{}", DISABLED_PREFIX, filename_str);
-        let relative_parent_path = relative_path_buf.parent();
-
-        let full_path_if_enabled = base_mods_path.join(&relative_path_buf);
-        let full_path_if_disabled = match relative_parent_path {
-            Some(parent) if parent.as_os_str().len() > 0 => base_mods_path.join(parent).join(&disabled_filename),
-            _ => base_mods_path.join(&disabled_filename),
-        };
-
-        let mod_folder_path_on_disk = if full_path_if_enabled.is_dir() {
-            Some(full_path_if_enabled)
-        } else if full_path_if_disabled.is_dir() {
-            Some(full_path_if_disabled)
-        } else {
-            None
-        };
-
+        if asset_disk_paths(&base_mods_path, &relative_path_buf).is_none() {
+            return Err(AppError::ModOperation(format!("Could not extract filename from DB path: {}", asset_info.clean_relative_path)));
+        }
+        let mod_folder_path_on_disk = resolve_asset_on_disk(&base_mods_path, &relative_path_buf).map(|(path, _)| path);
+
         drop(conn_guard);
-        println!("[open_asset_folder] DB lock released.");
+        info!("[open_asset_folder] DB lock released.");
 
         match mod_folder_path_on_disk {
             Some(mod_path) => {
-                println!("[open_asset_folder] Target mod folder: {}", mod_path.display());
-
-                let command_name;
-                let arg;
-
-                // --- FIX: Ensure backslashes for Windows explorer ---
-                if cfg!(target_os = "windows") {
-                    command_name = "explorer";
-                    // Convert to string and explicitly replace forward slashes
-                    arg = mod_path.to_string_lossy().replace("/", "\\");
-                } else if cfg!(target_os = "macos") {
-                    command_name = "open";
-                    arg = mod_path.to_str().ok_or("Invalid UTF-8 path string for macOS")?.to_string();
-                } else {
-                    command_name = "xdg-open";
-                    arg = mod_path.to_str().ok_or("Invalid UTF-8 path string for Linux")?.to_string();
-                }
-                // --- End Fix ---
+                info!("[open_asset_folder] Target mod folder: {}", mod_path.display());
 
-                println!("Executing: {} \"{}\"", command_name, arg);
-
-                match Command::new(command_name).args(&[arg]).spawn() {
-                    Ok((_, _child)) => {
-                        println!("File explorer command spawned successfully.");
-                        Ok(())
-                    },
-                    Err(e) => {
-                        eprintln!("Failed to spawn file explorer command '{}': {}", command_name, e);
-                        Err(format!("Failed to open folder using '{}': {}", command_name, e))
-                    }
-                }
+                spawn_file_explorer(&mod_path, true)
             }
             None => {
-                 println!("[open_asset_folder] Mod folder not found on disk for asset ID {}", asset_id);
-                 Err(format!("Mod folder not found for asset ID {}", asset_id))
+                 info!("[open_asset_folder] Mod folder not found on disk for asset ID {}", asset_id);
+                 Err(AppError::NotFound(format!("Mod folder not found for asset ID {}", asset_id)))
             }
         }
 
     })(); // Execute closure
 
-    println!("[open_asset_folder] COMMAND END for asset ID: {}", asset_id);
+    info!("[open_asset_folder] COMMAND END for asset ID: {}", asset_id);
     result
 }
 
+// --- Global Hotkeys ---
+
+// Registers an OS-wide shortcut that toggles `asset_id` and reports the outcome via HOTKEY_TOGGLE_EVENT.
+fn register_asset_hotkey(app_handle: &AppHandle, asset_id: i64, accelerator: &str) -> CmdResult<()> {
+    let mut manager = app_handle.global_shortcut_manager();
+    if manager.is_registered(accelerator).unwrap_or(false) {
+        return Err(AppError::ModOperation(format!("Shortcut '{}' is already in use.", accelerator)));
+    }
+
+    let handle = app_handle.clone();
+    let accelerator_owned = accelerator.to_string();
+    // The callback runs on the main (event loop) thread; the rename and DB work must not block it
+    manager.register(accelerator, move || {
+        let handle = handle.clone();
+        let accelerator_owned = accelerator_owned.clone();
+        async_runtime::spawn_blocking(move || {
+            let db_state: State<DbState> = handle.state();
+            let asset_name = lock_db(&db_state)
+                .query_row("SELECT name FROM assets WHERE id = ?1", params![asset_id], |row| row.get::<_, String>(0)).ok()
+                .unwrap_or_else(|| format!("#{}", asset_id));
+            info!("[hotkey] '{}' pressed, toggling asset {} ({})", accelerator_owned, asset_id, asset_name);
+
+            let payload = match toggle_asset_on_disk(&db_state, asset_id, &asset_name) {
+                Ok(new_state) => HotkeyTogglePayload { asset_id, accelerator: accelerator_owned, new_enabled_state: Some(new_state), error: None },
+                Err(e) => {
+                    error!("[hotkey] Toggle failed for asset {}: {}", asset_id, e);
+                    HotkeyTogglePayload { asset_id, accelerator: accelerator_owned, new_enabled_state: None, error: Some(e.to_string()) }
+                }
+            };
+            handle.emit_all(HOTKEY_TOGGLE_EVENT, payload).ok();
+        });
+    }).map_err(|e| AppError::ModOperation(format!("Failed to register shortcut '{}': {}", accelerator, e)))?;
+
+    info!("[register_asset_hotkey] Registered '{}' for asset {}", accelerator, asset_id);
+    Ok(())
+}
+
+// Re-registers every persisted hotkey; called once during setup. Failures are logged, not fatal.
+fn register_saved_hotkeys(app_handle: &AppHandle) {
+    let db_state: State<DbState> = app_handle.state();
+    let saved: Vec<(i64, String)> = lock_db(&db_state).prepare("SELECT asset_id, accelerator FROM hotkeys")
+        .and_then(|mut stmt| stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect())
+        .unwrap_or_else(|e| { error!("[register_saved_hotkeys] Failed to read hotkeys: {}", e); Vec::new() });
+    for (asset_id, accelerator) in saved {
+        if let Err(e) = register_asset_hotkey(app_handle, asset_id, &accelerator) {
+            error!("[register_saved_hotkeys] {}", e);
+        }
+    }
+}
+
+fn unregister_all_hotkeys(app_handle: &AppHandle) {
+    if let Err(e) = app_handle.global_shortcut_manager().unregister_all() {
+        error!("Failed to unregister global shortcuts: {}", e);
+    }
+}
+
+#[command]
+fn get_asset_hotkeys(db_state: State<DbState>) -> CmdResult<Vec<AssetHotkey>> {
+    let conn = lock_db(&db_state);
+    let mut stmt = conn.prepare(
+        "SELECT h.asset_id, a.name, e.slug, h.accelerator
+         FROM hotkeys h
+         JOIN assets a ON h.asset_id = a.id
+         JOIN entities e ON a.entity_id = e.id
+         ORDER BY a.name COLLATE NOCASE",
+    )?;
+    let hotkeys = stmt.query_map([], |row| Ok(AssetHotkey {
+        asset_id: row.get(0)?,
+        asset_name: row.get(1)?,
+        entity_slug: row.get(2)?,
+        accelerator: row.get(3)?,
+    }))?.collect::<SqlResult<Vec<_>>>()?;
+    Ok(hotkeys)
+}
+
+// Binds (or with `None`/empty, clears) the global shortcut that toggles an asset.
+#[command]
+fn set_asset_hotkey(asset_id: i64, accelerator: Option<String>, app_handle: AppHandle, db_state: State<DbState>) -> CmdResult<()> {
+    let new_accelerator = accelerator.map(|a| a.trim().to_string()).filter(|a| !a.is_empty());
+
+    let previous: Option<String> = {
+        let conn = lock_db(&db_state);
+        conn.query_row("SELECT id FROM assets WHERE id = ?1", params![asset_id], |row| row.get::<_, i64>(0))
+            .optional()?
+            .ok_or_else(|| AppError::NotFound(format!("Asset with ID {} not found.", asset_id)))?;
+
+        if let Some(accel) = &new_accelerator {
+            let owner: Option<String> = conn.query_row(
+                "SELECT a.name FROM hotkeys h JOIN assets a ON h.asset_id = a.id WHERE h.accelerator = ?1 COLLATE NOCASE AND h.asset_id != ?2",
+                params![accel, asset_id],
+                |row| row.get(0),
+            ).optional()?;
+            if let Some(owner_name) = owner {
+                return Err(AppError::ModOperation(format!("Shortcut '{}' is already bound to '{}'.", accel, owner_name)));
+            }
+        }
+        conn.query_row("SELECT accelerator FROM hotkeys WHERE asset_id = ?1", params![asset_id], |row| row.get(0)).optional()?
+    };
+
+    if previous == new_accelerator {
+        return Ok(());
+    }
+
+    if let Some(old) = &previous {
+        if let Err(e) = app_handle.global_shortcut_manager().unregister(old) {
+            error!("[set_asset_hotkey] Failed to unregister '{}': {}", old, e);
+        }
+    }
+
+    if let Some(accel) = &new_accelerator {
+        if let Err(e) = register_asset_hotkey(&app_handle, asset_id, accel) {
+            // Restore the previous binding so a failed change doesn't silently drop it
+            if let Some(old) = &previous {
+                register_asset_hotkey(&app_handle, asset_id, old).ok();
+            }
+            return Err(e);
+        }
+    }
+
+    let conn = lock_db(&db_state);
+    match &new_accelerator {
+        Some(accel) => conn.execute(
+            "INSERT OR REPLACE INTO hotkeys (asset_id, accelerator) VALUES (?1, ?2)",
+            params![asset_id, accel],
+        )?,
+        None => conn.execute("DELETE FROM hotkeys WHERE asset_id = ?1", params![asset_id])?,
+    };
+    info!("[set_asset_hotkey] Asset {} hotkey: {:?} -> {:?}", asset_id, previous, new_accelerator);
+    Ok(())
+}
+
 #[command]
 fn add_asset_to_presets(asset_id: i64, preset_ids: Vec<i64>, db_state: State<DbState>) -> CmdResult<()> {
     if preset_ids.is_empty() {
         return Ok(()); // Nothing to do
     }
-    println!("[add_asset_to_presets] Adding/Updating asset ID {} in presets: {:?}", asset_id, preset_ids);
+    info!("[add_asset_to_presets] Adding/Updating asset ID {} in presets: {:?}", asset_id, preset_ids);
 
-    let base_mods_path = get_mods_base_path_from_settings(&db_state)
-        .map_err(|e| format!("Cannot add/update presets (failed to get mods path): {}", e))?;
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
 
-    let mut conn_guard = db_state.0.lock().map_err(|_| "DB lock poisoned".to_string())?;
+    let mut conn_guard = lock_db(&db_state);
 
     // Use a transaction for atomicity
-    let tx = conn_guard.transaction().map_err(|e| format!("Failed to start transaction: {}", e))?;
+    let tx = conn_guard.transaction()?;
 
     // Get current enabled state *once* before the loop
     let current_is_enabled = match get_current_asset_enabled_state(&tx, asset_id, &base_mods_path) {
          Ok(enabled) => if enabled { 1 } else { 0 },
          Err(e) => {
-             eprintln!("[add_asset_to_presets] Error getting current state for asset {}: {}. Aborting.", asset_id, e);
-             return Err(format!("Failed to determine current enabled state for asset {}: {}", asset_id, e));
+             error!("[add_asset_to_presets] Error getting current state for asset {}: {}. Aborting.", asset_id, e);
+             return Err(AppError::ModOperation(format!("Failed to determine current enabled state for asset {}: {}", asset_id, e)));
          }
     };
 
-    println!("[add_asset_to_presets] Determined current enabled state for asset {} as: {}", asset_id, current_is_enabled);
+    info!("[add_asset_to_presets] Determined current enabled state for asset {} as: {}", asset_id, current_is_enabled);
 
     let mut changes_made = 0; // Tracks total rows affected (inserts + updates)
     { // Scope for the statement
         // --- *** THE FIX: Use INSERT OR REPLACE *** ---
         let mut upsert_stmt = tx.prepare_cached(
             "INSERT OR REPLACE INTO preset_assets (preset_id, asset_id, is_enabled) VALUES (?1, ?2, ?3)"
-        ).map_err(|e| format!("Failed to prepare upsert statement: {}", e))?;
+        )?;
         // --- *** END FIX *** ---
 
         for preset_id in preset_ids {
-            let changes = upsert_stmt.execute(params![preset_id, asset_id, current_is_enabled])
-                .map_err(|e| format!("Failed to upsert asset {} into preset {}: {}", asset_id, preset_id, e))?;
+            let changes = upsert_stmt.execute(params![preset_id, asset_id, current_is_enabled])?;
             changes_made += changes;
         }
     }
 
-    tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    tx.commit()?;
 
-    println!("[add_asset_to_presets] Successfully added/updated asset {} in presets. Rows affected: {}", asset_id, changes_made);
+    info!("[add_asset_to_presets] Successfully added/updated asset {} in presets. Rows affected: {}", asset_id, changes_made);
     Ok(())
 }
 
 #[command]
 fn get_available_games(app_handle: AppHandle) -> CmdResult<Vec<String>> {
-    let data_dir = get_app_data_dir(&app_handle).map_err(|e| e.to_string())?;
+    let data_dir = get_app_data_dir(&app_handle)?;
 
     let mut games: HashSet<String> = PREDEFINED_GAMES.iter().map(|&s| s.to_string()).collect();
+    // The active profile has no archive file while it's in use, which matters for custom ones
+    if let Ok(config) = read_app_config(&app_handle) {
+        games.insert(config.last_active_game);
+        games.insert(config.requested_active_game);
+    }
 
     if data_dir.is_dir() {
         match fs::read_dir(data_dir) {
@@ -4505,48 +9287,119 @@ fn get_available_games(app_handle: AppHandle) -> CmdResult<Vec<String>> {
                 }
             }
             Err(e) => {
-                eprintln!("Warning: Could not read app data directory to find existing game DBs: {}", e);
+                warn!("Could not read app data directory to find existing game DBs: {}", e);
             }
         }
     }
 
     let mut sorted_games: Vec<String> = games.into_iter().collect();
     sorted_games.sort(); // Sort alphabetically
-    println!("Available games: {:?}", sorted_games); // Log the final list
+    info!("Available games: {:?}", sorted_games); // Log the final list
     Ok(sorted_games)
 }
 
+// Each game keeps its own database (active one swapped in at startup), so its mods folder, definitions and
+// library travel with it. This reads the archived ones read-only to summarize every profile.
+#[derive(Serialize, Debug)]
+struct GameProfile {
+    slug: String,
+    is_active: bool,
+    has_database: bool,
+    mods_folder: Option<String>,
+    mod_count: Option<i64>,
+}
+
+fn read_game_profile_summary(conn: &Connection) -> Result<(Option<String>, i64), AppError> {
+    let mods_folder = get_setting_value(conn, SETTINGS_KEY_MODS_FOLDER)?;
+    let mod_count = conn.query_row("SELECT COUNT(*) FROM assets", [], |row| row.get(0))?;
+    Ok((mods_folder, mod_count))
+}
+
+// Game slugs end up in database file names, so keep them to simple identifiers
+fn is_valid_game_slug(slug: &str) -> bool {
+    !slug.is_empty() && slug.len() <= 32
+        && slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+}
+
+#[command]
+fn get_game_profiles(app_handle: AppHandle, db_state: State<DbState>) -> CmdResult<Vec<GameProfile>> {
+    let active_slug = read_app_config(&app_handle)?.last_active_game;
+    let data_dir = get_app_data_dir(&app_handle)?;
+
+    let mut profiles = Vec::new();
+    for slug in get_available_games(app_handle.clone())? {
+        let summary = if slug == active_slug {
+            Some(read_game_profile_summary(&lock_db(&db_state)))
+        } else {
+            let archive_path = data_dir.join(format!("{}{}.sqlite", DB_FILENAME_PREFIX, slug));
+            archive_path.is_file().then(|| {
+                Connection::open_with_flags(&archive_path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+                    .map_err(AppError::from)
+                    .and_then(|conn| read_game_profile_summary(&conn))
+            })
+        };
+        let has_database = summary.is_some();
+        let (mods_folder, mod_count) = match summary {
+            Some(Ok((folder, count))) => (folder, Some(count)),
+            Some(Err(e)) => { warn!("[get_game_profiles] Failed to read database for '{}': {}", slug, e); (None, None) }
+            None => (None, None),
+        };
+        profiles.push(GameProfile { is_active: slug == active_slug, has_database, slug, mods_folder, mod_count });
+    }
+    Ok(profiles)
+}
+
+#[command]
+fn get_bundled_definition_sets(app_handle: AppHandle) -> Vec<String> {
+    list_bundled_definition_sets(&app_handle)
+}
+
 #[command]
 fn get_active_game(app_handle: AppHandle) -> CmdResult<String> {
     read_app_config(&app_handle)
         .map(|config| config.requested_active_game) // Return the requested game
-        .map_err(|e| e.to_string())
 }
 
+// `definition_set` picks the bundled definitions for a new profile (e.g. a custom slug using "hsr").
 #[command]
-fn switch_game(app_handle: AppHandle, target_game_slug: String) -> CmdResult<String> { // Keep AppHandle for potential future use, though not needed for exit
-    println!("Requesting switch to game config: {}", target_game_slug);
+fn switch_game(app_handle: AppHandle, target_game_slug: String, definition_set: Option<String>) -> CmdResult<String> { // Keep AppHandle for potential future use, though not needed for exit
+    info!("Requesting switch to game config: {}", target_game_slug);
+    if !is_valid_game_slug(&target_game_slug) {
+        return Err(AppError::Config(format!("Invalid game name '{}': use lowercase letters, digits, '-' or '_'.", target_game_slug)));
+    }
 
-    let mut config = read_app_config(&app_handle).map_err(|e| e.to_string())?;
+    let mut config = read_app_config(&app_handle)?;
     let current_game_slug = config.requested_active_game.clone(); // Clone needed if used after config update
 
     if current_game_slug == target_game_slug {
-        println!("Already requested game: {}. No change needed.", target_game_slug);
+        info!("Already requested game: {}. No change needed.", target_game_slug);
         // Return a different message if no action needed
         return Ok("Game already selected. No action taken.".to_string());
     }
 
+    if let Some(set) = definition_set.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()) {
+        let archive_path = get_app_data_dir(&app_handle)?.join(format!("{}{}.sqlite", DB_FILENAME_PREFIX, target_game_slug));
+        if archive_path.exists() || target_game_slug == config.last_active_game {
+            return Err(AppError::Config(format!("'{}' already has a library; definitions can only be chosen for a new profile.", target_game_slug)));
+        }
+        if !list_bundled_definition_sets(&app_handle).contains(&set) {
+            return Err(AppError::NotFound(format!("Bundled definitions '{}' not found.", set)));
+        }
+        info!("New profile '{}' will use the '{}' definitions.", target_game_slug, set);
+        config.definition_sets.insert(target_game_slug.clone(), set);
+    }
+
     // Update only the requested game field
     config.requested_active_game = target_game_slug.clone();
 
     // Write the updated config back
     if let Err(e) = write_app_config(&app_handle, &config) {
-        let err_msg = format!("CRITICAL: Failed to update app config with requested game: {}", e);
-        eprintln!("{}", err_msg);
+        let err_msg = format!("Failed to update app config with requested game: {}", e);
+        error!("{}", err_msg);
         // Don't exit here, let the user know the config failed
-        return Err(err_msg);
+        return Err(AppError::Config(err_msg));
     }
-    println!("App config updated. Requested game: {}.", target_game_slug);
+    info!("App config updated. Requested game: {}.", target_game_slug);
 
     // --- Removed app_handle.restart() ---
 
@@ -4556,9 +9409,10 @@ fn switch_game(app_handle: AppHandle, target_game_slug: String) -> CmdResult<Str
 
 #[command]
 fn exit_app(app_handle: AppHandle) {
-    println!("Received request to exit application.");
+    info!("Received request to exit application.");
+    unregister_all_hotkeys(&app_handle);
     // Exit the entire application process. The '0' is the exit code (0 usually means success).
-    exit(0);
+    exit_process(0);
 }
 
 #[command]
@@ -4568,44 +9422,386 @@ fn run_traveler_migration(db_state: State<DbState>, app_handle: AppHandle) -> Cm
 }
 
 // --- Main Function ---
+#[derive(Serialize, Debug, Clone, Copy)]
+struct OptimizeDatabaseResult {
+    size_before: u64,
+    size_after: u64,
+}
+
+// VACUUM to reclaim space left by deletes, then let SQLite refresh its query planner stats
+#[command]
+fn optimize_database(db_state: State<DbState>, app_handle: AppHandle) -> CmdResult<OptimizeDatabaseResult> {
+    let _bulk_task_guard = BulkDbTaskGuard::acquire("database optimization")?;
+    let db_path = get_app_data_dir(&app_handle)?.join(DB_NAME);
+    let file_size = || fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
+
+    let conn = lock_db(&db_state);
+    let size_before = file_size();
+    info!("[optimize_database] Running VACUUM (current size {} bytes)...", size_before);
+    conn.execute_batch("VACUUM; PRAGMA optimize;")?;
+    let size_after = file_size();
+    info!("[optimize_database] Done: {} -> {} bytes.", size_before, size_after);
+    Ok(OptimizeDatabaseResult { size_before, size_after })
+}
+
+// --- Database Export/Import ---
+// Metadata only (organization, names, tags, notes); mod files stay where they are.
+const DATABASE_EXPORT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ExportedCategory {
+    name: String,
+    slug: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ExportedEntity {
+    category_slug: String,
+    name: String,
+    slug: String,
+    description: Option<String>,
+    details: Option<String>, // Raw JSON string, as stored
+    base_image: Option<String>,
+    default_category_tag: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ExportedAsset {
+    entity_slug: String,
+    name: String,
+    description: Option<String>,
+    folder_name: String, // Clean relative path
+    image_filename: Option<String>, // Only previews inside the mod folder, see portable_image_filename
+    author: Option<String>,
+    category_tag: Option<String>,
+    created_at: Option<i64>,
+    source_url: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+}
+
+// "external:" previews are named after the asset id in this install's previews folder, so they mean
+// nothing in another database. Exports leave them out; imports ignore them and keep the current preview.
+fn portable_image_filename(image_filename: Option<String>) -> Option<String> {
+    image_filename.filter(|name| !name.starts_with(EXTERNAL_PREVIEW_PREFIX))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct DatabaseExport {
+    format_version: u32,
+    game_slug: Option<String>,
+    exported_at: i64,
+    categories: Vec<ExportedCategory>,
+    entities: Vec<ExportedEntity>,
+    assets: Vec<ExportedAsset>,
+}
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct DatabaseImportResult {
+    categories_added: usize,
+    entities_added: usize,
+    assets_added: usize,
+    assets_updated: usize,
+    missing: Vec<String>, // Assets skipped because their folder isn't on disk or lies outside the mods folder
+}
+
+#[command]
+fn export_database_json(dest_path: String, db_state: State<DbState>) -> CmdResult<usize> {
+    info!("[export_database_json] Exporting to '{}'", dest_path);
+    let conn = lock_db(&db_state);
+
+    let categories = conn.prepare("SELECT name, slug FROM categories ORDER BY name")?
+        .query_map([], |row| Ok(ExportedCategory { name: row.get(0)?, slug: row.get(1)? }))?
+        .collect::<SqlResult<Vec<_>>>()?;
+    let entities = conn.prepare(
+        "SELECT c.slug, e.name, e.slug, e.description, e.details, e.base_image, e.default_category_tag
+         FROM entities e JOIN categories c ON e.category_id = c.id ORDER BY c.slug, e.slug"
+    )?.query_map([], |row| Ok(ExportedEntity {
+        category_slug: row.get(0)?, name: row.get(1)?, slug: row.get(2)?, description: row.get(3)?,
+        details: row.get(4)?, base_image: row.get(5)?, default_category_tag: row.get(6)?,
+    }))?.collect::<SqlResult<Vec<_>>>()?;
+    let assets = conn.prepare(
+        "SELECT e.slug, a.name, a.description, a.folder_name, a.image_filename, a.author, a.category_tag, a.created_at, a.source_url, a.version
+         FROM assets a JOIN entities e ON a.entity_id = e.id ORDER BY a.folder_name"
+    )?.query_map([], |row| Ok(ExportedAsset {
+        entity_slug: row.get(0)?, name: row.get(1)?, description: row.get(2)?,
+        folder_name: row.get::<_, String>(3)?.replace('\\', "/"), image_filename: portable_image_filename(row.get(4)?),
+        author: row.get(5)?, category_tag: row.get(6)?, created_at: row.get(7)?, source_url: row.get(8)?, version: row.get(9)?,
+    }))?.collect::<SqlResult<Vec<_>>>()?;
+
+    let export = DatabaseExport {
+        format_version: DATABASE_EXPORT_FORMAT_VERSION,
+        game_slug: get_setting_value(&conn, DB_INTERNAL_GAME_SLUG_KEY)?,
+        exported_at: unix_now(),
+        categories,
+        entities,
+        assets,
+    };
+    let asset_count = export.assets.len();
+    fs::write(&dest_path, serde_json::to_string_pretty(&export)?)?;
+    info!("[export_database_json] Exported {} categories, {} entities, {} assets.", export.categories.len(), export.entities.len(), asset_count);
+    Ok(asset_count)
+}
+
+// Restores metadata from export_database_json. Existing definition-managed entity fields are left alone;
+// assets are matched by folder and only restored if that folder is still on disk and inside the mods folder.
+#[command]
+fn import_database_json(src_path: String, db_state: State<DbState>) -> CmdResult<DatabaseImportResult> {
+    info!("[import_database_json] Importing from '{}'", src_path);
+    let export: DatabaseExport = serde_json::from_str(&fs::read_to_string(&src_path)?)?;
+    if export.format_version > DATABASE_EXPORT_FORMAT_VERSION {
+        return Err(AppError::Config(format!(
+            "This export was made by a newer version of the app (format {}, supported up to {}).",
+            export.format_version, DATABASE_EXPORT_FORMAT_VERSION
+        )));
+    }
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let mut conn = lock_db(&db_state);
+    if let (Some(export_game), Some(current_game)) = (&export.game_slug, get_setting_value(&conn, DB_INTERNAL_GAME_SLUG_KEY)?) {
+        if *export_game != current_game {
+            return Err(AppError::Config(format!("This export is for '{}' but the active game is '{}'.", export_game, current_game)));
+        }
+    }
+
+    let mut result = DatabaseImportResult::default();
+    let tx = conn.transaction()?;
+
+    for category in &export.categories {
+        result.categories_added += tx.execute(
+            "INSERT OR IGNORE INTO categories (name, slug) VALUES (?1, ?2)",
+            params![category.name, category.slug],
+        )?;
+    }
+
+    for entity in &export.entities {
+        let Some(category_id) = tx.query_row(
+            "SELECT id FROM categories WHERE slug = ?1", params![entity.category_slug], |row| row.get::<_, i64>(0),
+        ).optional()? else {
+            warn!("[import_database_json] Skipping entity '{}': category '{}' not found.", entity.slug, entity.category_slug);
+            continue;
+        };
+        let added = tx.execute(
+            "INSERT OR IGNORE INTO entities (category_id, name, slug, description, details, base_image, default_category_tag) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![category_id, entity.name, entity.slug, entity.description, entity.details, entity.base_image, entity.default_category_tag],
+        )?;
+        if added == 0 {
+            tx.execute(
+                "UPDATE entities SET default_category_tag = ?1 WHERE slug = ?2",
+                params![entity.default_category_tag, entity.slug],
+            )?;
+        }
+        result.entities_added += added;
+    }
+
+    for asset in &export.assets {
+        let image_filename = portable_image_filename(asset.image_filename.clone());
+        // An absolute path would replace the mods folder in join, and '..' would climb out of it; either way
+        // a later delete_asset would remove a folder that isn't a mod.
+        let folder_path = Path::new(&asset.folder_name);
+        if !folder_path.components().all(|c| matches!(c, Component::Normal(_))) {
+            warn!("[import_database_json] Skipping asset with a folder outside the mods folder: '{}'", asset.folder_name);
+            result.missing.push(asset.folder_name.clone());
+            continue;
+        }
+        let Some((_, is_enabled)) = resolve_asset_on_disk(&base_mods_path, folder_path) else {
+            result.missing.push(asset.folder_name.clone());
+            continue;
+        };
+        let Some(entity_id) = tx.query_row(
+            "SELECT id FROM entities WHERE slug = ?1", params![asset.entity_slug], |row| row.get::<_, i64>(0),
+        ).optional()? else {
+            warn!("[import_database_json] Skipping asset '{}': entity '{}' not found.", asset.folder_name, asset.entity_slug);
+            result.missing.push(asset.folder_name.clone());
+            continue;
+        };
+        let updated = tx.execute(
+            "UPDATE assets SET entity_id = ?1, name = ?2, description = ?3, image_filename = COALESCE(?4, image_filename), author = ?5, category_tag = ?6, created_at = COALESCE(?7, created_at), source_url = ?8, version = COALESCE(?10, version)
+             WHERE folder_name = ?9",
+            params![entity_id, asset.name, asset.description, image_filename, asset.author, asset.category_tag, asset.created_at, asset.source_url, asset.folder_name, asset.version],
+        )?;
+        if updated > 0 {
+            result.assets_updated += 1;
+        } else {
+            tx.execute(
+                "INSERT INTO assets (entity_id, name, description, folder_name, image_filename, author, category_tag, created_at, is_enabled, source_url, version) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![entity_id, asset.name, asset.description, asset.folder_name, image_filename, asset.author, asset.category_tag, asset.created_at, is_enabled, asset.source_url, asset.version],
+            )?;
+            result.assets_added += 1;
+        }
+    }
+
+    tx.commit()?;
+    info!("[import_database_json] Added {} categories, {} entities, {} assets; updated {} assets; {} missing on disk.",
+        result.categories_added, result.entities_added, result.assets_added, result.assets_updated, result.missing.len());
+    Ok(result)
+}
+
+// Flips the cancellation flag of a running operation. Returns false if nothing by that name is running.
+#[command]
+fn cancel_operation(operation: String) -> bool {
+    let flags = CANCEL_FLAGS.lock().unwrap_or_else(|e| e.into_inner());
+    match flags.get(operation.as_str()) {
+        Some(flag) => {
+            info!("[cancel_operation] Cancelling '{}'", operation);
+            flag.store(true, Ordering::SeqCst);
+            true
+        }
+        None => false,
+    }
+}
+
+// --- Mods Folder Backup/Restore ---
+// Emitted every this many files, plus once at the end
+const BACKUP_PROGRESS_INTERVAL: usize = 20;
+
+#[derive(Serialize, Debug, Clone, Default)]
+struct BackupRestoreResult {
+    files: usize, // Files written (backup) or restored (restore)
+    skipped: usize, // Restore only: files left alone because they already exist
+}
+
+fn emit_backup_progress(app_handle: &AppHandle, operation: &str, processed: usize, total: usize, current_file: Option<String>) {
+    if !processed.is_multiple_of(BACKUP_PROGRESS_INTERVAL) && processed != total { return; }
+    app_handle.emit_all(BACKUP_PROGRESS_EVENT, BackupProgress { operation: operation.to_string(), processed, total, current_file })
+        .unwrap_or_else(|e| warn!("Failed to emit backup progress: {}", e));
+}
+
+// Zips the whole mods folder (enabled and disabled mods) into dest_path.
+// Cancelling via cancel_operation("backup") deletes the partial zip.
+#[command]
+async fn backup_mods_folder(dest_path: String, db_state: State<'_, DbState>, app_handle: AppHandle) -> CmdResult<BackupRestoreResult> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    let dest = PathBuf::from(&dest_path);
+    if dest.starts_with(&base_mods_path) {
+        return Err(AppError::Config("The backup file cannot be saved inside the mods folder.".to_string()));
+    }
+    info!("[backup_mods_folder] Backing up '{}' to '{}'", base_mods_path.display(), dest.display());
+    let token = CancelToken::register("backup");
+
+    async_runtime::spawn_blocking(move || -> CmdResult<BackupRestoreResult> {
+        let entries: Vec<walkdir::DirEntry> = WalkDir::new(&base_mods_path).min_depth(1).into_iter()
+            .collect::<Result<_, _>>()
+            .map_err(|e| AppError::Io(e.into()))?;
+        let total = entries.iter().filter(|e| e.file_type().is_file()).count();
+
+        let mut partial = PartialOutput::default();
+        let file = File::create(&dest)?;
+        partial.track(dest.clone());
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        let mut result = BackupRestoreResult::default();
+        emit_backup_progress(&app_handle, "backup", 0, total, None);
+        for entry in &entries {
+            token.check()?;
+            let relative = entry.path().strip_prefix(&base_mods_path).unwrap_or(entry.path())
+                .to_string_lossy().replace('\\', "/");
+            if entry.file_type().is_dir() {
+                zip.add_directory(relative, options)?;
+                continue;
+            }
+            if !entry.file_type().is_file() { continue; } // Symlinks are not followed
+            let large_file = entry.metadata().map(|m| m.len() >= u32::MAX as u64).unwrap_or(false);
+            zip.start_file(relative.as_str(), options.large_file(large_file))?;
+            io::copy(&mut File::open(entry.path())?, &mut zip)?;
+            result.files += 1;
+            emit_backup_progress(&app_handle, "backup", result.files, total, Some(relative));
+        }
+        zip.finish()?;
+        partial.keep();
+        info!("[backup_mods_folder] Wrote {} files.", result.files);
+        Ok(result)
+    }).await.map_err(|e| AppError::Other(format!("Backup task failed: {}", e)))?
+}
+
+// Extracts a backup made by backup_mods_folder into the mods folder. Files that already exist are
+// left untouched, so restoring never overwrites the current library. Cancelling via
+// cancel_operation("restore") removes everything this run created. Run a scan afterwards.
+#[command]
+async fn restore_mods_backup(src_path: String, db_state: State<'_, DbState>, app_handle: AppHandle) -> CmdResult<BackupRestoreResult> {
+    let base_mods_path = get_mods_base_path_from_settings(&db_state)?;
+    info!("[restore_mods_backup] Restoring '{}' into '{}'", src_path, base_mods_path.display());
+    let token = CancelToken::register("restore");
+
+    async_runtime::spawn_blocking(move || -> CmdResult<BackupRestoreResult> {
+        let mut archive = ZipArchive::new(File::open(&src_path)?)?;
+        let total = archive.len();
+        let mut partial = PartialOutput::default();
+        let mut result = BackupRestoreResult::default();
+
+        emit_backup_progress(&app_handle, "restore", 0, total, None);
+        for i in 0..total {
+            token.check()?;
+            let mut entry = archive.by_index(i)?;
+            let Some(relative) = zip_entry_path(&entry) else {
+                warn!("[restore_mods_backup] Skipping unsafe entry '{}'", entry.name());
+                continue;
+            };
+            let dest = base_mods_path.join(&relative);
+            // Track the outermost folder this run creates, so cleanup removes the whole new subtree
+            let dir = if entry.is_dir() { dest.as_path() } else { dest.parent().unwrap_or(&base_mods_path) };
+            if let Some(new_root) = dir.ancestors().take_while(|p| !p.exists()).last() {
+                partial.track(new_root.to_path_buf());
+            }
+            fs::create_dir_all(dir)?;
+            if !entry.is_dir() {
+                if dest.exists() {
+                    result.skipped += 1;
+                } else {
+                    partial.track(dest.clone());
+                    copy_zip_entry(&mut entry, &mut File::create(&dest)?)?;
+                    result.files += 1;
+                }
+            }
+            emit_backup_progress(&app_handle, "restore", i + 1, total, Some(relative));
+        }
+        partial.keep();
+        info!("[restore_mods_backup] Restored {} files, skipped {} existing.", result.files, result.skipped);
+        Ok(result)
+    }).await.map_err(|e| AppError::Other(format!("Restore task failed: {}", e)))?
+}
+
 fn main() {
     let context = generate_context!(); // Generates context based on tauri.conf.json
+    if let Some(data_dir) = tauri::api::path::app_data_dir(context.config()) {
+        init_logging(&data_dir.join(LOG_DIR_NAME));
+    }
 
     tauri::Builder::default()
         .setup(|app| {
             let app_handle = app.handle();
-            println!("--- Application Setup Starting ---");
+            info!("--- Application Setup Starting ---");
 
             let data_dir = match get_app_data_dir(&app_handle) {
                 Ok(dir) => dir,
                 Err(e) => {
                      // If we can't even determine the path, it's fatal.
-                     eprintln!("FATAL: Cannot determine app data dir path: {}", e);
+                     error!("Cannot determine app data dir path: {}", e);
                      dialog::blocking::message(
                          app_handle.get_window("main").as_ref(),
                          "Fatal Error",
                          "Cannot determine the application data directory path."
                      );
-                     std::process::exit(1);
+                     exit_process(1);
                 }
             };
 
             // Attempt to create the directory if it doesn't exist.
             if !data_dir.exists() {
-                println!("App data directory does not exist, attempting to create: {}", data_dir.display());
+                info!("App data directory does not exist, attempting to create: {}", data_dir.display());
                 if let Err(e) = fs::create_dir_all(&data_dir) {
                     // If creation fails (permissions?), it's fatal.
-                    eprintln!("FATAL: Failed to create app data directory at {}: {}", data_dir.display(), e);
+                    error!("Failed to create app data directory at {}: {}", data_dir.display(), e);
                     dialog::blocking::message(
                         app_handle.get_window("main").as_ref(),
                         "Fatal Error",
                         &format!("Failed to create application data directory:\n{}\n\nPlease check permissions.", data_dir.display())
                     );
-                    std::process::exit(1);
+                    exit_process(1);
                 }
-                 println!("App data directory created successfully.");
+                 info!("App data directory created successfully.");
             } else {
-                println!("App data directory already exists: {}", data_dir.display());
+                info!("App data directory already exists: {}", data_dir.display());
             }
 
             // --- 1. Read Target Config ---
@@ -4614,37 +9810,37 @@ fn main() {
                  Ok(cfg) => cfg,
                  Err(e) => {
                      // If config can't be read/created, the app cannot function correctly.
-                     eprintln!("FATAL: Failed to read or create app config: {}", e);
+                     error!("Failed to read or create app config: {}", e);
                      // Show a blocking message to the user before exiting.
                      dialog::blocking::message(
                          app_handle.get_window("main").as_ref(), // Get main window handle if possible
                          "Fatal Configuration Error",
                          &format!("Could not read or create app configuration:\n{}", e)
                      );
-                     std::process::exit(1); // Exit the application.
+                     exit_process(1); // Exit the application.
                  }
             };
             // Store the slugs from the config for easier access.
             let last_slug = &config.last_active_game;
             let requested_slug = &config.requested_active_game;
-            println!("Config Read: Last Active='{}', Requested='{}'", last_slug, requested_slug);
+            info!("Config Read: Last Active='{}', Requested='{}'", last_slug, requested_slug);
 
             // --- 2. Perform Pre-Initialization DB Rename Logic ---
             // This block executes ONLY if the last known active game is different from the requested one.
             if last_slug != requested_slug {
-                println!("Switch required: '{}' -> '{}'", last_slug, requested_slug);
+                info!("Switch required: '{}' -> '{}'", last_slug, requested_slug);
                 // Get the application's data directory path.
                 let data_dir = match get_app_data_dir(&app_handle) {
                      Ok(dir) => dir,
                      Err(e) => {
                           // Cannot proceed without the data directory.
-                          eprintln!("FATAL: Cannot get app data dir: {}", e);
+                          error!("Cannot get app data dir: {}", e);
                           dialog::blocking::message(
                               app_handle.get_window("main").as_ref(),
                               "Fatal Error",
                               "Cannot determine application data directory."
                           );
-                          std::process::exit(1);
+                          exit_process(1);
                      }
                 };
                 // Define paths for the active DB and the archive files for the last and requested games.
@@ -4655,72 +9851,72 @@ fn main() {
                 // Step A: Archive the current active DB (if it exists).
                 // This should correspond to the 'last_slug'.
                 if active_db_path.exists() {
-                    println!("Archiving '{}' (from '{}') to '{}'", ACTIVE_DB_FILENAME, last_slug, last_game_archive_path.display());
+                    info!("Archiving '{}' (from '{}') to '{}'", ACTIVE_DB_FILENAME, last_slug, last_game_archive_path.display());
                     // Attempt to rename the active DB file to its archived name.
                     if let Err(e) = fs::rename(&active_db_path, &last_game_archive_path) {
                          // If renaming fails, it's a critical error preventing the switch.
                          let err_msg = format!("Failed to archive DB for '{}': {}", last_slug, e);
-                         eprintln!("FATAL: {}", err_msg);
+                         error!("{}", err_msg);
                          dialog::blocking::message(
                              app_handle.get_window("main").as_ref(),
                              "Fatal Startup Error",
                              &err_msg
                          );
-                         std::process::exit(1);
+                         exit_process(1);
                     }
                 } else {
                      // Log a warning if the active file doesn't exist, as it might indicate a previous issue.
-                     println!("Warning: {} not found, cannot archive game '{}'.", ACTIVE_DB_FILENAME, last_slug);
+                     warn!("{} not found, cannot archive game '{}'.", ACTIVE_DB_FILENAME, last_slug);
                 }
 
                 // Step B: Activate the requested DB by renaming its archive file (if it exists) to the active name.
                 if requested_game_archive_path.exists() {
-                     println!("Activating '{}' from '{}'", ACTIVE_DB_FILENAME, requested_game_archive_path.display());
+                     info!("Activating '{}' from '{}'", ACTIVE_DB_FILENAME, requested_game_archive_path.display());
                      // Attempt to rename the requested game's archive to the active DB name.
                      if let Err(e) = fs::rename(&requested_game_archive_path, &active_db_path) {
                           // If this rename fails, try to roll back the first rename (Step A) if possible.
                           if last_game_archive_path.exists() {
-                              println!("Attempting rollback: Renaming {} back to {}", last_game_archive_path.display(), active_db_path.display());
+                              info!("Attempting rollback: Renaming {} back to {}", last_game_archive_path.display(), active_db_path.display());
                               fs::rename(&last_game_archive_path, &active_db_path).ok(); // Ignore rollback error, main error is critical.
                           }
                           // Report the critical error that prevented activation.
                           let err_msg = format!("Failed to activate DB for '{}': {}", requested_slug, e);
-                          eprintln!("FATAL: {}", err_msg);
+                          error!("{}", err_msg);
                           dialog::blocking::message(
                               app_handle.get_window("main").as_ref(),
                               "Fatal Startup Error",
                               &err_msg
                           );
-                          std::process::exit(1);
+                          exit_process(1);
                      }
                 } else {
                      // If the requested game's archive doesn't exist, a new DB will be created later by initialize_database.
-                     println!("Archive for requested game '{}' ('{}') not found. New DB will be created.", requested_slug, requested_game_archive_path.display());
+                     info!("Archive for requested game '{}' ('{}') not found. New DB will be created.", requested_slug, requested_game_archive_path.display());
                 }
 
                 // Step C: Update the configuration file to reflect the successful switch.
                 // The 'last_active_game' should now match the 'requested_active_game'.
-                println!("Updating config to set last_active_game = requested_active_game ('{}')", requested_slug);
+                info!("Updating config to set last_active_game = requested_active_game ('{}')", requested_slug);
                 config.last_active_game = requested_slug.clone(); // Update the config struct in memory.
                 if let Err(e) = write_app_config(&app_handle, &config) {
                      // If writing the config fails, the state is inconsistent. Log a critical warning.
                      // The app will likely function for this session, but the next startup might be incorrect.
-                     eprintln!("CRITICAL WARNING: Failed to update config after DB rename: {}. Config may be out of sync!", e);
+                     warn!("Failed to update config after DB rename: {}. Config may be out of sync!", e);
                 } else {
-                     println!("Config synced successfully.");
+                     info!("Config synced successfully.");
                 }
-                println!("DB swap/activation completed for '{}'.", requested_slug);
+                info!("DB swap/activation completed for '{}'.", requested_slug);
 
             } else {
                 // If last_slug and requested_slug are the same, no switch is needed.
-                println!("No game switch needed (Last Active == Requested Active: '{}').", requested_slug);
+                info!("No game switch needed (Last Active == Requested Active: '{}').", requested_slug);
                 // As a sanity check, ensure the active DB file actually exists.
                  let active_db_path = get_app_data_dir(&app_handle).expect("Data dir checked previously").join(ACTIVE_DB_FILENAME);
                  if !active_db_path.exists() {
-                     println!("Warning: Config indicates no switch needed, but '{}' does not exist. A new DB will be created for '{}'.", ACTIVE_DB_FILENAME, requested_slug);
+                     warn!("Config indicates no switch needed, but '{}' does not exist. A new DB will be created for '{}'.", ACTIVE_DB_FILENAME, requested_slug);
                  }
             }
-            println!("Pre-initialization DB check complete.");
+            info!("Pre-initialization DB check complete.");
 
             // --- 3. Initialize DB Connection for State ---
             // Initialize the database connection using the (now correctly named) active DB file.
@@ -4729,30 +9925,31 @@ fn main() {
                  Ok(c) => c,
                  Err(e) => {
                      // If database initialization fails (e.g., cannot open/create file, schema error).
-                     eprintln!("FATAL: Database initialization failed: {}", e);
+                     error!("Database initialization failed: {}", e);
                      dialog::blocking::message(
                          app_handle.get_window("main").as_ref(),
                          "Fatal Database Error",
                          &format!("DB init failed for {}: {}", ACTIVE_DB_FILENAME, e)
                      );
-                     std::process::exit(1);
+                     exit_process(1);
                  }
             };
-            println!("Database connection established for {}.", ACTIVE_DB_FILENAME);
+            info!("Database connection established for {}.", ACTIVE_DB_FILENAME);
 
             // --- 4. Manage State & Final Checks ---
             // Make the database connection available to Tauri commands via managed state.
              app.manage(DbState(Arc::new(Mutex::new(conn))));
+             app.manage(StagedChangesState::default());
 
              // --- *** ADD MIGRATION CHECK *** ---
-            println!("--- Running Post-Init Checks/Migrations ---");
+            info!("--- Running Post-Init Checks/Migrations ---");
             let db_state_for_migration: State<DbState> = app.state(); // Get the managed state again
             let app_handle_for_migration = app.handle(); // Clone handle for migration logic
             match run_traveler_migration_logic(&db_state_for_migration, &app_handle_for_migration) {
-                 Ok(msg) => println!("[Setup Migration Check] {}", msg), // Log success/skip message
+                 Ok(msg) => info!("[Setup Migration Check] {}", msg), // Log success/skip message
                  Err(e) => {
                      // Log the error, but don't necessarily crash the app unless it's critical
-                     eprintln!("[Setup Migration Check] WARNING: Traveler migration check/run failed: {}", e);
+                     warn!("[Setup Migration Check] Traveler migration check/run failed: {}", e);
                      // Optionally show a non-fatal dialog to the user?
                      // dialog::blocking::message(
                      //    app_handle.get_window("main").as_ref(),
@@ -4761,48 +9958,575 @@ fn main() {
                      // );
                  }
             }
-            println!("--- Finished Post-Init Checks/Migrations ---");
+            info!("--- Finished Post-Init Checks/Migrations ---");
             // --- *** END MIGRATION CHECK *** ---
 
              // Perform a final check/log for a key setting (like mods folder) from the *active* DB.
              let db_state: State<DbState> = app.state(); // Get the managed state.
-             match get_setting_value(&db_state.0.lock().expect("DB lock poisoned during setup check"), SETTINGS_KEY_MODS_FOLDER) { // Lock mutex to access connection.
-                 Ok(Some(path)) => println!("Mods folder configured in active DB to: {}", path),
-                 _ => println!("WARN: Mods folder path is not configured yet in active DB."),
+             let (mods_folder_configured, auto_scan) = {
+                 let conn = lock_db(&db_state); // Lock mutex to access connection.
+                 let configured = match get_setting_value(&conn, SETTINGS_KEY_MODS_FOLDER) {
+                     Ok(Some(path)) => { info!("Mods folder configured in active DB to: {}", path); true }
+                     _ => { warn!("Mods folder path is not configured yet in active DB."); false }
+                 };
+                 let auto_scan = matches!(get_setting_value(&conn, SETTINGS_KEY_AUTO_SCAN_ON_STARTUP), Ok(Some(v)) if v == "true");
+                 (configured, auto_scan)
+             };
+             register_saved_hotkeys(&app.handle());
+
+             // --- 5. Optional background scan ---
+             // Spawned so the window shows immediately; progress goes out on the usual scan:// events.
+             if auto_scan && mods_folder_configured {
+                 info!("[Setup] Auto-scan on startup is enabled, starting background scan.");
+                 let scan_handle = app.handle();
+                 async_runtime::spawn(async move {
+                     let db_state: State<DbState> = scan_handle.state();
+                     match scan_mods_directory(db_state, scan_handle.clone()).await {
+                         Ok(()) => info!("[Setup] Startup scan finished."),
+                         Err(e) => warn!("[Setup] Startup scan failed: {}", e),
+                     }
+                 });
+             } else if auto_scan {
+                 info!("[Setup] Auto-scan on startup skipped: no mods folder configured.");
              }
-             println!("--- Application Setup Complete ---");
+             // Without a full scan, still pick up mods toggled outside the app since the last session
+             if mods_folder_configured && !auto_scan {
+                 spawn_background_reconcile(&app.handle());
+             }
+             info!("--- Application Setup Complete ---");
             Ok(()) // Indicate successful setup
         })
         .invoke_handler(generate_handler![
             // List ALL exposed Tauri commands here:
             // Settings
-            get_setting, set_setting, select_directory, select_file, launch_executable,
+            get_setting, set_setting, get_ui_prefs, set_ui_prefs, preview_name_cleanup, get_deduction_rules, add_deduction_rule, delete_deduction_rule, test_deduction_rules, set_mods_folder, is_first_run, guess_mods_folder, select_directory, select_file, launch_executable,
+            get_log_path,
             launch_executable_elevated,
             // Core
-            get_categories, get_category_entities, get_entities_by_category,
+            get_categories, get_category_entities, get_entities_by_category, get_all_entities,
+            get_entity_default_tag, set_entity_default_tag, create_entity, set_entity_order, set_asset_order,
             get_entity_details, get_assets_for_entity, toggle_asset_enabled,
-            get_asset_image_path, run_traveler_migration,
-            open_mods_folder,
+            get_asset_image_path, get_asset_image_info, list_asset_images, set_active_preview, run_traveler_migration, verify_disk_state, reconcile_state, normalize_disabled_prefixes, ensure_other_entities, check_integrity,
+            reload_definitions,
+            open_mods_folder, open_app_data_folder, export_database_json, import_database_json, optimize_database,
+            backup_mods_folder, restore_mods_backup, cancel_operation,
             // Scan & Count
-            scan_mods_directory, get_total_asset_count,
+            scan_mods_directory, preview_scan, find_orphaned_mods, apply_scan_decisions, scan_entity, debug_scan_report, get_total_asset_count,
             get_entities_by_category_with_counts,
             // Edit, Import, Delete (Assets)
-            update_asset_info, delete_asset, read_binary_file,
-            select_archive_file, analyze_archive,
-            import_archive,
-            read_archive_file_content,
+            update_asset_info, delete_asset, delete_assets, read_binary_file,
+            merge_entities, merge_assets, rededuce_other_assets, rename_asset_folder,
+            select_archive_file, verify_archive, analyze_archive,
+            import_archive, import_from_url, discard_downloaded_archive, fetch_gamebanana_metadata,
+            check_for_updates,
+            read_archive_file_content, read_archive_file_data_url,
             // Presets
-            create_preset, get_presets, get_favorite_presets, apply_preset,
+            create_preset, get_presets, get_favorite_presets, apply_preset, stage_enable, stage_disable, clear_staged, get_staged_state, commit_staged,
             toggle_preset_favorite, delete_preset, overwrite_preset,
             add_asset_to_presets,
             // Dashboard & Version
-            get_dashboard_stats, get_app_version,
+            get_dashboard_stats, get_app_version, get_app_info,
             // Keybinds
-            get_ini_keybinds, open_asset_folder,
+            get_ini_keybinds, get_asset_variants, get_ini_sections, toggle_ini_section, get_asset_path, open_asset_folder,
+            get_asset_hotkeys, set_asset_hotkey,
             // Multi-Game Commands
-            get_available_games, get_active_game, switch_game,
+            get_available_games, get_game_profiles, get_bundled_definition_sets, get_active_game, switch_game,
             exit_app
         ])
-        .run(context) // Runs the Tauri application loop.
-        .expect("error while running tauri application"); // Panic if the app fails to run.
-}
\ No newline at end of file
+        .on_window_event(|event| {
+            if let WindowEvent::Focused(true) = event.event() {
+                spawn_background_reconcile(&event.window().app_handle());
+            }
+        })
+        .build(context)
+        .expect("error while building tauri application") // Panic if the app fails to build.
+        .run(|app_handle, event| { // Runs the Tauri application loop.
+            if let RunEvent::Exit = event {
+                unregister_all_hotkeys(app_handle);
+                flush_logs();
+            }
+        });
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_zip(dir: &Path, name: &str, entries: &[(&str, &[u8])]) -> PathBuf {
+        let path = dir.join(name);
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        for (entry_name, contents) in entries {
+            writer.start_file(*entry_name, zip::write::FileOptions::default()).unwrap();
+            writer.write_all(contents).unwrap();
+        }
+        writer.finish().unwrap();
+        path
+    }
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        File::create(path).unwrap();
+    }
+
+    #[test]
+    fn reserved_windows_names_are_detected() {
+        for name in ["CON", "con", "nul.txt", "COM1", "LPT9", "Aux.tar.gz", "PRN ", "NUL."] {
+            assert!(is_windows_reserved_name(name), "{:?}", name);
+        }
+        for name in ["COM10", "LPT0", "console", "nullable.txt", "Con-Mod", ""] {
+            assert!(!is_windows_reserved_name(name), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn folder_names_and_slugs_avoid_reserved_names() {
+        assert_eq!(sanitize_folder_name("COM1"), "COM1_");
+        assert_eq!(sanitize_folder_name("Mod."), "Mod_");
+        assert_eq!(sanitize_folder_name("Lumière Skin"), "Lumière_Skin");
+
+        assert_eq!(slugify("CON"), "con_");
+        assert_eq!(slugify("Lpt9"), "lpt9_");
+        assert_eq!(slugify("Hu Tao "), "hu-tao");
+        assert_eq!(slugify("Mod."), "mod");
+        assert_eq!(slugify("rover_female"), "rover_female");
+        // Accents are folded; characters with no ASCII form act as separators
+        assert_eq!(slugify("Lumière"), "lumiere");
+        assert_eq!(slugify("Kamisato Ａyaka"), "kamisato-ayaka");
+        assert_eq!(slugify("Mod ★ Pack"), "mod-pack");
+    }
+
+    #[test]
+    fn path_components_drop_trailing_dots_and_reserved_names() {
+        assert_eq!(sanitize_path_component("CON"), "CON_");
+        assert_eq!(sanitize_path_component("nul.txt"), "nul.txt_");
+        assert_eq!(sanitize_path_component("LPT9 "), "LPT9_");
+        assert_eq!(sanitize_path_component("readme."), "readme");
+        assert_eq!(sanitize_path_component("Textures "), "Textures");
+        assert_eq!(sanitize_path_component(". ."), "_");
+        assert_eq!(sanitize_path_component("Lumière.ini"), "Lumière.ini");
+    }
+
+    #[test]
+    fn resolve_asset_on_disk_finds_nested_mods() {
+        let dir = tempfile::tempdir().unwrap();
+        let mods = dir.path();
+        fs::create_dir_all(mods.join("characters/ayaka/Pack/DISABLED_ModA")).unwrap();
+        fs::create_dir_all(mods.join("characters/ayaka/Pack/ModB")).unwrap();
+
+        let (path, enabled) = resolve_asset_on_disk(mods, Path::new("characters/ayaka/Pack/ModA")).unwrap();
+        assert_eq!((path, enabled), (mods.join("characters/ayaka/Pack/DISABLED_ModA"), false));
+        let (path, enabled) = resolve_asset_on_disk(mods, Path::new("characters/ayaka/Pack/ModB")).unwrap();
+        assert_eq!((path, enabled), (mods.join("characters/ayaka/Pack/ModB"), true));
+        // Only the leaf carries the prefix; a disabled parent is not followed here
+        assert!(resolve_asset_on_disk(mods, Path::new("characters/DISABLED_ayaka/Pack/ModB")).is_none());
+        assert!(resolve_asset_on_disk(mods, Path::new("characters/ayaka/Pack/ModC")).is_none());
+    }
+
+    #[test]
+    fn resolve_asset_on_disk_finds_root_level_mods() {
+        let dir = tempfile::tempdir().unwrap();
+        let mods = dir.path();
+        fs::create_dir_all(mods.join("ModA")).unwrap();
+        fs::create_dir_all(mods.join("DISABLED_ModB")).unwrap();
+        File::create(mods.join("ModC")).unwrap(); // A file is not a mod folder
+
+        assert_eq!(resolve_asset_on_disk(mods, Path::new("ModA")), Some((mods.join("ModA"), true)));
+        assert_eq!(resolve_asset_on_disk(mods, Path::new("ModB")), Some((mods.join("DISABLED_ModB"), false)));
+        assert_eq!(resolve_asset_on_disk(mods, Path::new("ModC")), None);
+    }
+
+    #[test]
+    fn mods_folder_must_exist_unless_creation_is_confirmed() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("Mods");
+        let missing_str = missing.to_string_lossy().to_string();
+
+        assert!(matches!(validate_mods_folder(&missing_str, false), Err(AppError::NotFound(_))));
+        assert!(!missing.exists());
+        assert_eq!(validate_mods_folder(&format!("  {}  ", missing_str), true).unwrap(), missing);
+        assert!(missing.is_dir());
+        assert!(!missing.join(".gmm_write_test").exists());
+        assert!(matches!(validate_mods_folder("   ", true), Err(AppError::Config(_))));
+    }
+
+    #[test]
+    fn mods_folder_rejects_file_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("Mods.txt");
+        File::create(&file_path).unwrap();
+        let file_str = file_path.to_string_lossy().to_string();
+
+        assert!(matches!(validate_mods_folder(&file_str, false), Err(AppError::Config(_))));
+        assert!(matches!(validate_mods_folder(&file_str, true), Err(AppError::Config(_))));
+        assert!(file_path.is_file());
+    }
+
+    #[test]
+    fn lock_connection_recovers_poisoned_mutex() {
+        let mutex = Arc::new(Mutex::new(Connection::open_in_memory().unwrap()));
+        let held = Arc::clone(&mutex);
+        let panicked = std::thread::spawn(move || {
+            let _guard = held.lock().unwrap();
+            panic!("command panicked while holding the DB lock");
+        }).join();
+        assert!(panicked.is_err());
+        assert!(mutex.is_poisoned());
+
+        let conn = lock_connection(&mutex);
+        let one: i64 = conn.query_row("SELECT 1", [], |row| row.get(0)).unwrap();
+        assert_eq!(one, 1);
+        drop(conn);
+        assert!(!mutex.is_poisoned());
+    }
+
+    #[test]
+    fn busy_timeout_waits_for_concurrent_writer() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("app_data.sqlite");
+        let writer = open_db_connection(&db_path).unwrap();
+        let timeout_ms: i64 = writer.query_row("PRAGMA busy_timeout", [], |row| row.get(0)).unwrap();
+        assert_eq!(timeout_ms, DB_BUSY_TIMEOUT.as_millis() as i64);
+        writer.execute("CREATE TABLE t (v INTEGER)", []).unwrap();
+        writer.execute_batch("BEGIN IMMEDIATE; INSERT INTO t (v) VALUES (1);").unwrap();
+
+        let other_path = db_path.clone();
+        let waiter = std::thread::spawn(move || {
+            let conn = open_db_connection(&other_path).unwrap();
+            let started = std::time::Instant::now();
+            conn.execute("INSERT INTO t (v) VALUES (2)", []).map(|_| started.elapsed())
+        });
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        writer.execute_batch("COMMIT").unwrap();
+
+        let waited = waiter.join().unwrap().expect("second writer should wait out the lock, not fail with SQLITE_BUSY");
+        assert!(waited >= std::time::Duration::from_millis(200));
+        let count: i64 = writer.query_row("SELECT COUNT(*) FROM t", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn grouping_folder_with_stray_ini_is_not_a_mod_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let group = dir.path().join("Character");
+        touch(&group.join("stray.ini"));
+        touch(&group.join("ModA").join("mod.ini"));
+        touch(&group.join("ModB").join("mod.ini"));
+
+        assert!(!is_mod_root_folder(&group));
+        assert!(is_mod_root_folder(&group.join("ModA")));
+    }
+
+    #[test]
+    fn ini_next_to_resources_is_a_mod_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let mod_dir = dir.path().join("ModA");
+        touch(&mod_dir.join("mod.ini"));
+        touch(&mod_dir.join("Body.dds"));
+        touch(&mod_dir.join("Position.buf"));
+        // A toggle variant with its own INI still belongs to the mod
+        touch(&mod_dir.join("Variant").join("variant.ini"));
+
+        assert!(is_mod_root_folder(&mod_dir));
+    }
+
+    #[test]
+    fn plain_mod_folder_is_a_mod_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let mod_dir = dir.path().join("ModA");
+        touch(&mod_dir.join("mod.ini"));
+        touch(&mod_dir.join("readme.txt"));
+        assert!(is_mod_root_folder(&mod_dir));
+
+        let no_ini = dir.path().join("Textures");
+        touch(&no_ini.join("Body.dds"));
+        assert!(!is_mod_root_folder(&no_ini));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn zip_extraction_keeps_executable_mode() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("scripts.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive_path).unwrap());
+        writer.start_file("ModA/install.sh", zip::write::FileOptions::default().unix_permissions(0o755)).unwrap();
+        writer.write_all(b"#!/bin/sh\n").unwrap();
+        writer.start_file("ModA/mod.ini", zip::write::FileOptions::default().unix_permissions(0o644)).unwrap();
+        writer.finish().unwrap();
+
+        let dest = dir.path().join("out");
+        assert_eq!(extract_zip_archive(&archive_path, &dest, Path::new("ModA"), |_, _, _| {}).unwrap(), 2);
+        let mode = |name: &str| fs::metadata(dest.join(name)).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode("install.sh"), 0o755);
+        assert_eq!(mode("mod.ini"), 0o644);
+    }
+
+    #[test]
+    fn relocating_disabled_mod_applies_enabled_override() {
+        let cases = [(None, "DISABLED_ModA", false), (Some(true), "ModA", true), (Some(false), "DISABLED_ModA", false)];
+        for (enabled_override, expected_folder, expected_enabled) in cases {
+            let dir = tempfile::tempdir().unwrap();
+            let mods = dir.path();
+            touch(&mods.join("characters/ayaka/DISABLED_ModA/mod.ini"));
+
+            let relocation = relocate_asset_folder(mods, "characters/ayaka/ModA", "characters", "raiden", enabled_override).unwrap();
+            assert_eq!(relocation.relative_path, "characters/raiden/ModA");
+            assert_eq!(relocation.enabled, expected_enabled, "override {:?}", enabled_override);
+            relocation.commit();
+
+            let raiden = mods.join("characters/raiden");
+            let folders: Vec<String> = fs::read_dir(&raiden).unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+                .collect();
+            assert_eq!(folders, vec![expected_folder.to_string()], "override {:?}", enabled_override);
+            assert!(raiden.join(expected_folder).join("mod.ini").is_file());
+            assert!(!mods.join("characters/ayaka/DISABLED_ModA").exists());
+        }
+    }
+
+    #[test]
+    fn uncommitted_relocation_moves_folder_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mods = dir.path();
+        touch(&mods.join("characters/ayaka/ModA/mod.ini"));
+
+        let relocation = relocate_asset_folder(mods, "characters/ayaka/ModA", "characters", "raiden", None).unwrap();
+        assert!(mods.join("characters/raiden/ModA/mod.ini").is_file());
+        assert!(!mods.join("characters/ayaka/ModA").exists());
+        drop(relocation);
+
+        assert!(mods.join("characters/ayaka/ModA/mod.ini").is_file());
+        assert!(!mods.join("characters/raiden/ModA").exists());
+    }
+
+    #[test]
+    fn committed_relocation_stays_at_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let mods = dir.path();
+        touch(&mods.join("characters/ayaka/ModA/mod.ini"));
+
+        let relocation = relocate_asset_folder(mods, "characters/ayaka/ModA", "characters", "raiden", None).unwrap();
+        assert_eq!(relocation.relative_path, "characters/raiden/ModA");
+        relocation.commit();
+
+        assert!(!mods.join("characters/ayaka/ModA").exists());
+        assert!(mods.join("characters/raiden/ModA/mod.ini").is_file());
+    }
+
+    #[test]
+    fn archive_limits_refuse_too_many_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let names: Vec<String> = (0..5).map(|i| format!("mod/file{}.txt", i)).collect();
+        let entries: Vec<(&str, &[u8])> = names.iter().map(|name| (name.as_str(), &b"x"[..])).collect();
+        let path = write_zip(dir.path(), "many.zip", &entries);
+
+        let limits = ArchiveLimits { max_entries: 4, max_uncompressed_bytes: u64::MAX };
+        assert!(matches!(check_archive_limits(&path, &limits), Err(AppError::ModOperation(_))));
+        let limits = ArchiveLimits { max_entries: 5, ..limits };
+        assert!(check_archive_limits(&path, &limits).is_ok());
+    }
+
+    #[test]
+    fn archive_limits_refuse_oversized_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        // 8 MB of zeros deflates to a few KB, so only the declared size gives it away
+        let zeros = vec![0u8; 8 * 1024 * 1024];
+        let path = write_zip(dir.path(), "bomb.zip", &[("mod/zeros.bin", &zeros)]);
+        assert!(fs::metadata(&path).unwrap().len() < 64 * 1024);
+
+        let limits = ArchiveLimits { max_entries: 10, max_uncompressed_bytes: 1024 * 1024 };
+        assert!(matches!(check_archive_limits(&path, &limits), Err(AppError::ModOperation(_))));
+        let limits = ArchiveLimits { max_uncompressed_bytes: 16 * 1024 * 1024, ..limits };
+        assert!(check_archive_limits(&path, &limits).is_ok());
+    }
+
+    // Writes the name straight into the header: tar::Builder would normalize "./" away and refuse "..".
+    fn append_tar_entry(builder: &mut tar::Builder<impl Write>, name: &str, entry_type: tar::EntryType, contents: &[u8]) {
+        let mut header = tar::Header::new_gnu();
+        header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_entry_type(entry_type);
+        header.set_mode(0o644);
+        header.set_size(contents.len() as u64);
+        if entry_type == tar::EntryType::Symlink { header.set_link_name("/etc/passwd").unwrap(); }
+        header.set_cksum();
+        builder.append(&header, contents).unwrap();
+    }
+
+    #[test]
+    fn tar_gz_extraction_keeps_safe_entries_under_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("pack.tar.gz");
+        let gz = flate2::write::GzEncoder::new(File::create(&archive_path).unwrap(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(gz);
+        append_tar_entry(&mut builder, "./", tar::EntryType::Directory, b"");
+        append_tar_entry(&mut builder, "./Pack/readme.txt", tar::EntryType::Regular, b"readme");
+        append_tar_entry(&mut builder, "./Pack/ModA/mod.ini", tar::EntryType::Regular, b"[TextureOverride]");
+        append_tar_entry(&mut builder, "./Pack/ModA/Textures/Body.dds", tar::EntryType::Regular, b"dds");
+        append_tar_entry(&mut builder, "./Pack/ModA/link", tar::EntryType::Symlink, b"");
+        append_tar_entry(&mut builder, "../escape.txt", tar::EntryType::Regular, b"escape");
+        append_tar_entry(&mut builder, "./Pack/ModA/../../escape.txt", tar::EntryType::Regular, b"escape");
+        builder.into_inner().unwrap().finish().unwrap();
+        assert_eq!(archive_type(&archive_path).as_deref(), Some("tar.gz"));
+
+        let dest = dir.path().join("out/ModA");
+        let mut seen = Vec::new();
+        let count = extract_tar_archive(&archive_path, true, &dest, Path::new("Pack/ModA"), |_, path| seen.push(path.to_string())).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(seen, ["Pack/readme.txt", "Pack/ModA/mod.ini", "Pack/ModA/Textures/Body.dds"]);
+        assert_eq!(fs::read(dest.join("mod.ini")).unwrap(), b"[TextureOverride]");
+        assert!(dest.join("Textures/Body.dds").is_file());
+        assert!(!dest.join("link").exists());
+        assert!(!dest.join("readme.txt").exists());
+        assert!(!dir.path().join("escape.txt").exists() && !dir.path().join("out/escape.txt").exists());
+
+        let all = dir.path().join("all");
+        assert_eq!(extract_tar_archive(&archive_path, true, &all, Path::new(""), |_, _| {}).unwrap(), 3);
+        assert!(all.join("Pack/ModA/mod.ini").is_file());
+        assert!(all.join("Pack/readme.txt").is_file());
+        assert_eq!(fs::read_dir(&all).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn temporary_download_paths_must_stay_in_a_download_folder() {
+        assert!(is_temporary_download(&downloads_dir().join("123").join("mod.zip")));
+        assert!(!is_temporary_download(&downloads_dir().join("mod.zip")));
+        assert!(!is_temporary_download(&downloads_dir().join("123").join("sub").join("mod.zip")));
+        assert!(!is_temporary_download(&std::env::temp_dir().join("mod.zip")));
+
+        // Looks like it is under the downloads folder, but resolves to a folder elsewhere in temp
+        let outside = tempfile::tempdir().unwrap();
+        let victim = outside.path().join("Mods").join("mod.zip");
+        touch(&victim);
+        let sneaky = downloads_dir().join("123").join("..").join("..")
+            .join(outside.path().file_name().unwrap()).join("Mods").join("mod.zip");
+        assert!(sneaky.starts_with(downloads_dir()));
+        assert!(!is_temporary_download(&sneaky));
+        remove_temporary_download(&sneaky);
+        assert!(victim.is_file());
+    }
+
+    #[test]
+    fn copy_zip_entry_rejects_under_declared_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let contents = b"this entry is longer than its header says";
+        let path = dir.path().join("forged.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        writer.start_file("mod/forged.ini", options).unwrap();
+        writer.write_all(contents).unwrap();
+        writer.finish().unwrap();
+
+        // Forge the uncompressed size in the central directory header, which is what the reader trusts
+        let mut bytes = fs::read(&path).unwrap();
+        let header = bytes.windows(4).position(|w| w == b"PK\x01\x02").unwrap();
+        bytes[header + 24..header + 28].copy_from_slice(&4u32.to_le_bytes());
+        fs::write(&path, &bytes).unwrap();
+
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut entry = archive.by_index(0).unwrap();
+        assert_eq!(entry.size(), 4);
+        let err = copy_zip_entry(&mut entry, &mut io::sink()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn enabling_folder_disabled_mod_keeps_optional_disabled_inis() {
+        let dir = tempfile::tempdir().unwrap();
+        let mods = dir.path();
+        touch(&mods.join("characters/ayaka/DISABLED_ModA/mod.ini"));
+        touch(&mods.join("characters/ayaka/DISABLED_ModA/DISABLED_alt.ini"));
+
+        let path = set_asset_state_on_disk(mods, Path::new("characters/ayaka/ModA"), true, false).unwrap();
+        assert_eq!(path, mods.join("characters/ayaka/ModA"));
+        assert!(path.join("mod.ini").is_file());
+        assert!(path.join("DISABLED_alt.ini").is_file());
+
+        // Off and on again with the folder strategy still leaves the alternate alone
+        set_asset_state_on_disk(mods, Path::new("characters/ayaka/ModA"), false, false).unwrap();
+        assert!(mods.join("characters/ayaka/DISABLED_ModA/DISABLED_alt.ini").is_file());
+        let path = set_asset_state_on_disk(mods, Path::new("characters/ayaka/ModA"), true, false).unwrap();
+        assert!(path.join("DISABLED_alt.ini").is_file());
+        assert!(!path.join("alt.ini").exists());
+    }
+
+    #[test]
+    fn enabling_ini_disabled_mod_restores_its_inis() {
+        let dir = tempfile::tempdir().unwrap();
+        let mods = dir.path();
+        touch(&mods.join("ModA/mod.ini"));
+        touch(&mods.join("ModA/extra.ini"));
+
+        let path = set_asset_state_on_disk(mods, Path::new("ModA"), false, true).unwrap();
+        assert_eq!(path, mods.join("ModA"));
+        assert!(path.join("DISABLED_mod.ini").is_file() && path.join("DISABLED_extra.ini").is_file());
+
+        set_asset_state_on_disk(mods, Path::new("ModA"), true, true).unwrap();
+        assert!(path.join("mod.ini").is_file() && path.join("extra.ini").is_file());
+        assert!(!path.join("DISABLED_mod.ini").exists());
+    }
+
+    #[test]
+    fn merged_files_move_back_unless_committed() {
+        let dir = tempfile::tempdir().unwrap();
+        let (from, to) = (dir.path().join("ModB/textures/a.dds"), dir.path().join("ModA/ModB/textures/a.dds"));
+        touch(&from);
+        fs::create_dir_all(to.parent().unwrap()).unwrap();
+        fs::rename(&from, &to).unwrap();
+        let created_dirs = vec![dir.path().join("ModA"), dir.path().join("ModA/ModB"), dir.path().join("ModA/ModB/textures")];
+        drop(MergedFiles { moved: vec![(from.clone(), to.clone())], created_dirs, committed: false });
+        assert!(from.is_file());
+        assert!(!dir.path().join("ModA").exists());
+
+        fs::create_dir_all(to.parent().unwrap()).unwrap();
+        fs::rename(&from, &to).unwrap();
+        MergedFiles { moved: vec![(from.clone(), to.clone())], created_dirs: Vec::new(), committed: false }.commit();
+        assert!(to.is_file() && !from.exists());
+    }
+
+    const SECTION_TEST_INI: &str = "[Constants]\nglobal $swap = 0\n\n[TextureOverrideBody]\nhash = 0123abcd\nib = ResourceBodyIB\n\n[KeySwap]\nkey = F8\n";
+
+    fn toggle_section_round_trip(ini: &str, section: &str) -> String {
+        let disabled = set_ini_section_enabled(ini, section, false).unwrap().unwrap();
+        assert_ne!(disabled, ini);
+        assert_eq!(set_ini_section_enabled(&disabled, section, true).unwrap().as_deref(), Some(ini));
+        disabled
+    }
+
+    #[test]
+    fn ini_section_disable_then_enable_restores_identical_bytes() {
+        let disabled = toggle_section_round_trip(SECTION_TEST_INI, "TextureOverrideBody");
+        let lines: Vec<&str> = disabled.lines().collect();
+        assert_eq!(lines[3], format!("{}[TextureOverrideBody]", INI_SECTION_DISABLED_MARKER));
+        assert!(lines[4].starts_with(INI_SECTION_DISABLED_MARKER));
+        // Sections before and after are left alone
+        assert_eq!(lines[0], "[Constants]");
+        assert_eq!(lines[7], "[KeySwap]");
+    }
+
+    #[test]
+    fn ini_section_toggle_keeps_crlf_line_endings() {
+        let ini = SECTION_TEST_INI.replace('\n', "\r\n");
+        let disabled = toggle_section_round_trip(&ini, "TextureOverrideBody");
+        assert_eq!(disabled.matches("\r\n").count(), disabled.matches('\n').count());
+    }
+
+    #[test]
+    fn ini_section_toggle_keeps_bom_first() {
+        let ini = format!("\u{feff}{}", SECTION_TEST_INI);
+        let disabled = toggle_section_round_trip(&ini, "Constants");
+        assert!(disabled.starts_with(&format!("\u{feff}{}[Constants]", INI_SECTION_DISABLED_MARKER)));
+    }
+
+    #[test]
+    fn ini_section_toggle_handles_last_section_without_trailing_newline() {
+        toggle_section_round_trip(SECTION_TEST_INI, "KeySwap");
+        toggle_section_round_trip(SECTION_TEST_INI.trim_end(), "KeySwap");
+    }
+
+    #[test]
+    fn ini_section_already_in_requested_state_is_unchanged() {
+        let disabled = set_ini_section_enabled(SECTION_TEST_INI, "keyswap", false).unwrap().unwrap();
+        assert_eq!(set_ini_section_enabled(&disabled, "KeySwap", false).unwrap(), None);
+        assert_eq!(set_ini_section_enabled(SECTION_TEST_INI, "KeySwap", true).unwrap(), None);
+        assert!(matches!(set_ini_section_enabled(SECTION_TEST_INI, "Missing", false), Err(AppError::NotFound(_))));
+    }
+}
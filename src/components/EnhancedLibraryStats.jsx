@@ -29,6 +29,13 @@ const AnimatedCounter = ({ value, duration = 1000, prefix = '', suffix = '' }) =
 };
 
 // Main component for enhanced stats display
+const formatBytes = (bytes) => {
+  if (!bytes) return '0 B';
+  const units = ['B', 'KB', 'MB', 'GB', 'TB'];
+  const exponent = Math.min(Math.floor(Math.log(bytes) / Math.log(1024)), units.length - 1);
+  return `${(bytes / Math.pow(1024, exponent)).toFixed(exponent === 0 ? 0 : 1)} ${units[exponent]}`;
+};
+
 const EnhancedLibraryStats = ({ stats, loading, error }) => {
   // For category chart data
   const getCategoryData = () => {
@@ -86,6 +93,9 @@ const EnhancedLibraryStats = ({ stats, loading, error }) => {
           <div className="stat-info">
             <span className="stat-label">Total Mods</span>
             <span className="stat-value"><AnimatedCounter value={stats.total_mods} /></span>
+            {stats.disk_usage_bytes > 0 && (
+              <span className="stat-label">{formatBytes(stats.disk_usage_bytes)} across {stats.total_entities} entities</span>
+            )}
           </div>
         </div>
         
@@ -116,6 +126,16 @@ const EnhancedLibraryStats = ({ stats, loading, error }) => {
             </div>
           </div>
         )}
+
+        {stats.missing_mods > 0 && (
+          <div className="stat-card uncategorized" title="In the library but not found in the mods folder">
+            <i className="fas fa-question-circle stat-icon"></i>
+            <div className="stat-info">
+              <span className="stat-label">Missing on Disk</span>
+              <span className="stat-value"><AnimatedCounter value={stats.missing_mods} /></span>
+            </div>
+          </div>
+        )}
       </div>
       
       {/* Charts Section */}
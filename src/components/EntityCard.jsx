@@ -66,7 +66,7 @@ const DEFAULT_PLACEHOLDER_IMAGE = '/images/unknown.jpg';
 
 function EntityCard({ entity }) {
     // Destructure props including counts
-    const { slug, name, details: detailsJson, base_image, total_mods, enabled_mods } = entity;
+    const { slug, name, details: detailsJson, base_image, total_mods, enabled_mods, disabled_mods } = entity;
 
     const details = parseDetails(detailsJson);
     
@@ -115,8 +115,8 @@ function EntityCard({ entity }) {
                 )}
                   {/* Enabled Mod Count Badge */}
                 {enabled_mods > 0 && (
-                    <div className="card-badge enabled-badge" title={`${enabled_mods} mods enabled`}>
-                        {enabled_mods} <i className="fas fa-check-circle fa-fw" style={{ marginLeft: '3px' }}></i>
+                    <div className="card-badge enabled-badge" title={`${enabled_mods} of ${total_mods} mods enabled${disabled_mods ? `, ${disabled_mods} disabled` : ''}`}>
+                        {enabled_mods}/{total_mods} <i className="fas fa-check-circle fa-fw" style={{ marginLeft: '3px' }}></i>
                     </div>
                 )}
             </div>
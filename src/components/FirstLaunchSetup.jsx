@@ -25,6 +25,7 @@ function FirstLaunchSetup() {
     const [gameSwitchError, setGameSwitchError] = useState('');
     const [isSaving, setIsSaving] = useState(false);
     const [saveError, setSaveError] = useState('');
+    const [suggestedFolders, setSuggestedFolders] = useState([]);
 
     // Fetch available games and the *actual* current game on mount
     useEffect(() => {
@@ -49,6 +50,15 @@ function FirstLaunchSetup() {
         return () => { isMounted = false; };
     }, []);
 
+    // Probe common install locations for a Mods folder (suggestions only, never auto-saved)
+    useEffect(() => {
+        let isMounted = true;
+        invoke('guess_mods_folder')
+            .then(candidates => { if (isMounted) setSuggestedFolders(candidates || []); })
+            .catch(err => console.warn("Could not guess mods folder:", err));
+        return () => { isMounted = false; };
+    }, [currentGameForSetup]);
+
     // Update local path state if context values change after initial load
     useEffect(() => {
         setSelectedModsFolder(initialModsFolder || '');
@@ -89,7 +99,7 @@ function FirstLaunchSetup() {
             }
         } catch (err) {
             console.error("Error selecting directory:", err);
-            setSaveError(`Failed to select folder: ${err}`);
+            setSaveError(`Failed to select folder: ${err?.message || err}`);
         }
     };
 
@@ -102,7 +112,7 @@ function FirstLaunchSetup() {
             }
         } catch (err) {
             console.error("Error selecting file:", err);
-             setSaveError(`Failed to select file: ${err}`);
+             setSaveError(`Failed to select file: ${err?.message || err}`);
         }
     };
 
@@ -192,6 +202,25 @@ function FirstLaunchSetup() {
                     </button>
                 </div>
 
+                {/* Suggested Mods Folders */}
+                {suggestedFolders.length > 0 && (
+                    <div style={styles.suggestionBox}>
+                        <span style={styles.infoText}>Detected possible Mods folders:</span>
+                        {suggestedFolders.map(folder => (
+                            <button
+                                key={folder}
+                                onClick={() => { setSelectedModsFolder(folder); setSaveError(''); }}
+                                disabled={isActionDisabled}
+                                className="btn btn-outline"
+                                style={styles.suggestionButton}
+                                title={folder}
+                            >
+                                <i className={`fas ${folder === selectedModsFolder ? 'fa-circle-check' : 'fa-lightbulb'} fa-fw`}></i> {folder}
+                            </button>
+                        ))}
+                    </div>
+                )}
+
                 {/* Quick Launch Selection */}
                 <div style={styles.settingItem}>
                     <label style={styles.label}>Quick Launch:</label>
@@ -285,6 +314,19 @@ const styles = {
         textAlign: 'center',
         marginTop: '10px',
     },
+    suggestionBox: {
+        display: 'flex',
+        flexDirection: 'column',
+        gap: '6px',
+        marginBottom: '20px',
+    },
+    suggestionButton: {
+        width: '100%',
+        overflow: 'hidden',
+        textOverflow: 'ellipsis',
+        whiteSpace: 'nowrap',
+        textAlign: 'left',
+    },
     description: {
         fontSize: '15px',
         color: 'rgba(255, 255, 255, 0.8)',
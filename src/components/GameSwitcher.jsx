@@ -4,6 +4,7 @@ import { ask } from '@tauri-apps/api/dialog';
 
 function GameSwitcher({ 
   availableGames, 
+  profiles = [], // Optional get_game_profiles result, for per-game tooltips
   activeGame, 
   onGameSwitch, 
   isLoading, 
@@ -33,6 +34,17 @@ function GameSwitcher({
     onGameSwitch(gameSlug);
   };
   
+  const getTooltip = (gameSlug) => {
+    const profile = profiles.find(p => p.slug === gameSlug);
+    const action = gameSlug === activeGame ? gameSlug.toUpperCase() : `Switch to ${gameSlug.toUpperCase()}`;
+    if (!profile?.has_database) return action;
+    const details = [
+      profile.mods_folder ? `Mods folder: ${profile.mods_folder}` : 'Mods folder not set',
+      profile.mod_count != null ? `${profile.mod_count} mod(s)` : null,
+    ].filter(Boolean).join('\n');
+    return `${action}\n${details}`;
+  };
+
   // Handle image loading errors
   const handleLogoError = (e) => {
     e.target.src = '/images/logos/default.png';
@@ -59,7 +71,7 @@ function GameSwitcher({
               key={gameSlug} 
               className={`game-logo-item ${gameSlug === activeGame ? 'active' : ''} ${isLoading ? 'disabled' : ''}`}
               onClick={() => handleLogoClick(gameSlug)}
-              title={`Switch to ${gameSlug.toUpperCase()}`}
+              title={getTooltip(gameSlug)}
             >
               <div className="logo-wrapper">
                 <img 
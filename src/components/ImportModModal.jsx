@@ -2,8 +2,19 @@
 import React, { useState, useEffect, useMemo, useRef, useCallback } from 'react';
 import ReactDOM from 'react-dom';
 import { invoke } from '@tauri-apps/api/tauri';
-import { open } from '@tauri-apps/api/dialog';
+import { open, ask } from '@tauri-apps/api/dialog';
+import { listen } from '@tauri-apps/api/event';
 import Select from 'react-select';
+import CreatableSelect from 'react-select/creatable';
+import { getUiPrefs, updateUiPrefs } from '../utils/uiPrefs';
+
+
+// Deductions below this (e.g. from the archive filename alone) are shown as a hint instead of pre-selected
+const AUTO_SELECT_CONFIDENCE = 0.6;
+const DEDUCTION_SOURCE_LABELS = {
+    user_rule: 'your entity rule', ini_target: 'INI target', ini_hash: 'INI hashes', internal_filename: 'file names',
+    name_token: 'mod name', archive_filename: 'archive name',
+};
 
 const reactSelectStyles = {
     control: (baseStyles, state) => ({
@@ -182,7 +193,14 @@ const styles = {
 
 const FALLBACK_MOD_IMAGE_MODAL = '/images/placeholder.jpg';
 
-function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
+const formatBytes = (bytes) => {
+    if (!bytes) return '0 B';
+    const units = ['B', 'KB', 'MB', 'GB'];
+    const exponent = Math.min(Math.floor(Math.log(bytes) / Math.log(1024)), units.length - 1);
+    return `${(bytes / Math.pow(1024, exponent)).toFixed(exponent === 0 ? 0 : 1)} ${units[exponent]}`;
+};
+
+function ImportModModal({ analysisResult, sourceUrl, onClose, onImportSuccess }) {
     // Form State
     const [modName, setModName] = useState('');
     const [description, setDescription] = useState('');
@@ -190,6 +208,9 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
     const [categoryTag, setCategoryTag] = useState('');
     const [selectedInternalRoot, setSelectedInternalRoot] = useState('');
     const [extractAllFiles, setExtractAllFiles] = useState(false);
+    const [expandedDirs, setExpandedDirs] = useState(() => new Set()); // Entry indices of open folders
+    const [deleteArchiveAfterImport, setDeleteArchiveAfterImport] = useState(false);
+    const [importDisabled, setImportDisabled] = useState(false);
     // Entity Selection State
     const [categories, setCategories] = useState([]);
     const [entities, setEntities] = useState([]);
@@ -205,11 +226,13 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
     // Preview State
     const [previewImageUrl, setPreviewImageUrl] = useState(FALLBACK_MOD_IMAGE_MODAL);
     const [selectedPreviewAbsPath, setSelectedPreviewAbsPath] = useState(null);
+    const [selectedInternalPreviewPath, setSelectedInternalPreviewPath] = useState(null); // Image inside the archive
     const previewObjectUrlRef = useRef(null);
     const [previewLoading, setPreviewLoading] = useState(false);
     const [pastedImageFile, setPastedImageFile] = useState(null);
     // Modal State
     const [isImporting, setIsImporting] = useState(false);
+    const [importProgress, setImportProgress] = useState(null); // { extracted, total, current_file }
     const [error, setError] = useState('');
 
     // Cleanup Blob URL
@@ -224,8 +247,35 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
     const categoryOptions = useMemo(() => categories.map(cat => ({ value: cat.slug, label: cat.name })), [categories]);
     const entityOptions = useMemo(() => entities.map(ent => ({ value: ent.slug, label: ent.name })), [entities]);
     const presetOptions = useMemo(() => allPresets.map(p => ({ value: p.id, label: p.name })), [allPresets]);
+    // Archive images limited to the selected root (all of them when extracting everything)
+    const previewCandidatePaths = useMemo(() => {
+        const candidates = analysisResult?.preview_candidate_paths || [];
+        if (extractAllFiles || !selectedInternalRoot) return candidates;
+        const rootPrefix = selectedInternalRoot.endsWith('/') ? selectedInternalRoot : `${selectedInternalRoot}/`;
+        return candidates.filter(path => path.startsWith(rootPrefix));
+    }, [analysisResult, extractAllFiles, selectedInternalRoot]);
+    // Folder tree from the parent_index links: child entry indices per parent ('root' for the top level),
+    // plus the folders that have a likely mod root somewhere below them
+    const entryTree = useMemo(() => {
+        const entries = analysisResult?.entries || [];
+        const children = new Map();
+        const hasRootBelow = new Set();
+        entries.forEach((entry, index) => {
+            const key = entry.parent_index ?? 'root';
+            if (!children.has(key)) children.set(key, []);
+            children.get(key).push(index);
+            if (entry.is_likely_mod_root) {
+                for (let p = entry.parent_index; p != null; p = entries[p].parent_index) hasRootBelow.add(p);
+            }
+        });
+        return { children, hasRootBelow };
+    }, [analysisResult]);
     // -------------------------
 
+    useEffect(() => {
+        getUiPrefs().then(prefs => setDeleteArchiveAfterImport(!!prefs.delete_archive_after_import));
+    }, []);
+
     // Fetch Categories AND Presets on Mount
     useEffect(() => {
         setCategoryLoading(true);
@@ -268,10 +318,21 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
         setPastedImageFile(null);
         cleanupPreviewObjectUrl();
 
-        const nameGuess = analysisResult.deduced_mod_name || analysisResult.file_path.split('/').pop().split('\\').pop().replace(/\.(zip|rar|7z)$/i, '');
+        const nameGuess = analysisResult.deduced_mod_name || analysisResult.file_path.split('/').pop().split('\\').pop().replace(/\.(zip|rar|7z|tar|tar\.gz|tgz)$/i, '');
         setModName(nameGuess);
         setAuthor(analysisResult.deduced_author || '');
 
+        // Open the folders leading to likely roots; with none, just the top level
+        const initiallyExpanded = new Set();
+        const entries = analysisResult.entries || [];
+        entries.forEach((entry, index) => {
+            if (entry.is_likely_mod_root) {
+                for (let p = entry.parent_index; p != null; p = entries[p].parent_index) initiallyExpanded.add(p);
+            }
+        });
+        if (initiallyExpanded.size === 0) entries.forEach((e, index) => { if (e.is_dir && e.parent_index == null) initiallyExpanded.add(index); });
+        setExpandedDirs(initiallyExpanded);
+
         const likelyRoot = analysisResult.entries?.find(e => e.is_likely_mod_root);
         const firstDir = analysisResult.entries?.find(e => e.is_dir);
         const rootToSelect = likelyRoot ? likelyRoot.path : (firstDir ? firstDir.path : '');
@@ -292,27 +353,15 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
              setSelectedEntityOption(null);
         }
 
+        setSelectedInternalPreviewPath(analysisResult.detected_preview_internal_path || null);
         if (analysisResult.detected_preview_internal_path) {
             setPreviewLoading(true);
-            invoke('read_archive_file_content', {
+            invoke('read_archive_file_data_url', {
                 archivePathStr: analysisResult.file_path,
                 internalFilePath: analysisResult.detected_preview_internal_path
             })
-            .then(fileData => {
-                if (!isMounted || !fileData) return;
-                 try {
-                     const extension = analysisResult.detected_preview_internal_path.split('.').pop().toLowerCase();
-                     let mimeType = 'image/png';
-                     if (['jpg', 'jpeg'].includes(extension)) mimeType = 'image/jpeg';
-                     else if (extension === 'gif') mimeType = 'image/gif';
-                     else if (extension === 'webp') mimeType = 'image/webp';
-                     const blob = new Blob([new Uint8Array(fileData)], { type: mimeType });
-                     const url = URL.createObjectURL(blob);
-                     if (isMounted) {
-                        previewObjectUrlRef.current = url;
-                        setPreviewImageUrl(url);
-                     } else { URL.revokeObjectURL(url); }
-                 } catch (e) { console.error("Error creating blob for detected preview:", e); if (isMounted) setPreviewImageUrl(FALLBACK_MOD_IMAGE_MODAL); }
+            .then(dataUrl => {
+                if (isMounted && dataUrl) setPreviewImageUrl(dataUrl);
             })
             .catch(err => { console.warn("Failed to load detected preview:", err); if (isMounted) setPreviewImageUrl(FALLBACK_MOD_IMAGE_MODAL); })
             .finally(() => { if (isMounted) setPreviewLoading(false); });
@@ -339,7 +388,8 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
                      if (!isMounted) return;
                      setEntities(loadedEntities);
                      // Pre-selection logic (same as before)
-                     if (categorySlug === analysisResult?.deduced_category_slug && analysisResult?.deduced_entity_slug) {
+                     if (categorySlug === analysisResult?.deduced_category_slug && analysisResult?.deduced_entity_slug
+                         && (analysisResult.confidence ?? 1) >= AUTO_SELECT_CONFIDENCE) {
                          const deducedEntityOption = loadedEntities
                               .map(ent => ({ value: ent.slug, label: ent.name }))
                               .find(opt => opt.value === analysisResult.deduced_entity_slug);
@@ -371,7 +421,7 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
                 setEntityLoading(false);
             }
             return () => { isMounted = false; }
-        }, [selectedCategoryOption, analysisResult?.deduced_category_slug, analysisResult?.deduced_entity_slug, analysisResult?.raw_ini_target]);
+        }, [selectedCategoryOption, analysisResult?.deduced_category_slug, analysisResult?.deduced_entity_slug, analysisResult?.confidence, analysisResult?.raw_ini_target]);
 
     const handlePaste = useCallback((event) => {
         setError('');
@@ -385,6 +435,7 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
                     console.log("Pasted image file for import:", file);
                     cleanupPreviewObjectUrl();
                     setSelectedPreviewAbsPath(null); // Clear file path selection
+                    setSelectedInternalPreviewPath(null);
                     setPastedImageFile(file); // Store the File object
 
                     const url = URL.createObjectURL(file);
@@ -398,6 +449,93 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
         else { console.log("No image found in paste for import."); }
     }, [cleanupPreviewObjectUrl]);
 
+    // --- Pick Preview From Archive Handler ---
+    const handleSelectInternalPreview = (internalPath) => {
+        setError('');
+        if (!internalPath) return;
+        cleanupPreviewObjectUrl();
+        setPastedImageFile(null);
+        setSelectedPreviewAbsPath(null);
+        setSelectedInternalPreviewPath(internalPath);
+        setPreviewLoading(true);
+        invoke('read_archive_file_data_url', { archivePathStr: analysisResult.file_path, internalFilePath: internalPath })
+            .then(setPreviewImageUrl)
+            .catch(err => { console.warn("Failed to load archive preview:", err); setPreviewImageUrl(FALLBACK_MOD_IMAGE_MODAL); })
+            .finally(() => setPreviewLoading(false));
+    };
+
+    // --- Use an image file on disk as the preview ---
+    const loadPreviewFromPath = useCallback((absolutePath) => {
+        cleanupPreviewObjectUrl();
+        setPastedImageFile(null);
+        setSelectedInternalPreviewPath(null);
+        setSelectedPreviewAbsPath(absolutePath);
+        setPreviewLoading(true);
+        invoke('read_binary_file', { path: absolutePath })
+            .then(fileData => {
+                 try {
+                     // Basic mime type detection
+                     let mimeType = 'image/png';
+                     const ext = absolutePath.split('.').pop().toLowerCase();
+                     if (['jpg', 'jpeg'].includes(ext)) mimeType = 'image/jpeg';
+                     else if (ext === 'gif') mimeType = 'image/gif';
+                     else if (ext === 'webp') mimeType = 'image/webp';
+
+                     const blob = new Blob([new Uint8Array(fileData)], { type: mimeType });
+                     const url = URL.createObjectURL(blob);
+                     previewObjectUrlRef.current = url;
+                     setPreviewImageUrl(url);
+                 } catch(e) { throw new Error("Cannot create preview blob"); }
+            })
+            .catch(readError => {
+                console.error("Error reading selected file for preview:", readError);
+                setError('Could not read selected image for preview.');
+                setPreviewImageUrl(FALLBACK_MOD_IMAGE_MODAL);
+                setSelectedPreviewAbsPath(null);
+            })
+            .finally(() => setPreviewLoading(false));
+    }, [cleanupPreviewObjectUrl]);
+
+    // --- Prefill from GameBanana when the archive was downloaded from a mod page ---
+    useEffect(() => {
+        if (!sourceUrl || !analysisResult || categoryLoading || presetsLoading) return;
+        if (!/gamebanana\.com\/mods\//i.test(sourceUrl)) return;
+        let isMounted = true;
+        invoke('fetch_gamebanana_metadata', { modIdOrUrl: sourceUrl })
+            .then(metadata => {
+                if (!isMounted || !metadata) return;
+                if (metadata.name) setModName(metadata.name);
+                if (metadata.author) setAuthor(metadata.author);
+                if (metadata.preview_image_path) loadPreviewFromPath(metadata.preview_image_path);
+            })
+            .catch(err => console.warn("Could not fetch GameBanana metadata:", err?.message || err));
+        return () => { isMounted = false; };
+    }, [sourceUrl, analysisResult, categoryLoading, presetsLoading, loadPreviewFromPath]);
+
+    // --- Prefill tag from the selected entity's default ---
+    useEffect(() => {
+        const entitySlug = selectedEntityOption?.value;
+        if (!entitySlug || selectedEntityOption.newEntityName) return;
+        let isMounted = true;
+        invoke('get_entity_default_tag', { entitySlug })
+            .then(defaultTag => {
+                if (isMounted && defaultTag) setCategoryTag(current => current.trim() ? current : defaultTag);
+            })
+            .catch(err => console.warn("Could not load entity default tag:", err?.message || err));
+        return () => { isMounted = false; };
+    }, [selectedEntityOption]);
+
+    const handleSaveDefaultTag = async () => {
+        const entitySlug = selectedEntityOption?.value;
+        if (!entitySlug) return;
+        try {
+            await invoke('set_entity_default_tag', { entitySlug, categoryTag: categoryTag.trim() || null });
+        } catch (err) {
+            console.error("Failed to save default tag:", err);
+            setError(`Failed to save default tag: ${err?.message || err}`);
+        }
+    };
+
     // --- Select Separate Preview Handler ---
     const handleSelectPreviewImage = async () => {
         setError('');
@@ -412,34 +550,7 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
             if (selected && typeof selected === 'string') absolutePath = selected;
             else if (Array.isArray(selected) && selected.length > 0) absolutePath = selected[0];
 
-            if (absolutePath) {
-                setPastedImageFile(null);
-                setSelectedPreviewAbsPath(absolutePath);
-                setPreviewLoading(true);
-                invoke('read_binary_file', { path: absolutePath })
-                    .then(fileData => {
-                         try {
-                             // Basic mime type detection
-                             let mimeType = 'image/png';
-                             const ext = absolutePath.split('.').pop().toLowerCase();
-                             if (['jpg', 'jpeg'].includes(ext)) mimeType = 'image/jpeg';
-                             else if (ext === 'gif') mimeType = 'image/gif';
-                             else if (ext === 'webp') mimeType = 'image/webp';
-
-                             const blob = new Blob([new Uint8Array(fileData)], { type: mimeType });
-                             const url = URL.createObjectURL(blob);
-                             previewObjectUrlRef.current = url;
-                             setPreviewImageUrl(url);
-                         } catch(e) { throw new Error("Cannot create preview blob"); }
-                    })
-                    .catch(readError => {
-                        console.error("Error reading selected file for preview:", readError);
-                        setError('Could not read selected image for preview.');
-                        setPreviewImageUrl(FALLBACK_MOD_IMAGE_MODAL);
-                        setSelectedPreviewAbsPath(null);
-                    })
-                    .finally(() => setPreviewLoading(false));
-            }
+            if (absolutePath) loadPreviewFromPath(absolutePath);
         } catch (err) {
              console.error("Error selecting image:", err);
              setError('Failed to open image file dialog.');
@@ -451,7 +562,8 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
     const handleConfirmImport = async () => {
         setError('');
         const targetEntitySlugValue = selectedEntityOption?.value;
-        if (!targetEntitySlugValue) { setError('Please select the target character/entity.'); return; }
+        const newEntityName = selectedEntityOption?.newEntityName;
+        if (!targetEntitySlugValue && !newEntityName) { setError('Please select the target character/entity.'); return; }
         if (!modName.trim()) { setError('Please enter a mod name.'); return; }
         const hasDirectories = analysisResult?.entries?.some(e => e.is_dir);
         if (!extractAllFiles && !selectedInternalRoot && hasDirectories) {
@@ -475,24 +587,53 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
 
         const presetIdsToSend = selectedPresets.length > 0 ? selectedPresets.map(opt => opt.value) : null;
 
+        setImportProgress(null);
+        const unlistenProgress = await listen('import://progress', (event) => setImportProgress(event.payload));
+
+        const importWith = (onConflict) => invoke('import_archive', {
+            archivePathStr: analysisResult.file_path,
+            targetEntitySlug: targetEntitySlugValue || '',
+            selectedInternalRoot: extractAllFiles ? "" : (selectedInternalRoot || ""),
+            modName: modName.trim(),
+            description: description || null,
+            author: author || null,
+            categoryTag: categoryTag || null,
+            presetIds: presetIdsToSend,
+            options: {
+                image_data: imageDataToSend,
+                selected_preview_absolute_path: imageDataToSend ? null : selectedPreviewAbsPath,
+                selected_internal_preview_path: (imageDataToSend || selectedPreviewAbsPath) ? null : selectedInternalPreviewPath,
+                delete_archive_after_import: deleteArchiveAfterImport,
+                source_url: sourceUrl || null,
+                import_disabled: importDisabled,
+                on_conflict: onConflict,
+                create_entity: newEntityName ? { category_slug: selectedCategoryOption.value, name: newEntityName } : null,
+                version: analysisResult?.deduced_version || null,
+            },
+        });
+
         try {
-            await invoke('import_archive', {
-                archivePathStr: analysisResult.file_path,
-                targetEntitySlug: targetEntitySlugValue,
-                selectedInternalRoot: extractAllFiles ? "" : (selectedInternalRoot || ""),
-                modName: modName.trim(),
-                description: description || null,
-                author: author || null,
-                categoryTag: categoryTag || null,
-                imageData: imageDataToSend,
-                selectedPreviewAbsolutePath: imageDataToSend ? null : selectedPreviewAbsPath,
-                presetIds: presetIdsToSend,
-            });
-            onImportSuccess(targetEntitySlugValue, selectedCategoryOption?.value || 'characters');
+            let imported;
+            try {
+                imported = await importWith('abort');
+            } catch (err) {
+                const message = err?.message || String(err);
+                if (err?.kind !== 'ModOperation' || !message.includes('already exists')) throw err;
+                if (await ask(`${message}\n\nImport it as a numbered copy instead?`, { title: 'Mod Already Exists', type: 'warning' })) {
+                    imported = await importWith('suffix');
+                } else if (await ask(`Replace the existing mod with this archive?\n\nThe existing mod folder will be deleted.`, { title: 'Overwrite Mod', type: 'warning' })) {
+                    imported = await importWith('overwrite');
+                } else {
+                    return;
+                }
+            }
+            onImportSuccess(imported.entity_slug, selectedCategoryOption?.value || 'characters', imported);
         } catch (err) {
              const errorString = typeof err === 'string' ? err : (err?.message || 'Unknown import error');
              setError(`Import Failed: ${errorString}`);
         } finally {
+            unlistenProgress();
+            setImportProgress(null);
             setIsImporting(false);
         }
     };
@@ -500,16 +641,26 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
     // Memoized archive filename for display
     const archiveFilename = useMemo(() => analysisResult?.file_path?.split('/').pop()?.split('\\').pop() || 'archive', [analysisResult]);
 
-    // Helper to render file tree node
-    const renderFileNode = (entry) => {
-        const indentLevel = entry.path.includes('/') ? entry.path.split('/').length - 1 : 0;
+    const toggleDirExpanded = (index) => {
+        setExpandedDirs(prev => {
+            const next = new Set(prev);
+            if (next.has(index)) next.delete(index); else next.add(index);
+            return next;
+        });
+    };
+
+    // Helper to render file tree node (and its children when expanded)
+    const renderFileNode = (index) => {
+        const entry = analysisResult.entries[index];
+        const indentLevel = entry.depth ?? 0;
         const isSelected = entry.path === selectedInternalRoot;
         const canSelect = entry.is_dir;
         const isDisabled = extractAllFiles;
+        const isExpanded = expandedDirs.has(index);
 
        return (
+           <React.Fragment key={entry.path}>
             <div
-               key={entry.path}
                style={{
                    ...styles.fileListItem,
                    ...(isSelected && !isDisabled ? styles.fileListItemSelected : {}), // Highlight only if selectable and selected
@@ -520,10 +671,17 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
                onClick={() => canSelect && !isDisabled && setSelectedInternalRoot(entry.path)} // Only allow click if enabled
                title={isDisabled ? "Selection disabled (Extract All checked)" : entry.path}
            >
-                <i className={`fas ${entry.is_dir ? 'fa-folder' : 'fa-file-alt'} fa-fw`} style={{...styles.icon, color: entry.is_dir ? 'var(--accent)' : undefined}}></i>
-                <span style={{flexGrow: 1}}>{entry.path.split('/').pop() || entry.path}</span>
-                {entry.is_likely_mod_root && <i className="fas fa-star fa-fw" style={{color:'var(--accent)', marginLeft:'auto', fontSize:'11px', flexShrink:0}} title="Likely Mod Root (Contains INI)"></i>}
+                {entry.is_dir
+                    ? <i className={`fas fa-caret-${isExpanded ? 'down' : 'right'} fa-fw`} style={{ cursor: 'pointer', opacity: 0.7, flexShrink: 0 }} onClick={(e) => { e.stopPropagation(); toggleDirExpanded(index); }} title={isExpanded ? 'Collapse' : 'Expand'}></i>
+                    : <span className="fa-fw" style={{ flexShrink: 0 }}></span>}
+                <i className={`fas ${entry.is_dir ? (isExpanded ? 'fa-folder-open' : 'fa-folder') : 'fa-file-alt'} fa-fw`} style={{...styles.icon, color: entry.is_dir ? 'var(--accent)' : undefined}}></i>
+                <span style={{flexGrow: 1}}>{entry.path.replace(/\/$/, '').split('/').pop() || entry.path}</span>
+                {!entry.is_dir && <span style={{fontSize:'11px', opacity:0.6, marginLeft:'8px', flexShrink:0}}>{formatBytes(entry.uncompressed_size)}</span>}
+                {entry.is_likely_mod_root && <i className="fas fa-star fa-fw" style={{color:'var(--accent)', marginLeft:'auto', fontSize:'11px', flexShrink:0}} title="Likely Mod Root (Contains INI or mod textures)"></i>}
+                {!entry.is_likely_mod_root && !isExpanded && entryTree.hasRootBelow.has(index) && <i className="far fa-star fa-fw" style={{color:'var(--accent)', marginLeft:'auto', fontSize:'11px', flexShrink:0, opacity: 0.6}} title="Contains a likely mod root"></i>}
             </div>
+            {entry.is_dir && isExpanded && (entryTree.children.get(index) || []).map(renderFileNode)}
+           </React.Fragment>
        );
    }
 
@@ -540,10 +698,13 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
                 <div style={styles.content}>
                     {/* Left Panel: Archive Contents & Selection */}
                     <div style={styles.leftPanel}>
-                        <label style={styles.label}>Select Mod Root Folder (or check Extract All):</label>
+                        <label style={styles.label}>
+                            Select Mod Root Folder (or check Extract All):
+                            {analysisResult?.total_uncompressed_size > 0 && <span style={{opacity:0.6, fontWeight:'normal'}}> {formatBytes(analysisResult.total_uncompressed_size)} total</span>}
+                        </label>
                         <div style={{ ...styles.fileListContainer, opacity: extractAllFiles ? 0.5 : 1 }}> {/* Dim if extractAll is checked */}
                             {analysisResult?.entries?.length > 0 ? (
-                                analysisResult.entries.map(renderFileNode)
+                                (entryTree.children.get('root') || []).map(renderFileNode)
                             ) : (
                                 <p style={{padding:'10px', textAlign:'center', fontSize:'13px', color:'rgba(255,255,255,0.6)'}}>Analyzing archive or archive empty...</p>
                             )}
@@ -582,6 +743,78 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
                                 Extract All Files (ignore selected root)
                             </label>
                         </div>
+                        {/* --- Delete Archive Checkbox --- */}
+                        <div
+                            style={{
+                                ...styles.checkboxContainer,
+                                ...(isImporting ? styles.checkboxDisabled : {})
+                            }}
+                        >
+                            <div style={styles.checkboxWrapper}>
+                                <input
+                                    type="checkbox"
+                                    style={styles.checkboxInput}
+                                    checked={deleteArchiveAfterImport}
+                                    onChange={(e) => {
+                                        if (isImporting) return;
+                                        setDeleteArchiveAfterImport(e.target.checked);
+                                        updateUiPrefs({ delete_archive_after_import: e.target.checked });
+                                    }}
+                                    disabled={isImporting}
+                                    id="delete-archive-checkbox"
+                                />
+                                <div
+                                    style={{
+                                        ...styles.checkboxVisual,
+                                        ...(deleteArchiveAfterImport ? styles.checkboxVisualChecked : {})
+                                    }}
+                                >
+                                    {deleteArchiveAfterImport && (
+                                        <i className="fas fa-check" style={{ color: 'white', fontSize: '11px' }}></i>
+                                    )}
+                                </div>
+                            </div>
+                            <label
+                                htmlFor="delete-archive-checkbox"
+                                style={styles.checkboxLabel}
+                            >
+                                Delete archive after successful import
+                            </label>
+                        </div>
+                        {/* --- Import Disabled Checkbox --- */}
+                        <div
+                            style={{
+                                ...styles.checkboxContainer,
+                                ...(isImporting ? styles.checkboxDisabled : {})
+                            }}
+                        >
+                            <div style={styles.checkboxWrapper}>
+                                <input
+                                    type="checkbox"
+                                    style={styles.checkboxInput}
+                                    checked={importDisabled}
+                                    onChange={(e) => { if (!isImporting) setImportDisabled(e.target.checked); }}
+                                    disabled={isImporting}
+                                    id="import-disabled-checkbox"
+                                />
+                                <div
+                                    style={{
+                                        ...styles.checkboxVisual,
+                                        ...(importDisabled ? styles.checkboxVisualChecked : {})
+                                    }}
+                                >
+                                    {importDisabled && (
+                                        <i className="fas fa-check" style={{ color: 'white', fontSize: '11px' }}></i>
+                                    )}
+                                </div>
+                            </div>
+                            <label
+                                htmlFor="import-disabled-checkbox"
+                                style={styles.checkboxLabel}
+                            >
+                                Import as disabled (don't activate yet)
+                            </label>
+                        </div>
                         {/* --- End Checkbox --- */}
                         <p style={{fontSize:'12px', color:'rgba(255,255,255,0.6)', marginTop:'0px', minHeight:'16px'}}>
                             Selected Root: {extractAllFiles ? '(Extracting All)' : (selectedInternalRoot || '(None)')}
@@ -595,6 +828,18 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
                                 : previewImageUrl !== FALLBACK_MOD_IMAGE_MODAL ? <img src={previewImageUrl} alt="Preview" style={styles.imagePreview} onError={() => setPreviewImageUrl(FALLBACK_MOD_IMAGE_MODAL)} />
                                 : <p style={styles.imagePlaceholderText}>No preview.</p>}
                             </div>
+                            {previewCandidatePaths.length > 1 && (
+                                <select
+                                    value={selectedInternalPreviewPath && previewCandidatePaths.includes(selectedInternalPreviewPath) ? selectedInternalPreviewPath : ''}
+                                    onChange={e => handleSelectInternalPreview(e.target.value)}
+                                    style={{...styles.input, marginTop:'10px'}}
+                                    disabled={isImporting}
+                                    title="Use an image from the archive as preview"
+                                >
+                                    <option value="" disabled>Pick image from archive...</option>
+                                    {previewCandidatePaths.map(path => <option key={path} value={path}>{path}</option>)}
+                                </select>
+                            )}
                             <button className="btn btn-outline" style={{marginTop:'10px', width:'100%'}} onClick={handleSelectPreviewImage} disabled={isImporting}>
                                 <i className="fas fa-image fa-fw"></i> Change Image...
                             </button>
@@ -612,7 +857,19 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
                         {/* Entity Select */}
                         <div style={styles.formGroup}>
                             <label style={styles.label} htmlFor="import-entity">Target Entity:</label>
-                            <Select id="import-entity" styles={reactSelectStyles} options={entityOptions} value={selectedEntityOption} onChange={setSelectedEntityOption} placeholder={entityLoading ? 'Loading...' : (selectedCategoryOption ? (entities.length > 0 ? 'Select or type to search...' : 'No entities found') : 'Select Category First')} isLoading={entityLoading} isDisabled={isImporting || !selectedCategoryOption || entityLoading || entities.length === 0} isClearable={false} isSearchable={true} menuPosition={'fixed'} />
+                            <CreatableSelect id="import-entity" styles={reactSelectStyles} options={entityOptions} value={selectedEntityOption} onChange={setSelectedEntityOption}
+                                onCreateOption={name => setSelectedEntityOption({ value: '', label: `${name.trim()} (new)`, newEntityName: name.trim() })}
+                                formatCreateLabel={name => `Create new entity "${name}"`}
+                                placeholder={entityLoading ? 'Loading...' : (selectedCategoryOption ? (entities.length > 0 ? 'Select, search or type a new name...' : 'Type a name to create an entity') : 'Select Category First')}
+                                isLoading={entityLoading} isDisabled={isImporting || !selectedCategoryOption || entityLoading} isClearable={false} isSearchable={true} menuPosition={'fixed'} />
+                            {analysisResult?.deduced_entity_slug && analysisResult.deduction_source && (
+                                <span style={{ fontSize: '12px', color: 'rgba(255,255,255,0.6)', marginTop: '4px', display: 'block' }}>
+                                    {analysisResult.confidence >= AUTO_SELECT_CONFIDENCE ? 'Detected' : 'Possible match (not pre-selected)'}
+                                    {`: ${analysisResult.deduced_entity_slug} from ${DEDUCTION_SOURCE_LABELS[analysisResult.deduction_source] || analysisResult.deduction_source}`}
+                                    {analysisResult.deduced_entity_token && ` "${analysisResult.deduced_entity_token}"`}
+                                    {` (${Math.round(analysisResult.confidence * 100)}%)`}
+                                </span>
+                            )}
                         </div>
                         {/* Mod Name */}
                         <div style={styles.formGroup}>
@@ -628,6 +885,9 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
                         <div style={styles.formGroup}>
                             <label style={styles.label} htmlFor="import-category-tag">Category Tags (comma-separated):</label>
                             <input id="import-category-tag" type="text" value={categoryTag} onChange={e => setCategoryTag(e.target.value)} style={styles.input} placeholder="Outfit, Retexture, Effect..." disabled={isImporting}/>
+                            <button className="btn btn-outline" style={{marginTop:'6px', padding:'4px 10px', fontSize:'12px'}} onClick={handleSaveDefaultTag} disabled={isImporting || !selectedEntityOption?.value} title="Use these tags by default when importing for this entity">
+                                Save as entity default
+                            </button>
                         </div>
                         {/* Description */}
                         <div style={styles.formGroup}>
@@ -651,7 +911,7 @@ function ImportModModal({ analysisResult, onClose, onImportSuccess }) {
                         onClick={handleConfirmImport}
                         disabled={isImporting || !selectedEntityOption || !modName.trim() || (!extractAllFiles && !selectedInternalRoot && analysisResult?.entries?.some(e=>e.is_dir))}
                     >
-                        {isImporting ? <><i className="fas fa-spinner fa-spin fa-fw"></i> Importing...</> : <><i className="fas fa-check fa-fw"></i> Confirm Import</>}
+                        {isImporting ? <><i className="fas fa-spinner fa-spin fa-fw"></i> {importProgress?.total ? `Importing ${importProgress.extracted}/${importProgress.total}...` : 'Importing...'}</> : <><i className="fas fa-check fa-fw"></i> Confirm Import</>}
                     </button>
                 </div>
             </div> {/* End Modal */}
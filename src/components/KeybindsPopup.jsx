@@ -56,6 +56,9 @@ const styles = {
     errorText: {
         color: 'var(--danger)', marginTop: '15px', fontSize: '14px',
     },
+    variantSummary: {
+        fontSize: '13px', color: 'var(--accent)', marginBottom: '15px',
+    },
     placeholderText: {
         color: 'rgba(255, 255, 255, 0.5)', fontStyle: 'italic',
     }
@@ -67,6 +70,7 @@ function KeybindsPopup({
     assetId,
     assetName,
     keybinds,
+    variants,
     isLoading,
     error,
 }) {
@@ -74,13 +78,21 @@ function KeybindsPopup({
         return null;
     }
 
+    // e.g. "3 variants, cycles with VK_F8 (back: VK_F7)"
+    const mainBinding = variants?.bindings?.find(b => b.states === variants.variant_count);
+    const variantSummary = mainBinding && variants.variant_count > 1
+        ? `${variants.variant_count} variants, ${mainBinding.kind === 'cycle' ? 'cycles' : mainBinding.kind + 's'} with ${mainBinding.key}`
+            + (mainBinding.back ? ` (back: ${mainBinding.back})` : '')
+            + (mainBinding.requires_active ? ' while on screen' : '')
+        : null;
+
     const handleOpenFolder = async () => {
         try {
             await invoke('open_asset_folder', { assetId });
             // Keep popup open after opening folder
         } catch (err) {
             console.error("Failed to open asset folder:", err);
-            alert(`Error opening folder: ${err}`); // Simple feedback
+            alert(`Error opening folder: ${err?.message || err}`); // Simple feedback
         }
     };
 
@@ -91,6 +103,7 @@ function KeybindsPopup({
                 <p style={styles.subtitle}>{assetName}</p>
 
                 <div style={styles.content}>
+                    {variantSummary && <p style={styles.variantSummary}><i className="fas fa-layer-group fa-fw"></i> {variantSummary}</p>}
                     {isLoading && <p style={styles.placeholderText}><i className="fas fa-spinner fa-spin fa-fw"></i> Loading...</p>}
                     {error && <p style={styles.errorText}>{error}</p>}
                     {!isLoading && !error && (
@@ -2,6 +2,7 @@ import React, { useState, useEffect, useCallback, useMemo, useRef } from 'react'
 import { invoke, convertFileSrc } from '@tauri-apps/api/tauri';
 import KeybindsPopup from './KeybindsPopup';
 import { toast } from 'react-toastify';
+import { open } from '@tauri-apps/api/shell';
 import AddToPresetModal from './AddToPresetModal';
 
 // Helper to split tags, trimming whitespace and filtering empty ones
@@ -30,6 +31,9 @@ function ModCard({
 }) {
     // State
     const isEnabled = asset.is_enabled;
+    const isHiddenByParent = asset.hidden_by_disabled_parent; // A parent folder is DISABLED_ on disk
+    const toggleTitle = isHiddenByParent ? 'Hidden by a disabled parent folder'
+        : asset.state_source === 'disk' ? `${isEnabled ? 'Enabled' : 'Disabled'} outside the app` : undefined;
     const [cleanRelativePath, setCleanRelativePath] = useState('');
     const [imageUrl, setImageUrl] = useState(null); // State holds the URL string
     const folderNameOnDisk = asset.folder_name; // Reflects disk state
@@ -45,6 +49,7 @@ function ModCard({
     const [keybinds, setKeybinds] = useState([]);
     const [keybindsLoading, setKeybindsLoading] = useState(false);
     const [keybindsError, setKeybindsError] = useState('');
+    const [variants, setVariants] = useState(null);
     // --- NEW STATE for Add To Preset Modal ---
     const [isAddToPresetModalOpen, setIsAddToPresetModalOpen] = useState(false);
     // ---------------------------------------
@@ -114,6 +119,11 @@ function ModCard({
         setKeybindsLoading(true);
         setKeybindsError('');
         setKeybinds([]);
+        setVariants(null);
+        // Variant info is a bonus; a failure here shouldn't hide the keybinds
+        invoke('get_asset_variants', { assetId: asset.id })
+            .then(setVariants)
+            .catch(err => console.warn(`Failed to read variants for asset ${asset.id}:`, err));
         try {
             const fetchedKeybinds = await invoke('get_ini_keybinds', { assetId: asset.id });
             setKeybinds(fetchedKeybinds || []);
@@ -132,6 +142,7 @@ function ModCard({
         setKeybindsLoading(false);
         setKeybindsError('');
         setKeybinds([]);
+        setVariants(null);
     }, []);
 
     // --- NEW: Add To Preset Handlers ---
@@ -154,11 +165,23 @@ function ModCard({
             await invoke('open_asset_folder', { assetId: asset.id });
         } catch (err) {
             console.error(`Failed to open folder for asset ${asset.id}:`, err);
-            toast.error(`Failed to open folder: ${err}`); // User feedback
+            toast.error(`Failed to open folder: ${err?.message || err}`); // User feedback
         }
     }, [asset.id]);
     // -----------------------------
 
+    const handleOpenSourceUrl = useCallback(async (e) => {
+        e.stopPropagation();
+        e.preventDefault();
+        if (!asset.source_url) return;
+        try {
+            await open(asset.source_url);
+        } catch (err) {
+            console.error(`Failed to open source URL for asset ${asset.id}:`, err);
+            toast.error(`Failed to open mod page: ${err?.message || err}`);
+        }
+    }, [asset.id, asset.source_url]);
+
     // Checkbox change handler
     const handleCheckboxChange = useCallback((e) => {
          onSelectChange(asset.id, e.target.checked);
@@ -289,13 +312,14 @@ function ModCard({
                      </div>
                      {/* Toggle */}
                      <label className="toggle-switch compact-toggle" style={listStyles.toggle}>
-                         <input type="checkbox" checked={isEnabled} onChange={handleToggle} disabled={isToggling} aria-label={`Enable/Disable ${asset.name} mod`} />
+                         <input type="checkbox" checked={isEnabled} onChange={handleToggle} disabled={isToggling || isHiddenByParent} title={toggleTitle} aria-label={`Enable/Disable ${asset.name} mod`} />
                          <span className="slider"></span>
                      </label>
                      {/* Actions */}
                      <div className="mod-list-actions" style={listStyles.actions}>
                          {/* --- ADDED: Open Folder Button --- */}
                          <button onClick={handleOpenFolder} className="btn-icon compact-btn" title="Open Mod Folder" disabled={isToggling}> <i className="fas fa-folder-open fa-fw"></i> </button>
+                         {asset.source_url && <button onClick={handleOpenSourceUrl} className="btn-icon compact-btn" title={`Open Mod Page: ${asset.source_url}`}> <i className="fas fa-external-link-alt fa-fw"></i> </button>}
                          {/* --- End Added Button --- */}
                          <button onClick={handleOpenKeybindsPopup} className="btn-icon compact-btn" title="View Keybinds" disabled={isToggling}> <i className="fas fa-keyboard fa-fw"></i> </button>
                          <button onClick={handleEditClick} className="btn-icon compact-btn" title="Edit Mod Info" disabled={isToggling}> <i className="fas fa-pencil-alt fa-fw"></i> </button>
@@ -303,7 +327,7 @@ function ModCard({
                      </div>
                 </div>
                  {/* Modals */}
-                <KeybindsPopup isOpen={isKeybindsPopupOpen} onClose={handleCloseKeybindsPopup} assetId={asset.id} assetName={asset.name} keybinds={keybinds} isLoading={keybindsLoading} error={keybindsError} />
+                <KeybindsPopup isOpen={isKeybindsPopupOpen} onClose={handleCloseKeybindsPopup} assetId={asset.id} assetName={asset.name} keybinds={keybinds} variants={variants} isLoading={keybindsLoading} error={keybindsError} />
                 <AddToPresetModal assetId={asset.id} assetName={asset.name} isOpen={isAddToPresetModalOpen} onClose={handleCloseAddToPreset} />
             </>
         );
@@ -320,25 +344,26 @@ function ModCard({
                     <div style={{ display: 'flex', alignItems: 'center', marginLeft: 'auto', gap: '5px' }}>
                         <button onClick={handleEditClick} className="btn-icon" title="Edit Mod Info" style={gridButtonStyles.edit} onMouseOver={(e) => e.currentTarget.style.opacity = 1} onMouseOut={(e) => e.currentTarget.style.opacity = 0.7} disabled={isToggling} > <i className="fas fa-pencil-alt fa-fw"></i> </button>
                         <button onClick={handleDeleteClick} className="btn-icon" title="Delete Mod" style={gridButtonStyles.delete} onMouseOver={(e) => e.currentTarget.style.opacity = 1} onMouseOut={(e) => e.currentTarget.style.opacity = 0.7} disabled={isToggling} > <i className="fas fa-trash-alt fa-fw"></i> </button>
-                        <label className="toggle-switch" style={{ marginLeft: '5px' }}> <input type="checkbox" checked={isEnabled} onChange={handleToggle} disabled={isToggling} aria-label={`Enable/Disable ${asset.name} mod`} /> <span className="slider"></span> </label>
+                        <label className="toggle-switch" style={{ marginLeft: '5px' }}> <input type="checkbox" checked={isEnabled} onChange={handleToggle} disabled={isToggling || isHiddenByParent} title={toggleTitle} aria-label={`Enable/Disable ${asset.name} mod`} /> <span className="slider"></span> </label>
                     </div>
                 </div>
                 {tags.length > 0 && ( <div className="mod-tags-container" style={{ marginBottom: '12px', display: 'flex', flexWrap: 'wrap', gap: '5px' }}> {tags.map((tag, index) => ( <span key={index} className="mod-category">{tag}</span> ))} </div> )}
                 {asset.description ? ( <p className="mod-description">{asset.description}</p> ) : ( <p className="mod-description placeholder-text" style={{padding:0, textAlign:'left', fontStyle:'italic'}}>(No description)</p> )}
                 <div className="mod-details">
-                    <div className="mod-author">{asset.author ? `By: ${asset.author}` : '(Unknown author)'}</div>
+                    <div className="mod-author">{asset.author ? `By: ${asset.author}` : '(Unknown author)'}{asset.version && <span title="Declared mod version" style={{ marginLeft: '6px', opacity: 0.7 }}>v{asset.version}</span>}</div>
                      <div style={{ display: 'flex', gap: '5px' }}> {/* Button group */}
                          {/* Add to Preset Button */}
                          <button className="btn-icon add-preset-button" onClick={handleOpenAddToPreset} title="Add to Preset(s)" style={gridButtonStyles.addPreset} disabled={isToggling} > <i className="fas fa-plus-circle fa-fw"></i> </button>
                          {/* --- ADDED: Open Folder Button --- */}
                          <button className="btn-icon open-folder-button" onClick={handleOpenFolder} title="Open Mod Folder" style={gridButtonStyles.openFolder} disabled={isToggling} > <i className="fas fa-folder-open fa-fw"></i> </button>
+                         {asset.source_url && <button className="btn-icon" onClick={handleOpenSourceUrl} title={`Open Mod Page: ${asset.source_url}`} style={gridButtonStyles.openFolder} > <i className="fas fa-external-link-alt fa-fw"></i> </button>}
                          {/* --- End Added Button --- */}
                          {/* Keybind Button */}
                          <button className="btn-icon keybind-button" onClick={handleOpenKeybindsPopup} title="View Keybinds" style={gridButtonStyles.keybind} disabled={isToggling} aria-label={`View keybinds for ${asset.name}`} > <i className="fas fa-keyboard fa-fw"></i> </button>
                      </div>
                 </div>
             </div>
-             <KeybindsPopup isOpen={isKeybindsPopupOpen} onClose={handleCloseKeybindsPopup} assetId={asset.id} assetName={asset.name} keybinds={keybinds} isLoading={keybindsLoading} error={keybindsError} />
+             <KeybindsPopup isOpen={isKeybindsPopupOpen} onClose={handleCloseKeybindsPopup} assetId={asset.id} assetName={asset.name} keybinds={keybinds} variants={variants} isLoading={keybindsLoading} error={keybindsError} />
              {/* --- Render AddToPresetModal --- */}
              <AddToPresetModal assetId={asset.id} assetName={asset.name} isOpen={isAddToPresetModalOpen} onClose={handleCloseAddToPreset} />
              {/* ----------------------------- */}
@@ -4,6 +4,7 @@ import ReactDOM from 'react-dom';
 import { invoke, convertFileSrc } from '@tauri-apps/api/tauri';
 import { open } from '@tauri-apps/api/dialog';
 import Select from 'react-select';
+import { getAssetImageUrl } from '../utils/imageCache';
 
 const reactSelectStyles = {
     control: (baseStyles, state) => ({
@@ -132,6 +133,11 @@ const styles = {
         marginBottom: '10px', objectFit: 'contain',
     },
     imagePlaceholderText: { fontSize: '13px', color: 'rgba(255,255,255,0.5)' },
+    folderImageStrip: { display: 'flex', gap: '8px', marginTop: '10px', overflowX: 'auto', paddingBottom: '4px' },
+    folderImageThumb: {
+        width: '64px', height: '40px', objectFit: 'cover', borderRadius: '4px', cursor: 'pointer',
+        border: '2px solid transparent', flexShrink: 0,
+    },
     buttonGroup: {
         display: 'flex', justifyContent: 'flex-end', gap: '15px',
         marginTop: '30px', paddingTop: '20px',
@@ -142,14 +148,21 @@ const styles = {
 
 const FALLBACK_MOD_IMAGE_MODAL = '/images/placeholder.jpg';
 
+const RELOCATE_STATE_OPTIONS = [
+    { value: null, label: 'Keep current state' },
+    { value: true, label: 'Enabled' },
+    { value: false, label: 'Disabled' },
+];
+
 function ModEditModal({ asset, currentEntitySlug, onClose, onSaveSuccess }) {
     // Form State
-    const [formData, setFormData] = useState({ name: '', description: '', author: '', category_tag: '' });
+    const [formData, setFormData] = useState({ name: '', description: '', author: '', category_tag: '', source_url: '' });
     // Relocation State
     const [categories, setCategories] = useState([]);
     const [entities, setEntities] = useState([]);
     const [selectedCategoryOption, setSelectedCategoryOption] = useState(null);
     const [selectedEntityOption, setSelectedEntityOption] = useState(null);
+    const [relocateState, setRelocateState] = useState(RELOCATE_STATE_OPTIONS[0]); // Only used when moving to another entity
     const [categoryLoading, setCategoryLoading] = useState(true);
     const [entityLoading, setEntityLoading] = useState(false);
     // Preview State
@@ -157,11 +170,17 @@ function ModEditModal({ asset, currentEntitySlug, onClose, onSaveSuccess }) {
     const [imageLoading, setImageLoading] = useState(false); // Add loading state for image
     const [imageLoadingError, setImageLoadingError] = useState(false);
     const [selectedImageAbsPath, setSelectedImageAbsPath] = useState(null);
+    // Other images already in the mod folder, and the one picked among them
+    const [folderImages, setFolderImages] = useState([]);
+    const [chosenFolderImage, setChosenFolderImage] = useState(null);
     const [pastedImageFile, setPastedImageFile] = useState(null);
     const previewObjectUrlRef = useRef(null); // Ref for blob URLs needing cleanup
     // Modal State
     const [isSaving, setIsSaving] = useState(false);
     const [error, setError] = useState('');
+    // Global toggle hotkey
+    const [hotkey, setHotkey] = useState('');
+    const [initialHotkey, setInitialHotkey] = useState('');
 
     // Format options for Select
     const categoryOptions = useMemo(() => categories.map(cat => ({ value: cat.slug, label: cat.name })), [categories]);
@@ -187,6 +206,7 @@ function ModEditModal({ asset, currentEntitySlug, onClose, onSaveSuccess }) {
             description: asset.description || '',
             author: asset.author || '',
             category_tag: asset.category_tag || '',
+            source_url: asset.source_url || '',
         });
         setSelectedImageAbsPath(null);
         setPastedImageFile(null);
@@ -196,6 +216,16 @@ function ModEditModal({ asset, currentEntitySlug, onClose, onSaveSuccess }) {
         // Don't reset currentImageUrl or cleanup blob here; Effect 3 handles it.
         setCategoryLoading(true); setEntityLoading(true); setSelectedCategoryOption(null); setSelectedEntityOption(null);
 
+        // Fetch the current toggle hotkey, if any
+        setHotkey(''); setInitialHotkey('');
+        invoke('get_asset_hotkeys')
+            .then(hotkeys => {
+                if (!isMounted) return;
+                const bound = (hotkeys || []).find(h => h.asset_id === asset.id);
+                setHotkey(bound?.accelerator || ''); setInitialHotkey(bound?.accelerator || '');
+            })
+            .catch(err => console.warn("Failed to load hotkeys:", err));
+
         // Fetch Categories
         invoke('get_categories')
             .then(fetchedCategories => {
@@ -232,33 +262,11 @@ function ModEditModal({ asset, currentEntitySlug, onClose, onSaveSuccess }) {
             });
 
 
-        // Load existing image preview (same logic as before)
-        if (asset.image_filename && asset.folder_name) {
-            invoke('get_asset_image_path', {
-                entitySlug: '', // Not needed if folder_name is relative path on disk
-                folderNameOnDisk: asset.folder_name,
-                imageFilename: asset.image_filename
-            })
-            .then(filePath => {
-                if (!isMounted) return;
-                console.log(`[ModEditModal Image Effect ${asset.id}] Got absolute path: ${filePath}`);
-                if (!filePath) throw new Error("Backend returned empty path.");
-                // Use convertFileSrc for existing files served via asset protocol
-                const assetUrl = convertFileSrc(filePath);
-                console.log(`[ModEditModal Image Effect ${asset.id}] Setting image URL to asset: ${assetUrl}`);
-                setCurrentImageUrl(assetUrl);
-            })
-            .catch(err => {
-                if (isMounted) {
-                    console.error(`[ModEditModal Image Effect ${asset.id}] Failed load existing image:`, err);
-                    setCurrentImageUrl(FALLBACK_MOD_IMAGE_MODAL);
-                    setImageLoadingError(true);
-                }
-            });
-        } else {
-             console.log(`[ModEditModal Image Effect ${asset.id}] No existing image filename/folder.`);
-             setCurrentImageUrl(FALLBACK_MOD_IMAGE_MODAL);
-        }
+        // The existing image preview is loaded by the image effect below
+        setFolderImages([]); setChosenFolderImage(null);
+        invoke('list_asset_images', { assetId: asset.id })
+            .then(images => { if (isMounted) setFolderImages(images || []); })
+            .catch(err => console.warn("Failed to list mod folder images:", err));
 
         return () => { isMounted = false; cleanupObjectUrl(); };
     }, [asset, currentEntitySlug]); // Rerun when the asset prop or current entity slug changes
@@ -324,41 +332,12 @@ function ModEditModal({ asset, currentEntitySlug, onClose, onSaveSuccess }) {
         cleanupObjectUrl(); // Clean up any previous blob URL first
 
         if (asset.image_filename && asset.folder_name) {
-            invoke('get_asset_image_path', {
-                entitySlug: '', // Send empty or potentially currentEntitySlug if needed by backend logic
-                folderNameOnDisk: asset.folder_name,
-                imageFilename: asset.image_filename
-            })
-            .then(filePath => {
-                if (!isMounted) return Promise.reject(new Error("Component unmounted before reading binary"));
-                if (!filePath) throw new Error("Backend returned empty path for existing image.");
-                console.log(`[ModEditModal Image Effect ${asset.id}] Got absolute path: ${filePath}`);
-                return invoke('read_binary_file', { path: filePath }); // Read the binary data
-            })
-            .then(fileData => {
-                 if (!isMounted || !fileData) return Promise.reject(new Error("Component unmounted or no binary data"));
-                 console.log(`[ModEditModal Image Effect ${asset.id}] Read binary data (length: ${fileData.length})`);
-                 try {
-                     // Determine mime type (same as before)
-                     const extension = asset.image_filename.split('.').pop().toLowerCase();
-                     let mimeType = 'image/png';
-                     if (['jpg', 'jpeg'].includes(extension)) mimeType = 'image/jpeg';
-                     else if (extension === 'gif') mimeType = 'image/gif';
-                     else if (extension === 'webp') mimeType = 'image/webp';
-
-                     const blob = new Blob([new Uint8Array(fileData)], { type: mimeType });
-                     const url = URL.createObjectURL(blob);
-                     if (isMounted) {
-                         previewObjectUrlRef.current = url; // Store for cleanup
-                         setCurrentImageUrl(url); // Set the blob URL for preview
-                         console.log(`[ModEditModal Image Effect ${asset.id}] Created Blob URL: ${url}`);
-                     } else {
-                         URL.revokeObjectURL(url); // Revoke immediately if unmounted
-                     }
-                 } catch (blobError) {
-                     console.error(`[ModEditModal Image Effect ${asset.id}] Error creating blob/URL:`, blobError);
-                     if(isMounted) { setCurrentImageUrl(FALLBACK_MOD_IMAGE_MODAL); setImageLoadingError(true); }
-                 }
+            // Cached by path/mtime/size, so reopening the modal doesn't re-read unchanged images
+            getAssetImageUrl(asset.id)
+            .then(url => {
+                if (!isMounted) return;
+                console.log(`[ModEditModal Image Effect ${asset.id}] Using image URL: ${url}`);
+                setCurrentImageUrl(url);
             })
             .catch(err => {
                  if (isMounted) {
@@ -498,14 +477,27 @@ function ModEditModal({ asset, currentEntitySlug, onClose, onSaveSuccess }) {
             await invoke('update_asset_info', {
                 assetId: asset.id,
                 name: formData.name,
-                description: formData.description || null,
-                author: formData.author || null,
-                categoryTag: formData.category_tag || null,
-                selectedImageAbsolutePath: imageDataToSend ? null : selectedImageAbsPath, // Send path only if no data
-                imageData: imageDataToSend,
-                newTargetEntitySlug: newTargetSlug
+                options: {
+                    description: formData.description || null,
+                    author: formData.author || null,
+                    category_tag: formData.category_tag || null,
+                    selected_image_absolute_path: imageDataToSend ? null : selectedImageAbsPath, // Send path only if no data
+                    image_data: imageDataToSend,
+                    new_target_entity_slug: newTargetSlug,
+                    source_url: formData.source_url || null,
+                    relocate_enabled: newTargetSlug !== currentEntitySlug ? relocateState.value : null,
+                },
             });
 
+            // Switch to another image already in the folder, unless a new one was pasted/selected
+            if (chosenFolderImage && !imageDataToSend && !selectedImageAbsPath && chosenFolderImage !== asset.image_filename) {
+                await invoke('set_active_preview', { assetId: asset.id, filename: chosenFolderImage });
+            }
+
+            if (hotkey.trim() !== initialHotkey) {
+                await invoke('set_asset_hotkey', { assetId: asset.id, accelerator: hotkey.trim() || null });
+            }
+
             console.log("Asset info saved successfully.");
 
             onSaveSuccess(newTargetSlug);
@@ -565,6 +557,21 @@ function ModEditModal({ asset, currentEntitySlug, onClose, onSaveSuccess }) {
                             />
                         </div>
                     </div>
+                    {selectedEntityOption && selectedEntityOption.value !== currentEntitySlug && (
+                        <div style={styles.formGroup}>
+                            <label style={styles.label} htmlFor="edit-relocate-state">State After Moving:</label>
+                            <Select
+                                id="edit-relocate-state"
+                                styles={reactSelectStyles}
+                                options={RELOCATE_STATE_OPTIONS}
+                                value={relocateState}
+                                onChange={setRelocateState}
+                                isDisabled={isSaving}
+                                isSearchable={false}
+                                menuPosition={'fixed'}
+                            />
+                        </div>
+                    )}
 
 
                      <h3 style={{fontSize:'16px', fontWeight:500, marginBottom:'15px', borderBottom:'1px solid rgba(255,255,255,0.05)', paddingBottom:'8px'}}>Mod Details</h3>
@@ -583,11 +590,21 @@ function ModEditModal({ asset, currentEntitySlug, onClose, onSaveSuccess }) {
                         <input id="mod-author" type="text" name="author" value={formData.author} onChange={handleInputChange} style={styles.input} disabled={isSaving} />
                     </div>
 
+                    <div style={styles.formGroup}>
+                        <label style={styles.label} htmlFor="mod-source-url">Source URL:</label>
+                        <input id="mod-source-url" type="text" name="source_url" value={formData.source_url} onChange={handleInputChange} style={styles.input} placeholder="e.g., https://gamebanana.com/mods/..." disabled={isSaving} />
+                    </div>
+
                     <div style={styles.formGroup}>
                         <label style={styles.label} htmlFor="mod-category-tag">Category Tags (comma-separated):</label>
                         <input id="mod-category-tag" type="text" name="category_tag" value={formData.category_tag} onChange={handleInputChange} style={styles.input} placeholder="e.g., Outfit, Retexture, Effect" disabled={isSaving} />
                     </div>
 
+                    <div style={styles.formGroup}>
+                        <label style={styles.label} htmlFor="mod-hotkey">Toggle Hotkey (global):</label>
+                        <input id="mod-hotkey" type="text" value={hotkey} onChange={(e) => setHotkey(e.target.value)} style={styles.input} placeholder="e.g., CmdOrCtrl+Shift+F1 (leave empty for none)" disabled={isSaving} />
+                    </div>
+
                     {/* Image Preview Section */}
                     <div style={styles.formGroup}>
                         <label style={styles.label}>Preview Image:</label>
@@ -602,6 +619,23 @@ function ModEditModal({ asset, currentEntitySlug, onClose, onSaveSuccess }) {
                                 <p style={styles.imagePlaceholderText}>No preview image set.</p>
                             )}
                         </div>
+                        {folderImages.length > 1 && !pastedImageFile && !selectedImageAbsPath && (
+                            <div style={styles.folderImageStrip} title="Images found in the mod folder">
+                                {folderImages.map(img => {
+                                    const isChosen = chosenFolderImage ? img.filename === chosenFolderImage : img.is_active;
+                                    return (
+                                        <img
+                                            key={img.filename}
+                                            src={convertFileSrc(img.path)}
+                                            alt={img.filename}
+                                            title={img.filename}
+                                            style={{ ...styles.folderImageThumb, borderColor: isChosen ? 'var(--primary)' : 'transparent' }}
+                                            onClick={() => { if (isSaving) return; setChosenFolderImage(img.filename); setCurrentImageUrl(convertFileSrc(img.path)); setImageLoadingError(false); }}
+                                        />
+                                    );
+                                })}
+                            </div>
+                        )}
                         <button className="btn btn-outline" style={{marginTop:'10px', width:'100%'}} onClick={handleSelectImage} disabled={isSaving} >
                             <i className="fas fa-image fa-fw"></i> Change Image...
                         </button>
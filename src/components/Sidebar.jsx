@@ -21,6 +21,13 @@ function Sidebar() {
     const [isImportModalOpen, setIsImportModalOpen] = useState(false);
     const [importAnalysisResult, setImportAnalysisResult] = useState(null);
     const [importError, setImportError] = useState('');
+    const [showUrlImport, setShowUrlImport] = useState(false);
+    const [importUrl, setImportUrl] = useState('');
+    const [isDownloading, setIsDownloading] = useState(false);
+    const [downloadProgress, setDownloadProgress] = useState(null); // { downloaded, total, file_name }
+    const [analyzeProgress, setAnalyzeProgress] = useState(null); // { processed, total, current_file }
+    const [importIsDownload, setImportIsDownload] = useState(false); // Archive lives in the temp downloads folder
+    const [importSourceUrl, setImportSourceUrl] = useState(null); // URL the archive was downloaded from, if any
     const [favoritePresets, setFavoritePresets] = useState([]);
     const [isLoadingFavs, setIsLoadingFavs] = useState(true);
     const [applyErrorSidebar, setApplyErrorSidebar] = useState('');
@@ -56,12 +63,12 @@ function Sidebar() {
          setIsDraggingOver(false);
          setDropError('');
          const validFiles = Array.from(files).filter(file =>
-             /\.(zip|7z|rar)$/i.test(file.name)
+             /\.(zip|7z|rar|tar|tar\.gz|tgz)$/i.test(file.name)
          );
 
          if (validFiles.length === 0) {
              console.log("No valid archive files dropped.");
-             setDropError("Please drop .zip, .7z, or .rar files.");
+             setDropError("Please drop .zip, .7z, .rar or .tar(.gz) files.");
              return;
          }
 
@@ -91,6 +98,12 @@ function Sidebar() {
             });
     }, []); // Run only once on mount
 
+    // --- Archive Analysis Progress Listener ---
+    useEffect(() => {
+        const unlistenPromise = listen('analyze://progress', (event) => setAnalyzeProgress(event.payload));
+        return () => { unlistenPromise.then(unlisten => unlisten()); };
+    }, []);
+
     // --- Tauri Window Drop Listener ---
     useEffect(() => {
          let unlisten = null;
@@ -100,12 +113,12 @@ function Sidebar() {
                  if (event.payload.type === 'drop') {
                      setDropError(''); // Clear previous errors
                      const validFiles = event.payload.paths.filter(path =>
-                         /\.(zip|7z|rar)$/i.test(path)
+                         /\.(zip|7z|rar|tar|tar\.gz|tgz)$/i.test(path)
                      );
 
                      if (validFiles.length === 0) {
                          console.log("No valid archive files dropped on window.");
-                         setDropError("Only .zip, .7z, or .rar files are supported.");
+                         setDropError("Only .zip, .7z, .rar or .tar(.gz) files are supported.");
                          return;
                      }
 
@@ -118,6 +131,7 @@ function Sidebar() {
                              setImportError(''); // Clear previous import errors
                              setImportAnalysisResult(null);
                              const analysis = await invoke('analyze_archive', { filePathStr: validFiles[0] });
+                             setImportIsDownload(false);
                              setImportAnalysisResult(analysis);
                              setIsImportModalOpen(true);
                           } catch (err) {
@@ -125,6 +139,8 @@ function Sidebar() {
                              console.error("Failed to initiate dropped mod import:", errorString);
                              setImportError(`Dropped Import Error: ${errorString}`); // Show error near import button
                              setIsImportModalOpen(false);
+                          } finally {
+                             setAnalyzeProgress(null);
                           }
                      }
                  } else if (event.payload.type === 'hover') {
@@ -208,7 +224,7 @@ function Sidebar() {
             console.log("Quick Launch: Normal launch successful or detached.");
             // Success, no need to do anything else
         } catch (normalError) {
-            const errorString = typeof normalError === 'string' ? normalError : String(normalError);
+            const errorString = typeof normalError === 'string' ? normalError : (normalError?.message || String(normalError));
             console.warn("Quick Launch: Normal launch failed:", errorString);
 
             // --- Check for Elevation Error ---
@@ -221,9 +237,9 @@ function Sidebar() {
                     console.log("Quick Launch: Elevated launch initiated.");
                     setLaunchError(''); // Clear message on successful initiation
                 } catch (elevatedError) {
-                    const elevatedErrorString = typeof elevatedError === 'string' ? elevatedError : String(elevatedError);
+                    const elevatedErrorString = typeof elevatedError === 'string' ? elevatedError : (elevatedError?.message || String(elevatedError));
                     console.error("Quick Launch: Elevated launch failed:", elevatedErrorString);
-                    if (elevatedErrorString.includes("cancelled by user")) {
+                    if (elevatedError?.kind === 'UserCancelled') {
                          setLaunchError("Admin launch cancelled by user.");
                      } else {
                          setLaunchError(`Admin Launch Failed: ${elevatedErrorString}`);
@@ -250,6 +266,7 @@ function Sidebar() {
             console.log("Selected/Provided archive:", selectedPath);
             const analysis = await invoke('analyze_archive', { filePathStr: selectedPath });
             console.log("Analysis result:", analysis);
+            setImportIsDownload(false);
             setImportAnalysisResult(analysis);
             setIsImportModalOpen(true);
         } catch (err) {
@@ -257,17 +274,56 @@ function Sidebar() {
             console.error("Failed to initiate mod import:", errorString);
             setImportError(`Error: ${errorString}`);
             setIsImportModalOpen(false);
+        } finally {
+            setAnalyzeProgress(null);
         }
      }, []); // Removed dependency on handleInitiateImport itself
 
+     const handleImportFromUrl = useCallback(async () => {
+        const url = importUrl.trim();
+        if (!url) return;
+        setImportError('');
+        setImportAnalysisResult(null);
+        setDropError('');
+        setIsDownloading(true);
+        setDownloadProgress(null);
+        const unlistenProgress = await listen('download://progress', (event) => setDownloadProgress(event.payload));
+        try {
+            const analysis = await invoke('import_from_url', { url });
+            setImportIsDownload(true);
+            setImportSourceUrl(url);
+            setImportAnalysisResult(analysis);
+            setIsImportModalOpen(true);
+            setShowUrlImport(false);
+            setImportUrl('');
+        } catch (err) {
+            const errorString = typeof err === 'string' ? err : (err?.message || 'Unknown error during download');
+            console.error("Failed to import from URL:", errorString);
+            setImportError(`Download Error: ${errorString}`);
+        } finally {
+            unlistenProgress();
+            setIsDownloading(false);
+            setDownloadProgress(null);
+            setAnalyzeProgress(null);
+        }
+     }, [importUrl]);
+
      const handleCloseImportModal = useCallback(() => {
+        // Downloaded archives are temporary; drop it unless the import already consumed it
+        if (importIsDownload && importAnalysisResult?.file_path) {
+            invoke('discard_downloaded_archive', { archivePathStr: importAnalysisResult.file_path })
+                .catch(err => console.warn("Failed to discard downloaded archive:", err));
+        }
+        setImportIsDownload(false);
+        setImportSourceUrl(null);
         setIsImportModalOpen(false);
         setImportAnalysisResult(null);
         setImportError('');
-     }, []);
+     }, [importIsDownload, importAnalysisResult]);
 
-    const handleImportSuccess = useCallback((importedEntitySlug, importedCategorySlug) => {
+    const handleImportSuccess = useCallback((importedEntitySlug, importedCategorySlug, importedAsset) => {
         handleCloseImportModal();
+        if (importedAsset) console.log(`Imported asset ${importedAsset.asset_id} "${importedAsset.name}" at ${importedAsset.folder_name}`);
         if (importedEntitySlug && location.pathname === `/entity/${importedEntitySlug}`) {
             window.location.reload();
         } else if (importedEntitySlug) {
@@ -333,6 +389,39 @@ function Sidebar() {
             <button className="btn btn-outline" style={{ width: '100%', marginBottom: '15px' }} onClick={() => handleInitiateImport()} disabled={!modsFolder || isActionDisabled} title={!modsFolder ? "Set Mods Folder path first" : "Import Mod from Archive"} >
                  <i className="fas fa-file-import fa-fw"></i> Import Mod
             </button>
+            <button className="btn btn-outline" style={{ width: '100%', marginBottom: '15px' }} onClick={() => setShowUrlImport(prev => !prev)} disabled={!modsFolder || isActionDisabled || isDownloading} title={!modsFolder ? "Set Mods Folder path first" : "Import Mod from a download link or GameBanana page"} >
+                 <i className="fas fa-link fa-fw"></i> Import from URL
+            </button>
+            {(showUrlImport || isDownloading) && (
+                <div style={{ display: 'flex', gap: '6px', marginBottom: '15px' }}>
+                    <input
+                        type="text"
+                        value={importUrl}
+                        onChange={(e) => setImportUrl(e.target.value)}
+                        onKeyDown={(e) => { if (e.key === 'Enter') handleImportFromUrl(); }}
+                        placeholder="https://gamebanana.com/mods/..."
+                        disabled={isDownloading}
+                        style={{ flexGrow: 1, minWidth: 0, padding: '8px 10px', background: 'rgba(0,0,0,0.2)', border: '1px solid rgba(255,255,255,0.1)', borderRadius: '6px', color: 'var(--light)', fontSize: '12px' }}
+                    />
+                    <button className="btn btn-primary" onClick={handleImportFromUrl} disabled={!importUrl.trim() || isDownloading} title="Download and import" style={{ padding: '8px 10px' }}>
+                        {isDownloading ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-download fa-fw"></i>}
+                    </button>
+                </div>
+            )}
+            {isDownloading && downloadProgress && (
+                <p style={{ fontSize: '12px', textAlign: 'center', marginBottom: '10px', color: 'rgba(255,255,255,0.7)' }}>
+                    {downloadProgress.total
+                        ? `Downloading... ${Math.floor((downloadProgress.downloaded / downloadProgress.total) * 100)}%`
+                        : `Downloading... ${(downloadProgress.downloaded / (1024 * 1024)).toFixed(1)} MB`}
+                </p>
+            )}
+            {analyzeProgress && (
+                <p style={{ fontSize: '12px', textAlign: 'center', marginBottom: '10px', color: 'rgba(255,255,255,0.7)' }} title={analyzeProgress.current_file}>
+                    {analyzeProgress.total
+                        ? `Analyzing... ${analyzeProgress.processed} / ${analyzeProgress.total} files`
+                        : `Analyzing... ${analyzeProgress.processed} files`}
+                </p>
+            )}
              {/* Show Import or Drop errors */}
              {(importError || dropError) && <p style={{color: 'var(--danger)', fontSize:'12px', textAlign:'center', marginBottom:'10px'}}>{importError || dropError}</p>}
              {launchError && <p style={{color: 'var(--danger)', fontSize:'12px', textAlign:'center', marginBottom:'10px'}}>{launchError}</p>}
@@ -381,7 +470,7 @@ function Sidebar() {
             </div>
 
              {/* Import Modal */}
-            {isImportModalOpen && importAnalysisResult && ( <ImportModModal analysisResult={importAnalysisResult} onClose={handleCloseImportModal} onImportSuccess={handleImportSuccess} /> )}
+            {isImportModalOpen && importAnalysisResult && ( <ImportModModal analysisResult={importAnalysisResult} sourceUrl={importSourceUrl} onClose={handleCloseImportModal} onImportSuccess={handleImportSuccess} /> )}
 
              {/* Apply Progress Popup (Sidebar) */}
             <ScanProgressPopup
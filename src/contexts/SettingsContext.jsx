@@ -1,6 +1,7 @@
 // src/contexts/SettingsContext.jsx
 import React, { createContext, useState, useEffect, useContext, useCallback } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
+import { ask } from '@tauri-apps/api/dialog';
 
 const SettingsContext = createContext(null);
 
@@ -46,7 +47,19 @@ export function SettingsProvider({ children }) {
 
     const updateSetting = useCallback(async (key, value) => {
         try {
-            await invoke('set_setting', { key, value });
+            if (key === SETTINGS_KEY_MODS_FOLDER) {
+                // Mods folder goes through backend validation (exists, is a dir, writable)
+                try {
+                    await invoke('set_mods_folder', { path: value });
+                } catch (err) {
+                    if (err?.kind !== 'NotFound') throw err;
+                    const create = await ask(`The folder "${value}" does not exist.\nDo you want to create it?`, { title: 'Create Mods Folder', type: 'warning' });
+                    if (!create) return false;
+                    await invoke('set_mods_folder', { path: value, createIfMissing: true });
+                }
+            } else {
+                await invoke('set_setting', { key, value });
+            }
             // Update local state after successful save
             if (key === SETTINGS_KEY_MODS_FOLDER) {
                 setModsFolder(value);
@@ -58,7 +71,7 @@ export function SettingsProvider({ children }) {
             return true; // Indicate success
         } catch (err) {
             console.error(`Failed to set setting ${key}:`, err);
-            setError(`Failed to save setting: ${key}`);
+            setError(`Failed to save setting: ${key}${err?.message ? ` (${err.message})` : ''}`);
             return false; // Indicate failure
         }
     }, []);
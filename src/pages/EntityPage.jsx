@@ -2,10 +2,13 @@
 import React, { useState, useEffect, useCallback, useMemo, useRef } from 'react';
 import { useParams, useNavigate } from 'react-router-dom';
 import { invoke } from '@tauri-apps/api/tauri';
+import { listen } from '@tauri-apps/api/event';
+import { ask } from '@tauri-apps/api/dialog';
 import ModCard from '../components/ModCard';
 import ModEditModal from '../components/ModEditModal';
 import ConfirmationModal from '../components/ConfirmationModal';
 import { getLocalStorageItem, setLocalStorageItem } from '../utils/localStorage';
+import { getUiPrefs, updateUiPrefs } from '../utils/uiPrefs';
 import ModCardSkeleton from '../components/ModCardSkeleton';
 import { FixedSizeList, FixedSizeGrid } from 'react-window';
 import useMeasure from 'react-use-measure';
@@ -30,10 +33,11 @@ const getWikiUrl = (entityName, activeGame) => {
     return `${baseUrl}${formattedName}`;
 };
 
-// Helper function to parse details JSON
+// Helper function to parse details JSON (get_entity_details already returns an object)
 const parseDetails = (detailsJson) => {
     try {
         if (!detailsJson) return {};
+        if (typeof detailsJson === 'object') return detailsJson;
         return JSON.parse(detailsJson);
     } catch (e) {
         console.error("Failed to parse entity details JSON:", e);
@@ -41,6 +45,13 @@ const parseDetails = (detailsJson) => {
     }
 };
 
+const formatBytes = (bytes) => {
+    if (!bytes) return '0 B';
+    const units = ['B', 'KB', 'MB', 'GB'];
+    const exponent = Math.min(Math.floor(Math.log(bytes) / Math.log(1024)), units.length - 1);
+    return `${(bytes / Math.pow(1024, exponent)).toFixed(exponent === 0 ? 0 : 1)} ${units[exponent]}`;
+};
+
 const getRarityColor = (value) => {
     if (!value) return '#888'; // Default color for unknown rarity
     const val = value.toLowerCase();
@@ -207,6 +218,7 @@ const SORT_OPTIONS = [
     { value: 'id-asc', label: 'Date Added (Oldest First)' },
     { value: 'enabled-desc', label: 'Status (Enabled First)' },
     { value: 'enabled-asc', label: 'Status (Disabled First)' },
+    { value: 'custom', label: 'Custom Order (drag in list view)' },
 ];
 const DEFAULT_SORT_OPTION = 'name-asc';
 // ----------------------
@@ -232,6 +244,7 @@ function EntityPage() {
     // ---------------------
     // --- Type filtering ---
     const [activeTypeFilters, setActiveTypeFilters] = useState([]);
+    const [draggedAssetId, setDraggedAssetId] = useState(null);
     const [availableTypes, setAvailableTypes] = useState([]);
     // ---------------------
     const [listContainerRef, bounds] = useMeasure();
@@ -288,10 +301,11 @@ function EntityPage() {
 
     // Fetch data (includes loading view mode and sort option)
     const fetchData = useCallback(async () => {
-        const savedViewMode = getLocalStorageItem(VIEW_MODE_STORAGE_KEY, 'grid');
-        setViewMode(savedViewMode);
-        // --- Load saved sort option ---
-        const savedSort = getLocalStorageItem(sortStorageKey, DEFAULT_SORT_OPTION);
+        const uiPrefs = await getUiPrefs();
+        // Older versions kept the view mode in localStorage only
+        setViewMode(uiPrefs.asset_view_mode || getLocalStorageItem(VIEW_MODE_STORAGE_KEY, 'grid'));
+        // --- Load saved sort option (per entity, falling back to the last sort picked anywhere) ---
+        const savedSort = getLocalStorageItem(sortStorageKey, uiPrefs.asset_sort || DEFAULT_SORT_OPTION);
         setSortOption(savedSort);
         // ---------------------------------
 
@@ -322,7 +336,7 @@ function EntityPage() {
         } catch (err) {
             const errorString = typeof err === 'string' ? err : (err?.message || 'Unknown error');
             console.error(`[EntityPage ${entitySlug}] Failed to load data:`, errorString);
-            if (errorString.includes("not found")) setError(`Entity '${entitySlug}' not found.`);
+            if (err?.kind === 'NotFound') setError(`Entity '${entitySlug}' not found.`);
             else setError(`Could not load details or mods for ${entitySlug}. Details: ${errorString}`);
         } finally {
             setLoading(false);
@@ -356,7 +370,7 @@ function EntityPage() {
         setAssets(currentAssets =>
             currentAssets.map(asset => {
                 if (asset.id === assetId) {
-                    const updatedAsset = { ...asset, is_enabled: newIsEnabledState };
+                    const updatedAsset = { ...asset, is_enabled: newIsEnabledState, state_source: 'app' };
                     return updatedAsset;
                 }
                 return asset;
@@ -372,6 +386,32 @@ function EntityPage() {
 
     }, [entitySlug]);
 
+    // Keep cards in sync when a mod is toggled through its global hotkey
+    useEffect(() => {
+        let unlisten = null;
+        listen('hotkey://asset-toggled', (event) => {
+            const { asset_id, accelerator, new_enabled_state, error } = event.payload;
+            if (error) {
+                toast.error(`Hotkey ${accelerator} failed: ${error}`);
+                return;
+            }
+            handleToggleComplete(asset_id, new_enabled_state);
+        }).then(fn => { unlisten = fn; });
+        return () => { if (unlisten) unlisten(); };
+    }, [handleToggleComplete]);
+
+    // Stored enabled states are trusted on load; refresh quietly if a background reconcile found them out of date
+    useEffect(() => {
+        let unlisten = null;
+        listen('assets://state-drift', (event) => {
+            if (!Array.isArray(event.payload) || !event.payload.includes(entitySlug)) return;
+            invoke('get_assets_for_entity', { entitySlug })
+                .then(setAssets)
+                .catch(err => console.error(`[EntityPage ${entitySlug}] Failed to refresh assets after state drift:`, err));
+        }).then(fn => { unlisten = fn; });
+        return () => { if (unlisten) unlisten(); };
+    }, [entitySlug]);
+
     // goBack function
     const goBack = () => {
         if (window.history.length > 2) {
@@ -450,7 +490,7 @@ function EntityPage() {
     const toggleViewMode = (newMode) => {
         if (newMode !== viewMode) {
             setViewMode(newMode);
-            setLocalStorageItem(VIEW_MODE_STORAGE_KEY, newMode); // Save preference globally
+            updateUiPrefs({ asset_view_mode: newMode }); // Save preference globally
             setSelectedAssetIds(new Set()); // Clear selection when changing view mode
         }
     };
@@ -460,6 +500,7 @@ function EntityPage() {
         const newSortOption = event.target.value;
         setSortOption(newSortOption);
         setLocalStorageItem(sortStorageKey, newSortOption);
+        updateUiPrefs({ asset_sort: newSortOption });
     }, [sortStorageKey]);
     // --------------------------------
 
@@ -509,14 +550,16 @@ function EntityPage() {
                     return a.name.localeCompare(b.name);
                 case 'name-desc':
                     return b.name.localeCompare(a.name);
-                case 'id-desc': // Newest first
-                    return b.id - a.id;
+                case 'id-desc': // Newest first (created_at, falling back to insertion order)
+                    return ((b.created_at ?? 0) - (a.created_at ?? 0)) || (b.id - a.id);
                 case 'id-asc': // Oldest first
-                    return a.id - b.id;
+                    return ((a.created_at ?? 0) - (b.created_at ?? 0)) || (a.id - b.id);
                 case 'enabled-desc': // Enabled first (true > false)
                     return (b.is_enabled === a.is_enabled) ? 0 : b.is_enabled ? 1 : -1;
                 case 'enabled-asc': // Disabled first (false < true)
                     return (a.is_enabled === b.is_enabled) ? 0 : a.is_enabled ? 1 : -1;
+                case 'custom': // User-arranged order, unarranged mods last by name
+                    return ((a.sort_order ?? Number.MAX_SAFE_INTEGER) - (b.sort_order ?? Number.MAX_SAFE_INTEGER)) || a.name.localeCompare(b.name);
                 default:
                     return a.name.localeCompare(b.name); // Fallback
             }
@@ -624,6 +667,90 @@ function EntityPage() {
             .catch(err => console.error("Failed refetch entity details after bulk toggle:", err));
     };
 
+    // Picks up mods dropped into this entity's folder without running a full scan
+    const handleRescanEntity = async () => {
+        setIsBulkProcessing(true);
+        try {
+            const result = await invoke('scan_entity', { entitySlug });
+            const summary = `Added ${result.inserted} new mod${result.inserted === 1 ? '' : 's'}.`;
+            if (result.errors.length === 0) {
+                toast.success(summary);
+            } else {
+                toast.warning(`${summary} ${result.errors.length} failed: ${result.errors[0]}`, { autoClose: 8000 });
+            }
+            if (result.inserted > 0) fetchData();
+        } catch (err) {
+            toast.error(`Rescan failed: ${err?.message || String(err)}`);
+        } finally {
+            setIsBulkProcessing(false);
+        }
+    };
+
+    // Merges the second selected mod into the first (in list order); offers nesting on file collisions
+    const handleMergeSelected = async () => {
+        if (selectedAssetIds.size !== 2 || isBulkProcessing) return;
+        const [primary, secondary] = filteredAndSortedAssets.filter(a => selectedAssetIds.has(a.id));
+        if (!primary || !secondary) return;
+        const confirmed = await ask(
+            `Move all files of "${secondary.name}" into "${primary.name}" and remove "${secondary.name}" from the library?\n\n"${primary.name}" keeps its name, description and preview.`,
+            { title: 'Merge Mods', type: 'warning' }
+        );
+        if (!confirmed) return;
+
+        setIsBulkProcessing(true);
+        try {
+            let result;
+            try {
+                result = await invoke('merge_assets', { primaryId: primary.id, secondaryId: secondary.id, nest: false });
+            } catch (err) {
+                const message = err?.message || String(err);
+                if (err?.kind !== 'ModOperation' || !message.includes('already exist')) throw err;
+                const nest = await ask(`${message}\n\nMerge into a "${secondary.name}" subfolder instead?`, { title: 'File Conflicts', type: 'warning' });
+                if (!nest) return;
+                result = await invoke('merge_assets', { primaryId: primary.id, secondaryId: secondary.id, nest: true });
+            }
+            toast.success(`Merged "${secondary.name}" into "${primary.name}" (${result.moved_files} files${result.nested_folder ? ` in ${result.nested_folder}/` : ''}).`);
+            setSelectedAssetIds(new Set());
+            fetchData();
+        } catch (err) {
+            console.error("Failed to merge mods:", err);
+            toast.error(`Merge failed: ${err?.message || String(err)}`);
+        } finally {
+            setIsBulkProcessing(false);
+        }
+    };
+
+    // Deletes every selected mod after a single confirmation; failures don't stop the rest
+    const handleBulkDelete = async () => {
+        if (selectedAssetIds.size === 0 || isBulkProcessing) return;
+        const count = selectedAssetIds.size;
+        const confirmed = await ask(
+            `Permanently delete ${count} selected mod${count === 1 ? '' : 's'} and their folders?\n\nThis cannot be undone.`,
+            { title: 'Delete Mods', type: 'warning' }
+        );
+        if (!confirmed) return;
+
+        setIsBulkProcessing(true);
+        try {
+            const result = await invoke('delete_assets', { assetIds: Array.from(selectedAssetIds) });
+            const failed = result.results.filter(r => !r.success);
+            failed.forEach(r => console.error(`Bulk delete failed for asset ${r.asset_id}:`, r.error));
+            const summary = `Deleted ${result.deleted} mod${result.deleted === 1 ? '' : 's'}, freed ${formatBytes(result.bytes_freed)}.`;
+            if (failed.length === 0) {
+                toast.success(summary);
+            } else {
+                toast.warning(`${summary} ${failed.length} failed: ${failed[0].error}`, { autoClose: 8000 });
+            }
+            setSelectedAssetIds(new Set());
+            fetchData();
+        } catch (err) {
+            console.error("Failed to delete mods:", err);
+            toast.error(`Delete failed: ${err?.message || String(err)}`);
+        } finally {
+            setIsBulkProcessing(false);
+        }
+    };
+
     // --- End Bulk Action Handlers ---
 
     const handleShowContextMenu = useCallback((event, asset) => {
@@ -711,7 +838,21 @@ function EntityPage() {
                     try {
                         await invoke('open_asset_folder', { assetId: contextMenuAsset.id });
                     } catch (err) {
-                        toast.error(`Failed to open folder: ${err}`);
+                        toast.error(`Failed to open folder: ${err?.message || err}`);
+                    }
+                }
+            },
+            {
+                label: 'Copy Folder Path',
+                icon: 'fas fa-copy',
+                onClick: async () => {
+                    handleCloseContextMenu();
+                    try {
+                        const path = await invoke('get_asset_path', { assetId: contextMenuAsset.id });
+                        await navigator.clipboard.writeText(path);
+                        toast.success('Mod folder path copied to clipboard.');
+                    } catch (err) {
+                        toast.error(`Failed to get folder path: ${err?.message || err}`);
                     }
                 }
             },
@@ -746,11 +887,31 @@ function EntityPage() {
     }, [contextMenuAsset, handleCloseContextMenu, handleOpenAddToPresetModal, handleOpenEditModal, handleOpenDeleteModal]);
     // --------------------------------
 
+    // Reordering only applies to the full custom-sorted list
+    const canArrangeAssets = sortOption === 'custom' && !modSearchTerm && activeTypeFilters.length === 0;
+
+    const handleAssetDrop = (targetAssetId) => {
+        if (draggedAssetId === null || draggedAssetId === targetAssetId) return;
+        const orderedIds = filteredAndSortedAssets.map(a => a.id).filter(id => id !== draggedAssetId);
+        orderedIds.splice(orderedIds.indexOf(targetAssetId), 0, draggedAssetId);
+        const positions = new Map(orderedIds.map((id, position) => [id, position]));
+        setAssets(prev => prev.map(a => ({ ...a, sort_order: positions.get(a.id) ?? a.sort_order })));
+        invoke('set_asset_order', { entitySlug, orderedIds })
+            .catch(err => toast.error(`Failed to save mod order: ${err?.message || String(err)}`));
+    };
+
     const ListItem = ({ index, style }) => {
         const asset = filteredAndSortedAssets[index]; // Use sorted/filtered list
         const isSelected = selectedAssetIds.has(asset.id);
+        const dragProps = canArrangeAssets ? {
+            draggable: true,
+            onDragStart: () => setDraggedAssetId(asset.id),
+            onDragOver: (e) => e.preventDefault(),
+            onDrop: () => handleAssetDrop(asset.id),
+            onDragEnd: () => setDraggedAssetId(null),
+        } : {};
         return (
-            <div style={style}>
+            <div style={{ ...style, opacity: draggedAssetId === asset.id ? 0.5 : 1 }} {...dragProps}>
                 <ModCard
                     key={asset.id}
                     asset={asset}
@@ -1088,6 +1249,14 @@ function EntityPage() {
                                                 <button className="btn btn-outline" onClick={() => handleBulkToggle(false)} disabled={isBulkProcessing} title="Disable selected mods">
                                                     {isBulkProcessing ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-times fa-fw"></i>} Disable ({selectedAssetIds.size})
                                                 </button>
+                                                <button className="btn btn-outline" onClick={handleBulkDelete} disabled={isBulkProcessing} title="Delete selected mods">
+                                                    <i className="fas fa-trash-alt fa-fw"></i> Delete ({selectedAssetIds.size})
+                                                </button>
+                                                {selectedAssetIds.size === 2 && (
+                                                    <button className="btn btn-outline" onClick={handleMergeSelected} disabled={isBulkProcessing} title="Merge the second selected mod into the first">
+                                                        <i className="fas fa-object-group fa-fw"></i> Merge
+                                                    </button>
+                                                )}
                                             </div>
                                         )}
 
@@ -1155,6 +1324,9 @@ function EntityPage() {
                                                 <input type="text" placeholder={`Search mods...`} value={modSearchTerm} onChange={(e) => setModSearchTerm(e.target.value)} aria-label={`Search mods`} data-global-search="true" />
                                             </div>
                                         </div>
+                                        <button className="btn-icon" onClick={handleRescanEntity} disabled={isBulkProcessing} title={`Rescan ${entity.name}'s mods folder`}>
+                                            <i className={`fas ${isBulkProcessing ? 'fa-spinner fa-spin' : 'fa-sync-alt'} fa-fw`}></i>
+                                        </button>
                                         <div className="view-mode-toggle">
                                             <button className={`btn-icon ${viewMode === 'grid' ? 'active' : ''}`} onClick={() => toggleViewMode('grid')} title="Grid View"><i className="fas fa-th fa-fw"></i></button>
                                             <button className={`btn-icon ${viewMode === 'list' ? 'active' : ''}`} onClick={() => toggleViewMode('list')} title="List View"><i className="fas fa-list fa-fw"></i></button>
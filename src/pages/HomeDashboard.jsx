@@ -64,6 +64,7 @@ function HomeDashboard() {
 
     // --- Game Switching State ---
     const [availableGames, setAvailableGames] = useState([]);
+    const [gameProfiles, setGameProfiles] = useState([]);
     const [activeGame, setActiveGame] = useState('');
     const [isSwitchingGame, setIsSwitchingGame] = useState(false);
     const [gameSwitchError, setGameSwitchError] = useState('');
@@ -86,11 +87,12 @@ function HomeDashboard() {
         setGameSwitchError(''); // Clear game switch error on data refresh
 
         try {
-            const [statsResult, versionResult, gamesResult, activeGameResult] = await Promise.allSettled([
+            const [statsResult, versionResult, gamesResult, activeGameResult, profilesResult] = await Promise.allSettled([
                 invoke('get_dashboard_stats'),
                 invoke('get_app_version'),
                 invoke('get_available_games'),
-                invoke('get_active_game')
+                invoke('get_active_game'),
+                invoke('get_game_profiles')
             ]);
 
             // Handle Stats
@@ -119,6 +121,14 @@ function HomeDashboard() {
                 setAvailableGames([]);
             }
 
+            // Per-game mods folder/count, only used for tooltips
+            if (profilesResult.status === 'fulfilled') {
+                setGameProfiles(profilesResult.value || []);
+            } else {
+                console.warn("Error fetching game profiles:", profilesResult.reason);
+                setGameProfiles([]);
+            }
+
              // Handle Active Game
              if (activeGameResult.status === 'fulfilled') {
                  setActiveGame(activeGameResult.value || '');
@@ -258,6 +268,7 @@ function HomeDashboard() {
                         {/* Replace the dropdown with our new GameSwitcher component */}
                         <GameSwitcher
                             availableGames={availableGames}
+                            profiles={gameProfiles}
                             activeGame={activeGame}
                             onGameSwitch={handleGameSwitch}
                             isLoading={isSwitchingGame}
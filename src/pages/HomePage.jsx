@@ -1,9 +1,10 @@
 // src/pages/HomePage.jsx
-import React, { useState, useEffect, useMemo, useCallback } from 'react';
+import React, { useState, useEffect, useMemo, useCallback, useRef } from 'react';
 import { useParams } from 'react-router-dom';
 import { invoke } from '@tauri-apps/api/tauri';
 import EntityCard from '../components/EntityCard';
 import { getLocalStorageItem, setLocalStorageItem } from '../utils/localStorage';
+import { getUiPrefs, updateUiPrefs } from '../utils/uiPrefs';
 import EntityCardSkeleton from '../components/EntityCardSkeleton';
 
 // Element data for Genshin
@@ -60,6 +61,7 @@ const sortOptions = [
     { value: 'total-asc', label: 'Total Mods (Low-High)' },
     { value: 'enabled-desc', label: 'Enabled Mods (High-Low)' },
     { value: 'enabled-asc', label: 'Enabled Mods (Low-High)' },
+    { value: 'custom', label: 'Custom Order (drag to arrange)' },
 ];
 const DEFAULT_SORT_OPTION = 'name-asc';
 const OTHER_ENTITY_SUFFIX = '-other'; // Make sure this matches backend
@@ -76,8 +78,18 @@ function HomePage() {
     const [selectedAttribute, setSelectedAttribute] = useState('all');
     const [selectedResonatorAttr, setSelectedResonatorAttr] = useState('all');
     const [sortOption, setSortOption] = useState(DEFAULT_SORT_OPTION);
+    const [draggedSlug, setDraggedSlug] = useState(null);
     const [activeGame, setActiveGame] = useState('genshin');
     const sortStorageKey = `categorySort_${categorySlug}`;
+    const [hideEmpty, setHideEmpty] = useState(null); // null until UI preferences are loaded
+    const uiPrefsRef = useRef({});
+
+    useEffect(() => {
+        getUiPrefs().then(prefs => {
+            uiPrefsRef.current = prefs;
+            setHideEmpty(!!prefs.hide_empty_entities);
+        });
+    }, []);
 
     // Fetch Active Game Info
     useEffect(() => {
@@ -96,19 +108,21 @@ function HomePage() {
 
     // Fetch Category Info and Entities with Counts
     useEffect(() => {
+        if (hideEmpty === null) return; // Wait for preferences so the list is fetched once
         setLoadingEntities(true);
         setError(null);
         setEntitiesWithCounts([]); // Clear previous entities
         setSelectedElement('all');
         setSelectedAttribute('all');
         setSearchTerm('');
-        const savedSort = getLocalStorageItem(sortStorageKey, DEFAULT_SORT_OPTION);
+        const savedSort = getLocalStorageItem(sortStorageKey, uiPrefsRef.current.entity_sort || DEFAULT_SORT_OPTION);
         setSortOption(savedSort);
+        updateUiPrefs({ last_category: categorySlug });
         // Simple category name update, could fetch real name later if needed
         setCategoryInfo({ name: categorySlug ? categorySlug.charAt(0).toUpperCase() + categorySlug.slice(1) : 'Unknown', id: null });
 
         // Call the new backend command
-        invoke('get_entities_by_category_with_counts', { categorySlug })
+        invoke('get_entities_by_category_with_counts', { categorySlug, onlyWithMods: hideEmpty })
             .then(fetchedData => {
                 // Add console log to verify data structure
                 setEntitiesWithCounts(fetchedData || []); // Ensure it's an array
@@ -119,13 +133,21 @@ function HomePage() {
             })
             .finally(() => setLoadingEntities(false));
 
-    }, [categorySlug, sortStorageKey, activeGame]); // Dependencies for fetching data
+    }, [categorySlug, sortStorageKey, activeGame, hideEmpty]); // Dependencies for fetching data
+
+    // Handle Hide Empty Toggle
+    const handleHideEmptyChange = (event) => {
+        setHideEmpty(event.target.checked);
+        updateUiPrefs({ hide_empty_entities: event.target.checked });
+    };
 
     // Handle Sort Change
     const handleSortChange = (event) => {
         const newSortOption = event.target.value;
         setSortOption(newSortOption);
         setLocalStorageItem(sortStorageKey, newSortOption);
+        uiPrefsRef.current = { ...uiPrefsRef.current, entity_sort: newSortOption };
+        updateUiPrefs({ entity_sort: newSortOption }); // Becomes the default for categories without their own choice
     };
 
     // Memoized filtered AND sorted list
@@ -181,6 +203,7 @@ function HomePage() {
                 case 'total-asc': return (a.total_mods ?? 0) - (b.total_mods ?? 0);
                 case 'enabled-desc': return (b.enabled_mods ?? 0) - (a.enabled_mods ?? 0);
                 case 'enabled-asc': return (a.enabled_mods ?? 0) - (b.enabled_mods ?? 0);
+                case 'custom': return ((a.sort_order ?? Number.MAX_SAFE_INTEGER) - (b.sort_order ?? Number.MAX_SAFE_INTEGER)) || a.name.localeCompare(b.name);
                 default: return a.name.localeCompare(b.name); // Fallback to name ascending
             }
         });
@@ -189,6 +212,19 @@ function HomePage() {
     }, [entitiesWithCounts, searchTerm, selectedElement, selectedAttribute, selectedResonatorAttr, categorySlug, sortOption, activeGame]); // Dependencies for memoization
 
 
+    // Dragging only makes sense on the full, unfiltered custom-ordered list
+    const canArrange = sortOption === 'custom' && !searchTerm && selectedElement === 'all' && selectedAttribute === 'all' && selectedResonatorAttr === 'all';
+
+    const handleArrangeDrop = useCallback((targetSlug) => {
+        if (!draggedSlug || draggedSlug === targetSlug || targetSlug.endsWith(OTHER_ENTITY_SUFFIX)) return;
+        const orderedSlugs = filteredAndSortedEntities.map(e => e.slug).filter(slug => !slug.endsWith(OTHER_ENTITY_SUFFIX) && slug !== draggedSlug);
+        orderedSlugs.splice(orderedSlugs.indexOf(targetSlug), 0, draggedSlug);
+        const positions = new Map(orderedSlugs.map((slug, index) => [slug, index]));
+        setEntitiesWithCounts(prev => prev.map(e => ({ ...e, sort_order: positions.has(e.slug) ? positions.get(e.slug) : e.sort_order })));
+        invoke('set_entity_order', { categorySlug, orderedSlugs })
+            .catch(err => console.error(`Failed to save entity order for ${categorySlug}:`, err));
+    }, [draggedSlug, filteredAndSortedEntities, categorySlug]);
+
     const pageTitle = categoryInfo.name; // Use state for title
     const showElementFilters = categorySlug === 'characters' && activeGame === 'genshin';
     const showAttributeFilters = categorySlug === 'characters' && activeGame === 'zzz';
@@ -208,6 +244,10 @@ function HomePage() {
                     <select id="sort-select" value={sortOption} onChange={handleSortChange} style={styles.sortSelect} aria-label="Sort entities">
                         {sortOptions.map(option => ( <option key={option.value} value={option.value}>{option.label}</option> ))}
                     </select>
+                    <label style={{ ...styles.sortLabel, marginLeft: '15px', cursor: 'pointer' }} title="Hide entities that have no mods">
+                        <input type="checkbox" checked={!!hideEmpty} onChange={handleHideEmptyChange} style={{ marginRight: '5px' }} />
+                        Hide empty
+                    </label>
                 </div>
 
                  {/* Element Filters (Conditional for Genshin) */}
@@ -292,7 +332,21 @@ function HomePage() {
                 <div className="cards-grid">
                     {filteredAndSortedEntities.length > 0 ? (
                         filteredAndSortedEntities.map(entityData => (
-                            <EntityCard key={entityData.slug} entity={entityData} />
+                            canArrange && !entityData.slug.endsWith(OTHER_ENTITY_SUFFIX) ? (
+                                <div
+                                    key={entityData.slug}
+                                    draggable
+                                    onDragStart={() => setDraggedSlug(entityData.slug)}
+                                    onDragOver={(e) => e.preventDefault()}
+                                    onDrop={() => handleArrangeDrop(entityData.slug)}
+                                    onDragEnd={() => setDraggedSlug(null)}
+                                    style={{ opacity: draggedSlug === entityData.slug ? 0.5 : 1, cursor: 'grab' }}
+                                >
+                                    <EntityCard entity={entityData} />
+                                </div>
+                            ) : (
+                                <EntityCard key={entityData.slug} entity={entityData} />
+                            )
                         ))
                     ) : entitiesWithCounts.length > 0 ? (
                         <p className="placeholder-text" style={{ gridColumn: '1 / -1' }}>
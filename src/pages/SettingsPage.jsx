@@ -3,6 +3,7 @@ import React, { useState, useEffect, useCallback, useRef } from 'react';
 import { useSettings } from '../contexts/SettingsContext';
 import { invoke } from '@tauri-apps/api/tauri';
 import { listen } from '@tauri-apps/api/event';
+import { ask, open, save } from '@tauri-apps/api/dialog';
 import ScanProgressPopup from '../components/ScanProgressPopup';
 import { toast } from 'react-toastify';
 
@@ -10,6 +11,25 @@ import { toast } from 'react-toastify';
 const SCAN_PROGRESS_EVENT = "scan://progress";
 const SCAN_COMPLETE_EVENT = "scan://complete";
 const SCAN_ERROR_EVENT = "scan://error";
+const SETTINGS_KEY_SCAN_IGNORE_PATTERNS = "scan_ignore_patterns";
+const SETTINGS_KEY_SCAN_THREADS = "scan_threads";
+const SCAN_THREAD_OPTIONS = [
+    { value: '0', label: 'Auto' }, { value: '1', label: '1' }, { value: '2', label: '2' },
+    { value: '4', label: '4' }, { value: '8', label: '8' },
+];
+const SETTINGS_KEY_AUTO_SCAN_ON_STARTUP = "auto_scan_on_startup";
+const SETTINGS_KEY_DISABLE_STRATEGY = "disable_strategy";
+const DISABLE_STRATEGY_OPTIONS = [
+    { value: 'folder', label: 'Rename folder' }, { value: 'ini', label: 'Rename INI files' },
+];
+const SETTINGS_KEY_FALLBACK_CATEGORY = "fallback_category";
+const SETTINGS_KEY_PREVIEW_STORAGE = "preview_storage";
+const PREVIEW_STORAGE_OPTIONS = [
+    { value: 'mod_folder', label: 'Mod folder' }, { value: 'app', label: 'App data' },
+];
+const SETTINGS_KEY_NAME_CLEANUP_PATTERNS = "name_cleanup_patterns";
+const DEFAULT_CLEANUP_SAMPLE = "RaidenShogun_Bikini_v2_byAuthor";
+const BACKUP_PROGRESS_EVENT = "backup://progress";
 // Add pruning events if you want specific UI updates for them
 // const PRUNING_START_EVENT = "prune://start";
 // const PRUNING_COMPLETE_EVENT = "prune://complete";
@@ -39,10 +59,41 @@ function SettingsPage() {
 
     // State for the manual scan button and its popup
     const [isManualScanning, setIsManualScanning] = useState(false);
+    const [isVerifyingState, setIsVerifyingState] = useState(false);
+    const [isNormalizingPrefixes, setIsNormalizingPrefixes] = useState(false);
+    const [isRepairingOtherEntities, setIsRepairingOtherEntities] = useState(false);
+    const [isCheckingIntegrity, setIsCheckingIntegrity] = useState(false);
+    const [isReloadingDefinitions, setIsReloadingDefinitions] = useState(false);
+    const [isCheckingUpdates, setIsCheckingUpdates] = useState(false);
+    const [isBuildingScanReport, setIsBuildingScanReport] = useState(false);
+    const [isFindingOrphans, setIsFindingOrphans] = useState(false);
+    const [appInfo, setAppInfo] = useState(null);
+    const [scanThreads, setScanThreads] = useState('0');
+    const [autoScanOnStartup, setAutoScanOnStartup] = useState('false');
+    const [disableStrategy, setDisableStrategy] = useState('folder');
+    const [fallbackCategory, setFallbackCategory] = useState('');
+    const [previewStorage, setPreviewStorage] = useState('mod_folder');
+    const [categories, setCategories] = useState([]);
+    const [isOptimizingDb, setIsOptimizingDb] = useState(false);
+    const [backupOperation, setBackupOperation] = useState(null); // 'backup' | 'restore' while running
+    const [backupProgress, setBackupProgress] = useState(null);
     const [showScanPopup, setShowScanPopup] = useState(false);
     const [scanProgressData, setScanProgressData] = useState(null);
     const [scanSummary, setScanSummary] = useState('');
     const [scanError, setScanError] = useState('');
+    const [scanIgnorePatterns, setScanIgnorePatterns] = useState('');
+    const [savedScanIgnorePatterns, setSavedScanIgnorePatterns] = useState('');
+    const [isSavingIgnorePatterns, setIsSavingIgnorePatterns] = useState(false);
+    const [cleanupPatterns, setCleanupPatterns] = useState('');
+    const [savedCleanupPatterns, setSavedCleanupPatterns] = useState('');
+    const [cleanupSample, setCleanupSample] = useState(DEFAULT_CLEANUP_SAMPLE);
+    const [cleanupPreview, setCleanupPreview] = useState({ result: '', error: '' });
+    const [deductionRules, setDeductionRules] = useState([]);
+    const [allEntities, setAllEntities] = useState([]);
+    const [newRulePattern, setNewRulePattern] = useState('');
+    const [newRuleEntity, setNewRuleEntity] = useState('');
+    const [ruleSample, setRuleSample] = useState('');
+    const [ruleTestResult, setRuleTestResult] = useState(null); // Matching rule, or null
     const scanListenersRef = useRef({ unlistenProgress: null, unlistenComplete: null, unlistenError: null });
 
     // Effect to sync local input with context value when context loads/changes
@@ -52,6 +103,167 @@ function SettingsPage() {
         }
     }, [customLibraryUrl]);
 
+    useEffect(() => {
+        invoke('get_setting', { key: SETTINGS_KEY_SCAN_IGNORE_PATTERNS })
+            .then(value => { setScanIgnorePatterns(value || ''); setSavedScanIgnorePatterns(value || ''); })
+            .catch(err => console.error("Failed to load scan ignore patterns:", err));
+        invoke('get_setting', { key: SETTINGS_KEY_NAME_CLEANUP_PATTERNS })
+            .then(value => { setCleanupPatterns(value || ''); setSavedCleanupPatterns(value || ''); })
+            .catch(err => console.error("Failed to load name cleanup patterns:", err));
+        invoke('get_setting', { key: SETTINGS_KEY_SCAN_THREADS })
+            .then(value => setScanThreads(value || '0'))
+            .catch(err => console.error("Failed to load scan thread setting:", err));
+        invoke('get_setting', { key: SETTINGS_KEY_AUTO_SCAN_ON_STARTUP })
+            .then(value => setAutoScanOnStartup(value === 'true' ? 'true' : 'false'))
+            .catch(err => console.error("Failed to load auto-scan setting:", err));
+        invoke('get_setting', { key: SETTINGS_KEY_DISABLE_STRATEGY })
+            .then(value => setDisableStrategy(value || 'folder'))
+            .catch(err => console.error("Failed to load disable strategy setting:", err));
+        invoke('get_setting', { key: SETTINGS_KEY_PREVIEW_STORAGE })
+            .then(value => setPreviewStorage(value || 'mod_folder'))
+            .catch(err => console.error("Failed to load preview storage setting:", err));
+        invoke('get_setting', { key: SETTINGS_KEY_FALLBACK_CATEGORY })
+            .then(value => setFallbackCategory(value || ''))
+            .catch(err => console.error("Failed to load fallback category setting:", err));
+        invoke('get_categories')
+            .then(setCategories)
+            .catch(err => console.error("Failed to load categories:", err));
+        invoke('get_deduction_rules')
+            .then(setDeductionRules)
+            .catch(err => console.error("Failed to load entity rules:", err));
+        invoke('get_all_entities')
+            .then(setAllEntities)
+            .catch(err => console.error("Failed to load entities:", err));
+        invoke('get_app_info')
+            .then(setAppInfo)
+            .catch(err => console.error("Failed to load app info:", err));
+    }, []);
+
+    const handleScanThreadsChange = useCallback(async (e) => {
+        const value = e.target.value;
+        setScanThreads(value);
+        try {
+            await invoke('set_setting', { key: SETTINGS_KEY_SCAN_THREADS, value });
+        } catch (err) {
+            console.error("Failed to save scan thread setting:", err);
+            toast.error(`Failed to save scan threads: ${err?.message || String(err)}`);
+        }
+    }, []);
+
+    const handleAutoScanOnStartupChange = useCallback(async (e) => {
+        const value = e.target.value;
+        setAutoScanOnStartup(value);
+        try {
+            await invoke('set_setting', { key: SETTINGS_KEY_AUTO_SCAN_ON_STARTUP, value });
+        } catch (err) {
+            console.error("Failed to save auto-scan setting:", err);
+            toast.error(`Failed to save scan on startup: ${err?.message || String(err)}`);
+        }
+    }, []);
+
+    const handleDisableStrategyChange = useCallback(async (e) => {
+        const value = e.target.value;
+        setDisableStrategy(value);
+        try {
+            await invoke('set_setting', { key: SETTINGS_KEY_DISABLE_STRATEGY, value });
+        } catch (err) {
+            console.error("Failed to save disable strategy setting:", err);
+            toast.error(`Failed to save disable method: ${err?.message || String(err)}`);
+        }
+    }, []);
+
+    const handlePreviewStorageChange = useCallback(async (e) => {
+        const value = e.target.value;
+        setPreviewStorage(value);
+        try {
+            await invoke('set_setting', { key: SETTINGS_KEY_PREVIEW_STORAGE, value });
+        } catch (err) {
+            console.error("Failed to save preview storage setting:", err);
+            toast.error(`Failed to save preview location: ${err?.message || String(err)}`);
+        }
+    }, []);
+
+    const handleFallbackCategoryChange = useCallback(async (e) => {
+        const value = e.target.value;
+        setFallbackCategory(value);
+        try {
+            await invoke('set_setting', { key: SETTINGS_KEY_FALLBACK_CATEGORY, value });
+        } catch (err) {
+            console.error("Failed to save fallback category setting:", err);
+            toast.error(`Failed to save fallback category: ${err?.message || String(err)}`);
+        }
+    }, []);
+
+    const handleSaveIgnorePatterns = useCallback(async () => {
+        setIsSavingIgnorePatterns(true);
+        try {
+            await invoke('set_setting', { key: SETTINGS_KEY_SCAN_IGNORE_PATTERNS, value: scanIgnorePatterns });
+            setSavedScanIgnorePatterns(scanIgnorePatterns);
+            toast.success("Scan ignore list saved.");
+        } catch (err) {
+            console.error("Failed to save scan ignore patterns:", err);
+            toast.error(`Failed to save scan ignore list: ${err?.message || String(err)}`);
+        } finally {
+            setIsSavingIgnorePatterns(false);
+        }
+    }, [scanIgnorePatterns]);
+
+    // Live preview of the (unsaved) cleanup patterns against the sample name
+    useEffect(() => {
+        let isMounted = true;
+        const timer = setTimeout(() => {
+            invoke('preview_name_cleanup', { sample: cleanupSample, patterns: cleanupPatterns.split('\n') })
+                .then(result => { if (isMounted) setCleanupPreview({ result, error: '' }); })
+                .catch(err => { if (isMounted) setCleanupPreview({ result: '', error: err?.message || String(err) }); });
+        }, 250);
+        return () => { isMounted = false; clearTimeout(timer); };
+    }, [cleanupSample, cleanupPatterns]);
+
+    const handleSaveCleanupPatterns = useCallback(async () => {
+        try {
+            await invoke('set_setting', { key: SETTINGS_KEY_NAME_CLEANUP_PATTERNS, value: cleanupPatterns });
+            setSavedCleanupPatterns(cleanupPatterns);
+            toast.success("Name cleanup patterns saved.");
+        } catch (err) {
+            console.error("Failed to save name cleanup patterns:", err);
+            toast.error(`Failed to save name cleanup patterns: ${err?.message || String(err)}`);
+        }
+    }, [cleanupPatterns]);
+
+    const handleAddDeductionRule = useCallback(async () => {
+        try {
+            const rule = await invoke('add_deduction_rule', { pattern: newRulePattern, entitySlug: newRuleEntity });
+            setDeductionRules(rules => [...rules, rule]);
+            setNewRulePattern('');
+            toast.success(`Rule added: ${rule.pattern} → ${rule.entity_name}.`);
+        } catch (err) {
+            console.error("Failed to add entity rule:", err);
+            toast.error(`Failed to add rule: ${err?.message || String(err)}`);
+        }
+    }, [newRulePattern, newRuleEntity]);
+
+    const handleDeleteDeductionRule = useCallback(async (ruleId) => {
+        try {
+            await invoke('delete_deduction_rule', { ruleId });
+            setDeductionRules(rules => rules.filter(rule => rule.id !== ruleId));
+        } catch (err) {
+            console.error("Failed to delete entity rule:", err);
+            toast.error(`Failed to delete rule: ${err?.message || String(err)}`);
+        }
+    }, []);
+
+    // Which saved rule would claim the sample folder name
+    useEffect(() => {
+        if (!ruleSample.trim()) { setRuleTestResult(null); return; }
+        let isMounted = true;
+        const timer = setTimeout(() => {
+            invoke('test_deduction_rules', { sample: ruleSample })
+                .then(rule => { if (isMounted) setRuleTestResult(rule); })
+                .catch(err => console.error("Failed to test entity rules:", err));
+        }, 250);
+        return () => { isMounted = false; clearTimeout(timer); };
+    }, [ruleSample, deductionRules]);
+
     // --- Path Changing Logic ---
     const handleChangeModsFolder = useCallback(async () => {
         setIsChangingFolder(true);
@@ -120,6 +332,25 @@ function SettingsPage() {
         setScanError('');
 
         try {
+            const plannedChanges = await invoke('preview_scan');
+            const added = plannedChanges.filter(c => c.action === 'add');
+            const removed = plannedChanges.filter(c => c.action === 'remove');
+            const unresolved = plannedChanges.filter(c => c.action === 'unresolved');
+            if (plannedChanges.length > 0) {
+                const listed = added.slice(0, 10).map(c => `  + ${c.mod_name} → ${c.entity_name || c.entity_slug}`);
+                if (added.length > listed.length) listed.push(`  ...and ${added.length - listed.length} more`);
+                const message = [
+                    `${added.length} new mod(s) will be added, ${removed.length} missing mod(s) removed.`,
+                    unresolved.length > 0 ? `${unresolved.length} folder(s) could not be placed and will be skipped.` : null,
+                    listed.length > 0 ? `\n${listed.join('\n')}` : null,
+                    '\nContinue with the scan?',
+                ].filter(Boolean).join('\n');
+                const confirmed = await ask(message, { title: 'Review Scan Changes', type: 'info' });
+                if (!confirmed) {
+                    setIsManualScanning(false);
+                    return;
+                }
+            }
             await invoke('scan_mods_directory');
         } catch (err) {
             console.error("Failed to invoke scan command:", err);
@@ -130,6 +361,338 @@ function SettingsPage() {
         }
     }, []); // Keep dependencies minimal if the function itself doesn't rely on changing props/state
 
+    // Registers mod folders added by hand since the last scan, without pruning or re-checking anything else
+    const handleFindOrphanedMods = useCallback(async () => {
+        setIsFindingOrphans(true);
+        try {
+            const orphans = await invoke('find_orphaned_mods');
+            const resolved = orphans.filter(o => o.action === 'add');
+            const unresolved = orphans.length - resolved.length;
+            if (resolved.length === 0) {
+                toast.info(unresolved > 0
+                    ? `${unresolved} unregistered folder(s) found, but none could be matched to an entity.`
+                    : 'Every mod folder is already in the library.');
+                return;
+            }
+            const listed = resolved.slice(0, 10).map(o => `  + ${o.mod_name} → ${o.entity_name || o.entity_slug}`);
+            if (resolved.length > listed.length) listed.push(`  ...and ${resolved.length - listed.length} more`);
+            const message = [
+                `${resolved.length} mod folder(s) are not in the library yet.`,
+                unresolved > 0 ? `${unresolved} other folder(s) could not be placed and will be skipped.` : null,
+                `\n${listed.join('\n')}`,
+                '\nAdd them now?',
+            ].filter(Boolean).join('\n');
+            const confirmed = await ask(message, { title: 'Unregistered Mods', type: 'info' });
+            if (!confirmed) return;
+
+            const result = await invoke('apply_scan_decisions', { decisions: resolved.map(o => [o.folder_path, o.entity_slug]) });
+            if (result.errors.length > 0) {
+                console.warn("Some folders could not be added:", result.errors);
+                toast.warn(`Added ${result.inserted} mod(s); ${result.errors.length} failed (see console).`);
+            } else {
+                toast.success(`Added ${result.inserted} mod(s) to the library.`);
+            }
+        } catch (err) {
+            console.error("Failed to find unregistered mods:", err);
+            toast.error(`Failed to find unregistered mods: ${err?.message || String(err)}`);
+        } finally {
+            setIsFindingOrphans(false);
+        }
+    }, []);
+
+    const handleCopyScanReport = useCallback(async () => {
+        setIsBuildingScanReport(true);
+        try {
+            const report = await invoke('debug_scan_report');
+            const lines = report.map(entry => {
+                const target = entry.entity_slug ? ` -> ${entry.entity_slug}` : '';
+                const detail = entry.detail ? ` (${entry.detail})` : '';
+                return `[${entry.status}${entry.has_ini ? ', ini' : ''}] ${entry.relative_path}${target}${detail}`;
+            });
+            await navigator.clipboard.writeText(lines.join('\n'));
+            toast.success(`Scan report for ${report.length} folders copied to clipboard.`);
+        } catch (err) {
+            console.error("Failed to build scan report:", err);
+            toast.error(`Failed to build scan report: ${err?.message || String(err)}`);
+        } finally {
+            setIsBuildingScanReport(false);
+        }
+    }, []);
+
+    const handleOpenAppDataFolder = useCallback(async () => {
+        try {
+            await invoke('open_app_data_folder');
+        } catch (err) {
+            console.error("Failed to open app data folder:", err);
+            toast.error(`Failed to open app data folder: ${err?.message || String(err)}`);
+        }
+    }, []);
+
+    const handleCopyAppInfo = useCallback(async () => {
+        if (!appInfo) return;
+        const lines = [
+            `GMM version: ${appInfo.app_version}`,
+            `Database schema: v${appInfo.schema_version}`,
+            `Active game: ${appInfo.active_game || 'unknown'}`,
+            `Database: ${appInfo.database_path}`,
+            `Mods folder: ${appInfo.mods_folder || 'not set'}`,
+            `Log: ${appInfo.log_path}`,
+        ];
+        try {
+            await navigator.clipboard.writeText(lines.join('\n'));
+            toast.success("App info copied to clipboard.");
+        } catch (err) {
+            console.error("Failed to copy app info:", err);
+            toast.error("Failed to copy app info.");
+        }
+    }, [appInfo]);
+
+    const handleCopyLogPath = useCallback(async () => {
+        try {
+            const logPath = await invoke('get_log_path');
+            await navigator.clipboard.writeText(logPath);
+            toast.success("Log file path copied to clipboard.");
+        } catch (err) {
+            console.error("Failed to get log path:", err);
+            toast.error(`Failed to get log path: ${err?.message || String(err)}`);
+        }
+    }, []);
+
+    const handleOptimizeDatabase = useCallback(async () => {
+        setIsOptimizingDb(true);
+        try {
+            const { size_before, size_after } = await invoke('optimize_database');
+            const toMb = (bytes) => (bytes / 1048576).toFixed(2);
+            toast.success(`Database optimized: ${toMb(size_before)} MB → ${toMb(size_after)} MB.`);
+        } catch (err) {
+            console.error("Failed to optimize database:", err);
+            toast.error(`Failed to optimize database: ${err?.message || String(err)}`);
+        } finally {
+            setIsOptimizingDb(false);
+        }
+    }, []);
+
+    const handleExportDatabase = useCallback(async () => {
+        try {
+            const destPath = await save({ defaultPath: 'gmm-library.json', filters: [{ name: 'JSON', extensions: ['json'] }] });
+            if (!destPath) return;
+            const assetCount = await invoke('export_database_json', { destPath });
+            toast.success(`Exported ${assetCount} mods to JSON.`);
+        } catch (err) {
+            console.error("Failed to export database:", err);
+            toast.error(`Failed to export database: ${err?.message || String(err)}`);
+        }
+    }, []);
+
+    const handleImportDatabase = useCallback(async () => {
+        try {
+            const srcPath = await open({ multiple: false, filters: [{ name: 'JSON', extensions: ['json'] }] });
+            if (!srcPath || Array.isArray(srcPath)) return;
+            const confirmed = await ask("Restore names, tags and organization from this export? Existing entries for the same folders will be overwritten.", { title: 'Import Library Metadata', type: 'warning' });
+            if (!confirmed) return;
+            const result = await invoke('import_database_json', { srcPath });
+            toast.success(`Imported: ${result.assets_added} added, ${result.assets_updated} updated.`);
+            if (result.missing.length > 0) toast.warn(`${result.missing.length} mod(s) skipped because their folders are missing or outside the mods folder.`);
+        } catch (err) {
+            console.error("Failed to import database:", err);
+            toast.error(`Failed to import database: ${err?.message || String(err)}`);
+        }
+    }, []);
+
+    const runBackupOperation = useCallback(async (operation, command, args) => {
+        setBackupOperation(operation);
+        setBackupProgress(null);
+        const unlisten = await listen(BACKUP_PROGRESS_EVENT, (event) => setBackupProgress(event.payload));
+        try {
+            return await invoke(command, args);
+        } finally {
+            unlisten();
+            setBackupOperation(null);
+            setBackupProgress(null);
+        }
+    }, []);
+
+    const handleBackupMods = useCallback(async () => {
+        try {
+            const destPath = await save({ defaultPath: 'gmm-mods-backup.zip', filters: [{ name: 'Zip', extensions: ['zip'] }] });
+            if (!destPath) return;
+            const result = await runBackupOperation('backup', 'backup_mods_folder', { destPath });
+            toast.success(`Backed up ${result.files} files.`);
+        } catch (err) {
+            if (err?.kind === 'UserCancelled') { toast.info('Backup cancelled.'); return; }
+            console.error("Failed to back up mods folder:", err);
+            toast.error(`Failed to back up mods: ${err?.message || String(err)}`);
+        }
+    }, [runBackupOperation]);
+
+    const handleRestoreMods = useCallback(async () => {
+        try {
+            const srcPath = await open({ multiple: false, filters: [{ name: 'Zip', extensions: ['zip'] }] });
+            if (!srcPath || Array.isArray(srcPath)) return;
+            const confirmed = await ask("Restore mods from this backup into the mods folder? Files that already exist are kept as they are.", { title: 'Restore Mods Backup', type: 'warning' });
+            if (!confirmed) return;
+            const result = await runBackupOperation('restore', 'restore_mods_backup', { srcPath });
+            toast.success(`Restored ${result.files} files (${result.skipped} already present). Run a scan to add them to the library.`);
+        } catch (err) {
+            if (err?.kind === 'UserCancelled') { toast.info('Restore cancelled. Files restored so far were removed.'); return; }
+            console.error("Failed to restore mods backup:", err);
+            toast.error(`Failed to restore backup: ${err?.message || String(err)}`);
+        }
+    }, [runBackupOperation]);
+
+    const handleCancelBackup = useCallback(() => {
+        if (backupOperation) invoke('cancel_operation', { operation: backupOperation }).catch(err => console.error("Failed to cancel:", err));
+    }, [backupOperation]);
+
+    const handleReloadDefinitions = useCallback(async () => {
+        setIsReloadingDefinitions(true);
+        try {
+            const counts = await invoke('reload_definitions');
+            toast.success(`Definitions reloaded: ${counts.added} added, ${counts.updated} updated.`);
+        } catch (err) {
+            console.error("Failed to reload definitions:", err);
+            toast.error(`Failed to reload definitions: ${err?.message || String(err)}`);
+        } finally {
+            setIsReloadingDefinitions(false);
+        }
+    }, []);
+
+    const handleCheckForUpdates = useCallback(async () => {
+        setIsCheckingUpdates(true);
+        try {
+            const statuses = await invoke('check_for_updates', { force: true });
+            if (statuses.length === 0) {
+                toast.info('No mods with a GameBanana source URL to check.');
+                return;
+            }
+            const outdated = statuses.filter(s => s.is_outdated);
+            const failed = statuses.filter(s => s.error);
+            if (failed.length > 0) toast.warn(`Could not check ${failed.length} mod(s): ${failed[0].error}`);
+            if (outdated.length === 0) {
+                toast.success(`All ${statuses.length} checked mod(s) are up to date.`);
+                return;
+            }
+            const formatVersions = (m) => (m.local_version || m.remote_version)
+                ? ` [v${m.local_version || '?'} → v${m.remote_version || '?'}]`
+                : '';
+            const preview = outdated.slice(0, 10).map(m => `• ${m.asset_name} (${m.entity_slug})${formatVersions(m)}`).join('\n');
+            const more = outdated.length > 10 ? `\n…and ${outdated.length - 10} more` : '';
+            await ask(
+                `${outdated.length} mod(s) have been updated on GameBanana since you imported them:\n\n${preview}${more}`,
+                { title: 'Mod Updates Available', type: 'info' }
+            );
+        } catch (err) {
+            console.error("Failed to check for mod updates:", err);
+            toast.error(`Failed to check for updates: ${err?.message || String(err)}`);
+        } finally {
+            setIsCheckingUpdates(false);
+        }
+    }, []);
+
+    const handleVerifyDiskState = useCallback(async () => {
+        setIsVerifyingState(true);
+        try {
+            const report = await invoke('reconcile_state');
+            const changed = report.state_changes.length;
+            const parts = [`${changed} state change(s)`];
+            if (report.case_fixed > 0) parts.push(`${report.case_fixed} path casing fix(es)`);
+            if (report.hidden_by_disabled_parent > 0) parts.push(`${report.hidden_by_disabled_parent} hidden by a disabled folder`);
+            toast.success(`Checked ${report.checked} mods: ${parts.join(', ')}.`);
+
+            // A known state that flipped was renamed by hand or by another tool, not by this app
+            const changedOutside = report.state_changes.filter(c => c.previous_enabled !== null && c.current_enabled !== null);
+            if (changedOutside.length > 0) {
+                const names = changedOutside.slice(0, 5).map(c => `${c.name} (${c.current_enabled ? 'enabled' : 'disabled'})`).join(', ');
+                const more = changedOutside.length > 5 ? ` and ${changedOutside.length - 5} more` : '';
+                toast.info(`Changed outside the app: ${names}${more}.`, { autoClose: 8000 });
+            }
+
+            if (report.missing.length > 0) {
+                const preview = report.missing.slice(0, 10).map(m => `• ${m.name} (${m.folder_name})`).join('\n');
+                const more = report.missing.length > 10 ? `\n…and ${report.missing.length - 10} more` : '';
+                const prune = await ask(
+                    `${report.missing.length} mod folder(s) could not be found on disk:\n\n${preview}${more}\n\nRemove them from the library?`,
+                    { title: 'Missing Mod Folders', type: 'warning' }
+                );
+                if (prune) {
+                    const pruneReport = await invoke('reconcile_state', { pruneMissing: true });
+                    toast.info(`Removed ${pruneReport.pruned} missing mod(s) from the library.`);
+                }
+            }
+        } catch (err) {
+            console.error("Failed to reconcile library state:", err);
+            toast.error(`Failed to verify mod states: ${err?.message || String(err)}`);
+        } finally {
+            setIsVerifyingState(false);
+        }
+    }, []);
+
+    const handleNormalizePrefixes = useCallback(async () => {
+        setIsNormalizingPrefixes(true);
+        try {
+            const report = await invoke('normalize_disabled_prefixes');
+            const parts = [`${report.renamed.length} renamed`];
+            if (report.db_paths_fixed > 0) parts.push(`${report.db_paths_fixed} library path(s) fixed`);
+            if (report.reconcile.state_changes.length > 0) parts.push(`${report.reconcile.state_changes.length} state change(s)`);
+            toast.success(`Disabled prefixes: ${parts.join(', ')}.`);
+            const problems = [
+                ...report.conflicts.map(path => `• ${path} (normalized name already exists)`),
+                ...report.errors.map(message => `• ${message}`),
+            ];
+            if (problems.length > 0) {
+                const more = problems.length > 10 ? `\n…and ${problems.length - 10} more` : '';
+                toast.warn(`Some items were left unchanged:\n${problems.slice(0, 10).join('\n')}${more}`, { autoClose: false });
+            }
+        } catch (err) {
+            console.error("Failed to normalize disabled prefixes:", err);
+            toast.error(`Failed to fix disabled prefixes: ${err?.message || String(err)}`);
+        } finally {
+            setIsNormalizingPrefixes(false);
+        }
+    }, []);
+
+    const handleRepairOtherEntities = useCallback(async () => {
+        setIsRepairingOtherEntities(true);
+        try {
+            const report = await invoke('ensure_other_entities');
+            if (report.created.length === 0 && report.assets_reassigned === 0) {
+                toast.success('All categories have their "Other" entity and no mods are orphaned.');
+            } else {
+                toast.success(`Recreated ${report.created.length} "Other" entit${report.created.length === 1 ? 'y' : 'ies'}, moved ${report.assets_reassigned} orphaned mod(s).`);
+            }
+        } catch (err) {
+            console.error("Failed to repair Other entities:", err);
+            toast.error(`Failed to repair "Other" entities: ${err?.message || String(err)}`);
+        } finally {
+            setIsRepairingOtherEntities(false);
+        }
+    }, []);
+
+    const handleCheckIntegrity = useCallback(async () => {
+        setIsCheckingIntegrity(true);
+        const describe = (violations) => violations.map(v => `• ${v.count} row(s) in ${v.table} point to a missing ${v.parent} entry`).join('\n');
+        try {
+            const check = await invoke('check_integrity', { repair: false });
+            if (check.found.length === 0) {
+                toast.success('Database integrity check passed.');
+                return;
+            }
+            const confirmed = await ask(
+                `Broken references found:\n\n${describe(check.found)}\n\nRepair them? Orphaned mods are moved to "Other" entities and dangling preset/hotkey entries are removed.`,
+                { title: 'Database Integrity', type: 'warning' }
+            );
+            if (!confirmed) return;
+            const report = await invoke('check_integrity', { repair: true });
+            toast.success(`Repaired: ${report.assets_reassigned} mod(s) reassigned, ${report.rows_deleted} dangling entr${report.rows_deleted === 1 ? 'y' : 'ies'} removed.`);
+            if (report.remaining.length > 0) toast.warn(`Could not repair:\n${describe(report.remaining)}`, { autoClose: false });
+        } catch (err) {
+            console.error("Failed to check database integrity:", err);
+            toast.error(`Integrity check failed: ${err?.message || String(err)}`);
+        } finally {
+            setIsCheckingIntegrity(false);
+        }
+    }, []);
+
     useEffect(() => {
         const setupListeners = async () => {
              scanListenersRef.current.unlistenProgress = await listen(SCAN_PROGRESS_EVENT, (event) => {
@@ -262,6 +825,383 @@ function SettingsPage() {
                             {isManualScanning && !scanSummary && !scanError ? ' Scanning...' : ' Scan Now'}
                         </button>
                      </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel} htmlFor="scan-ignore">Scan Ignore List:</label>
+                        <input
+                            id="scan-ignore"
+                            type="text"
+                            placeholder="e.g., BufferValues, *.backup, _tools (a .gmmignore file in the mods folder also works)"
+                            value={scanIgnorePatterns}
+                            onChange={(e) => setScanIgnorePatterns(e.target.value)}
+                            style={styles.input}
+                            disabled={isSavingIgnorePatterns || isManualScanning}
+                        />
+                        <button
+                            className="btn btn-outline"
+                            onClick={handleSaveIgnorePatterns}
+                            disabled={isSavingIgnorePatterns || isManualScanning || scanIgnorePatterns === savedScanIgnorePatterns}
+                            style={{ minWidth: '120px' }}
+                        >
+                            {isSavingIgnorePatterns ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-save fa-fw"></i>}
+                            {' '}Save
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel} htmlFor="name-cleanup">Name Cleanup:</label>
+                        <div style={{ flexGrow: 1, display: 'flex', flexDirection: 'column', gap: '6px' }}>
+                            <textarea
+                                id="name-cleanup"
+                                placeholder={"One regular expression per line, removed from detected mod names, e.g. (?i)_by\\w+$"}
+                                value={cleanupPatterns}
+                                onChange={(e) => setCleanupPatterns(e.target.value)}
+                                rows={3}
+                                style={{ ...styles.input, fontFamily: 'monospace', resize: 'vertical' }}
+                            />
+                            <input
+                                type="text"
+                                aria-label="Sample mod name"
+                                value={cleanupSample}
+                                onChange={(e) => setCleanupSample(e.target.value)}
+                                style={styles.input}
+                            />
+                            <span style={{ fontSize: '13px', color: cleanupPreview.error ? 'var(--danger)' : 'rgba(255, 255, 255, 0.7)' }}>
+                                {cleanupPreview.error || `Result: ${cleanupPreview.result || '(empty, the folder name would be used)'}`}
+                            </span>
+                        </div>
+                        <button
+                            className="btn btn-outline"
+                            onClick={handleSaveCleanupPatterns}
+                            disabled={!!cleanupPreview.error || cleanupPatterns === savedCleanupPatterns}
+                            style={{ minWidth: '120px' }}
+                        >
+                            <i className="fas fa-save fa-fw"></i>{' '}Save
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel} htmlFor="rule-pattern">Entity Rules:</label>
+                        <div style={{ flexGrow: 1, display: 'flex', flexDirection: 'column', gap: '6px' }}>
+                            <span style={{ fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)' }}>
+                                Mod folders matching a pattern always go to its entity, before any automatic detection. The first matching rule wins.
+                            </span>
+                            {deductionRules.map(rule => (
+                                <div key={rule.id} style={{ display: 'flex', alignItems: 'center', gap: '8px', fontSize: '13px' }}>
+                                    <code style={{ flexGrow: 1 }}>{rule.pattern}</code>
+                                    <span style={{ color: rule.entity_name ? 'inherit' : 'var(--danger)' }}>
+                                        → {rule.entity_name || `${rule.entity_slug} (missing, ignored)`}
+                                    </span>
+                                    <button className="btn-icon" onClick={() => handleDeleteDeductionRule(rule.id)} title="Delete rule">
+                                        <i className="fas fa-trash-alt fa-fw"></i>
+                                    </button>
+                                </div>
+                            ))}
+                            <div style={{ display: 'flex', gap: '6px' }}>
+                                <input
+                                    id="rule-pattern"
+                                    type="text"
+                                    placeholder="e.g., *Raiden*, or characters/*/Raiden*"
+                                    value={newRulePattern}
+                                    onChange={(e) => setNewRulePattern(e.target.value)}
+                                    style={{ ...styles.input, fontFamily: 'monospace' }}
+                                />
+                                <select
+                                    aria-label="Rule entity"
+                                    value={newRuleEntity}
+                                    onChange={(e) => setNewRuleEntity(e.target.value)}
+                                    style={{ ...styles.input, flexGrow: 0, minWidth: '160px' }}
+                                >
+                                    <option value="">Entity...</option>
+                                    {categories.map(category => (
+                                        <optgroup key={category.slug} label={category.name}>
+                                            {allEntities.filter(entity => entity.category_slug === category.slug).map(entity => (
+                                                <option key={entity.slug} value={entity.slug}>{entity.name}</option>
+                                            ))}
+                                        </optgroup>
+                                    ))}
+                                </select>
+                            </div>
+                            <input
+                                type="text"
+                                aria-label="Test folder name"
+                                placeholder="Test a folder name or path..."
+                                value={ruleSample}
+                                onChange={(e) => setRuleSample(e.target.value)}
+                                style={styles.input}
+                            />
+                            {ruleSample.trim() && (
+                                <span style={{ fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)' }}>
+                                    {ruleTestResult ? `Matches ${ruleTestResult.pattern} → ${ruleTestResult.entity_name}` : 'No rule matches; automatic detection would be used.'}
+                                </span>
+                            )}
+                        </div>
+                        <button
+                            className="btn btn-outline"
+                            onClick={handleAddDeductionRule}
+                            disabled={!newRulePattern.trim() || !newRuleEntity}
+                            style={{ minWidth: '120px' }}
+                        >
+                            <i className="fas fa-plus fa-fw"></i>{' '}Add Rule
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel} htmlFor="scan-threads">Scan Threads:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            How many folders a scan analyzes at once. Lower it if scans make the PC unresponsive.
+                        </span>
+                        <select
+                            id="scan-threads"
+                            value={scanThreads}
+                            onChange={handleScanThreadsChange}
+                            style={{ ...styles.input, flexGrow: 0, minWidth: '120px' }}
+                            disabled={isManualScanning}
+                        >
+                            {SCAN_THREAD_OPTIONS.map(option => (<option key={option.value} value={option.value}>{option.label}</option>))}
+                        </select>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel} htmlFor="auto-scan-on-startup">Scan on Startup:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Scan the mods folder in the background each time the app starts, so mods added outside the app show up.
+                        </span>
+                        <select
+                            id="auto-scan-on-startup"
+                            value={autoScanOnStartup}
+                            onChange={handleAutoScanOnStartupChange}
+                            style={{ ...styles.input, flexGrow: 0, minWidth: '120px' }}
+                        >
+                            <option value="false">Off</option>
+                            <option value="true">On</option>
+                        </select>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel} htmlFor="disable-strategy">Disable Method:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            How mods are disabled. Renaming INI files to DISABLED_*.ini keeps folder paths stable. Mods disabled either way are recognized.
+                        </span>
+                        <select
+                            id="disable-strategy"
+                            value={disableStrategy}
+                            onChange={handleDisableStrategyChange}
+                            style={{ ...styles.input, flexGrow: 0, minWidth: '120px' }}
+                        >
+                            {DISABLE_STRATEGY_OPTIONS.map(option => (<option key={option.value} value={option.value}>{option.label}</option>))}
+                        </select>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel} htmlFor="preview-storage">Custom Previews:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Where preview images you pick or paste are saved. "App data" leaves mod folders exactly as downloaded. Existing previews stay where they are.
+                        </span>
+                        <select
+                            id="preview-storage"
+                            value={previewStorage}
+                            onChange={handlePreviewStorageChange}
+                            style={{ ...styles.input, flexGrow: 0, minWidth: '120px' }}
+                        >
+                            {PREVIEW_STORAGE_OPTIONS.map(option => (<option key={option.value} value={option.value}>{option.label}</option>))}
+                        </select>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel} htmlFor="fallback-category">Fallback Category:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Scanned mods with no recognizable character or category are filed under this category's "Other" entity.
+                        </span>
+                        <select
+                            id="fallback-category"
+                            value={fallbackCategory}
+                            onChange={handleFallbackCategoryChange}
+                            style={{ ...styles.input, flexGrow: 0, minWidth: '120px' }}
+                        >
+                            <option value="">Default</option>
+                            {categories.map(category => (<option key={category.slug} value={category.slug}>{category.name}</option>))}
+                        </select>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>Verify Mod States:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Resync enabled/disabled states and find missing mod folders after editing them outside the app.
+                        </span>
+                        <button
+                            className="btn btn-outline"
+                            onClick={handleVerifyDiskState}
+                            disabled={isVerifyingState || isManualScanning || !modsFolder}
+                            title={!modsFolder ? "Set Mods Folder path first" : "Check every mod folder on disk"}
+                            style={{ minWidth: '120px' }}
+                        >
+                            {isVerifyingState ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-check-double fa-fw"></i>}
+                            {isVerifyingState ? ' Verifying...' : ' Verify'}
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>Disabled Prefixes:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Collapse names like DISABLED_DISABLED_Mod left by manual renaming into a single DISABLED_ prefix.
+                        </span>
+                        <button
+                            className="btn btn-outline"
+                            onClick={handleNormalizePrefixes}
+                            disabled={isNormalizingPrefixes || isManualScanning || !modsFolder}
+                            title={!modsFolder ? "Set Mods Folder path first" : "Rename folders and INIs with repeated DISABLED prefixes"}
+                            style={{ minWidth: '120px' }}
+                        >
+                            {isNormalizingPrefixes ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-broom fa-fw"></i>}
+                            {isNormalizingPrefixes ? ' Fixing...' : ' Fix'}
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>"Other" Entities:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Recreate missing "Other/Unknown" entities and move mods whose character or entity was deleted into them.
+                        </span>
+                        <button
+                            className="btn btn-outline"
+                            onClick={handleRepairOtherEntities}
+                            disabled={isRepairingOtherEntities || isManualScanning}
+                            style={{ minWidth: '120px' }}
+                        >
+                            {isRepairingOtherEntities ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-wrench fa-fw"></i>}
+                            {isRepairingOtherEntities ? ' Repairing...' : ' Repair'}
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>Database Integrity:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Look for mods, presets or hotkeys that reference deleted entries (possible after manual edits or failed migrations).
+                        </span>
+                        <button
+                            className="btn btn-outline"
+                            onClick={handleCheckIntegrity}
+                            disabled={isCheckingIntegrity || isManualScanning}
+                            style={{ minWidth: '120px' }}
+                        >
+                            {isCheckingIntegrity ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-stethoscope fa-fw"></i>}
+                            {isCheckingIntegrity ? ' Checking...' : ' Check'}
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>Unregistered Mods:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Find mod folders copied in by hand that aren't in the library yet, without a full scan.
+                        </span>
+                        <button
+                            className="btn btn-outline"
+                            onClick={handleFindOrphanedMods}
+                            disabled={isFindingOrphans || isManualScanning || !modsFolder}
+                            style={{ minWidth: '120px' }}
+                        >
+                            {isFindingOrphans ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-search-plus fa-fw"></i>}
+                            {' '}Find
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>Scan Report:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Copy how the scanner sees every folder (without changing anything), for bug reports.
+                        </span>
+                        <button
+                            className="btn btn-outline"
+                            onClick={handleCopyScanReport}
+                            disabled={isBuildingScanReport || isManualScanning || !modsFolder}
+                            style={{ minWidth: '120px' }}
+                        >
+                            {isBuildingScanReport ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-clipboard fa-fw"></i>}
+                            {' '}Copy
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>Library Metadata:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Back up or restore mod names, tags and organization as JSON (mod files are not included).
+                        </span>
+                        <button className="btn btn-outline" onClick={handleImportDatabase} disabled={isManualScanning || !modsFolder}>
+                            <i className="fas fa-file-import fa-fw"></i>{' '}Import
+                        </button>
+                        <button className="btn btn-outline" onClick={handleExportDatabase} disabled={isManualScanning} style={{ minWidth: '120px' }}>
+                            <i className="fas fa-file-export fa-fw"></i>{' '}Export
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>Mods Backup:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            {backupOperation
+                                ? `${backupOperation === 'backup' ? 'Backing up' : 'Restoring'}... ${backupProgress ? `${backupProgress.processed} / ${backupProgress.total} files` : ''}`
+                                : 'Zip the whole mods folder, or restore missing files from such a zip.'}
+                        </span>
+                        {backupOperation ? (
+                            <button className="btn btn-outline" onClick={handleCancelBackup} style={{ minWidth: '120px' }}>
+                                <i className="fas fa-times fa-fw"></i>{' '}Cancel
+                            </button>
+                        ) : (<>
+                            <button className="btn btn-outline" onClick={handleRestoreMods} disabled={isManualScanning || !modsFolder}>
+                                <i className="fas fa-undo fa-fw"></i>{' '}Restore
+                            </button>
+                            <button className="btn btn-outline" onClick={handleBackupMods} disabled={isManualScanning || !modsFolder} style={{ minWidth: '120px' }}>
+                                <i className="fas fa-archive fa-fw"></i>{' '}Back Up
+                            </button>
+                        </>)}
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>Optimize Database:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Compact the database file after many imports and deletions.
+                        </span>
+                        <button className="btn btn-outline" onClick={handleOptimizeDatabase} disabled={isOptimizingDb || isManualScanning} style={{ minWidth: '120px' }}>
+                            {isOptimizingDb ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-compress-alt fa-fw"></i>}
+                            {isOptimizingDb ? ' Optimizing...' : ' Optimize'}
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>About:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            {appInfo
+                                ? `GMM v${appInfo.app_version} · database schema v${appInfo.schema_version} · ${(appInfo.active_game || 'unknown game').toUpperCase()}`
+                                : 'Loading...'}
+                        </span>
+                        <button className="btn btn-outline" onClick={handleCopyAppInfo} disabled={!appInfo} title="Copy versions and paths for a bug report" style={{ minWidth: '120px' }}>
+                            <i className="fas fa-clipboard fa-fw"></i>{' '}Copy
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>App Data & Logs:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Open the folder holding the database and log files to attach them to a bug report.
+                        </span>
+                        <button className="btn btn-outline" onClick={handleCopyLogPath} title="Copy the current log file's path">
+                            <i className="fas fa-copy fa-fw"></i>
+                        </button>
+                        <button className="btn btn-outline" onClick={handleOpenAppDataFolder} style={{ minWidth: '120px' }}>
+                            <i className="fas fa-folder-open fa-fw"></i>{' '}Open
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>Reload Definitions:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Re-read bundled and custom definition files to pick up new or edited characters.
+                        </span>
+                        <button
+                            className="btn btn-outline"
+                            onClick={handleReloadDefinitions}
+                            disabled={isReloadingDefinitions || isManualScanning}
+                            style={{ minWidth: '120px' }}
+                        >
+                            {isReloadingDefinitions ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-book fa-fw"></i>}
+                            {isReloadingDefinitions ? ' Reloading...' : ' Reload'}
+                        </button>
+                     </div>
+                     <div style={styles.settingRow}>
+                        <label style={styles.settingLabel}>Mod Updates:</label>
+                        <span style={{flexGrow: 1, fontSize: '13px', color: 'rgba(255, 255, 255, 0.7)'}}>
+                            Check mods imported from GameBanana for newer uploads.
+                        </span>
+                        <button
+                            className="btn btn-outline"
+                            onClick={handleCheckForUpdates}
+                            disabled={isCheckingUpdates}
+                            style={{ minWidth: '120px' }}
+                        >
+                            {isCheckingUpdates ? <i className="fas fa-spinner fa-spin fa-fw"></i> : <i className="fas fa-cloud-download-alt fa-fw"></i>}
+                            {isCheckingUpdates ? ' Checking...' : ' Check'}
+                        </button>
+                     </div>
                 </div>
             )}
 
@@ -0,0 +1,39 @@
+// src/utils/imageCache.js
+// Blob URLs for mod preview images, reused while the file's path, mtime and size are unchanged
+import { invoke } from '@tauri-apps/api/tauri';
+
+const MAX_CACHED_IMAGES = 100;
+const cache = new Map(); // assetId -> { key, url }, oldest first
+
+const mimeTypeFor = (path) => {
+    const extension = path.split('.').pop().toLowerCase();
+    if (['jpg', 'jpeg'].includes(extension)) return 'image/jpeg';
+    if (extension === 'gif') return 'image/gif';
+    if (extension === 'webp') return 'image/webp';
+    return 'image/png';
+};
+
+// Returns a blob URL owned by the cache; callers must not revoke it
+export const getAssetImageUrl = async (assetId) => {
+    const info = await invoke('get_asset_image_info', { assetId });
+    const key = `${info.path}|${info.modified}|${info.size}`;
+    const cached = cache.get(assetId);
+    if (cached?.key === key) {
+        cache.delete(assetId);
+        cache.set(assetId, cached); // Mark as most recently used
+        return cached.url;
+    }
+
+    const fileData = await invoke('read_binary_file', { path: info.path });
+    const url = URL.createObjectURL(new Blob([new Uint8Array(fileData)], { type: mimeTypeFor(info.path) }));
+    if (cached) URL.revokeObjectURL(cached.url);
+    cache.delete(assetId);
+    cache.set(assetId, { key, url });
+
+    if (cache.size > MAX_CACHED_IMAGES) {
+        const [oldestId, oldest] = cache.entries().next().value;
+        URL.revokeObjectURL(oldest.url);
+        cache.delete(oldestId);
+    }
+    return url;
+};
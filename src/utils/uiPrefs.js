@@ -0,0 +1,28 @@
+// src/utils/uiPrefs.js
+import { invoke } from '@tauri-apps/api/tauri';
+
+// Typed view preferences kept by the backend in one settings row (see UiPrefs in main.rs).
+// Loaded once per session; updates patch the cached copy and save the whole object.
+let prefsPromise = null;
+
+export const getUiPrefs = () => {
+    if (!prefsPromise) {
+        prefsPromise = invoke('get_ui_prefs').catch(err => {
+            console.error("Failed to load UI preferences:", err);
+            prefsPromise = null; // Retry on next call
+            return {};
+        });
+    }
+    return prefsPromise;
+};
+
+export const updateUiPrefs = async (patch) => {
+    const prefs = { ...(await getUiPrefs()), ...patch };
+    prefsPromise = Promise.resolve(prefs);
+    try {
+        await invoke('set_ui_prefs', { prefs });
+    } catch (err) {
+        console.error("Failed to save UI preferences:", err);
+    }
+    return prefs;
+};